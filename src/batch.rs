@@ -0,0 +1,154 @@
+/*!
+This module contains the [`BatchInput`] type which is used to evaluate a
+[`QuantityFunction`](crate::QuantityFunction) for many sets of influencing
+factors at once via [`QuantityFunction::call_batch`](crate::QuantityFunction::call_batch).
+
+Evaluating a function element-wise (e.g. for every cell of a mesh) via
+[`QuantityFunction::call`](crate::QuantityFunction::call) requires a dynamic
+dispatch and a search for the matching influencing factors per element. The
+batch API instead takes the influencing factors as columns: Each
+[`BatchInput`] contains the values of one influencing factor for all elements.
+The functions provided by this crate select their columns once and then loop
+over the raw values.
+*/
+
+use dyn_quantity::Unit;
+
+/**
+A column of influencing factor values which all share the same unit.
+
+The `i`-th entry of [`BatchInput::values`] belongs to the `i`-th element of
+the batch. The values are interpreted in SI base units (the same
+representation as the `value` field of a
+[`DynQuantity`](dyn_quantity::DynQuantity)).
+
+# Examples
+
+```
+use dyn_quantity::{DynQuantity, PredefUnit, Unit};
+use var_quantity::{BatchInput, QuantityFunction, unary::Linear};
+
+// R = 1 Ω + 0.01 Ω/K * T
+let fun = Linear::new(
+    DynQuantity::new(0.01, Unit::from(PredefUnit::ElectricResistance) / PredefUnit::Temperature.into()),
+    DynQuantity::new(1.0, PredefUnit::ElectricResistance),
+);
+
+let temperatures = [100.0, 200.0, 300.0];
+let currents = [1.0, 2.0, 3.0];
+let inputs = [
+    BatchInput::new(PredefUnit::ElectricCurrent, &currents),
+    BatchInput::new(PredefUnit::Temperature, &temperatures),
+];
+
+let mut resistances = [0.0; 3];
+fun.call_batch(&inputs, &mut resistances);
+assert_eq!(resistances, [2.0, 3.0, 4.0]);
+```
+ */
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BatchInput<'a> {
+    /// Unit of all values.
+    pub unit: Unit,
+    /// Values of the influencing factor in SI base units.
+    pub values: &'a [f64],
+}
+
+impl<'a> BatchInput<'a> {
+    /**
+    Creates a new instance of [`BatchInput`].
+     */
+    pub fn new<U: Into<Unit>>(unit: U, values: &'a [f64]) -> Self {
+        return Self {
+            unit: unit.into(),
+            values,
+        };
+    }
+}
+
+/**
+Panics if the length of a column in `inputs` differs from `len`.
+ */
+pub(crate) fn assert_lengths(inputs: &[BatchInput], len: usize) {
+    for input in inputs.iter() {
+        assert_eq!(
+            input.values.len(),
+            len,
+            "the batch input with unit {} has {} values, but the output has {} values",
+            input.unit,
+            input.values.len(),
+            len
+        );
+    }
+}
+
+/**
+The batch counterpart of [`filter_unary_function`](crate::filter_unary_function):
+Selects the first column in `inputs` whose unit is `match_for` and writes
+`with_matched(value)` into `output` for each of its values. If no column
+matches, `output` is filled with the value returned by `no_match`.
+
+This function is mainly here to simplify custom implementations of
+[`QuantityFunction::call_batch`](crate::QuantityFunction::call_batch) for
+unary functions.
+
+# Panics
+
+Panics if the length of a column in `inputs` differs from that of `output`.
+
+# Examples
+
+```
+use dyn_quantity::{DynQuantity, PredefUnit, Unit};
+use var_quantity::{BatchInput, QuantityFunction, filter_unary_batch, filter_unary_function};
+
+// The serde annotations are just here because the doctests of this crate use
+// the serde feature - they are not needed if the serde feature is disabled.
+#[derive(Clone, serde::Deserialize, serde::Serialize)]
+struct Square;
+
+// Again, the macro annotation is just here because of the serde feature
+#[typetag::serde]
+impl QuantityFunction for Square {
+    fn call(&self, influencing_factors: &[DynQuantity<f64>]) -> DynQuantity<f64> {
+        return filter_unary_function(
+            influencing_factors,
+            PredefUnit::Length.into(),
+            |x| x.powi(2),
+            || DynQuantity::new(0.0, Unit::from(PredefUnit::Length).powi(2)),
+        );
+    }
+
+    fn call_batch(&self, inputs: &[BatchInput], output: &mut [f64]) {
+        filter_unary_batch(inputs, PredefUnit::Length.into(), output, |x| x.powi(2), || 0.0);
+    }
+}
+
+let mut output = [0.0; 2];
+Square.call_batch(&[BatchInput::new(PredefUnit::Length, &[2.0, 3.0])], &mut output);
+assert_eq!(output, [4.0, 9.0]);
+
+Square.call_batch(&[], &mut output);
+assert_eq!(output, [0.0, 0.0]);
+```
+ */
+pub fn filter_unary_batch<F, G>(
+    inputs: &[BatchInput],
+    match_for: Unit,
+    output: &mut [f64],
+    with_matched: F,
+    no_match: G,
+) where
+    F: Fn(f64) -> f64,
+    G: FnOnce() -> f64,
+{
+    assert_lengths(inputs, output.len());
+    match inputs.iter().find(|input| input.unit == match_for) {
+        Some(input) => {
+            for (out, value) in output.iter_mut().zip(input.values.iter()) {
+                *out = with_matched(*value);
+            }
+        }
+        None => output.fill(no_match()),
+    }
+}
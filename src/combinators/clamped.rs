@@ -0,0 +1,280 @@
+/*!
+A [`QuantityFunction`] trait object whose output is [`Clamped`].
+*/
+
+use dyn_quantity::{DynQuantity, Unit};
+
+use crate::{
+    BatchInput, DualQuantity, EvaluationError, Evaluator, InfluencingFactor, Layout,
+    QuantityFunction, RangeError, validity::ValidityRange,
+};
+
+/**
+A wrapper around a [`QuantityFunction`] trait object which clamps the output of
+[`QuantityFunction::call`] using the provided upper and lower limits.
+
+This is the non-generic counterpart to [`ClampedQuantity`](crate::ClampedQuantity).
+Since it holds a trait object, it implements [`QuantityFunction`] (including
+serialization / deserialization via typetag if the `serde` feature is enabled)
+regardless of the wrapped type. Hence, clamping a custom function does not
+require writing a `#[typetag::serde] impl QuantityFunction for ClampedQuantity<YourType>`
+block.
+
+The limits are given as quantities and are checked against the output unit of
+the function. Either limit can be omitted to clamp only on one side.
+
+# Examples
+
+```
+use std::str::FromStr;
+use dyn_quantity::DynQuantity;
+use var_quantity::{QuantityFunction, combinators::Clamped, unary::Linear};
+
+let q = |s: &str| DynQuantity::<f64>::from_str(s).unwrap();
+
+let lin = Linear::new(q("1 mOhm/K"), q("0 ohm"));
+let clamped = Clamped::new(Box::new(lin), Some(q("0 ohm")), Some(q("10 mOhm"))).expect("valid limits");
+assert_eq!(clamped.call(&[q("5 K")]), q("5 mOhm"));
+assert_eq!(clamped.call(&[q("20 K")]), q("10 mOhm"));
+assert_eq!(clamped.call(&[q("-20 K")]), q("0 ohm"));
+```
+
+# Features:
+This struct can be serialized / deserialized if the `serde` feature is enabled.
+The function is serialized as a trait object and the limits as raw values in
+the output unit of the function (a missing limit is omitted). When
+deserializing, the limits can also be given with a unit (requires the
+`from_str` feature):
+
+```text
+Clamped:
+  upper_limit: 10 mOhm
+  function:
+    Linear:
+      slope: 1 mOhm/K
+      base_value: 0 ohm
+```
+*/
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct Clamped {
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "is_infinite"))]
+    upper_limit: f64,
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "is_infinite"))]
+    lower_limit: f64,
+    function: Box<dyn QuantityFunction>,
+}
+
+impl Clamped {
+    /**
+    Creates a new instance of [`Clamped`]. Fails if the unit of a limit differs
+    from the [`QuantityFunction::output_unit`] of `function`, if a limit is NaN
+    or infinite on the wrong side (e.g. a lower limit of positive infinity) or
+    if the lower limit is larger than the upper limit.
+
+    # Examples
+
+    ```
+    use std::str::FromStr;
+    use dyn_quantity::DynQuantity;
+    use var_quantity::{QuantityFunction, RangeError, combinators::Clamped, unary::Linear};
+
+    let q = |s: &str| DynQuantity::<f64>::from_str(s).unwrap();
+    let lin = || Box::new(Linear::new(q("1 mOhm/K"), q("0 ohm")));
+
+    // Only a lower limit
+    let clamped = Clamped::new(lin(), Some(q("0 ohm")), None).unwrap();
+    assert_eq!(clamped.call(&[q("1 MK")]), q("1 kohm"));
+    assert_eq!(clamped.upper_limit(), f64::INFINITY);
+
+    assert!(matches!(
+        Clamped::new(lin(), None, Some(q("10 A"))),
+        Err(RangeError::UnitsNotEqual(_))
+    ));
+    assert!(matches!(
+        Clamped::new(lin(), Some(q("10 mOhm")), Some(q("5 mOhm"))),
+        Err(RangeError::LowerAboveUpper { .. })
+    ));
+    assert!(matches!(
+        Clamped::new(lin(), Some(DynQuantity::new(f64::NAN, q("1 ohm").unit)), None),
+        Err(RangeError::NotFinite(_))
+    ));
+    assert!(matches!(
+        Clamped::new(lin(), None, Some(DynQuantity::new(f64::NEG_INFINITY, q("1 ohm").unit))),
+        Err(RangeError::NotFinite(_))
+    ));
+    ```
+     */
+    pub fn new(
+        function: Box<dyn QuantityFunction>,
+        lower_limit: Option<DynQuantity<f64>>,
+        upper_limit: Option<DynQuantity<f64>>,
+    ) -> Result<Self, RangeError> {
+        let (lower_limit, upper_limit) =
+            crate::check_limits(function.output_unit(), lower_limit, upper_limit)?;
+        return Ok(Self {
+            upper_limit,
+            lower_limit,
+            function,
+        });
+    }
+
+    /**
+    Returns the wrapped function.
+     */
+    pub fn function(&self) -> &dyn QuantityFunction {
+        return &*self.function;
+    }
+
+    /// Returns the upper limit (infinite if there is none).
+    pub fn upper_limit(&self) -> f64 {
+        return self.upper_limit;
+    }
+
+    /// Returns the lower limit (negative infinite if there is none).
+    pub fn lower_limit(&self) -> f64 {
+        return self.lower_limit;
+    }
+
+    /// Returns whether `value` is strictly outside of the limits.
+    fn is_outside(&self, value: f64) -> bool {
+        return value < self.lower_limit || value > self.upper_limit;
+    }
+
+    fn clamp(&self, mut quantity: DynQuantity<f64>) -> DynQuantity<f64> {
+        quantity.value = quantity.value.clamp(self.lower_limit, self.upper_limit);
+        return quantity;
+    }
+}
+
+#[cfg_attr(feature = "serde", typetag::serde)]
+impl QuantityFunction for Clamped {
+    fn call(&self, influencing_factors: &[DynQuantity<f64>]) -> DynQuantity<f64> {
+        return self.clamp(self.function.call(influencing_factors));
+    }
+
+    fn call_batch(&self, inputs: &[BatchInput], output: &mut [f64]) {
+        self.function.call_batch(inputs, output);
+        for out in output.iter_mut() {
+            *out = out.clamp(self.lower_limit, self.upper_limit);
+        }
+    }
+
+    fn bind(&self, layout: &Layout) -> Box<dyn Evaluator + '_> {
+        let evaluator = self.function.bind(layout);
+        return Box::new(move |values: &[f64]| {
+            evaluator
+                .eval(values)
+                .clamp(self.lower_limit, self.upper_limit)
+        });
+    }
+
+    fn output_unit(&self) -> Unit {
+        return self.function.output_unit();
+    }
+
+    fn influencing_units(&self) -> Option<Vec<Unit>> {
+        return self.function.influencing_units();
+    }
+
+    fn validity_ranges(&self) -> Vec<ValidityRange> {
+        return self.function.validity_ranges();
+    }
+
+    fn call_named(&self, influencing_factors: &[InfluencingFactor]) -> DynQuantity<f64> {
+        return self.clamp(self.function.call_named(influencing_factors));
+    }
+
+    fn try_call(
+        &self,
+        influencing_factors: &[DynQuantity<f64>],
+    ) -> Result<DynQuantity<f64>, EvaluationError> {
+        return Ok(self.clamp(self.function.try_call(influencing_factors)?));
+    }
+
+    fn try_call_named(
+        &self,
+        influencing_factors: &[InfluencingFactor],
+    ) -> Result<DynQuantity<f64>, EvaluationError> {
+        return Ok(self.clamp(self.function.try_call_named(influencing_factors)?));
+    }
+
+    fn derivative(
+        &self,
+        influencing_factors: &[DynQuantity<f64>],
+        wrt: Unit,
+    ) -> Option<DynQuantity<f64>> {
+        let output = self.function.call(influencing_factors);
+        if self.is_outside(output.value) {
+            return Some(DynQuantity::new(0.0, output.unit / wrt));
+        }
+        return self.function.derivative(influencing_factors, wrt);
+    }
+
+    fn call_dual(&self, influencing_factors: &[DualQuantity]) -> DualQuantity {
+        let output = self.function.call_dual(influencing_factors);
+        if self.is_outside(output.value.value) {
+            return DualQuantity::constant(self.clamp(output.value));
+        }
+        return output;
+    }
+}
+
+impl Clone for Clamped {
+    fn clone(&self) -> Self {
+        return Self {
+            upper_limit: self.upper_limit,
+            lower_limit: self.lower_limit,
+            function: dyn_clone::clone_box(&*self.function),
+        };
+    }
+}
+
+impl std::fmt::Debug for Clamped {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Clamped")
+            .field("output_unit", &self.function.output_unit())
+            .field("lower_limit", &self.lower_limit)
+            .field("upper_limit", &self.upper_limit)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Used to omit missing limits when serializing.
+#[cfg(feature = "serde")]
+fn is_infinite(value: &f64) -> bool {
+    return value.is_infinite();
+}
+
+#[cfg(feature = "serde")]
+mod serde_impl {
+    use super::*;
+
+    use serde::de::{Deserialize, Deserializer};
+
+    use crate::serde_impl::Limit;
+
+    impl<'de> Deserialize<'de> for Clamped {
+        fn deserialize<D>(deserializer: D) -> Result<Clamped, D::Error>
+        where
+            D: Deserializer<'de>,
+        {
+            #[derive(serde::Deserialize)]
+            struct ClampedAlias {
+                #[serde(default)]
+                upper_limit: Option<Limit>,
+                #[serde(default)]
+                lower_limit: Option<Limit>,
+                function: Box<dyn QuantityFunction>,
+            }
+
+            let alias = ClampedAlias::deserialize(deserializer)?;
+            let unit = alias.function.output_unit();
+            return Self::new(
+                alias.function,
+                alias.lower_limit.map(|limit| limit.into_quantity(unit)),
+                alias.upper_limit.map(|limit| limit.into_quantity(unit)),
+            )
+            .map_err(serde::de::Error::custom);
+        }
+    }
+}
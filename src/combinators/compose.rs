@@ -0,0 +1,509 @@
+/*!
+A [`Compose`] function which feeds the output of one [`QuantityFunction`]
+trait object into another.
+*/
+
+use dyn_quantity::{DynQuantity, Unit};
+
+use crate::{
+    BatchInput, CompositionError, DualQuantity, EvaluationError, Evaluator, InfluencingFactor,
+    Layout, QuantityFunction, named_quantities, validity::ValidityRange,
+};
+
+/**
+The composition of two [`QuantityFunction`] trait objects:
+
+`y = outer(inner(x), x)`
+
+First, the `inner` function is evaluated with the given influencing factors.
+Its output is then injected into the influencing factors of the `outer`
+function. Any influencing factor with the same unit as the output of `inner`
+is replaced, so the `outer` function always sees the computed intermediate
+quantity. All other influencing factors are forwarded unchanged. For named
+influencing factors, see [`Compose::with_output_name`].
+
+This is useful if a property depends on an intermediate quantity which is
+itself modeled as a function, e.g. the thermal conductivity depends on the
+temperature, which in turn depends on the losses. The constructor
+[`Compose::new`] checks that the `outer` function actually consumes the output
+unit of `inner`.
+
+# Examples
+
+```
+use std::str::FromStr;
+use dyn_quantity::DynQuantity;
+use var_quantity::{QuantityFunction, combinators::Compose, unary::Linear};
+
+let q = |s: &str| DynQuantity::<f64>::from_str(s).unwrap();
+
+// Temperature as a function of the losses
+let temperature = Linear::new(q("0.1 K/W"), q("300 K"));
+
+// Thermal conductivity as a function of the temperature
+let conductivity = Linear::new(q("-0.1 W/(m*K^2)"), q("400 W/(m*K)"));
+
+let fun = Compose::new(Box::new(conductivity), Box::new(temperature)).expect("units match");
+assert_eq!(fun.output_unit(), q("1 W/(m*K)").unit);
+assert_eq!(fun.influencing_units(), Some(vec![q("1 W").unit]));
+
+// 1000 W of losses result in a temperature of 400 K. A temperature given as
+// influencing factor is replaced by the computed one.
+assert_eq!(fun.call(&[q("1000 W")]), q("360 W/(m*K)"));
+assert_eq!(fun.call(&[q("1000 W"), q("20 K")]), q("360 W/(m*K)"));
+
+// The inner output unit must be consumed by the outer function
+let current = Linear::new(q("1 A/W"), q("0 A"));
+let conductivity = Linear::new(q("-0.1 W/(m*K^2)"), q("400 W/(m*K)"));
+assert!(Compose::new(Box::new(conductivity), Box::new(current)).is_err());
+```
+
+# Features:
+This struct can be serialized / deserialized if the `serde` feature is enabled.
+The `outer` and `inner` functions are serialized as trait objects. The unit
+check of [`Compose::new`] is performed during deserialization as well.
+*/
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct Compose {
+    outer: Box<dyn QuantityFunction>,
+    inner: Box<dyn QuantityFunction>,
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    output_name: Option<String>,
+}
+
+impl Compose {
+    /**
+    Checks if the `outer` function consumes the output unit of `inner`, i.e.
+    if the output unit of `inner` is one of the
+    [`QuantityFunction::influencing_units`] of `outer`. If this is the case,
+    a new instance of [`Compose`] is returned. If `outer` does not declare its
+    influencing units, the check is skipped.
+     */
+    pub fn new(
+        outer: Box<dyn QuantityFunction>,
+        inner: Box<dyn QuantityFunction>,
+    ) -> Result<Self, CompositionError> {
+        let unit = inner.output_unit();
+        if let Some(influencing_units) = outer.influencing_units()
+            && !influencing_units.contains(&unit)
+        {
+            return Err(CompositionError::NotConsumed {
+                unit,
+                influencing_units,
+            });
+        }
+        return Ok(Self {
+            outer,
+            inner,
+            output_name: None,
+        });
+    }
+
+    /**
+    Sets the name under which the output of the `inner` function is passed to
+    the `outer` function in [`QuantityFunction::call_named`]. This is needed
+    if the `outer` function is bound to a named influencing factor (e.g. via
+    [`Linear::with_input_name`](crate::unary::Linear::with_input_name)).
+    Only the influencing factor with this name and the output unit of `inner`
+    is replaced, all other factors (including those with the same unit but a
+    different name) are forwarded unchanged.
+
+    Without a name, [`QuantityFunction::call_named`] discards the names after
+    evaluating `inner` and evaluates `outer` via [`QuantityFunction::call`].
+
+    # Examples
+
+    ```
+    use std::str::FromStr;
+    use dyn_quantity::DynQuantity;
+    use var_quantity::{InfluencingFactor, QuantityFunction, combinators::Compose, unary::Linear};
+
+    let q = |s: &str| DynQuantity::<f64>::from_str(s).unwrap();
+
+    let temperature = Linear::new(q("0.1 K/W"), q("300 K"));
+    let conductivity = Linear::new(q("-0.1 W/(m*K^2)"), q("400 W/(m*K)"))
+        .with_input_name("winding_temperature");
+    let fun = Compose::new(Box::new(conductivity), Box::new(temperature))
+        .expect("units match")
+        .with_output_name("winding_temperature");
+
+    let factors = [
+        InfluencingFactor::new("ambient_temperature", q("20 K")),
+        InfluencingFactor::new("losses", q("1000 W")),
+    ];
+    assert_eq!(fun.output_name(), Some("winding_temperature"));
+    assert_eq!(fun.call_named(&factors), q("360 W/(m*K)"));
+
+    // The ambient temperature is still available to the outer function
+    let conductivity = Linear::new(q("-0.1 W/(m*K^2)"), q("400 W/(m*K)"))
+        .with_input_name("ambient_temperature");
+    let fun = Compose::new(Box::new(conductivity), Box::new(Linear::new(q("0.1 K/W"), q("300 K"))))
+        .expect("units match")
+        .with_output_name("winding_temperature");
+    assert_eq!(fun.call_named(&factors), q("398 W/(m*K)"));
+    ```
+     */
+    pub fn with_output_name<N: Into<String>>(mut self, name: N) -> Self {
+        self.output_name = Some(name.into());
+        return self;
+    }
+
+    /**
+    Returns the name under which the output of the `inner` function is passed
+    to the `outer` function, if any. See [`Compose::with_output_name`].
+     */
+    pub fn output_name(&self) -> Option<&str> {
+        return self.output_name.as_deref();
+    }
+
+    /**
+    Returns the outer function.
+     */
+    pub fn outer(&self) -> &dyn QuantityFunction {
+        return &*self.outer;
+    }
+
+    /**
+    Returns the inner function.
+     */
+    pub fn inner(&self) -> &dyn QuantityFunction {
+        return &*self.inner;
+    }
+
+    /**
+    Returns the influencing factors for the `outer` function: The output of
+    the `inner` function followed by all `influencing_factors` which do not
+    have its unit.
+     */
+    fn inject(
+        intermediate: DynQuantity<f64>,
+        influencing_factors: &[DynQuantity<f64>],
+    ) -> Vec<DynQuantity<f64>> {
+        let mut factors = Vec::with_capacity(influencing_factors.len() + 1);
+        factors.push(intermediate);
+        factors.extend(
+            influencing_factors
+                .iter()
+                .filter(|factor| factor.unit != intermediate.unit),
+        );
+        return factors;
+    }
+
+    /**
+    Named counterpart to [`Compose::inject`]: The output of the `inner`
+    function with the name `name` followed by all `influencing_factors`
+    except the one it replaces (the factor with the same name and unit).
+     */
+    fn inject_named(
+        name: &str,
+        intermediate: DynQuantity<f64>,
+        influencing_factors: &[InfluencingFactor],
+    ) -> Vec<InfluencingFactor> {
+        let mut factors = Vec::with_capacity(influencing_factors.len() + 1);
+        factors.push(InfluencingFactor::new(name, intermediate));
+        factors.extend(
+            influencing_factors
+                .iter()
+                .filter(|factor| factor.name != name || factor.quantity.unit != intermediate.unit)
+                .cloned(),
+        );
+        return factors;
+    }
+}
+
+#[cfg_attr(feature = "serde", typetag::serde)]
+impl QuantityFunction for Compose {
+    fn call(&self, influencing_factors: &[DynQuantity<f64>]) -> DynQuantity<f64> {
+        let intermediate = self.inner.call(influencing_factors);
+        return self
+            .outer
+            .call(&Self::inject(intermediate, influencing_factors));
+    }
+
+    fn call_batch(&self, inputs: &[BatchInput], output: &mut [f64]) {
+        crate::batch::assert_lengths(inputs, output.len());
+        let mut intermediate = vec![0.0; output.len()];
+        self.inner.call_batch(inputs, &mut intermediate);
+
+        // Analogous to Compose::inject
+        let unit = self.inner.output_unit();
+        let mut outer_inputs = Vec::with_capacity(inputs.len() + 1);
+        outer_inputs.push(BatchInput::new(unit, &intermediate));
+        outer_inputs.extend(inputs.iter().filter(|input| input.unit != unit).copied());
+        self.outer.call_batch(&outer_inputs, output);
+    }
+
+    fn bind(&self, layout: &Layout) -> Box<dyn Evaluator + '_> {
+        let inner = self.inner.bind(layout);
+
+        // Analogous to Compose::inject and Compose::inject_named: The outer
+        // function is bound to the output of the inner function followed by
+        // the forwarded influencing factors.
+        let unit = self.inner.output_unit();
+        let units = layout.units();
+        let (forwarded, outer_layout): (Vec<usize>, Layout) =
+            match (layout.names(), self.output_name.as_deref()) {
+                (Some(names), Some(name)) => {
+                    let forwarded: Vec<usize> = (0..layout.len())
+                        .filter(|index| names[*index] != name || units[*index] != unit)
+                        .collect();
+                    let outer_layout = Layout::named(
+                        std::iter::once((name.to_string(), unit)).chain(
+                            forwarded
+                                .iter()
+                                .map(|index| (names[*index].clone(), units[*index])),
+                        ),
+                    );
+                    (forwarded, outer_layout)
+                }
+                _ => {
+                    let forwarded: Vec<usize> = (0..layout.len())
+                        .filter(|index| units[*index] != unit)
+                        .collect();
+                    let outer_layout = Layout::new(
+                        std::iter::once(unit)
+                            .chain(forwarded.iter().map(|index| units[*index]))
+                            .collect(),
+                    );
+                    (forwarded, outer_layout)
+                }
+            };
+        let outer = self.outer.bind(&outer_layout);
+        return Box::new(move |values: &[f64]| {
+            let mut outer_values = Vec::with_capacity(forwarded.len() + 1);
+            outer_values.push(inner.eval(values));
+            outer_values.extend(forwarded.iter().map(|index| values[*index]));
+            outer.eval(&outer_values)
+        });
+    }
+
+    fn output_unit(&self) -> Unit {
+        return self.outer.output_unit();
+    }
+
+    fn influencing_units(&self) -> Option<Vec<Unit>> {
+        let intermediate = self.inner.output_unit();
+        let mut units = self.inner.influencing_units()?;
+        for unit in self.outer.influencing_units()? {
+            if unit != intermediate && !units.contains(&unit) {
+                units.push(unit);
+            }
+        }
+        return Some(units);
+    }
+
+    fn validity_ranges(&self) -> Vec<ValidityRange> {
+        // The ranges of the outer function which apply to the intermediate
+        // quantity are omitted, since it is not an influencing factor of the
+        // composition.
+        let intermediate = self.inner.output_unit();
+        let mut ranges = self.inner.validity_ranges();
+        for range in self.outer.validity_ranges() {
+            if range.unit() != intermediate && !ranges.contains(&range) {
+                ranges.push(range);
+            }
+        }
+        return ranges;
+    }
+
+    fn call_named(&self, influencing_factors: &[InfluencingFactor]) -> DynQuantity<f64> {
+        let intermediate = self.inner.call_named(influencing_factors);
+        match self.output_name.as_deref() {
+            Some(name) => {
+                return self.outer.call_named(&Self::inject_named(
+                    name,
+                    intermediate,
+                    influencing_factors,
+                ));
+            }
+            None => {
+                return self.outer.call(&Self::inject(
+                    intermediate,
+                    &named_quantities(influencing_factors),
+                ));
+            }
+        }
+    }
+
+    fn try_call(
+        &self,
+        influencing_factors: &[DynQuantity<f64>],
+    ) -> Result<DynQuantity<f64>, EvaluationError> {
+        let intermediate = self.inner.try_call(influencing_factors)?;
+        return self
+            .outer
+            .try_call(&Self::inject(intermediate, influencing_factors));
+    }
+
+    fn try_call_named(
+        &self,
+        influencing_factors: &[InfluencingFactor],
+    ) -> Result<DynQuantity<f64>, EvaluationError> {
+        let intermediate = self.inner.try_call_named(influencing_factors)?;
+        match self.output_name.as_deref() {
+            Some(name) => {
+                return self.outer.try_call_named(&Self::inject_named(
+                    name,
+                    intermediate,
+                    influencing_factors,
+                ));
+            }
+            None => {
+                return self.outer.try_call(&Self::inject(
+                    intermediate,
+                    &named_quantities(influencing_factors),
+                ));
+            }
+        }
+    }
+
+    fn derivative(
+        &self,
+        influencing_factors: &[DynQuantity<f64>],
+        wrt: Unit,
+    ) -> Option<DynQuantity<f64>> {
+        let unit = self.outer.output_unit() / wrt;
+        if !influencing_factors.iter().any(|factor| factor.unit == wrt) {
+            return Some(DynQuantity::new(0.0, unit));
+        }
+
+        // Chain rule: d/dx outer(y(x), x) = ∂outer/∂y * dy/dx + ∂outer/∂x
+        let intermediate = self.inner.call(influencing_factors);
+        let factors = Self::inject(intermediate, influencing_factors);
+        let douter_dy = self.outer.derivative(&factors, intermediate.unit)?.value;
+        let dy_dx = self.inner.derivative(influencing_factors, wrt)?.value;
+        let mut value = douter_dy * dy_dx;
+
+        // If wrt is the unit of the intermediate quantity, the corresponding
+        // influencing factor has been replaced and the outer function does not
+        // depend on it directly.
+        if wrt != intermediate.unit {
+            value += self.outer.derivative(&factors, wrt)?.value;
+        }
+        return Some(DynQuantity::new(value, unit));
+    }
+
+    fn call_dual(&self, influencing_factors: &[DualQuantity]) -> DualQuantity {
+        let intermediate = self.inner.call_dual(influencing_factors);
+        let unit = intermediate.value.unit;
+        let mut factors = Vec::with_capacity(influencing_factors.len() + 1);
+        factors.push(intermediate);
+        factors.extend(
+            influencing_factors
+                .iter()
+                .filter(|factor| factor.value.unit != unit)
+                .cloned(),
+        );
+        return self.outer.call_dual(&factors);
+    }
+}
+
+impl Clone for Compose {
+    fn clone(&self) -> Self {
+        return Self {
+            outer: dyn_clone::clone_box(&*self.outer),
+            inner: dyn_clone::clone_box(&*self.inner),
+            output_name: self.output_name.clone(),
+        };
+    }
+}
+
+impl std::fmt::Debug for Compose {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Compose")
+            .field("output_unit", &self.outer.output_unit())
+            .field("output_name", &self.output_name)
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(feature = "serde")]
+mod serde_impl {
+    use super::*;
+
+    use serde::de::{Deserialize, Deserializer};
+
+    impl<'de> Deserialize<'de> for Compose {
+        fn deserialize<D>(deserializer: D) -> Result<Compose, D::Error>
+        where
+            D: Deserializer<'de>,
+        {
+            #[derive(serde::Deserialize)]
+            struct ComposeAlias {
+                outer: Box<dyn QuantityFunction>,
+                inner: Box<dyn QuantityFunction>,
+                #[serde(default)]
+                output_name: Option<String>,
+            }
+
+            let alias = ComposeAlias::deserialize(deserializer)?;
+            let mut compose =
+                Self::new(alias.outer, alias.inner).map_err(serde::de::Error::custom)?;
+            compose.output_name = alias.output_name;
+            return Ok(compose);
+        }
+    }
+}
+
+// =============================================================================
+
+#[cfg(feature = "serde")]
+#[cfg_attr(feature = "serde", typetag::serde)]
+impl QuantityFunction for crate::ClampedQuantity<Compose> {
+    fn call(&self, influencing_factors: &[DynQuantity<f64>]) -> DynQuantity<f64> {
+        return self.call_clamped(influencing_factors);
+    }
+
+    fn output_unit(&self) -> Unit {
+        return self.inner().output_unit();
+    }
+
+    fn influencing_units(&self) -> Option<Vec<Unit>> {
+        return self.inner().influencing_units();
+    }
+
+    fn validity_ranges(&self) -> Vec<ValidityRange> {
+        return self.inner().validity_ranges();
+    }
+
+    fn call_named(&self, influencing_factors: &[InfluencingFactor]) -> DynQuantity<f64> {
+        return self.call_named_clamped(influencing_factors);
+    }
+
+    fn try_call(
+        &self,
+        influencing_factors: &[DynQuantity<f64>],
+    ) -> Result<DynQuantity<f64>, EvaluationError> {
+        return self.try_call_clamped(influencing_factors);
+    }
+
+    fn try_call_named(
+        &self,
+        influencing_factors: &[InfluencingFactor],
+    ) -> Result<DynQuantity<f64>, EvaluationError> {
+        return self.try_call_named_clamped(influencing_factors);
+    }
+
+    fn derivative(
+        &self,
+        influencing_factors: &[DynQuantity<f64>],
+        wrt: Unit,
+    ) -> Option<DynQuantity<f64>> {
+        return self.derivative_clamped(influencing_factors, wrt);
+    }
+
+    fn call_dual(&self, influencing_factors: &[DualQuantity]) -> DualQuantity {
+        return self.call_dual_clamped(influencing_factors);
+    }
+
+    fn call_batch(&self, inputs: &[BatchInput], output: &mut [f64]) {
+        self.call_batch_clamped(inputs, output);
+    }
+
+    fn bind(&self, layout: &crate::Layout) -> Box<dyn crate::Evaluator + '_> {
+        return self.bind_clamped(layout);
+    }
+}
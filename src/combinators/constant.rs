@@ -0,0 +1,153 @@
+/*!
+A [`Constant`] function which implements [`QuantityFunction`].
+*/
+
+use dyn_quantity::{DynQuantity, Unit};
+
+use crate::{BatchInput, DualQuantity, Evaluator, Layout, QuantityFunction};
+
+/**
+A function which always returns the same `value`, regardless of the
+influencing factors.
+
+For a standalone constant quantity, [`VarQuantity::Constant`](crate::VarQuantity::Constant)
+should be used instead, since it avoids the dynamic dispatch. This struct is
+meant as a building block for the other combinators, e.g. to add a constant
+offset to a function via [`Sum`](super::Sum). It is also used when combining
+[`VarQuantity`](crate::VarQuantity) instances with arithmetic operators.
+
+# Examples
+
+```
+use std::str::FromStr;
+use dyn_quantity::DynQuantity;
+use var_quantity::{QuantityFunction, combinators::Constant};
+
+let q = |s: &str| DynQuantity::<f64>::from_str(s).unwrap();
+
+let constant = Constant::new(q("2 ohm"));
+assert_eq!(constant.call(&[q("300 K")]), q("2 ohm"));
+assert_eq!(constant.derivative(&[q("300 K")], q("1 K").unit), Some(q("0 ohm/K")));
+```
+
+# Features:
+This struct can be serialized / deserialized if the `serde` feature is enabled.
+*/
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Constant {
+    value: DynQuantity<f64>,
+}
+
+impl Constant {
+    /**
+    Creates a new instance of [`Constant`].
+     */
+    pub fn new(value: DynQuantity<f64>) -> Self {
+        return Self { value };
+    }
+
+    /**
+    Returns the `value`.
+     */
+    pub fn value(&self) -> &DynQuantity<f64> {
+        return &self.value;
+    }
+}
+
+#[cfg_attr(feature = "serde", typetag::serde)]
+impl QuantityFunction for Constant {
+    fn call(&self, _influencing_factors: &[DynQuantity<f64>]) -> DynQuantity<f64> {
+        return self.value;
+    }
+
+    fn call_batch(&self, inputs: &[BatchInput], output: &mut [f64]) {
+        crate::batch::assert_lengths(inputs, output.len());
+        output.fill(self.value.value);
+    }
+
+    fn bind(&self, _layout: &Layout) -> Box<dyn Evaluator + '_> {
+        let value = self.value.value;
+        return Box::new(move |_: &[f64]| value);
+    }
+
+    fn output_unit(&self) -> Unit {
+        return self.value.unit;
+    }
+
+    fn influencing_units(&self) -> Option<Vec<Unit>> {
+        return Some(Vec::new());
+    }
+
+    fn derivative(
+        &self,
+        _influencing_factors: &[DynQuantity<f64>],
+        wrt: Unit,
+    ) -> Option<DynQuantity<f64>> {
+        return Some(DynQuantity::new(0.0, self.value.unit / wrt));
+    }
+
+    fn call_dual(&self, _influencing_factors: &[DualQuantity]) -> DualQuantity {
+        return DualQuantity::constant(self.value);
+    }
+}
+
+// =============================================================================
+
+#[cfg(feature = "serde")]
+#[cfg_attr(feature = "serde", typetag::serde)]
+impl QuantityFunction for crate::ClampedQuantity<Constant> {
+    fn call(&self, influencing_factors: &[DynQuantity<f64>]) -> DynQuantity<f64> {
+        return self.call_clamped(influencing_factors);
+    }
+
+    fn output_unit(&self) -> Unit {
+        return self.inner().output_unit();
+    }
+
+    fn influencing_units(&self) -> Option<Vec<Unit>> {
+        return self.inner().influencing_units();
+    }
+
+    fn validity_ranges(&self) -> Vec<crate::validity::ValidityRange> {
+        return self.inner().validity_ranges();
+    }
+
+    fn call_named(&self, influencing_factors: &[crate::InfluencingFactor]) -> DynQuantity<f64> {
+        return self.call_named_clamped(influencing_factors);
+    }
+
+    fn try_call(
+        &self,
+        influencing_factors: &[DynQuantity<f64>],
+    ) -> Result<DynQuantity<f64>, crate::EvaluationError> {
+        return self.try_call_clamped(influencing_factors);
+    }
+
+    fn try_call_named(
+        &self,
+        influencing_factors: &[crate::InfluencingFactor],
+    ) -> Result<DynQuantity<f64>, crate::EvaluationError> {
+        return self.try_call_named_clamped(influencing_factors);
+    }
+
+    fn derivative(
+        &self,
+        influencing_factors: &[DynQuantity<f64>],
+        wrt: Unit,
+    ) -> Option<DynQuantity<f64>> {
+        return self.derivative_clamped(influencing_factors, wrt);
+    }
+
+    fn call_dual(&self, influencing_factors: &[DualQuantity]) -> DualQuantity {
+        return self.call_dual_clamped(influencing_factors);
+    }
+
+    fn call_batch(&self, inputs: &[BatchInput], output: &mut [f64]) {
+        self.call_batch_clamped(inputs, output);
+    }
+
+    fn bind(&self, layout: &Layout) -> Box<dyn Evaluator + '_> {
+        return self.bind_clamped(layout);
+    }
+}
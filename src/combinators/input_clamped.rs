@@ -0,0 +1,454 @@
+/*!
+A [`QuantityFunction`] trait object whose input is clamped, see [`InputClamped`].
+*/
+
+use std::borrow::Cow;
+
+use dyn_quantity::{DynQuantity, Unit};
+
+use crate::{
+    BatchInput, DualQuantity, EvaluationError, Evaluator, InfluencingFactor, Layout,
+    QuantityFunction, RangeError, validity::ValidityRange,
+};
+
+/**
+A wrapper around a [`QuantityFunction`] trait object which clamps the
+influencing factor with the unit of `lower_limit` / `upper_limit` before
+forwarding it to the function:
+
+`y = f(clamp(x, lower_limit, upper_limit))`
+
+In contrast to [`ClampedQuantity`](crate::ClampedQuantity), which clamps the
+output of a function, this holds the function flat outside of the given input
+range. For non-monotonic functions (e.g. a polynomial fitted to measurements
+within a certain temperature range), the two approaches are not equivalent.
+
+Only the first factor with a matching unit is clamped (the one which is read by
+the unary functions of this crate). If the wrapper is bound to a named input via
+[`InputClamped::with_input_name`], [`QuantityFunction::call_named`] clamps the
+factor with this name instead. All other factors are forwarded unchanged.
+
+# Examples
+
+```
+use std::str::FromStr;
+use dyn_quantity::DynQuantity;
+use var_quantity::{QuantityFunction, combinators::InputClamped, unary::Polynomial};
+
+let q = |s: &str| DynQuantity::<f64>::from_str(s).unwrap();
+
+// Parabola fitted between 200 K and 500 K
+let fit = Polynomial::new(vec![q("1e-5 ohm/K^2"), q("-0.007 ohm/K"), q("2 ohm")]).unwrap();
+let fun = InputClamped::new(Box::new(fit), q("200 K"), q("500 K")).expect("valid limits");
+
+assert_eq!(fun.call(&[q("350 K")]), q("0.775 ohm"));
+
+// The curve is held flat outside of the fitted range
+assert_eq!(fun.call(&[q("600 K")]), fun.call(&[q("500 K")]));
+assert_eq!(fun.call(&[q("0 K")]), fun.call(&[q("200 K")]));
+```
+
+# Features:
+This struct can be serialized / deserialized if the `serde` feature is enabled.
+The function is serialized as a trait object. The limits are checked during
+deserialization as well:
+
+```text
+InputClamped:
+  function:
+    Polynomial:
+      coefficients: [1e-5 ohm/K^2, -0.007 ohm/K, 2 ohm]
+  lower_limit: 200 K
+  upper_limit: 500 K
+```
+*/
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct InputClamped {
+    function: Box<dyn QuantityFunction>,
+    lower_limit: DynQuantity<f64>,
+    upper_limit: DynQuantity<f64>,
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    input_name: Option<String>,
+}
+
+impl InputClamped {
+    /**
+    Checks if `lower_limit` and `upper_limit` form a valid range (see
+    [`ValidityRange::new`]): They must have the same unit, must not be NaN and
+    `lower_limit` must not be larger than `upper_limit`. If this is the case, a
+    new instance of [`InputClamped`] is returned. Infinite limits can be used
+    to clamp only one side.
+
+    # Examples
+
+    ```
+    use std::str::FromStr;
+    use dyn_quantity::DynQuantity;
+    use var_quantity::{RangeError, combinators::InputClamped, unary::Linear};
+
+    let q = |s: &str| DynQuantity::<f64>::from_str(s).unwrap();
+    let lin = || Box::new(Linear::new(q("1 ohm/K"), q("0 ohm")));
+
+    assert!(InputClamped::new(lin(), q("200 K"), q("500 K")).is_ok());
+    assert!(matches!(
+        InputClamped::new(lin(), q("200 K"), q("500 A")),
+        Err(RangeError::UnitsNotEqual(_))
+    ));
+    assert!(matches!(
+        InputClamped::new(lin(), q("500 K"), q("200 K")),
+        Err(RangeError::LowerAboveUpper { .. })
+    ));
+    assert!(matches!(
+        InputClamped::new(lin(), DynQuantity::new(f64::NAN, q("1 K").unit), q("500 K")),
+        Err(RangeError::NotFinite(_))
+    ));
+    ```
+     */
+    pub fn new(
+        function: Box<dyn QuantityFunction>,
+        lower_limit: DynQuantity<f64>,
+        upper_limit: DynQuantity<f64>,
+    ) -> Result<Self, RangeError> {
+        ValidityRange::new(lower_limit, upper_limit)?;
+        return Ok(Self {
+            function,
+            lower_limit,
+            upper_limit,
+            input_name: None,
+        });
+    }
+
+    /**
+    Binds the wrapper to the influencing factor with the given name. When
+    evaluated via [`QuantityFunction::call_named`], only the factor with this
+    name (and the unit [`InputClamped::influencing_factor_unit`]) is clamped.
+    The name is ignored by [`QuantityFunction::call`], which still clamps the
+    first factor with a matching unit.
+     */
+    pub fn with_input_name<N: Into<String>>(mut self, name: N) -> Self {
+        self.input_name = Some(name.into());
+        return self;
+    }
+
+    /**
+    Returns the name of the influencing factor this wrapper is bound to, if
+    any. See [`InputClamped::with_input_name`].
+     */
+    pub fn input_name(&self) -> Option<&str> {
+        return self.input_name.as_deref();
+    }
+
+    /**
+    Returns the wrapped function.
+     */
+    pub fn function(&self) -> &dyn QuantityFunction {
+        return &*self.function;
+    }
+
+    /// Returns the lower limit.
+    pub fn lower_limit(&self) -> &DynQuantity<f64> {
+        return &self.lower_limit;
+    }
+
+    /// Returns the upper limit.
+    pub fn upper_limit(&self) -> &DynQuantity<f64> {
+        return &self.upper_limit;
+    }
+
+    /**
+    Returns the unit of the influencing factor which is clamped.
+     */
+    pub fn influencing_factor_unit(&self) -> Unit {
+        return self.lower_limit.unit;
+    }
+
+    /// Returns whether `value` is strictly outside of the limits.
+    fn is_outside(&self, value: f64) -> bool {
+        return value < self.lower_limit.value || value > self.upper_limit.value;
+    }
+
+    fn clamp(&self, mut quantity: DynQuantity<f64>) -> DynQuantity<f64> {
+        quantity.value = quantity
+            .value
+            .clamp(self.lower_limit.value, self.upper_limit.value);
+        return quantity;
+    }
+
+    /**
+    Clamps the first factor with the unit
+    [`InputClamped::influencing_factor_unit`]. The factors are only copied if
+    that factor is outside of the limits.
+     */
+    fn clamp_factors<'a>(
+        &self,
+        influencing_factors: &'a [DynQuantity<f64>],
+    ) -> Cow<'a, [DynQuantity<f64>]> {
+        let unit = self.influencing_factor_unit();
+        let Some(index) = influencing_factors
+            .iter()
+            .position(|factor| factor.unit == unit)
+        else {
+            return Cow::Borrowed(influencing_factors);
+        };
+        if !self.is_outside(influencing_factors[index].value) {
+            return Cow::Borrowed(influencing_factors);
+        }
+        let mut factors = influencing_factors.to_vec();
+        factors[index] = self.clamp(factors[index]);
+        return Cow::Owned(factors);
+    }
+
+    /**
+    Named counterpart to [`InputClamped::clamp_factors`], which respects
+    [`InputClamped::input_name`].
+     */
+    fn clamp_named_factors<'a>(
+        &self,
+        influencing_factors: &'a [InfluencingFactor],
+    ) -> Cow<'a, [InfluencingFactor]> {
+        let unit = self.influencing_factor_unit();
+        let name = self.input_name.as_deref();
+        let Some(index) = influencing_factors.iter().position(|factor| {
+            factor.quantity.unit == unit && name.is_none_or(|name| factor.name == name)
+        }) else {
+            return Cow::Borrowed(influencing_factors);
+        };
+        if !self.is_outside(influencing_factors[index].quantity.value) {
+            return Cow::Borrowed(influencing_factors);
+        }
+        let mut factors = influencing_factors.to_vec();
+        factors[index].quantity = self.clamp(factors[index].quantity);
+        return Cow::Owned(factors);
+    }
+}
+
+#[cfg_attr(feature = "serde", typetag::serde)]
+impl QuantityFunction for InputClamped {
+    fn call(&self, influencing_factors: &[DynQuantity<f64>]) -> DynQuantity<f64> {
+        return self.function.call(&self.clamp_factors(influencing_factors));
+    }
+
+    fn call_batch(&self, inputs: &[BatchInput], output: &mut [f64]) {
+        // Analogous to InputClamped::clamp_factors
+        let unit = self.influencing_factor_unit();
+        let Some(index) = inputs.iter().position(|input| input.unit == unit) else {
+            return self.function.call_batch(inputs, output);
+        };
+        if !inputs[index]
+            .values
+            .iter()
+            .any(|value| self.is_outside(*value))
+        {
+            return self.function.call_batch(inputs, output);
+        }
+        let clamped: Vec<f64> = inputs[index]
+            .values
+            .iter()
+            .map(|value| value.clamp(self.lower_limit.value, self.upper_limit.value))
+            .collect();
+        let mut clamped_inputs = inputs.to_vec();
+        clamped_inputs[index].values = &clamped;
+        self.function.call_batch(&clamped_inputs, output);
+    }
+
+    fn bind(&self, layout: &Layout) -> Box<dyn Evaluator + '_> {
+        let function = self.function.bind(layout);
+
+        // Analogous to InputClamped::clamp_factors and
+        // InputClamped::clamp_named_factors
+        let name = layout.names().and(self.input_name.as_deref());
+        let Some(index) = layout.position(self.influencing_factor_unit(), name) else {
+            return function;
+        };
+        return Box::new(move |values: &[f64]| {
+            if !self.is_outside(values[index]) {
+                return function.eval(values);
+            }
+            let mut clamped = values.to_vec();
+            clamped[index] = clamped[index].clamp(self.lower_limit.value, self.upper_limit.value);
+            function.eval(&clamped)
+        });
+    }
+
+    fn output_unit(&self) -> Unit {
+        return self.function.output_unit();
+    }
+
+    fn influencing_units(&self) -> Option<Vec<Unit>> {
+        return self.function.influencing_units();
+    }
+
+    fn validity_ranges(&self) -> Vec<ValidityRange> {
+        return self.function.validity_ranges();
+    }
+
+    fn call_named(&self, influencing_factors: &[InfluencingFactor]) -> DynQuantity<f64> {
+        return self
+            .function
+            .call_named(&self.clamp_named_factors(influencing_factors));
+    }
+
+    fn try_call(
+        &self,
+        influencing_factors: &[DynQuantity<f64>],
+    ) -> Result<DynQuantity<f64>, EvaluationError> {
+        return self
+            .function
+            .try_call(&self.clamp_factors(influencing_factors));
+    }
+
+    fn try_call_named(
+        &self,
+        influencing_factors: &[InfluencingFactor],
+    ) -> Result<DynQuantity<f64>, EvaluationError> {
+        return self
+            .function
+            .try_call_named(&self.clamp_named_factors(influencing_factors));
+    }
+
+    fn derivative(
+        &self,
+        influencing_factors: &[DynQuantity<f64>],
+        wrt: Unit,
+    ) -> Option<DynQuantity<f64>> {
+        // If the clamped factor is outside of the limits, the output does not
+        // depend on it
+        if wrt == self.influencing_factor_unit() {
+            let factor = influencing_factors.iter().find(|f| f.unit == wrt);
+            if factor.is_some_and(|factor| self.is_outside(factor.value)) {
+                return Some(DynQuantity::new(0.0, self.output_unit() / wrt));
+            }
+        }
+        return self
+            .function
+            .derivative(&self.clamp_factors(influencing_factors), wrt);
+    }
+
+    fn call_dual(&self, influencing_factors: &[DualQuantity]) -> DualQuantity {
+        let unit = self.influencing_factor_unit();
+        let index = influencing_factors
+            .iter()
+            .position(|f| f.value.unit == unit)
+            .filter(|index| self.is_outside(influencing_factors[*index].value.value));
+        let Some(index) = index else {
+            return self.function.call_dual(influencing_factors);
+        };
+        let mut factors = influencing_factors.to_vec();
+        factors[index] = DualQuantity::constant(self.clamp(factors[index].value));
+        return self.function.call_dual(&factors);
+    }
+}
+
+impl Clone for InputClamped {
+    fn clone(&self) -> Self {
+        return Self {
+            function: dyn_clone::clone_box(&*self.function),
+            lower_limit: self.lower_limit,
+            upper_limit: self.upper_limit,
+            input_name: self.input_name.clone(),
+        };
+    }
+}
+
+impl std::fmt::Debug for InputClamped {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("InputClamped")
+            .field("output_unit", &self.function.output_unit())
+            .field("lower_limit", &self.lower_limit)
+            .field("upper_limit", &self.upper_limit)
+            .field("input_name", &self.input_name)
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(feature = "serde")]
+mod serde_impl {
+    use super::*;
+
+    use serde::de::{Deserialize, Deserializer};
+
+    impl<'de> Deserialize<'de> for InputClamped {
+        fn deserialize<D>(deserializer: D) -> Result<InputClamped, D::Error>
+        where
+            D: Deserializer<'de>,
+        {
+            #[derive(serde::Deserialize)]
+            struct InputClampedAlias {
+                function: Box<dyn QuantityFunction>,
+                lower_limit: DynQuantity<f64>,
+                upper_limit: DynQuantity<f64>,
+                #[serde(default)]
+                input_name: Option<String>,
+            }
+
+            let alias = InputClampedAlias::deserialize(deserializer)?;
+            let mut clamped = Self::new(alias.function, alias.lower_limit, alias.upper_limit)
+                .map_err(serde::de::Error::custom)?;
+            clamped.input_name = alias.input_name;
+            return Ok(clamped);
+        }
+    }
+}
+
+// =============================================================================
+
+#[cfg(feature = "serde")]
+#[cfg_attr(feature = "serde", typetag::serde)]
+impl QuantityFunction for crate::ClampedQuantity<InputClamped> {
+    fn call(&self, influencing_factors: &[DynQuantity<f64>]) -> DynQuantity<f64> {
+        return self.call_clamped(influencing_factors);
+    }
+
+    fn output_unit(&self) -> Unit {
+        return self.inner().output_unit();
+    }
+
+    fn influencing_units(&self) -> Option<Vec<Unit>> {
+        return self.inner().influencing_units();
+    }
+
+    fn validity_ranges(&self) -> Vec<ValidityRange> {
+        return self.inner().validity_ranges();
+    }
+
+    fn call_named(&self, influencing_factors: &[InfluencingFactor]) -> DynQuantity<f64> {
+        return self.call_named_clamped(influencing_factors);
+    }
+
+    fn try_call(
+        &self,
+        influencing_factors: &[DynQuantity<f64>],
+    ) -> Result<DynQuantity<f64>, EvaluationError> {
+        return self.try_call_clamped(influencing_factors);
+    }
+
+    fn try_call_named(
+        &self,
+        influencing_factors: &[InfluencingFactor],
+    ) -> Result<DynQuantity<f64>, EvaluationError> {
+        return self.try_call_named_clamped(influencing_factors);
+    }
+
+    fn derivative(
+        &self,
+        influencing_factors: &[DynQuantity<f64>],
+        wrt: Unit,
+    ) -> Option<DynQuantity<f64>> {
+        return self.derivative_clamped(influencing_factors, wrt);
+    }
+
+    fn call_dual(&self, influencing_factors: &[DualQuantity]) -> DualQuantity {
+        return self.call_dual_clamped(influencing_factors);
+    }
+
+    fn call_batch(&self, inputs: &[BatchInput], output: &mut [f64]) {
+        self.call_batch_clamped(inputs, output);
+    }
+
+    fn bind(&self, layout: &crate::Layout) -> Box<dyn crate::Evaluator + '_> {
+        return self.bind_clamped(layout);
+    }
+}
@@ -0,0 +1,72 @@
+/*!
+This module contains combinators which build a new function out of other
+[`QuantityFunction`](crate::QuantityFunction) trait objects, e.g. a base curve
+multiplied by a correction factor. Since the combinators implement
+[`QuantityFunction`](crate::QuantityFunction) themselves, they can be nested
+arbitrarily. If the `serde` feature is enabled, they are serialized as nested
+trees of trait objects.
+
+The arithmetic operators of [`VarQuantity`](crate::VarQuantity) use these
+combinators to build composite quantities.
+*/
+
+pub mod clamped;
+pub mod compose;
+pub mod constant;
+pub mod input_clamped;
+pub mod product;
+pub mod quotient;
+pub mod scaled;
+pub mod sum;
+
+pub use clamped::Clamped;
+pub use compose::Compose;
+pub use constant::Constant;
+pub use input_clamped::InputClamped;
+pub use product::Product;
+pub use quotient::Quotient;
+pub use scaled::Scaled;
+pub use sum::Sum;
+
+use dyn_quantity::Unit;
+
+use crate::{QuantityFunction, validity::ValidityRange};
+
+/**
+Returns the units of the influencing factors of all `functions`, with each unit
+listed only once (in the order of first appearance). If any of the functions
+does not declare its influencing units, `None` is returned.
+ */
+fn union_of_units<'a, I>(functions: I) -> Option<Vec<Unit>>
+where
+    I: IntoIterator<Item = &'a Box<dyn QuantityFunction>>,
+{
+    let mut units: Vec<Unit> = Vec::new();
+    for function in functions {
+        for unit in function.influencing_units()? {
+            if !units.contains(&unit) {
+                units.push(unit);
+            }
+        }
+    }
+    return Some(units);
+}
+
+/**
+Returns the validity ranges of all `functions`, with identical ranges listed
+only once.
+ */
+fn validity_ranges_of<'a, I>(functions: I) -> Vec<ValidityRange>
+where
+    I: IntoIterator<Item = &'a Box<dyn QuantityFunction>>,
+{
+    let mut ranges: Vec<ValidityRange> = Vec::new();
+    for function in functions {
+        for range in function.validity_ranges() {
+            if !ranges.contains(&range) {
+                ranges.push(range);
+            }
+        }
+    }
+    return ranges;
+}
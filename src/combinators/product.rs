@@ -0,0 +1,299 @@
+/*!
+A [`Product`] of [`QuantityFunction`] trait objects.
+*/
+
+use dyn_quantity::{DynQuantity, Unit};
+
+use crate::{
+    BatchInput, DualQuantity, EvaluationError, Evaluator, InfluencingFactor, Layout,
+    QuantityFunction, validity::ValidityRange,
+};
+
+/**
+The product of multiple [`QuantityFunction`] trait objects:
+
+`y = f_1(x) * f_2(x) * ... * f_n(x)`
+
+The output unit is the product of the output units of all functions. The
+product of an empty list of functions is a dimensionless one.
+
+# Examples
+
+```
+use std::str::FromStr;
+use dyn_quantity::DynQuantity;
+use var_quantity::{QuantityFunction, combinators::Product, unary::Linear};
+
+let q = |s: &str| DynQuantity::<f64>::from_str(s).unwrap();
+
+// Resistivity depending on the temperature times a dimensionless correction
+// factor depending on the frequency (e.g. due to the skin effect)
+let resistivity = Linear::new(q("1e-10 ohm*m/K"), q("1.7e-8 ohm*m"));
+let skin_effect = Linear::new(q("0.001 1/Hz"), q("1"));
+let product = Product::new(vec![Box::new(resistivity), Box::new(skin_effect)]);
+assert_eq!(product.output_unit(), q("1 ohm*m").unit);
+
+let rho = product.call(&[q("100 K"), q("1000 Hz")]);
+approx::assert_abs_diff_eq!(rho.value, 2.7e-8 * 2.0, epsilon = 1e-20);
+```
+
+# Features:
+This struct can be serialized / deserialized if the `serde` feature is enabled.
+The functions are serialized as a list of trait objects, see [`Sum`](super::Sum).
+*/
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct Product {
+    functions: Vec<Box<dyn QuantityFunction>>,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    output_unit: Unit,
+}
+
+impl Product {
+    /**
+    Creates a new instance of [`Product`].
+     */
+    pub fn new(functions: Vec<Box<dyn QuantityFunction>>) -> Self {
+        let output_unit = functions.iter().fold(Unit::default(), |unit, function| {
+            unit * function.output_unit()
+        });
+        return Self {
+            functions,
+            output_unit,
+        };
+    }
+
+    /**
+    Returns the factors.
+     */
+    pub fn functions(&self) -> &[Box<dyn QuantityFunction>] {
+        return self.functions.as_slice();
+    }
+
+    /**
+    Returns the unit which will be returned from [`QuantityFunction::call`].
+     */
+    pub fn output_unit(&self) -> Unit {
+        return self.output_unit;
+    }
+}
+
+/// Multiplies the given factors.
+fn product<I: IntoIterator<Item = DynQuantity<f64>>>(factors: I) -> DynQuantity<f64> {
+    return factors
+        .into_iter()
+        .fold(DynQuantity::from(1.0), |product, factor| product * factor);
+}
+
+#[cfg_attr(feature = "serde", typetag::serde)]
+impl QuantityFunction for Product {
+    fn call(&self, influencing_factors: &[DynQuantity<f64>]) -> DynQuantity<f64> {
+        return product(
+            self.functions
+                .iter()
+                .map(|function| function.call(influencing_factors)),
+        );
+    }
+
+    fn call_batch(&self, inputs: &[BatchInput], output: &mut [f64]) {
+        crate::batch::assert_lengths(inputs, output.len());
+        output.fill(1.0);
+        let mut buffer = vec![0.0; output.len()];
+        for function in self.functions.iter() {
+            function.call_batch(inputs, &mut buffer);
+            for (out, value) in output.iter_mut().zip(buffer.iter()) {
+                *out *= value;
+            }
+        }
+    }
+
+    fn bind(&self, layout: &Layout) -> Box<dyn Evaluator + '_> {
+        let evaluators: Vec<_> = self.functions.iter().map(|f| f.bind(layout)).collect();
+        return Box::new(move |values: &[f64]| {
+            evaluators.iter().map(|e| e.eval(values)).product::<f64>()
+        });
+    }
+
+    fn output_unit(&self) -> Unit {
+        return Product::output_unit(self);
+    }
+
+    fn influencing_units(&self) -> Option<Vec<Unit>> {
+        return super::union_of_units(&self.functions);
+    }
+
+    fn validity_ranges(&self) -> Vec<ValidityRange> {
+        return super::validity_ranges_of(&self.functions);
+    }
+
+    fn call_named(&self, influencing_factors: &[InfluencingFactor]) -> DynQuantity<f64> {
+        return product(
+            self.functions
+                .iter()
+                .map(|function| function.call_named(influencing_factors)),
+        );
+    }
+
+    fn try_call(
+        &self,
+        influencing_factors: &[DynQuantity<f64>],
+    ) -> Result<DynQuantity<f64>, EvaluationError> {
+        let mut factors = Vec::with_capacity(self.functions.len());
+        for function in self.functions.iter() {
+            factors.push(function.try_call(influencing_factors)?);
+        }
+        return Ok(product(factors));
+    }
+
+    fn try_call_named(
+        &self,
+        influencing_factors: &[InfluencingFactor],
+    ) -> Result<DynQuantity<f64>, EvaluationError> {
+        let mut factors = Vec::with_capacity(self.functions.len());
+        for function in self.functions.iter() {
+            factors.push(function.try_call_named(influencing_factors)?);
+        }
+        return Ok(product(factors));
+    }
+
+    fn derivative(
+        &self,
+        influencing_factors: &[DynQuantity<f64>],
+        wrt: Unit,
+    ) -> Option<DynQuantity<f64>> {
+        let mut derivative = DynQuantity::new(0.0, Product::output_unit(self) / wrt);
+        if !influencing_factors.iter().any(|factor| factor.unit == wrt) {
+            return Some(derivative);
+        }
+
+        // Product rule: Sum over the derivative of each factor multiplied with
+        // all other factors
+        let values: Vec<f64> = self
+            .functions
+            .iter()
+            .map(|function| function.call(influencing_factors).value)
+            .collect();
+        for (i, function) in self.functions.iter().enumerate() {
+            let mut term = function.derivative(influencing_factors, wrt)?.value;
+            for (j, value) in values.iter().enumerate() {
+                if i != j {
+                    term *= value;
+                }
+            }
+            derivative.value += term;
+        }
+        return Some(derivative);
+    }
+
+    fn call_dual(&self, influencing_factors: &[DualQuantity]) -> DualQuantity {
+        return self
+            .functions
+            .iter()
+            .fold(DualQuantity::constant(1.0), |product, function| {
+                product * function.call_dual(influencing_factors)
+            });
+    }
+}
+
+impl Clone for Product {
+    fn clone(&self) -> Self {
+        return Self {
+            functions: self
+                .functions
+                .iter()
+                .map(|function| dyn_clone::clone_box(&**function))
+                .collect(),
+            output_unit: self.output_unit,
+        };
+    }
+}
+
+impl std::fmt::Debug for Product {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Product")
+            .field("output_unit", &self.output_unit)
+            .field("functions", &self.functions.len())
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(feature = "serde")]
+mod serde_impl {
+    use super::*;
+
+    use serde::de::{Deserialize, Deserializer};
+
+    impl<'de> Deserialize<'de> for Product {
+        fn deserialize<D>(deserializer: D) -> Result<Product, D::Error>
+        where
+            D: Deserializer<'de>,
+        {
+            #[derive(serde::Deserialize)]
+            struct ProductAlias {
+                functions: Vec<Box<dyn QuantityFunction>>,
+            }
+
+            let alias = ProductAlias::deserialize(deserializer)?;
+            return Ok(Self::new(alias.functions));
+        }
+    }
+}
+
+// =============================================================================
+
+#[cfg(feature = "serde")]
+#[cfg_attr(feature = "serde", typetag::serde)]
+impl QuantityFunction for crate::ClampedQuantity<Product> {
+    fn call(&self, influencing_factors: &[DynQuantity<f64>]) -> DynQuantity<f64> {
+        return self.call_clamped(influencing_factors);
+    }
+
+    fn output_unit(&self) -> Unit {
+        return self.inner().output_unit();
+    }
+
+    fn influencing_units(&self) -> Option<Vec<Unit>> {
+        return self.inner().influencing_units();
+    }
+
+    fn validity_ranges(&self) -> Vec<ValidityRange> {
+        return self.inner().validity_ranges();
+    }
+
+    fn call_named(&self, influencing_factors: &[InfluencingFactor]) -> DynQuantity<f64> {
+        return self.call_named_clamped(influencing_factors);
+    }
+
+    fn try_call(
+        &self,
+        influencing_factors: &[DynQuantity<f64>],
+    ) -> Result<DynQuantity<f64>, EvaluationError> {
+        return self.try_call_clamped(influencing_factors);
+    }
+
+    fn try_call_named(
+        &self,
+        influencing_factors: &[InfluencingFactor],
+    ) -> Result<DynQuantity<f64>, EvaluationError> {
+        return self.try_call_named_clamped(influencing_factors);
+    }
+
+    fn derivative(
+        &self,
+        influencing_factors: &[DynQuantity<f64>],
+        wrt: Unit,
+    ) -> Option<DynQuantity<f64>> {
+        return self.derivative_clamped(influencing_factors, wrt);
+    }
+
+    fn call_dual(&self, influencing_factors: &[DualQuantity]) -> DualQuantity {
+        return self.call_dual_clamped(influencing_factors);
+    }
+
+    fn call_batch(&self, inputs: &[BatchInput], output: &mut [f64]) {
+        self.call_batch_clamped(inputs, output);
+    }
+
+    fn bind(&self, layout: &Layout) -> Box<dyn Evaluator + '_> {
+        return self.bind_clamped(layout);
+    }
+}
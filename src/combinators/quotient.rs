@@ -0,0 +1,238 @@
+/*!
+A [`Quotient`] of two [`QuantityFunction`] trait objects.
+*/
+
+use dyn_quantity::{DynQuantity, Unit};
+
+use crate::{
+    BatchInput, DualQuantity, EvaluationError, Evaluator, InfluencingFactor, Layout,
+    QuantityFunction, validity::ValidityRange,
+};
+
+/**
+The quotient of two [`QuantityFunction`] trait objects:
+
+`y = numerator(x) / denominator(x)`
+
+The output unit is the output unit of the numerator divided by that of the
+denominator.
+
+# Examples
+
+```
+use std::str::FromStr;
+use dyn_quantity::DynQuantity;
+use var_quantity::{QuantityFunction, combinators::Quotient, unary::Linear};
+
+let q = |s: &str| DynQuantity::<f64>::from_str(s).unwrap();
+
+// Current density: Current depending on the time divided by an area depending
+// on the temperature (thermal expansion)
+let current = Linear::new(q("2 A/s"), q("0 A"));
+let area = Linear::new(q("0 m^2/K"), q("0.5 m^2"));
+let density = Quotient::new(Box::new(current), Box::new(area));
+assert_eq!(density.output_unit(), q("1 A/m^2").unit);
+assert_eq!(density.call(&[q("3 s"), q("300 K")]), q("12 A/m^2"));
+```
+
+# Features:
+This struct can be serialized / deserialized if the `serde` feature is enabled.
+The numerator and the denominator are serialized as trait objects.
+*/
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Quotient {
+    numerator: Box<dyn QuantityFunction>,
+    denominator: Box<dyn QuantityFunction>,
+}
+
+impl Quotient {
+    /**
+    Creates a new instance of [`Quotient`].
+     */
+    pub fn new(
+        numerator: Box<dyn QuantityFunction>,
+        denominator: Box<dyn QuantityFunction>,
+    ) -> Self {
+        return Self {
+            numerator,
+            denominator,
+        };
+    }
+
+    /**
+    Returns the numerator.
+     */
+    pub fn numerator(&self) -> &dyn QuantityFunction {
+        return &*self.numerator;
+    }
+
+    /**
+    Returns the denominator.
+     */
+    pub fn denominator(&self) -> &dyn QuantityFunction {
+        return &*self.denominator;
+    }
+
+    /**
+    Returns the unit which will be returned from [`QuantityFunction::call`].
+     */
+    pub fn output_unit(&self) -> Unit {
+        return self.numerator.output_unit() / self.denominator.output_unit();
+    }
+}
+
+#[cfg_attr(feature = "serde", typetag::serde)]
+impl QuantityFunction for Quotient {
+    fn call(&self, influencing_factors: &[DynQuantity<f64>]) -> DynQuantity<f64> {
+        return self.numerator.call(influencing_factors)
+            / self.denominator.call(influencing_factors);
+    }
+
+    fn call_batch(&self, inputs: &[BatchInput], output: &mut [f64]) {
+        self.numerator.call_batch(inputs, output);
+        let mut denominator = vec![0.0; output.len()];
+        self.denominator.call_batch(inputs, &mut denominator);
+        for (out, value) in output.iter_mut().zip(denominator.iter()) {
+            *out /= value;
+        }
+    }
+
+    fn bind(&self, layout: &Layout) -> Box<dyn Evaluator + '_> {
+        let numerator = self.numerator.bind(layout);
+        let denominator = self.denominator.bind(layout);
+        return Box::new(move |values: &[f64]| numerator.eval(values) / denominator.eval(values));
+    }
+
+    fn output_unit(&self) -> Unit {
+        return Quotient::output_unit(self);
+    }
+
+    fn influencing_units(&self) -> Option<Vec<Unit>> {
+        return super::union_of_units([&self.numerator, &self.denominator]);
+    }
+
+    fn validity_ranges(&self) -> Vec<ValidityRange> {
+        return super::validity_ranges_of([&self.numerator, &self.denominator]);
+    }
+
+    fn call_named(&self, influencing_factors: &[InfluencingFactor]) -> DynQuantity<f64> {
+        return self.numerator.call_named(influencing_factors)
+            / self.denominator.call_named(influencing_factors);
+    }
+
+    fn try_call(
+        &self,
+        influencing_factors: &[DynQuantity<f64>],
+    ) -> Result<DynQuantity<f64>, EvaluationError> {
+        return Ok(self.numerator.try_call(influencing_factors)?
+            / self.denominator.try_call(influencing_factors)?);
+    }
+
+    fn try_call_named(
+        &self,
+        influencing_factors: &[InfluencingFactor],
+    ) -> Result<DynQuantity<f64>, EvaluationError> {
+        return Ok(self.numerator.try_call_named(influencing_factors)?
+            / self.denominator.try_call_named(influencing_factors)?);
+    }
+
+    fn derivative(
+        &self,
+        influencing_factors: &[DynQuantity<f64>],
+        wrt: Unit,
+    ) -> Option<DynQuantity<f64>> {
+        let unit = Quotient::output_unit(self) / wrt;
+        if !influencing_factors.iter().any(|factor| factor.unit == wrt) {
+            return Some(DynQuantity::new(0.0, unit));
+        }
+
+        // Quotient rule: (u/v)' = (u' v - u v') / v²
+        let u = self.numerator.call(influencing_factors).value;
+        let v = self.denominator.call(influencing_factors).value;
+        let du = self.numerator.derivative(influencing_factors, wrt)?.value;
+        let dv = self.denominator.derivative(influencing_factors, wrt)?.value;
+        return Some(DynQuantity::new((du * v - u * dv) / (v * v), unit));
+    }
+
+    fn call_dual(&self, influencing_factors: &[DualQuantity]) -> DualQuantity {
+        return self.numerator.call_dual(influencing_factors)
+            / self.denominator.call_dual(influencing_factors);
+    }
+}
+
+impl Clone for Quotient {
+    fn clone(&self) -> Self {
+        return Self {
+            numerator: dyn_clone::clone_box(&*self.numerator),
+            denominator: dyn_clone::clone_box(&*self.denominator),
+        };
+    }
+}
+
+impl std::fmt::Debug for Quotient {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Quotient")
+            .field("output_unit", &self.output_unit())
+            .finish_non_exhaustive()
+    }
+}
+
+// =============================================================================
+
+#[cfg(feature = "serde")]
+#[cfg_attr(feature = "serde", typetag::serde)]
+impl QuantityFunction for crate::ClampedQuantity<Quotient> {
+    fn call(&self, influencing_factors: &[DynQuantity<f64>]) -> DynQuantity<f64> {
+        return self.call_clamped(influencing_factors);
+    }
+
+    fn output_unit(&self) -> Unit {
+        return self.inner().output_unit();
+    }
+
+    fn influencing_units(&self) -> Option<Vec<Unit>> {
+        return self.inner().influencing_units();
+    }
+
+    fn validity_ranges(&self) -> Vec<ValidityRange> {
+        return self.inner().validity_ranges();
+    }
+
+    fn call_named(&self, influencing_factors: &[InfluencingFactor]) -> DynQuantity<f64> {
+        return self.call_named_clamped(influencing_factors);
+    }
+
+    fn try_call(
+        &self,
+        influencing_factors: &[DynQuantity<f64>],
+    ) -> Result<DynQuantity<f64>, EvaluationError> {
+        return self.try_call_clamped(influencing_factors);
+    }
+
+    fn try_call_named(
+        &self,
+        influencing_factors: &[InfluencingFactor],
+    ) -> Result<DynQuantity<f64>, EvaluationError> {
+        return self.try_call_named_clamped(influencing_factors);
+    }
+
+    fn derivative(
+        &self,
+        influencing_factors: &[DynQuantity<f64>],
+        wrt: Unit,
+    ) -> Option<DynQuantity<f64>> {
+        return self.derivative_clamped(influencing_factors, wrt);
+    }
+
+    fn call_dual(&self, influencing_factors: &[DualQuantity]) -> DualQuantity {
+        return self.call_dual_clamped(influencing_factors);
+    }
+
+    fn call_batch(&self, inputs: &[BatchInput], output: &mut [f64]) {
+        self.call_batch_clamped(inputs, output);
+    }
+
+    fn bind(&self, layout: &Layout) -> Box<dyn Evaluator + '_> {
+        return self.bind_clamped(layout);
+    }
+}
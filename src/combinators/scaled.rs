@@ -0,0 +1,219 @@
+/*!
+A [`QuantityFunction`] trait object [`Scaled`] by a constant factor.
+*/
+
+use dyn_quantity::{DynQuantity, Unit};
+
+use crate::{
+    BatchInput, DualQuantity, EvaluationError, Evaluator, InfluencingFactor, Layout,
+    QuantityFunction, validity::ValidityRange,
+};
+
+/**
+A [`QuantityFunction`] trait object multiplied by a constant `factor`:
+
+`y = factor * f(x)`
+
+The output unit is the unit of `factor` multiplied with the output unit of
+the function. This is e.g. useful to convert a specific quantity into an
+absolute one (e.g. specific losses in W/kg into losses in W) or to apply a
+constant correction factor.
+
+# Examples
+
+```
+use std::str::FromStr;
+use dyn_quantity::DynQuantity;
+use var_quantity::{QuantityFunction, combinators::Scaled, unary::Linear};
+
+let q = |s: &str| DynQuantity::<f64>::from_str(s).unwrap();
+
+let specific_losses = Linear::new(q("0.01 W/(kg*Hz)"), q("0 W/kg"));
+let losses = Scaled::new(q("20 kg"), Box::new(specific_losses));
+assert_eq!(losses.output_unit(), q("1 W").unit);
+assert_eq!(losses.call(&[q("50 Hz")]), q("10 W"));
+```
+
+# Features:
+This struct can be serialized / deserialized if the `serde` feature is enabled.
+The function is serialized as a trait object.
+*/
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Scaled {
+    factor: DynQuantity<f64>,
+    function: Box<dyn QuantityFunction>,
+}
+
+impl Scaled {
+    /**
+    Creates a new instance of [`Scaled`].
+     */
+    pub fn new(factor: DynQuantity<f64>, function: Box<dyn QuantityFunction>) -> Self {
+        return Self { factor, function };
+    }
+
+    /**
+    Returns the constant `factor`.
+     */
+    pub fn factor(&self) -> &DynQuantity<f64> {
+        return &self.factor;
+    }
+
+    /**
+    Returns the scaled function.
+     */
+    pub fn function(&self) -> &dyn QuantityFunction {
+        return &*self.function;
+    }
+
+    /**
+    Returns the unit which will be returned from [`QuantityFunction::call`].
+     */
+    pub fn output_unit(&self) -> Unit {
+        return self.factor.unit * self.function.output_unit();
+    }
+}
+
+#[cfg_attr(feature = "serde", typetag::serde)]
+impl QuantityFunction for Scaled {
+    fn call(&self, influencing_factors: &[DynQuantity<f64>]) -> DynQuantity<f64> {
+        return self.factor * self.function.call(influencing_factors);
+    }
+
+    fn call_batch(&self, inputs: &[BatchInput], output: &mut [f64]) {
+        self.function.call_batch(inputs, output);
+        for out in output.iter_mut() {
+            *out *= self.factor.value;
+        }
+    }
+
+    fn bind(&self, layout: &Layout) -> Box<dyn Evaluator + '_> {
+        let evaluator = self.function.bind(layout);
+        return Box::new(move |values: &[f64]| self.factor.value * evaluator.eval(values));
+    }
+
+    fn output_unit(&self) -> Unit {
+        return Scaled::output_unit(self);
+    }
+
+    fn influencing_units(&self) -> Option<Vec<Unit>> {
+        return self.function.influencing_units();
+    }
+
+    fn validity_ranges(&self) -> Vec<ValidityRange> {
+        return self.function.validity_ranges();
+    }
+
+    fn call_named(&self, influencing_factors: &[InfluencingFactor]) -> DynQuantity<f64> {
+        return self.factor * self.function.call_named(influencing_factors);
+    }
+
+    fn try_call(
+        &self,
+        influencing_factors: &[DynQuantity<f64>],
+    ) -> Result<DynQuantity<f64>, EvaluationError> {
+        return Ok(self.factor * self.function.try_call(influencing_factors)?);
+    }
+
+    fn try_call_named(
+        &self,
+        influencing_factors: &[InfluencingFactor],
+    ) -> Result<DynQuantity<f64>, EvaluationError> {
+        return Ok(self.factor * self.function.try_call_named(influencing_factors)?);
+    }
+
+    fn derivative(
+        &self,
+        influencing_factors: &[DynQuantity<f64>],
+        wrt: Unit,
+    ) -> Option<DynQuantity<f64>> {
+        let unit = Scaled::output_unit(self) / wrt;
+        if !influencing_factors.iter().any(|factor| factor.unit == wrt) {
+            return Some(DynQuantity::new(0.0, unit));
+        }
+        let derivative = self.function.derivative(influencing_factors, wrt)?;
+        return Some(DynQuantity::new(self.factor.value * derivative.value, unit));
+    }
+
+    fn call_dual(&self, influencing_factors: &[DualQuantity]) -> DualQuantity {
+        return DualQuantity::constant(self.factor) * self.function.call_dual(influencing_factors);
+    }
+}
+
+impl Clone for Scaled {
+    fn clone(&self) -> Self {
+        return Self {
+            factor: self.factor,
+            function: dyn_clone::clone_box(&*self.function),
+        };
+    }
+}
+
+impl std::fmt::Debug for Scaled {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Scaled")
+            .field("output_unit", &self.output_unit())
+            .field("factor", &self.factor)
+            .finish_non_exhaustive()
+    }
+}
+
+// =============================================================================
+
+#[cfg(feature = "serde")]
+#[cfg_attr(feature = "serde", typetag::serde)]
+impl QuantityFunction for crate::ClampedQuantity<Scaled> {
+    fn call(&self, influencing_factors: &[DynQuantity<f64>]) -> DynQuantity<f64> {
+        return self.call_clamped(influencing_factors);
+    }
+
+    fn output_unit(&self) -> Unit {
+        return self.inner().output_unit();
+    }
+
+    fn influencing_units(&self) -> Option<Vec<Unit>> {
+        return self.inner().influencing_units();
+    }
+
+    fn validity_ranges(&self) -> Vec<ValidityRange> {
+        return self.inner().validity_ranges();
+    }
+
+    fn call_named(&self, influencing_factors: &[InfluencingFactor]) -> DynQuantity<f64> {
+        return self.call_named_clamped(influencing_factors);
+    }
+
+    fn try_call(
+        &self,
+        influencing_factors: &[DynQuantity<f64>],
+    ) -> Result<DynQuantity<f64>, EvaluationError> {
+        return self.try_call_clamped(influencing_factors);
+    }
+
+    fn try_call_named(
+        &self,
+        influencing_factors: &[InfluencingFactor],
+    ) -> Result<DynQuantity<f64>, EvaluationError> {
+        return self.try_call_named_clamped(influencing_factors);
+    }
+
+    fn derivative(
+        &self,
+        influencing_factors: &[DynQuantity<f64>],
+        wrt: Unit,
+    ) -> Option<DynQuantity<f64>> {
+        return self.derivative_clamped(influencing_factors, wrt);
+    }
+
+    fn call_dual(&self, influencing_factors: &[DualQuantity]) -> DualQuantity {
+        return self.call_dual_clamped(influencing_factors);
+    }
+
+    fn call_batch(&self, inputs: &[BatchInput], output: &mut [f64]) {
+        self.call_batch_clamped(inputs, output);
+    }
+
+    fn bind(&self, layout: &Layout) -> Box<dyn Evaluator + '_> {
+        return self.bind_clamped(layout);
+    }
+}
@@ -0,0 +1,308 @@
+/*!
+A [`Sum`] of [`QuantityFunction`] trait objects.
+*/
+
+use dyn_quantity::{DynQuantity, Unit, UnitsNotEqual};
+
+use crate::{
+    BatchInput, DualQuantity, EvaluationError, Evaluator, InfluencingFactor, Layout,
+    QuantityFunction, validity::ValidityRange,
+};
+
+/**
+The sum of multiple [`QuantityFunction`] trait objects:
+
+`y = f_1(x) + f_2(x) + ... + f_n(x)`
+
+All functions must have the same output unit, which is checked in the
+constructor [`Sum::new`]. The sum of an empty list of functions is a
+dimensionless zero.
+
+# Examples
+
+```
+use std::str::FromStr;
+use dyn_quantity::DynQuantity;
+use var_quantity::{QuantityFunction, combinators::Sum, unary::Linear};
+
+let q = |s: &str| DynQuantity::<f64>::from_str(s).unwrap();
+
+// Losses of two components as functions of the frequency
+let stator = Linear::new(q("0.5 W/Hz"), q("0 W"));
+let rotor = Linear::new(q("0.25 W/Hz"), q("10 W"));
+let losses = Sum::new(vec![Box::new(stator), Box::new(rotor.clone())]).expect("units match");
+assert_eq!(losses.call(&[q("100 Hz")]), q("85 W"));
+
+// The output units must match
+let current = Linear::new(q("1 A/Hz"), q("0 A"));
+assert!(Sum::new(vec![Box::new(rotor), Box::new(current)]).is_err());
+```
+
+# Features:
+This struct can be serialized / deserialized if the `serde` feature is enabled.
+The functions are serialized as a list of trait objects, hence a sum can be
+deserialized e.g. from the following YAML (as a [`QuantityFunction`] trait
+object):
+
+```text
+Sum:
+  functions:
+    - Linear:
+        slope: 0.5 ohm/K
+        base_value: 1 ohm
+    - Product:
+        functions:
+          - Linear:
+              slope: 0.01 ohm/K
+              base_value: 2 ohm
+          - Linear:
+              slope: 0.001 1/Hz
+              base_value: 1
+```
+*/
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct Sum {
+    functions: Vec<Box<dyn QuantityFunction>>,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    output_unit: Unit,
+}
+
+impl Sum {
+    /**
+    Checks if all `functions` have the same output unit. If this is the case,
+    a new instance of [`Sum`] is returned.
+     */
+    pub fn new(functions: Vec<Box<dyn QuantityFunction>>) -> Result<Self, UnitsNotEqual> {
+        let output_unit = functions
+            .first()
+            .map(|function| function.output_unit())
+            .unwrap_or_default();
+        for function in functions.iter() {
+            let unit = function.output_unit();
+            if unit != output_unit {
+                return Err(UnitsNotEqual(output_unit, unit));
+            }
+        }
+        return Ok(Self {
+            functions,
+            output_unit,
+        });
+    }
+
+    /**
+    Returns the summands.
+     */
+    pub fn functions(&self) -> &[Box<dyn QuantityFunction>] {
+        return self.functions.as_slice();
+    }
+
+    /**
+    Returns the unit which will be returned from [`QuantityFunction::call`].
+     */
+    pub fn output_unit(&self) -> Unit {
+        return self.output_unit;
+    }
+}
+
+#[cfg_attr(feature = "serde", typetag::serde)]
+impl QuantityFunction for Sum {
+    fn call(&self, influencing_factors: &[DynQuantity<f64>]) -> DynQuantity<f64> {
+        let value = self
+            .functions
+            .iter()
+            .map(|function| function.call(influencing_factors).value)
+            .sum();
+        return DynQuantity::new(value, self.output_unit);
+    }
+
+    fn call_batch(&self, inputs: &[BatchInput], output: &mut [f64]) {
+        crate::batch::assert_lengths(inputs, output.len());
+        output.fill(0.0);
+        let mut buffer = vec![0.0; output.len()];
+        for function in self.functions.iter() {
+            function.call_batch(inputs, &mut buffer);
+            for (out, value) in output.iter_mut().zip(buffer.iter()) {
+                *out += value;
+            }
+        }
+    }
+
+    fn bind(&self, layout: &Layout) -> Box<dyn Evaluator + '_> {
+        let evaluators: Vec<_> = self.functions.iter().map(|f| f.bind(layout)).collect();
+        return Box::new(move |values: &[f64]| {
+            evaluators.iter().map(|e| e.eval(values)).sum::<f64>()
+        });
+    }
+
+    fn output_unit(&self) -> Unit {
+        return self.output_unit;
+    }
+
+    fn influencing_units(&self) -> Option<Vec<Unit>> {
+        return super::union_of_units(&self.functions);
+    }
+
+    fn validity_ranges(&self) -> Vec<ValidityRange> {
+        return super::validity_ranges_of(&self.functions);
+    }
+
+    fn call_named(&self, influencing_factors: &[InfluencingFactor]) -> DynQuantity<f64> {
+        let value = self
+            .functions
+            .iter()
+            .map(|function| function.call_named(influencing_factors).value)
+            .sum();
+        return DynQuantity::new(value, self.output_unit);
+    }
+
+    fn try_call(
+        &self,
+        influencing_factors: &[DynQuantity<f64>],
+    ) -> Result<DynQuantity<f64>, EvaluationError> {
+        let mut value = 0.0;
+        for function in self.functions.iter() {
+            value += function.try_call(influencing_factors)?.value;
+        }
+        return Ok(DynQuantity::new(value, self.output_unit));
+    }
+
+    fn try_call_named(
+        &self,
+        influencing_factors: &[InfluencingFactor],
+    ) -> Result<DynQuantity<f64>, EvaluationError> {
+        let mut value = 0.0;
+        for function in self.functions.iter() {
+            value += function.try_call_named(influencing_factors)?.value;
+        }
+        return Ok(DynQuantity::new(value, self.output_unit));
+    }
+
+    fn derivative(
+        &self,
+        influencing_factors: &[DynQuantity<f64>],
+        wrt: Unit,
+    ) -> Option<DynQuantity<f64>> {
+        let mut derivative = DynQuantity::new(0.0, self.output_unit / wrt);
+        if !influencing_factors.iter().any(|factor| factor.unit == wrt) {
+            return Some(derivative);
+        }
+        for function in self.functions.iter() {
+            derivative.value += function.derivative(influencing_factors, wrt)?.value;
+        }
+        return Some(derivative);
+    }
+
+    fn call_dual(&self, influencing_factors: &[DualQuantity]) -> DualQuantity {
+        let mut output = DualQuantity::constant(DynQuantity::new(0.0, self.output_unit));
+        for function in self.functions.iter() {
+            let summand = function.call_dual(influencing_factors);
+            output.value.value += summand.value.value;
+            output.accumulate(1.0.into(), &summand);
+        }
+        return output;
+    }
+}
+
+impl Clone for Sum {
+    fn clone(&self) -> Self {
+        return Self {
+            functions: self
+                .functions
+                .iter()
+                .map(|function| dyn_clone::clone_box(&**function))
+                .collect(),
+            output_unit: self.output_unit,
+        };
+    }
+}
+
+impl std::fmt::Debug for Sum {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Sum")
+            .field("output_unit", &self.output_unit)
+            .field("functions", &self.functions.len())
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(feature = "serde")]
+mod serde_impl {
+    use super::*;
+
+    use serde::de::{Deserialize, Deserializer};
+
+    impl<'de> Deserialize<'de> for Sum {
+        fn deserialize<D>(deserializer: D) -> Result<Sum, D::Error>
+        where
+            D: Deserializer<'de>,
+        {
+            #[derive(serde::Deserialize)]
+            struct SumAlias {
+                functions: Vec<Box<dyn QuantityFunction>>,
+            }
+
+            let alias = SumAlias::deserialize(deserializer)?;
+            return Self::new(alias.functions).map_err(serde::de::Error::custom);
+        }
+    }
+}
+
+// =============================================================================
+
+#[cfg(feature = "serde")]
+#[cfg_attr(feature = "serde", typetag::serde)]
+impl QuantityFunction for crate::ClampedQuantity<Sum> {
+    fn call(&self, influencing_factors: &[DynQuantity<f64>]) -> DynQuantity<f64> {
+        return self.call_clamped(influencing_factors);
+    }
+
+    fn output_unit(&self) -> Unit {
+        return self.inner().output_unit();
+    }
+
+    fn influencing_units(&self) -> Option<Vec<Unit>> {
+        return self.inner().influencing_units();
+    }
+
+    fn validity_ranges(&self) -> Vec<ValidityRange> {
+        return self.inner().validity_ranges();
+    }
+
+    fn call_named(&self, influencing_factors: &[InfluencingFactor]) -> DynQuantity<f64> {
+        return self.call_named_clamped(influencing_factors);
+    }
+
+    fn try_call(
+        &self,
+        influencing_factors: &[DynQuantity<f64>],
+    ) -> Result<DynQuantity<f64>, EvaluationError> {
+        return self.try_call_clamped(influencing_factors);
+    }
+
+    fn try_call_named(
+        &self,
+        influencing_factors: &[InfluencingFactor],
+    ) -> Result<DynQuantity<f64>, EvaluationError> {
+        return self.try_call_named_clamped(influencing_factors);
+    }
+
+    fn derivative(
+        &self,
+        influencing_factors: &[DynQuantity<f64>],
+        wrt: Unit,
+    ) -> Option<DynQuantity<f64>> {
+        return self.derivative_clamped(influencing_factors, wrt);
+    }
+
+    fn call_dual(&self, influencing_factors: &[DualQuantity]) -> DualQuantity {
+        return self.call_dual_clamped(influencing_factors);
+    }
+
+    fn call_batch(&self, inputs: &[BatchInput], output: &mut [f64]) {
+        self.call_batch_clamped(inputs, output);
+    }
+
+    fn bind(&self, layout: &Layout) -> Box<dyn Evaluator + '_> {
+        return self.bind_clamped(layout);
+    }
+}
@@ -0,0 +1,316 @@
+/*!
+This module contains the [`Layout`] of influencing factors and the
+[`Evaluator`] trait, which together form a fast path for evaluating a
+[`QuantityFunction`](crate::QuantityFunction) in tight loops.
+
+[`QuantityFunction::call`](crate::QuantityFunction::call) has to search the
+influencing factors for the ones it needs on every call by comparing their
+units. If the caller always passes the influencing factors in the same order,
+this search can be done once up front: [`QuantityFunction::bind`](crate::QuantityFunction::bind)
+takes the [`Layout`] of the factors (their units and optionally their names,
+in order) and returns an [`Evaluator`] which reads the raw values directly by
+index. The checked API of [`QuantityFunction`](crate::QuantityFunction) is
+still available and yields the same results.
+
+# Examples
+
+```
+use std::str::FromStr;
+use dyn_quantity::{DynQuantity, PredefUnit};
+use var_quantity::{QuantityFunction, compiled::Layout, unary::Linear};
+
+let q = |s: &str| DynQuantity::<f64>::from_str(s).unwrap();
+
+let fun = Linear::new(q("0.01 ohm/K"), q("1 ohm"));
+
+// The caller passes the current and the temperature (in this order)
+let layout = Layout::new(vec![PredefUnit::ElectricCurrent.into(), PredefUnit::Temperature.into()]);
+let evaluator = fun.bind(&layout);
+
+let mut total = 0.0;
+for temperature in [300.0, 400.0, 500.0] {
+    total += evaluator.eval(&[2.0, temperature]);
+}
+assert_eq!(total, 15.0);
+assert_eq!(evaluator.eval(&[2.0, 300.0]), fun.call(&[q("2 A"), q("300 K")]).value);
+```
+*/
+
+use dyn_quantity::{DynQuantity, Unit};
+
+use crate::{InfluencingFactor, QuantityFunction};
+
+/**
+The layout of the influencing factors which are passed to an [`Evaluator`]:
+The `i`-th value passed to [`Evaluator::eval`] has the `i`-th unit of the
+layout (and is given in SI base units).
+
+If the layout is created via [`Layout::named`], the factors are selected by
+name and unit like in [`QuantityFunction::call_named`](crate::QuantityFunction::call_named).
+Otherwise, they are selected by unit like in
+[`QuantityFunction::call`](crate::QuantityFunction::call).
+ */
+#[derive(Debug, Clone, PartialEq)]
+pub struct Layout {
+    units: Vec<Unit>,
+    names: Option<Vec<String>>,
+}
+
+impl Layout {
+    /**
+    Creates a layout of unnamed influencing factors with the given `units`.
+     */
+    pub fn new(units: Vec<Unit>) -> Self {
+        return Self { units, names: None };
+    }
+
+    /**
+    Creates a layout of named influencing factors.
+
+    # Examples
+
+    ```
+    use dyn_quantity::PredefUnit;
+    use var_quantity::compiled::Layout;
+
+    let layout = Layout::named([
+        ("ambient", PredefUnit::Temperature.into()),
+        ("winding", PredefUnit::Temperature.into()),
+    ]);
+    assert_eq!(layout.position(PredefUnit::Temperature.into(), Some("winding")), Some(1));
+    assert_eq!(layout.position(PredefUnit::Temperature.into(), None), Some(0));
+    ```
+     */
+    pub fn named<N, I>(factors: I) -> Self
+    where
+        N: Into<String>,
+        I: IntoIterator<Item = (N, Unit)>,
+    {
+        let (names, units) = factors
+            .into_iter()
+            .map(|(name, unit)| (name.into(), unit))
+            .unzip();
+        return Self {
+            units,
+            names: Some(names),
+        };
+    }
+
+    /**
+    Returns the units of the influencing factors.
+     */
+    pub fn units(&self) -> &[Unit] {
+        return &self.units;
+    }
+
+    /**
+    Returns the names of the influencing factors if the layout is named.
+     */
+    pub fn names(&self) -> Option<&[String]> {
+        return self.names.as_deref();
+    }
+
+    /**
+    Returns the number of influencing factors.
+     */
+    pub fn len(&self) -> usize {
+        return self.units.len();
+    }
+
+    /**
+    Returns `true` if the layout does not contain any influencing factors.
+     */
+    pub fn is_empty(&self) -> bool {
+        return self.units.is_empty();
+    }
+
+    /**
+    Returns the index of the influencing factor with the unit `unit`. If the
+    layout is named and `name` is given, the factor also needs to have this
+    name (analogous to [`select_influencing_factor`](crate::select_influencing_factor)).
+    Otherwise, the first factor with the unit is selected (analogous to
+    [`filter_unary_function`](crate::filter_unary_function)).
+     */
+    pub fn position(&self, unit: Unit, name: Option<&str>) -> Option<usize> {
+        return match (&self.names, name) {
+            (Some(names), Some(name)) => self
+                .units
+                .iter()
+                .zip(names.iter())
+                .position(|(u, n)| *u == unit && n == name),
+            _ => self.units.iter().position(|u| *u == unit),
+        };
+    }
+
+    /**
+    Converts raw `values` into the influencing factors which are passed to
+    the checked API of [`QuantityFunction`].
+     */
+    fn quantities(&self, values: &[f64]) -> Vec<DynQuantity<f64>> {
+        return self
+            .units
+            .iter()
+            .zip(values.iter())
+            .map(|(unit, value)| DynQuantity::new(*value, *unit))
+            .collect();
+    }
+
+    /**
+    Named counterpart to [`Layout::quantities`].
+     */
+    fn named_quantities(&self, names: &[String], values: &[f64]) -> Vec<InfluencingFactor> {
+        return self
+            .quantities(values)
+            .into_iter()
+            .zip(names.iter())
+            .map(|(quantity, name)| InfluencingFactor::new(name.as_str(), quantity))
+            .collect();
+    }
+}
+
+/**
+A [`QuantityFunction`] which has been bound to a [`Layout`] via
+[`QuantityFunction::bind`](crate::QuantityFunction::bind).
+ */
+pub trait Evaluator: Send + Sync {
+    /**
+    Evaluates the function for the raw `values` of the influencing factors,
+    which are given in the order and units of the [`Layout`] the function has
+    been bound to. The output is given in the
+    [`QuantityFunction::output_unit`](crate::QuantityFunction::output_unit).
+
+    The values are not checked: Passing fewer values than defined by the
+    layout may panic.
+     */
+    fn eval(&self, values: &[f64]) -> f64;
+}
+
+/**
+Closures which take the raw values of the influencing factors are evaluators
+as well. This is mainly useful for custom implementations of
+[`QuantityFunction::bind`](crate::QuantityFunction::bind) which combine the
+evaluators of other functions.
+ */
+impl<F: Fn(&[f64]) -> f64 + Send + Sync> Evaluator for F {
+    fn eval(&self, values: &[f64]) -> f64 {
+        return self(values);
+    }
+}
+
+/**
+An [`Evaluator`] which forwards to the checked API of a [`QuantityFunction`].
+This is the default implementation of [`QuantityFunction::bind`](crate::QuantityFunction::bind).
+ */
+pub(crate) struct CheckedEvaluator<'a, F: ?Sized> {
+    function: &'a F,
+    layout: Layout,
+}
+
+impl<'a, F: QuantityFunction + ?Sized> CheckedEvaluator<'a, F> {
+    pub(crate) fn new(function: &'a F, layout: &Layout) -> Self {
+        return Self {
+            function,
+            layout: layout.clone(),
+        };
+    }
+}
+
+impl<F: QuantityFunction + ?Sized> Evaluator for CheckedEvaluator<'_, F> {
+    fn eval(&self, values: &[f64]) -> f64 {
+        match self.layout.names() {
+            Some(names) => {
+                return self
+                    .function
+                    .call_named(&self.layout.named_quantities(names, values))
+                    .value;
+            }
+            None => return self.function.call(&self.layout.quantities(values)).value,
+        }
+    }
+}
+
+/**
+An [`Evaluator`] for a unary function, see [`bind_unary`].
+ */
+struct UnaryEvaluator<F> {
+    index: Option<usize>,
+    with_matched: F,
+    default: f64,
+}
+
+impl<F: Fn(f64) -> f64 + Send + Sync> Evaluator for UnaryEvaluator<F> {
+    fn eval(&self, values: &[f64]) -> f64 {
+        match self.index {
+            Some(index) => return (self.with_matched)(values[index]),
+            None => return self.default,
+        }
+    }
+}
+
+/**
+The compiled counterpart of [`filter_unary_function`](crate::filter_unary_function):
+Resolves the index of the influencing factor with the unit `match_for` (and
+the name `name`, if the layout is named) in `layout` once and returns an
+[`Evaluator`] which applies `with_matched` to the value at this index. If the
+layout does not contain a matching factor, the evaluator always returns the
+value of `no_match`.
+
+This function is mainly here to simplify custom implementations of
+[`QuantityFunction::bind`](crate::QuantityFunction::bind) for unary
+functions.
+
+# Examples
+
+```
+use dyn_quantity::{DynQuantity, PredefUnit, Unit};
+use var_quantity::{
+    QuantityFunction, filter_unary_function,
+    compiled::{Evaluator, Layout, bind_unary},
+};
+
+// The serde annotations are just here because the doctests of this crate use
+// the serde feature - they are not needed if the serde feature is disabled.
+#[derive(Clone, serde::Deserialize, serde::Serialize)]
+struct Square;
+
+// Again, the macro annotation is just here because of the serde feature
+#[typetag::serde]
+impl QuantityFunction for Square {
+    fn call(&self, influencing_factors: &[DynQuantity<f64>]) -> DynQuantity<f64> {
+        return filter_unary_function(
+            influencing_factors,
+            PredefUnit::Length.into(),
+            |x| x.powi(2),
+            || DynQuantity::new(0.0, Unit::from(PredefUnit::Length).powi(2)),
+        );
+    }
+
+    fn bind(&self, layout: &Layout) -> Box<dyn Evaluator + '_> {
+        return bind_unary(layout, PredefUnit::Length.into(), None, |x| x.powi(2), || 0.0);
+    }
+}
+
+let layout = Layout::new(vec![PredefUnit::Time.into(), PredefUnit::Length.into()]);
+assert_eq!(Square.bind(&layout).eval(&[1.0, 3.0]), 9.0);
+
+let layout = Layout::new(vec![PredefUnit::Time.into()]);
+assert_eq!(Square.bind(&layout).eval(&[1.0]), 0.0);
+```
+ */
+pub fn bind_unary<'a, F, G>(
+    layout: &Layout,
+    match_for: Unit,
+    name: Option<&str>,
+    with_matched: F,
+    no_match: G,
+) -> Box<dyn Evaluator + 'a>
+where
+    F: Fn(f64) -> f64 + Send + Sync + 'a,
+    G: FnOnce() -> f64,
+{
+    return Box::new(UnaryEvaluator {
+        index: layout.position(match_for, name),
+        with_matched,
+        default: no_match(),
+    });
+}
@@ -0,0 +1,299 @@
+/*!
+This module contains the [`DualQuantity`] type which is used for forward-mode
+automatic differentiation of [`QuantityFunction`](crate::QuantityFunction)s via
+[`QuantityFunction::call_dual`](crate::QuantityFunction::call_dual).
+*/
+
+use std::ops::{Div, Mul, Neg};
+
+use dyn_quantity::{DynQuantity, Unit, UnitsNotEqual};
+
+/**
+A dual number consisting of a quantity `value` and its `gradient` with respect
+to a set of independent variables ("seeds").
+
+The `j`-th entry of the gradient is the partial derivative of `value` with
+respect to the `j`-th seed, hence its unit is the unit of `value` divided by
+the unit of that seed. An empty gradient denotes a constant (all partial
+derivatives are zero). The arithmetic operations defined for this type apply
+the chain rule, so that evaluating an arbitrary composition of operations with
+seeded inputs (see [`DualQuantity::seed`]) yields the exact gradient of the
+result.
+
+This type is used by [`QuantityFunction::call_dual`](crate::QuantityFunction::call_dual),
+which custom functions can override to provide exact derivatives without
+deriving them by hand.
+
+# Examples
+
+```
+use std::str::FromStr;
+use dyn_quantity::DynQuantity;
+use var_quantity::DualQuantity;
+
+let q = |s: &str| DynQuantity::<f64>::from_str(s).unwrap();
+
+// p = R * I²
+let inputs = DualQuantity::seed(&[q("2 ohm"), q("3 A")]);
+let (r, i) = (&inputs[0], &inputs[1]);
+let p = r.clone() * i.clone().powi(2);
+
+assert_eq!(p.value, q("18 W"));
+assert_eq!(p.gradient, vec![q("9 W/ohm"), q("12 W/A")]);
+```
+ */
+#[derive(Debug, Clone, PartialEq)]
+pub struct DualQuantity {
+    /// Value of the quantity.
+    pub value: DynQuantity<f64>,
+    /// Partial derivatives of the value with respect to the seeds.
+    pub gradient: Vec<DynQuantity<f64>>,
+}
+
+impl DualQuantity {
+    /**
+    Creates a constant dual number, i.e. one whose partial derivatives are all
+    zero.
+     */
+    pub fn constant<Q: Into<DynQuantity<f64>>>(value: Q) -> Self {
+        return Self {
+            value: value.into(),
+            gradient: Vec::new(),
+        };
+    }
+
+    /**
+    Creates one dual number per quantity, each of which is an independent
+    variable (seed): The gradient of the `i`-th dual number is one for the
+    `i`-th entry and zero for all other entries.
+
+    # Examples
+
+    ```
+    use dyn_quantity::{DynQuantity, PredefUnit, Unit};
+    use var_quantity::DualQuantity;
+
+    let seeds = DualQuantity::seed(&[
+        DynQuantity::new(2.0, PredefUnit::Temperature),
+        DynQuantity::new(3.0, PredefUnit::Time),
+    ]);
+    assert_eq!(seeds[0].gradient[0], DynQuantity::new(1.0, Unit::default()));
+    assert_eq!(
+        seeds[0].gradient[1],
+        DynQuantity::new(0.0, Unit::from(PredefUnit::Temperature) / Unit::from(PredefUnit::Time))
+    );
+    ```
+     */
+    pub fn seed(quantities: &[DynQuantity<f64>]) -> Vec<Self> {
+        return quantities
+            .iter()
+            .enumerate()
+            .map(|(i, quantity)| {
+                let gradient = quantities
+                    .iter()
+                    .enumerate()
+                    .map(|(j, seed)| {
+                        let value = if i == j { 1.0 } else { 0.0 };
+                        DynQuantity::new(value, quantity.unit / seed.unit)
+                    })
+                    .collect();
+                Self {
+                    value: *quantity,
+                    gradient,
+                }
+            })
+            .collect();
+    }
+
+    /**
+    Applies a function `f` to `self` via the chain rule, where `value` is
+    `f(self.value)` and `derivative` is the derivative of `f` evaluated at
+    `self.value`. This is the building block for functions which are not
+    provided as methods of this type.
+
+    # Examples
+
+    ```
+    use dyn_quantity::DynQuantity;
+    use var_quantity::DualQuantity;
+
+    let x = DualQuantity::seed(&[DynQuantity::from(0.5)]).remove(0);
+    let sin = x.chain(x.value.value.sin().into(), x.value.value.cos().into());
+    assert_eq!(sin.gradient[0].value, 0.5f64.cos());
+    ```
+     */
+    pub fn chain(&self, value: DynQuantity<f64>, derivative: DynQuantity<f64>) -> Self {
+        return Self {
+            value,
+            gradient: self.gradient.iter().map(|g| derivative * *g).collect(),
+        };
+    }
+
+    /**
+    Adds `other` to `self`. Fails if the units of the values are not equal.
+     */
+    pub fn try_add(&self, other: &Self) -> Result<Self, UnitsNotEqual> {
+        return Ok(Self {
+            value: self.value.try_add(&other.value)?,
+            gradient: add_gradients(&self.gradient, &other.gradient, 1.0),
+        });
+    }
+
+    /**
+    Subtracts `other` from `self`. Fails if the units of the values are not
+    equal.
+     */
+    pub fn try_sub(&self, other: &Self) -> Result<Self, UnitsNotEqual> {
+        return Ok(Self {
+            value: self.value.try_sub(&other.value)?,
+            gradient: add_gradients(&self.gradient, &other.gradient, -1.0),
+        });
+    }
+
+    /// Raises `self` to the integer power `n`.
+    pub fn powi(&self, n: i32) -> Self {
+        let derivative = if n == 0 {
+            DynQuantity::new(0.0, self.value.unit.powi(-1))
+        } else {
+            self.value.powi(n - 1) * n as f64
+        };
+        return self.chain(self.value.powi(n), derivative);
+    }
+
+    /**
+    Raises `self` to the power `n`. Fails if `self` is not dimensionless.
+     */
+    pub fn try_powf(&self, n: f64) -> Result<Self, UnitsNotEqual> {
+        let x = dimensionless(self)?;
+        return Ok(self.chain(x.powf(n).into(), (n * x.powf(n - 1.0)).into()));
+    }
+
+    /**
+    Returns the exponential of `self`. Fails if `self` is not dimensionless.
+     */
+    pub fn try_exp(&self) -> Result<Self, UnitsNotEqual> {
+        let x = dimensionless(self)?;
+        return Ok(self.chain(x.exp().into(), x.exp().into()));
+    }
+
+    /**
+    Returns the natural logarithm of `self`. Fails if `self` is not
+    dimensionless.
+     */
+    pub fn try_ln(&self) -> Result<Self, UnitsNotEqual> {
+        let x = dimensionless(self)?;
+        return Ok(self.chain(x.ln().into(), x.recip().into()));
+    }
+
+    /**
+    Accumulates the contribution `derivative * factor.gradient` of an
+    influencing factor to the gradient of `self`.
+     */
+    pub(crate) fn accumulate(&mut self, derivative: DynQuantity<f64>, factor: &DualQuantity) {
+        let contribution: Vec<DynQuantity<f64>> =
+            factor.gradient.iter().map(|g| derivative * *g).collect();
+        self.gradient = add_gradients(&self.gradient, &contribution, 1.0);
+    }
+}
+
+/// Returns the value of `dual` if it is dimensionless.
+fn dimensionless(dual: &DualQuantity) -> Result<f64, UnitsNotEqual> {
+    if dual.value.unit != Unit::default() {
+        return Err(UnitsNotEqual(Unit::default(), dual.value.unit));
+    }
+    return Ok(dual.value.value);
+}
+
+/**
+Calculates `left + sign * right` element-wise. An empty gradient is treated as
+zero. The units of the left gradient are used, since the units of both
+gradients must be identical if the values have identical units. Non-empty
+gradients must have the same length, since both are seeded from the same
+influencing factors.
+ */
+fn add_gradients(
+    left: &[DynQuantity<f64>],
+    right: &[DynQuantity<f64>],
+    sign: f64,
+) -> Vec<DynQuantity<f64>> {
+    if left.is_empty() {
+        return right.iter().map(|g| *g * sign).collect();
+    }
+    if right.is_empty() {
+        return left.to_vec();
+    }
+    debug_assert_eq!(
+        left.len(),
+        right.len(),
+        "gradients of dual quantities must have the same length"
+    );
+    return left
+        .iter()
+        .zip(right.iter())
+        .map(|(l, r)| DynQuantity::new(l.value + sign * r.value, l.unit))
+        .collect();
+}
+
+impl Mul for DualQuantity {
+    type Output = Self;
+
+    fn mul(self, rhs: Self) -> Self::Output {
+        let left: Vec<DynQuantity<f64>> = self.gradient.iter().map(|g| *g * rhs.value).collect();
+        let right: Vec<DynQuantity<f64>> = rhs.gradient.iter().map(|g| self.value * *g).collect();
+        return Self {
+            value: self.value * rhs.value,
+            gradient: add_gradients(&left, &right, 1.0),
+        };
+    }
+}
+
+impl Div for DualQuantity {
+    type Output = Self;
+
+    fn div(self, rhs: Self) -> Self::Output {
+        // (u/v)' = u'/v - u v' / v²
+        let left: Vec<DynQuantity<f64>> = self.gradient.iter().map(|g| *g / rhs.value).collect();
+        let factor = self.value / rhs.value.powi(2);
+        let right: Vec<DynQuantity<f64>> = rhs.gradient.iter().map(|g| factor * *g).collect();
+        return Self {
+            value: self.value / rhs.value,
+            gradient: add_gradients(&left, &right, -1.0),
+        };
+    }
+}
+
+impl Mul<f64> for DualQuantity {
+    type Output = Self;
+
+    fn mul(self, rhs: f64) -> Self::Output {
+        return Self {
+            value: self.value * rhs,
+            gradient: self.gradient.into_iter().map(|g| g * rhs).collect(),
+        };
+    }
+}
+
+impl Div<f64> for DualQuantity {
+    type Output = Self;
+
+    fn div(self, rhs: f64) -> Self::Output {
+        return Self {
+            value: self.value / rhs,
+            gradient: self.gradient.into_iter().map(|g| g / rhs).collect(),
+        };
+    }
+}
+
+impl Neg for DualQuantity {
+    type Output = Self;
+
+    fn neg(self) -> Self::Output {
+        return self * -1.0;
+    }
+}
+
+impl From<DynQuantity<f64>> for DualQuantity {
+    fn from(value: DynQuantity<f64>) -> Self {
+        return Self::constant(value);
+    }
+}
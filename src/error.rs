@@ -0,0 +1,388 @@
+/*!
+This module contains the errors which can occur when constructing a
+[`QuantityFunction`](crate::QuantityFunction) or when evaluating a
+[`VarQuantity`](crate::VarQuantity) or a [`FunctionWrapper`](crate::FunctionWrapper).
+*/
+
+use dyn_quantity::{DynQuantity, Unit, UnitsNotEqual};
+
+#[cfg(feature = "from_str")]
+use dyn_quantity::RootError;
+
+/**
+Error returned by the fallible evaluation methods
+[`FunctionWrapper::try_call`](crate::FunctionWrapper::try_call) and
+[`VarQuantity::try_get`](crate::VarQuantity::try_get).
+ */
+#[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
+pub enum EvaluationError {
+    /**
+    The [`QuantityFunction`](crate::QuantityFunction) trait object returned a
+    [`DynQuantity`] which could not be converted into the statically typed
+    quantity. This means that the implementation of the trait object violates
+    the requirement that the returned unit must always be the same.
+     */
+    UnitMismatch {
+        /// Quantity returned by the trait object.
+        output: DynQuantity<f64>,
+        /// Unit of the statically typed quantity.
+        expected: Unit,
+        /// Influencing factors which were used as input.
+        influencing_factors: Vec<DynQuantity<f64>>,
+    },
+    /**
+    An influencing factor is outside of the range for which the function is
+    defined, e.g. outside of the support points of a
+    [`Lookup`](crate::unary::Lookup) table which does not allow extrapolation.
+     */
+    InputOutOfRange {
+        /// Influencing factor which is out of range.
+        input: DynQuantity<f64>,
+        /// Lower limit of the valid range.
+        lower: DynQuantity<f64>,
+        /// Upper limit of the valid range.
+        upper: DynQuantity<f64>,
+    },
+}
+
+impl std::fmt::Display for EvaluationError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            EvaluationError::UnitMismatch {
+                output,
+                expected,
+                influencing_factors,
+            } => write!(
+                f,
+                "conversion of the function output {} into a quantity with unit {} failed for input {:?}. \
+                This means that the QuantityFunction trait object returns a different unit depending \
+                on the input, which is a bug in the implementation of the trait object.",
+                output, expected, influencing_factors
+            ),
+            EvaluationError::InputOutOfRange {
+                input,
+                lower,
+                upper,
+            } => write!(
+                f,
+                "input {} is outside of the valid range from {} to {}",
+                input, lower, upper
+            ),
+        }
+    }
+}
+
+impl std::error::Error for EvaluationError {}
+
+/**
+Error returned when constructing a function from a set of support points
+(e.g. [`Lookup::new`](crate::unary::Lookup::new)) fails.
+ */
+#[derive(Debug, Clone, PartialEq)]
+pub enum SupportPointsError {
+    /// The units of the support points are not consistent.
+    UnitsNotEqual(UnitsNotEqual),
+    /// Not enough support points were given.
+    NotEnoughPoints {
+        /// Minimum number of support points.
+        required: usize,
+        /// Number of given support points.
+        found: usize,
+    },
+    /**
+    The input values of the support points are not strictly increasing. The
+    contained index is that of the first offending support point.
+     */
+    NotStrictlyIncreasing(usize),
+    /**
+    The input or output value of a support point is not finite (NaN or
+    infinite). The contained index is that of the first offending support
+    point. For a [`Grid`](crate::multivariate::Grid), it is the index of the
+    point within its axis or the index of the value.
+     */
+    NotFinite(usize),
+    /**
+    Multiple inputs of a multivariate function (e.g. the axes of a
+    [`Grid`](crate::multivariate::Grid)) have the contained unit, hence it is
+    not possible to assign the influencing factors to the inputs.
+     */
+    AmbiguousUnit(Unit),
+    /// The number of given values does not match the expected number.
+    LengthMismatch {
+        /// Expected number of values.
+        expected: usize,
+        /// Number of given values.
+        found: usize,
+    },
+}
+
+impl std::fmt::Display for SupportPointsError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SupportPointsError::UnitsNotEqual(err) => err.fmt(f),
+            SupportPointsError::NotEnoughPoints { required, found } => write!(
+                f,
+                "at least {} support points are required, found {}",
+                required, found
+            ),
+            SupportPointsError::NotStrictlyIncreasing(index) => write!(
+                f,
+                "input values of the support points must be strictly increasing \
+                (violated by the support point at index {})",
+                index
+            ),
+            SupportPointsError::NotFinite(index) => write!(
+                f,
+                "the values of the support point at index {} must be finite",
+                index
+            ),
+            SupportPointsError::AmbiguousUnit(unit) => {
+                write!(f, "multiple inputs have the unit {}", unit)
+            }
+            SupportPointsError::LengthMismatch { expected, found } => {
+                write!(f, "expected {} values, found {}", expected, found)
+            }
+        }
+    }
+}
+
+impl std::error::Error for SupportPointsError {}
+
+impl From<UnitsNotEqual> for SupportPointsError {
+    fn from(value: UnitsNotEqual) -> Self {
+        return Self::UnitsNotEqual(value);
+    }
+}
+
+/**
+Error returned when composing two functions via
+[`Compose::new`](crate::combinators::Compose::new) fails.
+ */
+#[derive(Debug, Clone, PartialEq)]
+pub enum CompositionError {
+    /**
+    The outer function does not consume the output unit of the inner function,
+    i.e. the unit is not contained in the
+    [`QuantityFunction::influencing_units`](crate::QuantityFunction::influencing_units)
+    of the outer function.
+     */
+    NotConsumed {
+        /// Output unit of the inner function.
+        unit: Unit,
+        /// Influencing units of the outer function.
+        influencing_units: Vec<Unit>,
+    },
+}
+
+impl std::fmt::Display for CompositionError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CompositionError::NotConsumed {
+                unit,
+                influencing_units,
+            } => {
+                write!(
+                    f,
+                    "the output unit {} of the inner function is not consumed by the outer function (influencing units: [",
+                    unit
+                )?;
+                for (index, influencing_unit) in influencing_units.iter().enumerate() {
+                    if index > 0 {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "{}", influencing_unit)?;
+                }
+                write!(f, "])")
+            }
+        }
+    }
+}
+
+impl std::error::Error for CompositionError {}
+
+/**
+Error returned when constructing a range of quantities (e.g. a
+[`ValidityRange`](crate::validity::ValidityRange)) fails.
+ */
+#[derive(Debug, Clone, PartialEq)]
+pub enum RangeError {
+    /// The lower and the upper limit have different units.
+    UnitsNotEqual(UnitsNotEqual),
+    /// The lower limit is larger than the upper limit.
+    LowerAboveUpper {
+        /// Lower limit of the range.
+        lower: DynQuantity<f64>,
+        /// Upper limit of the range.
+        upper: DynQuantity<f64>,
+    },
+    /**
+    The contained limit is NaN or infinite. Only the lower limit of a
+    one-sided range may be negative infinity and only the upper limit may be
+    positive infinity.
+     */
+    NotFinite(DynQuantity<f64>),
+}
+
+impl std::fmt::Display for RangeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RangeError::UnitsNotEqual(err) => err.fmt(f),
+            RangeError::LowerAboveUpper { lower, upper } => write!(
+                f,
+                "lower limit {} must not be larger than the upper limit {}",
+                lower, upper
+            ),
+            RangeError::NotFinite(limit) => write!(f, "limit {} must be finite", limit),
+        }
+    }
+}
+
+impl std::error::Error for RangeError {}
+
+impl From<UnitsNotEqual> for RangeError {
+    fn from(value: UnitsNotEqual) -> Self {
+        return Self::UnitsNotEqual(value);
+    }
+}
+
+/**
+Error returned when constructing a function with a reference point (e.g.
+[`PowerLaw::new`](crate::unary::PowerLaw::new)) fails.
+ */
+#[derive(Debug, Clone, PartialEq)]
+pub enum ReferenceError {
+    /// The units of the parameters are not consistent.
+    UnitsNotEqual(UnitsNotEqual),
+    /// The contained reference point is zero or not finite (NaN or infinite).
+    InvalidReference(DynQuantity<f64>),
+}
+
+impl std::fmt::Display for ReferenceError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ReferenceError::UnitsNotEqual(err) => err.fmt(f),
+            ReferenceError::InvalidReference(reference) => write!(
+                f,
+                "reference point {} must be finite and nonzero",
+                reference
+            ),
+        }
+    }
+}
+
+impl std::error::Error for ReferenceError {}
+
+impl From<UnitsNotEqual> for ReferenceError {
+    fn from(value: UnitsNotEqual) -> Self {
+        return Self::UnitsNotEqual(value);
+    }
+}
+
+/**
+Error returned when parsing an [`Expression`](crate::expression::Expression)
+fails. Besides syntax errors, this includes expressions which are not
+dimensionally consistent. The contained positions are character offsets into
+the expression string.
+ */
+#[cfg(feature = "from_str")]
+#[derive(Debug, Clone, PartialEq)]
+pub enum ExpressionError {
+    /// The expression is not syntactically valid.
+    Syntax {
+        /// Position of the offending token.
+        position: usize,
+        /// Description of the problem.
+        message: String,
+    },
+    /// An identifier is neither a variable, a function nor a unit.
+    UnknownIdentifier {
+        /// Position of the identifier.
+        position: usize,
+        /// The identifier itself.
+        identifier: String,
+    },
+    /**
+    The operands of an addition, a subtraction or of the functions `min` and
+    `max` have different units.
+     */
+    UnitsNotEqual {
+        /// Position of the operator / function.
+        position: usize,
+        /// Unit of the left operand.
+        left: Unit,
+        /// Unit of the right operand.
+        right: Unit,
+    },
+    /**
+    The argument of a function which requires a dimensionless argument (e.g.
+    `exp`, `ln` or an exponent) has a unit.
+     */
+    NotDimensionless {
+        /// Position of the function / operator.
+        position: usize,
+        /// Unit of the argument.
+        unit: Unit,
+    },
+    /**
+    A base with a unit is raised to a power which is not a constant integer,
+    hence the unit of the result is not defined.
+     */
+    InvalidExponent {
+        /// Position of the power operator / function.
+        position: usize,
+        /// Unit of the base.
+        unit: Unit,
+    },
+    /// The square root of the argument's unit is not defined.
+    InvalidRoot {
+        /// Position of the function.
+        position: usize,
+        /// Underlying error.
+        error: RootError,
+    },
+}
+
+#[cfg(feature = "from_str")]
+impl std::fmt::Display for ExpressionError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ExpressionError::Syntax { position, message } => {
+                write!(f, "syntax error at position {}: {}", position, message)
+            }
+            ExpressionError::UnknownIdentifier {
+                position,
+                identifier,
+            } => write!(
+                f,
+                "identifier `{}` at position {} is neither a variable, a function nor a unit",
+                identifier, position
+            ),
+            ExpressionError::UnitsNotEqual {
+                position,
+                left,
+                right,
+            } => write!(
+                f,
+                "operands of the operation at position {} have different units ({} and {})",
+                position, left, right
+            ),
+            ExpressionError::NotDimensionless { position, unit } => write!(
+                f,
+                "argument of the operation at position {} must be dimensionless, but has unit {}",
+                position, unit
+            ),
+            ExpressionError::InvalidExponent { position, unit } => write!(
+                f,
+                "exponent at position {} must be a constant integer since the base has unit {}",
+                position, unit
+            ),
+            ExpressionError::InvalidRoot { position, error } => {
+                write!(f, "square root at position {} failed: {}", position, error)
+            }
+        }
+    }
+}
+
+#[cfg(feature = "from_str")]
+impl std::error::Error for ExpressionError {}
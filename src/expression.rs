@@ -0,0 +1,1028 @@
+/*!
+An [`Expression`] function which is parsed from a mathematical expression such
+as `"1.7e-8 ohm*m * (1 + 3.9e-3/K * (T - 293.15 K))"` and implements
+[`QuantityFunction`]. This module is only available if the `from_str` feature
+is enabled.
+*/
+
+use std::collections::BTreeMap;
+use std::str::FromStr;
+
+use dyn_quantity::{DynQuantity, Unit};
+
+use crate::{
+    BatchInput, DualQuantity, Evaluator, ExpressionError, InfluencingFactor, Layout,
+    QuantityFunction,
+};
+
+/**
+A function defined by a mathematical expression with named variables.
+
+The expression is parsed and checked for dimensional consistency in the
+constructor [`Expression::new`]. The following elements are supported:
+- Numbers (e.g. `2`, `0.5`, `1.7e-8`), optionally followed by a unit which
+binds tighter than any operator (e.g. `293.15 K`, `20 °C`, `2 m^2`).
+- Units on their own (e.g. `3.9e-3/K`), which are interpreted as a quantity
+with value one. Everything accepted by
+[`DynQuantity::from_str`](dyn_quantity::DynQuantity) can be used as a unit.
+- Variables bound to influencing factors (see below).
+- The operators `+`, `-`, `*`, `/` and `^` (power, right-associative) with
+the usual precedence as well as parentheses.
+- The functions `exp`, `ln`, `sqrt`, `abs`, `pow(base, exponent)`,
+`min(a, b)` and `max(a, b)`.
+
+Each variable is defined by its name and a default quantity. The unit of the
+default is the unit of the variable. When the function is evaluated via
+[`QuantityFunction::call`], the first influencing factor with this unit is
+used as value of the variable. Variables with the same unit can be
+distinguished via [`QuantityFunction::call_named`], which selects the
+influencing factor with the name of the variable. If no matching influencing
+factor is given, the default is used. Variables shadow units and functions
+with the same name (e.g. a variable `T` shadows the unit tesla).
+
+The dimensional analysis rejects expressions whose output unit is not well
+defined: The operands of `+`, `-`, `min` and `max` must have equal units,
+the arguments of `exp` and `ln` as well as exponents must be dimensionless,
+and a base with a unit may only be raised to a constant integer power.
+
+Derivatives (see [`QuantityFunction::derivative`] and
+[`QuantityFunction::call_dual`]) are calculated exactly by evaluating the
+expression with dual numbers.
+
+# Examples
+
+```
+use std::str::FromStr;
+use dyn_quantity::DynQuantity;
+use var_quantity::{QuantityFunction, expression::Expression};
+
+let q = |s: &str| DynQuantity::<f64>::from_str(s).unwrap();
+
+// Resistivity of copper depending on its temperature
+let rho = Expression::new(
+    "1.7e-8 ohm*m * (1 + 3.9e-3/K * (T - 293.15 K))",
+    [("T", q("293.15 K"))],
+).expect("valid expression");
+assert_eq!(rho.output_unit(), q("1 ohm*m").unit);
+
+approx::assert_abs_diff_eq!(rho.call(&[q("393.15 K")]).value, 1.7e-8 * 1.39, epsilon = 1e-20);
+
+// Without a temperature, the default value is used
+approx::assert_abs_diff_eq!(rho.call(&[]).value, 1.7e-8, epsilon = 1e-20);
+
+// Dimensionally inconsistent expressions are rejected
+assert!(Expression::new("T + 1 m", [("T", q("293.15 K"))]).is_err());
+```
+
+# Features:
+This struct can be serialized / deserialized if the `serde` feature is enabled.
+It is serialized as the original expression string and (if any) its variables.
+An expression without variables is serialized as a plain string:
+
+```
+use std::str::FromStr;
+use indoc::indoc;
+use dyn_quantity::DynQuantity;
+use var_quantity::{QuantityFunction, expression::Expression};
+
+let yaml = indoc! {"
+    ---
+    expression: 2 ohm * (1 + (T - 300 K) / (100 K))
+    variables:
+      T: 300 K
+    "};
+let fun: Expression = serde_yaml::from_str(yaml).expect("valid expression");
+
+let q = |s: &str| DynQuantity::<f64>::from_str(s).unwrap();
+assert_eq!(fun.call(&[q("400 K")]), q("4 ohm"));
+
+let constant: Expression = serde_yaml::from_str("---\n2 * pi * 50 Hz").expect("valid expression");
+assert_eq!(serde_yaml::to_string(&constant).unwrap(), "---\n2 * pi * 50 Hz\n");
+```
+*/
+#[derive(Debug, Clone, PartialEq)]
+pub struct Expression {
+    expression: String,
+    variables: BTreeMap<String, DynQuantity<f64>>,
+    ast: Expr,
+}
+
+impl Expression {
+    /**
+    Parses `expression` with the given `variables` (pairs of name and default
+    quantity, see the docstring of [`Expression`]) and checks it for
+    dimensional consistency. If a variable is given multiple times, the last
+    default is used.
+
+    # Examples
+
+    ```
+    use std::str::FromStr;
+    use dyn_quantity::DynQuantity;
+    use var_quantity::{ExpressionError, expression::Expression};
+
+    let q = |s: &str| DynQuantity::<f64>::from_str(s).unwrap();
+
+    assert!(Expression::new("sqrt(A / (4 * pi))", [("A", q("1 m^2"))]).is_ok());
+
+    // The argument of exp must be dimensionless
+    assert_eq!(
+        Expression::new("exp(t)", [("t", q("1 s"))]),
+        Err(ExpressionError::NotDimensionless { position: 0, unit: q("1 s").unit })
+    );
+
+    // `x` is neither a variable, a function nor a unit
+    assert_eq!(
+        Expression::new("2 * x", [("t", q("1 s"))]),
+        Err(ExpressionError::UnknownIdentifier { position: 4, identifier: "x".to_string() })
+    );
+    ```
+     */
+    pub fn new<E, I, N>(expression: E, variables: I) -> Result<Self, ExpressionError>
+    where
+        E: Into<String>,
+        I: IntoIterator<Item = (N, DynQuantity<f64>)>,
+        N: Into<String>,
+    {
+        let expression = expression.into();
+        let variables: BTreeMap<String, DynQuantity<f64>> = variables
+            .into_iter()
+            .map(|(name, default)| (name.into(), default))
+            .collect();
+        let ast = Parser::new(&expression, &variables)?.parse()?;
+        return Ok(Self {
+            expression,
+            variables,
+            ast,
+        });
+    }
+
+    /**
+    Returns the original expression string.
+     */
+    pub fn expression(&self) -> &str {
+        return self.expression.as_str();
+    }
+
+    /**
+    Returns the variables and their defaults.
+     */
+    pub fn variables(&self) -> &BTreeMap<String, DynQuantity<f64>> {
+        return &self.variables;
+    }
+
+    /**
+    Returns the units of the quantities which influence the variable quantity,
+    in the (alphabetical) order of the variables. Each unit is only listed once.
+
+    # Examples
+
+    ```
+    use std::str::FromStr;
+    use dyn_quantity::{DynQuantity, PredefUnit, Unit};
+    use var_quantity::expression::Expression;
+
+    let q = |s: &str| DynQuantity::<f64>::from_str(s).unwrap();
+
+    let fun = Expression::new(
+        "k * (T_winding - T_ambient)",
+        [("k", q("2 W/K")), ("T_winding", q("300 K")), ("T_ambient", q("300 K"))],
+    ).expect("valid expression");
+    assert_eq!(
+        fun.influencing_factor_units(),
+        vec![Unit::from(PredefUnit::Temperature), Unit::from(PredefUnit::Power) / Unit::from(PredefUnit::Temperature)]
+    );
+    assert_eq!(fun.output_unit(), Unit::from(PredefUnit::Power));
+    ```
+     */
+    pub fn influencing_factor_units(&self) -> Vec<Unit> {
+        let mut units: Vec<Unit> = Vec::with_capacity(self.variables.len());
+        for default in self.variables.values() {
+            if !units.contains(&default.unit) {
+                units.push(default.unit);
+            }
+        }
+        return units;
+    }
+
+    /**
+    Returns the unit which will be returned from [`QuantityFunction::call`].
+     */
+    pub fn output_unit(&self) -> Unit {
+        return self.ast.unit;
+    }
+}
+
+#[cfg_attr(feature = "serde", typetag::serde)]
+impl QuantityFunction for Expression {
+    fn call(&self, influencing_factors: &[DynQuantity<f64>]) -> DynQuantity<f64> {
+        let inputs: Vec<f64> = self
+            .variables
+            .values()
+            .map(|default| {
+                influencing_factors
+                    .iter()
+                    .find(|factor| factor.unit == default.unit)
+                    .unwrap_or(default)
+                    .value
+            })
+            .collect();
+        return DynQuantity::new(self.ast.evaluate(&inputs), self.ast.unit);
+    }
+
+    fn call_batch(&self, inputs: &[BatchInput], output: &mut [f64]) {
+        crate::batch::assert_lengths(inputs, output.len());
+
+        // Select the column of each variable only once
+        let columns: Vec<Result<&[f64], f64>> = self
+            .variables
+            .values()
+            .map(|default| {
+                inputs
+                    .iter()
+                    .find(|input| input.unit == default.unit)
+                    .map(|input| input.values)
+                    .ok_or(default.value)
+            })
+            .collect();
+        let mut values = vec![0.0; columns.len()];
+        for (index, out) in output.iter_mut().enumerate() {
+            for (value, column) in values.iter_mut().zip(columns.iter()) {
+                *value = match column {
+                    Ok(column) => column[index],
+                    Err(default) => *default,
+                };
+            }
+            *out = self.ast.evaluate(&values);
+        }
+    }
+
+    fn bind(&self, layout: &Layout) -> Box<dyn Evaluator + '_> {
+        // Resolve the index of each variable only once. Analogous to
+        // call_named, the variable names are only used for a named layout.
+        let columns: Vec<Result<usize, f64>> = self
+            .variables
+            .iter()
+            .map(|(name, default)| {
+                let name = layout.names().map(|_| name.as_str());
+                layout.position(default.unit, name).ok_or(default.value)
+            })
+            .collect();
+        return Box::new(move |values: &[f64]| {
+            let inputs: Vec<f64> = columns
+                .iter()
+                .map(|column| match column {
+                    Ok(index) => values[*index],
+                    Err(default) => *default,
+                })
+                .collect();
+            self.ast.evaluate(&inputs)
+        });
+    }
+
+    fn output_unit(&self) -> Unit {
+        return self.ast.unit;
+    }
+
+    fn influencing_units(&self) -> Option<Vec<Unit>> {
+        return Some(self.influencing_factor_units());
+    }
+
+    fn call_named(&self, influencing_factors: &[InfluencingFactor]) -> DynQuantity<f64> {
+        let inputs: Vec<f64> = self
+            .variables
+            .iter()
+            .map(|(name, default)| {
+                crate::select_influencing_factor(influencing_factors, Some(name), default.unit)
+                    .unwrap_or(*default)
+                    .value
+            })
+            .collect();
+        return DynQuantity::new(self.ast.evaluate(&inputs), self.ast.unit);
+    }
+
+    fn derivative(
+        &self,
+        influencing_factors: &[DynQuantity<f64>],
+        wrt: Unit,
+    ) -> Option<DynQuantity<f64>> {
+        let zero = DynQuantity::new(0.0, self.ast.unit / wrt);
+        let Some(index) = influencing_factors
+            .iter()
+            .position(|factor| factor.unit == wrt)
+        else {
+            return Some(zero);
+        };
+        let output = self.call_dual(&DualQuantity::seed(influencing_factors));
+        return Some(output.gradient.get(index).copied().unwrap_or(zero));
+    }
+
+    fn call_dual(&self, influencing_factors: &[DualQuantity]) -> DualQuantity {
+        let inputs: Vec<DualQuantity> = self
+            .variables
+            .values()
+            .map(|default| {
+                influencing_factors
+                    .iter()
+                    .find(|factor| factor.value.unit == default.unit)
+                    .cloned()
+                    .unwrap_or_else(|| DualQuantity::constant(*default))
+            })
+            .collect();
+        return self.ast.evaluate_dual(&inputs);
+    }
+}
+
+/// Functions with a single argument.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Function {
+    Exp,
+    Ln,
+    Sqrt,
+    Abs,
+}
+
+/// Node of the syntax tree of an [`Expression`].
+#[derive(Debug, Clone, PartialEq)]
+enum Node {
+    /// Raw value of a constant quantity.
+    Constant(f64),
+    /// Index of a variable in the (ordered) variable map.
+    Variable(usize),
+    Negate(Box<Expr>),
+    Add(Box<Expr>, Box<Expr>),
+    Subtract(Box<Expr>, Box<Expr>),
+    Multiply(Box<Expr>, Box<Expr>),
+    Divide(Box<Expr>, Box<Expr>),
+    /// Power with a constant integer exponent.
+    Powi(Box<Expr>, i32),
+    /// Power of dimensionless base and exponent.
+    Powf(Box<Expr>, Box<Expr>),
+    Function(Function, Box<Expr>),
+    Min(Box<Expr>, Box<Expr>),
+    Max(Box<Expr>, Box<Expr>),
+}
+
+/// Syntax tree node together with the unit of its value.
+#[derive(Debug, Clone, PartialEq)]
+struct Expr {
+    node: Node,
+    unit: Unit,
+}
+
+impl Expr {
+    /**
+    Creates a new expression. If it does not depend on any variable, it is
+    evaluated immediately and replaced by a constant.
+     */
+    fn new(node: Node, unit: Unit) -> Self {
+        let expr = Self { node, unit };
+        if expr.is_constant() {
+            return Self {
+                node: Node::Constant(expr.evaluate(&[])),
+                unit,
+            };
+        }
+        return expr;
+    }
+
+    /// Returns the constant value of the expression, if it is constant.
+    fn constant(&self) -> Option<f64> {
+        if let Node::Constant(value) = self.node {
+            return Some(value);
+        }
+        return None;
+    }
+
+    /// Returns true if the expression does not depend on any variable.
+    fn is_constant(&self) -> bool {
+        match &self.node {
+            Node::Constant(_) => return true,
+            Node::Variable(_) => return false,
+            Node::Negate(a) | Node::Powi(a, _) | Node::Function(_, a) => return a.is_constant(),
+            Node::Add(a, b)
+            | Node::Subtract(a, b)
+            | Node::Multiply(a, b)
+            | Node::Divide(a, b)
+            | Node::Powf(a, b)
+            | Node::Min(a, b)
+            | Node::Max(a, b) => return a.is_constant() && b.is_constant(),
+        }
+    }
+
+    /// Evaluates the expression with the raw values of the variables.
+    fn evaluate(&self, inputs: &[f64]) -> f64 {
+        match &self.node {
+            Node::Constant(value) => return *value,
+            Node::Variable(index) => return inputs[*index],
+            Node::Negate(a) => return -a.evaluate(inputs),
+            Node::Add(a, b) => return a.evaluate(inputs) + b.evaluate(inputs),
+            Node::Subtract(a, b) => return a.evaluate(inputs) - b.evaluate(inputs),
+            Node::Multiply(a, b) => return a.evaluate(inputs) * b.evaluate(inputs),
+            Node::Divide(a, b) => return a.evaluate(inputs) / b.evaluate(inputs),
+            Node::Powi(a, n) => return a.evaluate(inputs).powi(*n),
+            Node::Powf(a, b) => return a.evaluate(inputs).powf(b.evaluate(inputs)),
+            Node::Function(function, a) => {
+                let x = a.evaluate(inputs);
+                match function {
+                    Function::Exp => return x.exp(),
+                    Function::Ln => return x.ln(),
+                    Function::Sqrt => return x.sqrt(),
+                    Function::Abs => return x.abs(),
+                }
+            }
+            Node::Min(a, b) => return a.evaluate(inputs).min(b.evaluate(inputs)),
+            Node::Max(a, b) => return a.evaluate(inputs).max(b.evaluate(inputs)),
+        }
+    }
+
+    /**
+    Evaluates the expression with dual numbers. The units have been checked
+    during construction, hence the fallible operations cannot fail.
+     */
+    fn evaluate_dual(&self, inputs: &[DualQuantity]) -> DualQuantity {
+        const CHECKED: &str = "units are checked during construction";
+        match &self.node {
+            Node::Constant(value) => {
+                return DualQuantity::constant(DynQuantity::new(*value, self.unit));
+            }
+            Node::Variable(index) => return inputs[*index].clone(),
+            Node::Negate(a) => return -a.evaluate_dual(inputs),
+            Node::Add(a, b) => {
+                return a
+                    .evaluate_dual(inputs)
+                    .try_add(&b.evaluate_dual(inputs))
+                    .expect(CHECKED);
+            }
+            Node::Subtract(a, b) => {
+                return a
+                    .evaluate_dual(inputs)
+                    .try_sub(&b.evaluate_dual(inputs))
+                    .expect(CHECKED);
+            }
+            Node::Multiply(a, b) => return a.evaluate_dual(inputs) * b.evaluate_dual(inputs),
+            Node::Divide(a, b) => return a.evaluate_dual(inputs) / b.evaluate_dual(inputs),
+            Node::Powi(a, n) => return a.evaluate_dual(inputs).powi(*n),
+            Node::Powf(a, b) => {
+                let base = a.evaluate_dual(inputs);
+                let exponent = b.evaluate_dual(inputs);
+                if exponent.gradient.is_empty() {
+                    return base.try_powf(exponent.value.value).expect(CHECKED);
+                }
+                // x^y = exp(y * ln(x))
+                return (exponent * base.try_ln().expect(CHECKED))
+                    .try_exp()
+                    .expect(CHECKED);
+            }
+            Node::Function(function, a) => {
+                let x = a.evaluate_dual(inputs);
+                match function {
+                    Function::Exp => return x.try_exp().expect(CHECKED),
+                    Function::Ln => return x.try_ln().expect(CHECKED),
+                    Function::Sqrt => {
+                        let root = x.value.value.sqrt();
+                        return x.chain(
+                            DynQuantity::new(root, self.unit),
+                            DynQuantity::new(0.5 / root, self.unit / x.value.unit),
+                        );
+                    }
+                    Function::Abs => {
+                        let value = x.value.value;
+                        return x.chain(
+                            DynQuantity::new(value.abs(), self.unit),
+                            value.signum().into(),
+                        );
+                    }
+                }
+            }
+            Node::Min(a, b) | Node::Max(a, b) => {
+                let a = a.evaluate_dual(inputs);
+                let b = b.evaluate_dual(inputs);
+                let take_a = (a.value.value <= b.value.value) == matches!(self.node, Node::Min(..));
+                if take_a {
+                    return a;
+                }
+                return b;
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Number(f64),
+    Identifier(String),
+    Symbol(char),
+}
+
+/// Splits `expression` into tokens and their (character) positions.
+fn tokenize(expression: &str) -> Result<Vec<(usize, Token)>, ExpressionError> {
+    let chars: Vec<char> = expression.chars().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        let start = i;
+        if c.is_whitespace() {
+            i += 1;
+        } else if c.is_ascii_digit()
+            || (c == '.' && chars.get(i + 1).is_some_and(char::is_ascii_digit))
+        {
+            while i < chars.len() && (chars[i].is_ascii_digit() || chars[i] == '.') {
+                i += 1;
+            }
+            // Optional exponent, e.g. "1.7e-8"
+            if i < chars.len() && (chars[i] == 'e' || chars[i] == 'E') {
+                let sign = usize::from(matches!(chars.get(i + 1), Some('+') | Some('-')));
+                if chars.get(i + 1 + sign).is_some_and(char::is_ascii_digit) {
+                    i += 1 + sign;
+                    while i < chars.len() && chars[i].is_ascii_digit() {
+                        i += 1;
+                    }
+                }
+            }
+            let text: String = chars[start..i].iter().collect();
+            let value = text.parse::<f64>().map_err(|_| ExpressionError::Syntax {
+                position: start,
+                message: format!("invalid number `{}`", text),
+            })?;
+            tokens.push((start, Token::Number(value)));
+        } else if c == '%' {
+            i += 1;
+            tokens.push((start, Token::Identifier(c.to_string())));
+        } else if c.is_alphabetic() || c == '_' || c == '°' {
+            i += 1;
+            while i < chars.len() && (chars[i].is_alphanumeric() || chars[i] == '_') {
+                i += 1;
+            }
+            tokens.push((start, Token::Identifier(chars[start..i].iter().collect())));
+        } else if "+-*/^(),".contains(c) {
+            i += 1;
+            tokens.push((start, Token::Symbol(c)));
+        } else {
+            return Err(ExpressionError::Syntax {
+                position: start,
+                message: format!("unexpected character `{}`", c),
+            });
+        }
+    }
+    return Ok(tokens);
+}
+
+/**
+Recursive descent parser for the following grammar:
+
+```text
+expression := term (('+' | '-') term)*
+term       := unary (('*' | '/') unary)*
+unary      := '-' unary | power
+power      := atom ('^' unary)?
+atom       := number [unit] | identifier | function '(' arguments ')' | '(' expression ')'
+```
+
+The unit of every subexpression is determined during parsing.
+ */
+struct Parser<'a> {
+    tokens: Vec<(usize, Token)>,
+    index: usize,
+    end: usize,
+    variables: &'a BTreeMap<String, DynQuantity<f64>>,
+}
+
+impl<'a> Parser<'a> {
+    fn new(
+        expression: &str,
+        variables: &'a BTreeMap<String, DynQuantity<f64>>,
+    ) -> Result<Self, ExpressionError> {
+        return Ok(Self {
+            tokens: tokenize(expression)?,
+            index: 0,
+            end: expression.chars().count(),
+            variables,
+        });
+    }
+
+    /// Parses the whole token stream.
+    fn parse(mut self) -> Result<Expr, ExpressionError> {
+        let expr = self.expression()?;
+        if self.index < self.tokens.len() {
+            return Err(self.unexpected());
+        }
+        return Ok(expr);
+    }
+
+    fn peek(&self) -> Option<&Token> {
+        return self.tokens.get(self.index).map(|(_, token)| token);
+    }
+
+    /// Position of the current token (or the end of the expression).
+    fn position(&self) -> usize {
+        return self
+            .tokens
+            .get(self.index)
+            .map(|(position, _)| *position)
+            .unwrap_or(self.end);
+    }
+
+    /// Consumes the current token if it is the given symbol.
+    fn eat(&mut self, symbol: char) -> bool {
+        if self.peek() == Some(&Token::Symbol(symbol)) {
+            self.index += 1;
+            return true;
+        }
+        return false;
+    }
+
+    fn expect(&mut self, symbol: char) -> Result<(), ExpressionError> {
+        if self.eat(symbol) {
+            return Ok(());
+        }
+        return Err(ExpressionError::Syntax {
+            position: self.position(),
+            message: format!("expected `{}`", symbol),
+        });
+    }
+
+    /// Error for the current token, which is not allowed at its position.
+    fn unexpected(&self) -> ExpressionError {
+        let message = match self.peek() {
+            Some(Token::Number(value)) => format!("unexpected number `{}`", value),
+            Some(Token::Identifier(identifier)) => {
+                format!("unexpected identifier `{}`", identifier)
+            }
+            Some(Token::Symbol(symbol)) => format!("unexpected symbol `{}`", symbol),
+            None => "unexpected end of expression".to_string(),
+        };
+        return ExpressionError::Syntax {
+            position: self.position(),
+            message,
+        };
+    }
+
+    fn expression(&mut self) -> Result<Expr, ExpressionError> {
+        let mut left = self.term()?;
+        loop {
+            let position = self.position();
+            let subtract = if self.eat('+') {
+                false
+            } else if self.eat('-') {
+                true
+            } else {
+                return Ok(left);
+            };
+            let right = self.term()?;
+            let unit = same_unit(&left, &right, position)?;
+            let node = if subtract {
+                Node::Subtract(Box::new(left), Box::new(right))
+            } else {
+                Node::Add(Box::new(left), Box::new(right))
+            };
+            left = Expr::new(node, unit);
+        }
+    }
+
+    fn term(&mut self) -> Result<Expr, ExpressionError> {
+        let mut left = self.unary()?;
+        loop {
+            if self.eat('*') {
+                let right = self.unary()?;
+                let unit = left.unit * right.unit;
+                left = Expr::new(Node::Multiply(Box::new(left), Box::new(right)), unit);
+            } else if self.eat('/') {
+                let right = self.unary()?;
+                let unit = left.unit / right.unit;
+                left = Expr::new(Node::Divide(Box::new(left), Box::new(right)), unit);
+            } else {
+                return Ok(left);
+            }
+        }
+    }
+
+    fn unary(&mut self) -> Result<Expr, ExpressionError> {
+        if self.eat('-') {
+            let operand = self.unary()?;
+            let unit = operand.unit;
+            return Ok(Expr::new(Node::Negate(Box::new(operand)), unit));
+        }
+        return self.power();
+    }
+
+    fn power(&mut self) -> Result<Expr, ExpressionError> {
+        let base = self.atom()?;
+        let position = self.position();
+        if self.eat('^') {
+            let exponent = self.unary()?;
+            return power(base, exponent, position);
+        }
+        return Ok(base);
+    }
+
+    fn atom(&mut self) -> Result<Expr, ExpressionError> {
+        let position = self.position();
+        match self.peek().cloned() {
+            Some(Token::Number(value)) => {
+                self.index += 1;
+                return self.literal(value);
+            }
+            Some(Token::Identifier(identifier)) => {
+                self.index += 1;
+                return self.identifier(identifier, position);
+            }
+            Some(Token::Symbol('(')) => {
+                self.index += 1;
+                let expr = self.expression()?;
+                self.expect(')')?;
+                return Ok(expr);
+            }
+            _ => return Err(self.unexpected()),
+        }
+    }
+
+    /**
+    Parses a number which is optionally followed by a unit (and an integer
+    exponent of this unit), e.g. `293.15 K` or `2 m^2`. The quantity is parsed
+    as a whole by [`DynQuantity::from_str`] so that units with an offset like
+    `°C` are handled correctly.
+     */
+    fn literal(&mut self, value: f64) -> Result<Expr, ExpressionError> {
+        let position = self.position();
+        let Some(Token::Identifier(identifier)) = self.peek().cloned() else {
+            return Ok(Expr::new(Node::Constant(value), Unit::default()));
+        };
+        let is_call =
+            self.tokens.get(self.index + 1).map(|(_, token)| token) == Some(&Token::Symbol('('));
+        if self.variables.contains_key(&identifier) || is_call {
+            return Ok(Expr::new(Node::Constant(value), Unit::default()));
+        }
+        self.index += 1;
+
+        // Integer exponent of the unit, e.g. "m^2" or "m^-1"
+        let mut text = format!("{} {}", value, identifier);
+        let exponent = match self.tokens.get(self.index..self.index + 3) {
+            Some([(_, Token::Symbol('^')), (_, Token::Number(n)), ..]) => Some((*n, 2)),
+            Some(
+                [
+                    (_, Token::Symbol('^')),
+                    (_, Token::Symbol('-')),
+                    (_, Token::Number(n)),
+                ],
+            ) => Some((-*n, 3)),
+            _ => None,
+        };
+        if let Some((n, consumed)) = exponent
+            && n.fract() == 0.0
+        {
+            text.push_str(&format!("^{}", n));
+            self.index += consumed;
+        }
+
+        let quantity = DynQuantity::<f64>::from_str(&text).map_err(|_| {
+            ExpressionError::UnknownIdentifier {
+                position,
+                identifier,
+            }
+        })?;
+        return Ok(Expr::new(Node::Constant(quantity.value), quantity.unit));
+    }
+
+    /// Parses a variable, a function call or a unit.
+    fn identifier(&mut self, identifier: String, position: usize) -> Result<Expr, ExpressionError> {
+        if let Some(index) = self.variables.keys().position(|name| *name == identifier) {
+            let unit = self.variables[&identifier].unit;
+            return Ok(Expr {
+                node: Node::Variable(index),
+                unit,
+            });
+        }
+
+        let arity = match identifier.as_str() {
+            "exp" | "ln" | "sqrt" | "abs" => Some(1),
+            "pow" | "min" | "max" => Some(2),
+            _ => None,
+        };
+        if let Some(arity) = arity
+            && self.eat('(')
+        {
+            let mut arguments = vec![self.expression()?];
+            while self.eat(',') {
+                arguments.push(self.expression()?);
+            }
+            self.expect(')')?;
+            if arguments.len() != arity {
+                return Err(ExpressionError::Syntax {
+                    position,
+                    message: format!(
+                        "function `{}` expects {} argument(s), found {}",
+                        identifier,
+                        arity,
+                        arguments.len()
+                    ),
+                });
+            }
+            return function(&identifier, arguments, position);
+        }
+
+        let quantity = DynQuantity::<f64>::from_str(&identifier).map_err(|_| {
+            ExpressionError::UnknownIdentifier {
+                position,
+                identifier,
+            }
+        })?;
+        return Ok(Expr::new(Node::Constant(quantity.value), quantity.unit));
+    }
+}
+
+/// Checks that both operands have the same unit and returns it.
+fn same_unit(left: &Expr, right: &Expr, position: usize) -> Result<Unit, ExpressionError> {
+    if left.unit != right.unit {
+        return Err(ExpressionError::UnitsNotEqual {
+            position,
+            left: left.unit,
+            right: right.unit,
+        });
+    }
+    return Ok(left.unit);
+}
+
+/// Checks that the argument is dimensionless.
+fn dimensionless(argument: &Expr, position: usize) -> Result<(), ExpressionError> {
+    if !argument.unit.is_dimensionless() {
+        return Err(ExpressionError::NotDimensionless {
+            position,
+            unit: argument.unit,
+        });
+    }
+    return Ok(());
+}
+
+/// Creates the expression `base ^ exponent`.
+fn power(base: Expr, exponent: Expr, position: usize) -> Result<Expr, ExpressionError> {
+    dimensionless(&exponent, position)?;
+    if let Some(n) = exponent.constant()
+        && n.fract() == 0.0
+        && n.abs() <= i32::MAX as f64
+    {
+        let n = n as i32;
+        let unit = base.unit.powi(n);
+        return Ok(Expr::new(Node::Powi(Box::new(base), n), unit));
+    }
+    if !base.unit.is_dimensionless() {
+        return Err(ExpressionError::InvalidExponent {
+            position,
+            unit: base.unit,
+        });
+    }
+    return Ok(Expr::new(
+        Node::Powf(Box::new(base), Box::new(exponent)),
+        Unit::default(),
+    ));
+}
+
+/// Creates a function call expression. The number of arguments is checked by the caller.
+fn function(
+    name: &str,
+    mut arguments: Vec<Expr>,
+    position: usize,
+) -> Result<Expr, ExpressionError> {
+    if arguments.len() == 2 {
+        let second = arguments.remove(1);
+        let first = arguments.remove(0);
+        match name {
+            "pow" => return power(first, second, position),
+            "min" => {
+                let unit = same_unit(&first, &second, position)?;
+                return Ok(Expr::new(
+                    Node::Min(Box::new(first), Box::new(second)),
+                    unit,
+                ));
+            }
+            _ => {
+                let unit = same_unit(&first, &second, position)?;
+                return Ok(Expr::new(
+                    Node::Max(Box::new(first), Box::new(second)),
+                    unit,
+                ));
+            }
+        }
+    }
+
+    let argument = arguments.remove(0);
+    let (function, unit) = match name {
+        "exp" => {
+            dimensionless(&argument, position)?;
+            (Function::Exp, Unit::default())
+        }
+        "ln" => {
+            dimensionless(&argument, position)?;
+            (Function::Ln, Unit::default())
+        }
+        "sqrt" => {
+            let unit = argument
+                .unit
+                .try_nthroot(2)
+                .map_err(|error| ExpressionError::InvalidRoot { position, error })?;
+            (Function::Sqrt, unit)
+        }
+        _ => (Function::Abs, argument.unit),
+    };
+    return Ok(Expr::new(
+        Node::Function(function, Box::new(argument)),
+        unit,
+    ));
+}
+
+#[cfg(feature = "serde")]
+mod serde_impl {
+    use super::*;
+
+    use serde::de::{Deserialize, Deserializer};
+    use serde::ser::{Serialize, SerializeStruct, Serializer};
+
+    impl Serialize for Expression {
+        fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+        where
+            S: Serializer,
+        {
+            if self.variables.is_empty() {
+                return serializer.serialize_str(&self.expression);
+            }
+            let mut state = serializer.serialize_struct("Expression", 2)?;
+            state.serialize_field("expression", &self.expression)?;
+            state.serialize_field("variables", &self.variables)?;
+            return state.end();
+        }
+    }
+
+    impl<'de> Deserialize<'de> for Expression {
+        fn deserialize<D>(deserializer: D) -> Result<Expression, D::Error>
+        where
+            D: Deserializer<'de>,
+        {
+            #[derive(serde::Deserialize)]
+            #[serde(untagged)]
+            enum ExpressionAlias {
+                String(String),
+                Struct {
+                    expression: String,
+                    #[serde(default)]
+                    variables: BTreeMap<String, DynQuantity<f64>>,
+                },
+            }
+
+            let (expression, variables) = match ExpressionAlias::deserialize(deserializer)? {
+                ExpressionAlias::String(expression) => (expression, BTreeMap::new()),
+                ExpressionAlias::Struct {
+                    expression,
+                    variables,
+                } => (expression, variables),
+            };
+            return Self::new(expression, variables).map_err(serde::de::Error::custom);
+        }
+    }
+}
+
+// =============================================================================
+
+#[cfg(feature = "serde")]
+#[cfg_attr(feature = "serde", typetag::serde)]
+impl QuantityFunction for crate::ClampedQuantity<Expression> {
+    fn call(&self, influencing_factors: &[DynQuantity<f64>]) -> DynQuantity<f64> {
+        return self.call_clamped(influencing_factors);
+    }
+
+    fn output_unit(&self) -> Unit {
+        return self.inner().output_unit();
+    }
+
+    fn influencing_units(&self) -> Option<Vec<Unit>> {
+        return self.inner().influencing_units();
+    }
+
+    fn validity_ranges(&self) -> Vec<crate::validity::ValidityRange> {
+        return self.inner().validity_ranges();
+    }
+
+    fn call_named(&self, influencing_factors: &[InfluencingFactor]) -> DynQuantity<f64> {
+        return self.call_named_clamped(influencing_factors);
+    }
+
+    fn derivative(
+        &self,
+        influencing_factors: &[DynQuantity<f64>],
+        wrt: Unit,
+    ) -> Option<DynQuantity<f64>> {
+        return self.derivative_clamped(influencing_factors, wrt);
+    }
+
+    fn call_dual(&self, influencing_factors: &[DualQuantity]) -> DualQuantity {
+        return self.call_dual_clamped(influencing_factors);
+    }
+
+    fn call_batch(&self, inputs: &[crate::BatchInput], output: &mut [f64]) {
+        self.call_batch_clamped(inputs, output);
+    }
+
+    fn bind(&self, layout: &crate::Layout) -> Box<dyn crate::Evaluator + '_> {
+        return self.bind_clamped(layout);
+    }
+}
@@ -2,6 +2,7 @@
 #![deny(missing_docs)]
 
 use std::marker::PhantomData;
+use std::ops::{Add, Div, Mul, Sub};
 
 use dyn_quantity::{DynQuantity, Unit, UnitFromType, UnitsNotEqual};
 
@@ -12,7 +13,68 @@ pub use typetag;
 #[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
 
+pub mod batch;
+pub mod combinators;
+pub mod compiled;
+pub mod dual;
+pub mod error;
+#[cfg(feature = "from_str")]
+pub mod expression;
+pub mod multivariate;
 pub mod unary;
+pub mod validity;
+
+pub use batch::{BatchInput, filter_unary_batch};
+pub use compiled::{Evaluator, Layout, bind_unary};
+pub use dual::DualQuantity;
+pub use error::{
+    CompositionError, EvaluationError, RangeError, ReferenceError, SupportPointsError,
+};
+use validity::{
+    AtomicWarningRecord, ValidityPolicy, ValidityRange, ValidityWarning, WarningRecord,
+};
+
+#[cfg(feature = "from_str")]
+pub use error::ExpressionError;
+
+#[cfg(feature = "macros")]
+pub use var_quantity_macros::quantity_function;
+
+/**
+Items used by the code generated by [`quantity_function`]. Not part of the
+public API.
+ */
+#[cfg(feature = "macros")]
+#[doc(hidden)]
+pub mod __private {
+    pub use dyn_quantity::{DynQuantity, Unit};
+
+    use crate::InfluencingFactor;
+
+    /// Returns the value of the `occurrence`-th factor with the unit `unit`.
+    pub fn select_factor(
+        influencing_factors: &[DynQuantity<f64>],
+        unit: Unit,
+        occurrence: usize,
+    ) -> Option<f64> {
+        return influencing_factors
+            .iter()
+            .filter(|f| f.unit == unit)
+            .nth(occurrence)
+            .map(|f| f.value);
+    }
+
+    /// Returns the value of the factor with the name `name` and the unit
+    /// `unit`, analogous to [`select_influencing_factor`](crate::select_influencing_factor).
+    pub fn select_named_factor(
+        influencing_factors: &[InfluencingFactor],
+        name: &str,
+        unit: Unit,
+    ) -> Option<f64> {
+        return crate::select_influencing_factor(influencing_factors, Some(name), unit)
+            .map(|f| f.value);
+    }
+}
 
 /**
 This is a marker trait which defines trait bounds for all types `T` which can
@@ -104,6 +166,536 @@ pub trait QuantityFunction: dyn_clone::DynClone + Sync + Send + std::any::Any {
     [`QuantityFunction`] trait docstring for examples.
     */
     fn call(&self, influencing_factors: &[DynQuantity<f64>]) -> DynQuantity<f64>;
+
+    /**
+    Returns the [`Unit`] of the [`DynQuantity`] returned by
+    [`QuantityFunction::call`].
+
+    This method is used by [`FunctionWrapper::new`] to check whether the trait
+    object can be used for a quantity type `T`. The default implementation
+    simply calls [`QuantityFunction::call`] with an empty slice and returns
+    the unit of the result. Since this is both wasteful and potentially
+    unsound (the empty input could be handled by a special branch), it is
+    recommended to override this method if the output unit is known in advance.
+    All implementors of this trait provided by this crate do so.
+
+    # Examples
+
+    ```
+    use dyn_quantity::{DynQuantity, PredefUnit, Unit};
+    use var_quantity::QuantityFunction;
+
+    // The serde annotations are just here because the doctests of this crate use
+    // the serde feature - they are not needed if the serde feature is disabled.
+    #[derive(Clone, serde::Deserialize, serde::Serialize)]
+    struct Resistance;
+
+    // Again, the macro annotation is just here because of the serde feature
+    #[typetag::serde]
+    impl QuantityFunction for Resistance {
+        fn call(&self, influencing_factors: &[DynQuantity<f64>]) -> DynQuantity<f64> {
+            let temperature = influencing_factors
+                .iter()
+                .find(|f| f.unit == PredefUnit::Temperature.into())
+                .map(|f| f.value)
+                .unwrap_or(0.0);
+            return DynQuantity::new(1.0 + temperature / 100.0, PredefUnit::ElectricResistance);
+        }
+
+        fn output_unit(&self) -> Unit {
+            return PredefUnit::ElectricResistance.into();
+        }
+    }
+
+    assert_eq!(Resistance {}.output_unit(), Unit::from(PredefUnit::ElectricResistance));
+    ```
+    */
+    fn output_unit(&self) -> Unit {
+        return self.call(&[]).unit;
+    }
+
+    /**
+    Returns the units of all influencing factors which are read by
+    [`QuantityFunction::call`]. If none of them is given, the function falls
+    back to its default behaviour.
+
+    This method is meant for introspection, e.g. to check whether all factors
+    needed by a function are supplied (see
+    [`VarQuantity::missing_influencing_units`]). A function which does not
+    read any influencing factor (i.e. a constant) returns an empty vector. The
+    default implementation returns `None`, which means that the function does
+    not declare its inputs. All implementors of this trait provided by this
+    crate override this method.
+
+    # Examples
+
+    ```
+    use std::str::FromStr;
+    use dyn_quantity::{DynQuantity, PredefUnit, Unit};
+    use var_quantity::{QuantityFunction, unary::Linear};
+
+    let lin: Box<dyn QuantityFunction> = Box::new(Linear::new(
+        DynQuantity::from_str("0.5 ohm/K").unwrap(),
+        DynQuantity::from_str("1 ohm").unwrap(),
+    ));
+    assert_eq!(lin.influencing_units(), Some(vec![Unit::from(PredefUnit::Temperature)]));
+    ```
+    */
+    fn influencing_units(&self) -> Option<Vec<Unit>> {
+        return None;
+    }
+
+    /**
+    Returns the ranges of the influencing factors for which the function is
+    valid (e.g. the temperature range covered by the measurements a function
+    has been fitted to). An influencing factor without a range is considered
+    valid for all values.
+
+    The function itself does not enforce these ranges. Instead, they are
+    checked by a [`FunctionWrapper`] according to its
+    [`ValidityPolicy`](crate::validity::ValidityPolicy). The default
+    implementation returns an empty vector. The unary functions of this crate
+    return the range given via their `with_validity` method (e.g.
+    [`Linear::with_validity`](crate::unary::Linear::with_validity)) and the
+    combinators return the ranges of their functions.
+
+    # Examples
+
+    ```
+    use std::str::FromStr;
+    use dyn_quantity::DynQuantity;
+    use var_quantity::{QuantityFunction, unary::Linear, validity::ValidityRange};
+
+    let q = |s: &str| DynQuantity::<f64>::from_str(s).unwrap();
+    let range = ValidityRange::new(q("200 K"), q("500 K")).expect("valid range");
+
+    let lin: Box<dyn QuantityFunction> = Box::new(
+        Linear::new(q("0.5 ohm/K"), q("1 ohm")).with_validity(range.clone()).expect("units match")
+    );
+    assert_eq!(lin.validity_ranges(), vec![range]);
+    ```
+    */
+    fn validity_ranges(&self) -> Vec<ValidityRange> {
+        return Vec::new();
+    }
+
+    /**
+    Returns a quantity as a function of named `influencing_factors`.
+
+    In contrast to [`QuantityFunction::call`], each influencing factor carries
+    a name which can be used to disambiguate factors with the same unit (e.g.
+    the ambient temperature and the winding temperature of an electrical
+    machine). The default implementation discards the names and forwards the
+    quantities to [`QuantityFunction::call`]. The unary functions provided
+    by this crate can optionally be bound to a named input, see e.g.
+    [`Linear::with_input_name`](crate::unary::Linear::with_input_name).
+
+    # Examples
+
+    ```
+    use std::str::FromStr;
+    use dyn_quantity::{DynQuantity, PredefUnit};
+    use var_quantity::{InfluencingFactor, QuantityFunction, unary::Linear};
+
+    let lin = Linear::new(
+        DynQuantity::from_str("0.5 ohm/K").unwrap(),
+        DynQuantity::from_str("1 ohm").unwrap(),
+    ).with_input_name("winding_temperature");
+
+    let factors = [
+        InfluencingFactor::new("ambient_temperature", DynQuantity::new(2.0, PredefUnit::Temperature)),
+        InfluencingFactor::new("winding_temperature", DynQuantity::new(4.0, PredefUnit::Temperature)),
+    ];
+
+    // Only the winding temperature is used
+    assert_eq!(lin.call_named(&factors).value, 3.0);
+
+    // The unnamed call simply uses the first temperature
+    let quantities: Vec<_> = factors.iter().map(|f| f.quantity).collect();
+    assert_eq!(lin.call(&quantities).value, 2.0);
+    ```
+    */
+    fn call_named(&self, influencing_factors: &[InfluencingFactor]) -> DynQuantity<f64> {
+        return self.call(&named_quantities(influencing_factors));
+    }
+
+    /**
+    Fallible version of [`QuantityFunction::call`].
+
+    Some functions are only defined for a certain range of inputs (e.g. a
+    [`Lookup`](crate::unary::Lookup) table configured to reject extrapolation).
+    Their [`QuantityFunction::call`] implementation returns a quantity with a
+    NaN value for inputs outside of that range, while this method returns an
+    [`EvaluationError`] describing the problem. The default implementation
+    simply wraps the output of [`QuantityFunction::call`] in `Ok`.
+    */
+    fn try_call(
+        &self,
+        influencing_factors: &[DynQuantity<f64>],
+    ) -> Result<DynQuantity<f64>, EvaluationError> {
+        return Ok(self.call(influencing_factors));
+    }
+
+    /**
+    Fallible version of [`QuantityFunction::call_named`], see
+    [`QuantityFunction::try_call`]. The default implementation simply wraps
+    the output of [`QuantityFunction::call_named`] in `Ok`.
+    */
+    fn try_call_named(
+        &self,
+        influencing_factors: &[InfluencingFactor],
+    ) -> Result<DynQuantity<f64>, EvaluationError> {
+        return Ok(self.call_named(influencing_factors));
+    }
+
+    /**
+    Returns the partial derivative of [`QuantityFunction::call`] with respect
+    to the influencing factor with the unit `wrt`, evaluated at
+    `influencing_factors`. The unit of the returned quantity is the output unit
+    divided by `wrt`.
+
+    The default implementation approximates the derivative with a central
+    finite difference by perturbing the first factor whose unit is `wrt`. If
+    no such factor exists, `None` is returned. All implementors of this trait
+    provided by this crate override this method with an exact derivative (see
+    also [`filter_unary_derivative`]). In particular, they return a zero
+    derivative if the function does not depend on `wrt` or if the factor is
+    not given (since the function then falls back to its default behaviour).
+
+    # Examples
+
+    ```
+    use dyn_quantity::{DynQuantity, PredefUnit, Unit};
+    use var_quantity::QuantityFunction;
+
+    // The serde annotations are just here because the doctests of this crate use
+    // the serde feature - they are not needed if the serde feature is disabled.
+    #[derive(Clone, serde::Deserialize, serde::Serialize)]
+    struct Square;
+
+    // Again, the macro annotation is just here because of the serde feature
+    #[typetag::serde]
+    impl QuantityFunction for Square {
+        fn call(&self, influencing_factors: &[DynQuantity<f64>]) -> DynQuantity<f64> {
+            let x = influencing_factors.first().map(|f| f.value).unwrap_or(0.0);
+            return DynQuantity::new(x * x, PredefUnit::Area);
+        }
+    }
+
+    // Finite difference approximation of the derivative 2x
+    let x = DynQuantity::new(3.0, PredefUnit::Length);
+    let derivative = Square.derivative(&[x], PredefUnit::Length.into()).unwrap();
+    assert!((derivative.value - 6.0).abs() < 1e-6);
+    assert_eq!(derivative.unit, Unit::from(PredefUnit::Length));
+
+    // No factor with the requested unit
+    assert!(Square.derivative(&[x], PredefUnit::Time.into()).is_none());
+    ```
+    */
+    fn derivative(
+        &self,
+        influencing_factors: &[DynQuantity<f64>],
+        wrt: Unit,
+    ) -> Option<DynQuantity<f64>> {
+        let index = influencing_factors.iter().position(|f| f.unit == wrt)?;
+        let x = influencing_factors[index].value;
+
+        // Step size which balances the truncation and the rounding error
+        let step = f64::EPSILON.cbrt() * x.abs().max(1.0);
+        let mut factors = influencing_factors.to_vec();
+        factors[index].value = x + step;
+        let upper = self.call(&factors);
+        factors[index].value = x - step;
+        let lower = self.call(&factors);
+        return Some(DynQuantity::new(
+            (upper.value - lower.value) / (2.0 * step),
+            upper.unit / wrt,
+        ));
+    }
+
+    /**
+    Evaluates the function with dual numbers for forward-mode automatic
+    differentiation. The returned [`DualQuantity`] contains the same value as
+    [`QuantityFunction::call`] and its gradient with respect to the seeds of
+    the `influencing_factors` (see [`DualQuantity::seed`]).
+
+    The default implementation applies the chain rule using
+    [`QuantityFunction::derivative`] for the first factor of each unit (the
+    factor which is read by the unary functions of this crate). Hence, the
+    result is exact for all functions of this crate, but only a finite
+    difference approximation for custom functions which do not override
+    [`QuantityFunction::derivative`]. Alternatively, custom functions can opt
+    into exact derivatives by overriding this method and implementing their
+    relation with the arithmetic of [`DualQuantity`] (usually,
+    [`QuantityFunction::call`] then simply forwards to this method).
+
+    # Examples
+
+    ```
+    use dyn_quantity::{DynQuantity, PredefUnit, Unit};
+    use var_quantity::{DualQuantity, QuantityFunction};
+
+    // The serde annotations are just here because the doctests of this crate use
+    // the serde feature - they are not needed if the serde feature is disabled.
+    #[derive(Clone, serde::Deserialize, serde::Serialize)]
+    struct Losses;
+
+    impl Losses {
+        fn select(factors: &[DualQuantity], unit: PredefUnit) -> DualQuantity {
+            return factors
+                .iter()
+                .find(|f| f.value.unit == unit.into())
+                .cloned()
+                .unwrap_or(DualQuantity::constant(DynQuantity::new(0.0, unit)));
+        }
+    }
+
+    // Again, the macro annotation is just here because of the serde feature
+    #[typetag::serde]
+    impl QuantityFunction for Losses {
+        fn call(&self, influencing_factors: &[DynQuantity<f64>]) -> DynQuantity<f64> {
+            let factors: Vec<DualQuantity> =
+                influencing_factors.iter().cloned().map(DualQuantity::constant).collect();
+            return self.call_dual(&factors).value;
+        }
+
+        // p = k * f * B²
+        fn call_dual(&self, influencing_factors: &[DualQuantity]) -> DualQuantity {
+            let k = DualQuantity::constant(DynQuantity::new(
+                2.0,
+                Unit::from(PredefUnit::Power)
+                    / Unit::from(PredefUnit::Frequency)
+                    / Unit::from(PredefUnit::MagneticFluxDensity).powi(2),
+            ));
+            let b = Self::select(influencing_factors, PredefUnit::MagneticFluxDensity);
+            let f = Self::select(influencing_factors, PredefUnit::Frequency);
+            return k * f * b.powi(2);
+        }
+    }
+
+    let b = DynQuantity::new(1.5, PredefUnit::MagneticFluxDensity);
+    let f = DynQuantity::new(50.0, PredefUnit::Frequency);
+    let output = Losses.call_dual(&DualQuantity::seed(&[b, f]));
+    assert_eq!(output.value, DynQuantity::new(225.0, PredefUnit::Power));
+    assert_eq!(output.gradient[0].value, 300.0); // dp/dB = 2kfB
+    assert_eq!(output.gradient[1].value, 4.5); // dp/df = kB²
+    ```
+    */
+    fn call_dual(&self, influencing_factors: &[DualQuantity]) -> DualQuantity {
+        let values: Vec<DynQuantity<f64>> = influencing_factors.iter().map(|f| f.value).collect();
+        let mut output = DualQuantity::constant(self.call(&values));
+        for (i, factor) in influencing_factors.iter().enumerate() {
+            let unit = factor.value.unit;
+            if values[..i].iter().any(|v| v.unit == unit) {
+                continue;
+            }
+            if let Some(derivative) = self.derivative(&values, unit) {
+                output.accumulate(derivative, factor);
+            }
+        }
+        return output;
+    }
+
+    /**
+    Evaluates the function for a batch of influencing factors given as
+    columns (see the [`batch`] module) and writes the value of each output
+    into the corresponding entry of `output`. The `i`-th output is the value
+    of [`QuantityFunction::call`] for the `i`-th value of each [`BatchInput`],
+    given in [`QuantityFunction::output_unit`].
+
+    The default implementation simply calls [`QuantityFunction::call`] for
+    each element. All implementors of this trait provided by this crate
+    override this method to select the columns only once and to avoid the
+    dynamic dispatch per element (see e.g. [`filter_unary_batch`]).
+
+    # Panics
+
+    Panics if the length of a column in `inputs` differs from that of
+    `output`.
+
+    # Examples
+
+    ```
+    use dyn_quantity::{DynQuantity, PredefUnit};
+    use var_quantity::{BatchInput, QuantityFunction};
+
+    // The serde annotations are just here because the doctests of this crate use
+    // the serde feature - they are not needed if the serde feature is disabled.
+    #[derive(Clone, serde::Deserialize, serde::Serialize)]
+    struct Power;
+
+    // Again, the macro annotation is just here because of the serde feature
+    #[typetag::serde]
+    impl QuantityFunction for Power {
+        // p = U * I
+        fn call(&self, influencing_factors: &[DynQuantity<f64>]) -> DynQuantity<f64> {
+            let mut u = DynQuantity::new(0.0, PredefUnit::ElectricVoltage);
+            let mut i = DynQuantity::new(0.0, PredefUnit::ElectricCurrent);
+            for factor in influencing_factors.iter() {
+                if u.unit == factor.unit {
+                    u = *factor;
+                }
+                if i.unit == factor.unit {
+                    i = *factor;
+                }
+            }
+            return u * i;
+        }
+    }
+
+    let voltages = [1.0, 2.0, 3.0];
+    let currents = [4.0, 5.0, 6.0];
+    let mut output = [0.0; 3];
+    Power.call_batch(
+        &[
+            BatchInput::new(PredefUnit::ElectricVoltage, &voltages),
+            BatchInput::new(PredefUnit::ElectricCurrent, &currents),
+        ],
+        &mut output,
+    );
+    assert_eq!(output, [4.0, 10.0, 18.0]);
+    ```
+    */
+    fn call_batch(&self, inputs: &[BatchInput], output: &mut [f64]) {
+        batch::assert_lengths(inputs, output.len());
+        let mut factors: Vec<DynQuantity<f64>> = inputs
+            .iter()
+            .map(|input| DynQuantity::new(0.0, input.unit))
+            .collect();
+        for (index, out) in output.iter_mut().enumerate() {
+            for (factor, input) in factors.iter_mut().zip(inputs.iter()) {
+                factor.value = input.values[index];
+            }
+            *out = self.call(&factors).value;
+        }
+    }
+
+    /**
+    Binds the function to the [`Layout`] of the influencing factors the caller
+    will pass and returns an [`Evaluator`] which reads the raw values directly
+    by index (see the [`compiled`] module). The factors needed by the function
+    are looked up only once during binding instead of on every call, which
+    makes the evaluator suitable for tight loops. For the same influencing
+    factors, [`Evaluator::eval`] returns the value of
+    [`QuantityFunction::call`] (or [`QuantityFunction::call_named`] if the
+    layout is named).
+
+    The default implementation returns an evaluator which converts the raw
+    values into [`DynQuantity`] (or [`InfluencingFactor`]) and then forwards
+    to the checked API. All implementors of this trait provided by this crate
+    which select their factors by unit override this method to resolve the
+    lookup once (see e.g. [`bind_unary`]).
+
+    # Examples
+
+    ```
+    use std::str::FromStr;
+    use dyn_quantity::{DynQuantity, PredefUnit};
+    use var_quantity::{Layout, QuantityFunction, unary::Linear, combinators::Product};
+
+    let q = |s: &str| DynQuantity::<f64>::from_str(s).unwrap();
+
+    // Resistance times current
+    let fun = Product::new(vec![
+        Box::new(Linear::new(q("0.01 ohm/K"), q("1 ohm"))),
+        Box::new(Linear::new(q("1"), q("0 A"))),
+    ]);
+
+    let layout = Layout::new(vec![PredefUnit::Temperature.into(), PredefUnit::ElectricCurrent.into()]);
+    let evaluator = fun.bind(&layout);
+    assert_eq!(evaluator.eval(&[300.0, 2.0]), 8.0);
+    assert_eq!(evaluator.eval(&[300.0, 2.0]), fun.call(&[q("2 A"), q("300 K")]).value);
+    ```
+    */
+    fn bind(&self, layout: &Layout) -> Box<dyn Evaluator + '_> {
+        return Box::new(compiled::CheckedEvaluator::new(self, layout));
+    }
+}
+
+/**
+A named influencing factor which is used as input for
+[`QuantityFunction::call_named`].
+
+The name allows to distinguish between multiple factors with the same unit,
+e.g. the ambient temperature and the winding temperature of an electrical
+machine or a dimensionless slip and a dimensionless duty cycle (all
+dimensionless factors share the unit [`Unit::default`]).
+
+# Features
+
+This struct can be serialized / deserialized if the `serde` feature is enabled.
+ */
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct InfluencingFactor {
+    /// Name of the influencing factor.
+    pub name: String,
+    /// Value of the influencing factor.
+    pub quantity: DynQuantity<f64>,
+}
+
+impl InfluencingFactor {
+    /**
+    Creates a new instance of [`InfluencingFactor`].
+
+    ```
+    use dyn_quantity::{DynQuantity, PredefUnit};
+    use uom::si::{f64::ThermodynamicTemperature, thermodynamic_temperature::kelvin};
+    use var_quantity::InfluencingFactor;
+
+    let factor = InfluencingFactor::new("ambient_temperature", ThermodynamicTemperature::new::<kelvin>(300.0));
+    assert_eq!(factor.name, "ambient_temperature");
+    assert_eq!(factor.quantity, DynQuantity::new(300.0, PredefUnit::Temperature));
+    ```
+     */
+    pub fn new<N: Into<String>, Q: Into<DynQuantity<f64>>>(name: N, quantity: Q) -> Self {
+        return Self {
+            name: name.into(),
+            quantity: quantity.into(),
+        };
+    }
+}
+
+/// Gradient of a constant with the unit `output_unit`.
+fn zero_gradient(
+    output_unit: Unit,
+    influencing_factors: &[DynQuantity<f64>],
+) -> Vec<DynQuantity<f64>> {
+    return influencing_factors
+        .iter()
+        .map(|f| DynQuantity::new(0.0, output_unit / f.unit))
+        .collect();
+}
+
+/// Strips the names from a slice of [`InfluencingFactor`].
+fn named_quantities(influencing_factors: &[InfluencingFactor]) -> Vec<DynQuantity<f64>> {
+    return influencing_factors.iter().map(|f| f.quantity).collect();
+}
+
+/// Unified access to unnamed and named influencing factors.
+trait HasQuantity {
+    fn quantity(&self) -> &DynQuantity<f64>;
+    fn quantity_mut(&mut self) -> &mut DynQuantity<f64>;
+}
+
+impl HasQuantity for DynQuantity<f64> {
+    fn quantity(&self) -> &DynQuantity<f64> {
+        return self;
+    }
+
+    fn quantity_mut(&mut self) -> &mut DynQuantity<f64> {
+        return self;
+    }
+}
+
+impl HasQuantity for InfluencingFactor {
+    fn quantity(&self) -> &DynQuantity<f64> {
+        return &self.quantity;
+    }
+
+    fn quantity_mut(&mut self) -> &mut DynQuantity<f64> {
+        return &mut self.quantity;
+    }
 }
 
 /**
@@ -116,17 +708,25 @@ docstring, the unit of the [`DynQuantity`] returned by [`QuantityFunction::call`
 must always be the same. Even though this can unfortunately not be represented
 by the type system for reasons outlined in the trait docstring, this wrapper
 provides some checks to reduce the likelihood of wrong units:
-- When constructing the wrapper via [`FunctionWrapper::new`], it runs
-[`QuantityFunction::call`] once with an empty slice and checks that the output unit
-matches that of [`T::unit_from_type`](UnitFromType::unit_from_type). If that is
-not the case, the construction fails and an error is returned.
+- When constructing the wrapper via [`FunctionWrapper::new`], it checks that
+the unit declared by [`QuantityFunction::output_unit`] matches that of
+[`T::unit_from_type`](UnitFromType::unit_from_type). If that is not the case,
+the construction fails and an error is returned.
 - When calling the underlying function via [`FunctionWrapper::call`], it tries
 to convert the [`DynQuantity<f64>`] delivered from [`QuantityFunction::call`]
 into `T`. If that fails, the implementation of [`QuantityFunction`] violates
 the requirement outlined in the trait documentation. This is a bug, hence the
-function panics.
+function panics. In contexts where panics are unacceptable (e.g. when the
+trait object has been deserialized from user input),
+[`FunctionWrapper::try_call`] can be used instead, which returns an
+[`EvaluationError`] in that case.
+
+Additionally, the wrapper can check the influencing factors against the
+[`QuantityFunction::validity_ranges`] of the trait object before evaluating it.
+How out-of-range factors are handled is defined by the [`ValidityPolicy`] set
+via [`FunctionWrapper::with_validity_policy`]. By default, the ranges are
+ignored. See the [`validity`] module for more.
 
-This struct has the same memory representation as [`Box<dyn QuantityFunction>`].
 The underlying trait object can be accessed directly via [`FunctionWrapper::inner`].
 
 # Features
@@ -135,18 +735,25 @@ This struct can be serialized / deserialized if the `serde` feature is enabled.
 Since it is just a wrapper around a `Box<dyn QuantityFunction>` trait object,
 it serializes directly to the representation of that object and deserializes
 directly from it (it is["transparent"](https://serde.rs/container-attrs.html#transparent)).
+Therefore, the [`ValidityPolicy`] is not serialized and a deserialized wrapper
+always uses [`ValidityPolicy::Ignore`].
  */
 pub struct FunctionWrapper<T: IsQuantity> {
     function: Box<dyn QuantityFunction>,
+    policy: ValidityPolicy,
+    // Cached output of QuantityFunction::validity_ranges, only populated if
+    // the policy is not ValidityPolicy::Ignore
+    validity_ranges: Vec<ValidityRange>,
+    // One record per entry of validity_ranges
+    warnings: Vec<AtomicWarningRecord>,
     phantom: PhantomData<T>,
 }
 
 impl<T: IsQuantity> FunctionWrapper<T> {
     /**
-    Creates a new instance of `Self` and performs a type safety check by running
-    the [`QuantityFunction::call`] of `function` with an empty slice as
-    `influencing_factors`. The unit of the resulting [`DynQuantity`] is then
-    compared to that created by [`T::unit_from_type`](UnitFromType::unit_from_type).
+    Creates a new instance of `Self` and performs a type safety check by
+    comparing the unit returned by [`QuantityFunction::output_unit`] of
+    `function` to that created by [`T::unit_from_type`](UnitFromType::unit_from_type).
     If they don't match, an error is returned. See the docstring of
     [`FunctionWrapper`] for more.
 
@@ -180,19 +787,147 @@ impl<T: IsQuantity> FunctionWrapper<T> {
     ```
      */
     pub fn new(function: Box<dyn QuantityFunction>) -> Result<Self, UnitsNotEqual> {
-        // Call the function w/o any arguments and make sure the returned
-        // DynQuantity<f64> is convertible to T
-        let actual = function.call(&[]).unit;
+        // Make sure the declared output unit is convertible to T
+        let actual = function.output_unit();
         let expected = T::unit_from_type();
         if actual != expected {
             return Err(UnitsNotEqual(expected, actual));
         }
-        return Ok(Self {
+        return Ok(Self::new_unchecked(function));
+    }
+
+    /**
+    Creates a new instance of `Self` without checking the output unit of
+    `function`.
+     */
+    fn new_unchecked(function: Box<dyn QuantityFunction>) -> Self {
+        return Self {
             function,
+            policy: ValidityPolicy::default(),
+            validity_ranges: Vec::new(),
+            warnings: Vec::new(),
             phantom: PhantomData,
+        };
+    }
+
+    /**
+    Sets the [`ValidityPolicy`] which defines how influencing factors outside
+    of the [`QuantityFunction::validity_ranges`] of the wrapped trait object
+    are handled by [`FunctionWrapper::call`], [`FunctionWrapper::try_call`],
+    [`FunctionWrapper::call_named`] and [`FunctionWrapper::try_call_named`].
+    For each range, the first factor with a matching unit (and name, for
+    named evaluations of ranges bound to a name) is checked. Factors which
+    are not given are not checked. Derivatives are not affected by the policy.
+
+    See the [`validity`] module for an example.
+     */
+    pub fn with_validity_policy(mut self, policy: ValidityPolicy) -> Self {
+        self.policy = policy;
+        self.validity_ranges = match policy {
+            ValidityPolicy::Ignore => Vec::new(),
+            _ => self.function.validity_ranges(),
+        };
+        self.warnings = self
+            .validity_ranges
+            .iter()
+            .map(AtomicWarningRecord::new)
+            .collect();
+        return self;
+    }
+
+    /**
+    Returns the [`ValidityPolicy`] of the wrapper. See
+    [`FunctionWrapper::with_validity_policy`].
+     */
+    pub fn validity_policy(&self) -> ValidityPolicy {
+        return self.policy;
+    }
+
+    /**
+    Returns all warnings recorded since the last call of this method and
+    clears them. Warnings are only recorded if the policy is
+    [`ValidityPolicy::Warn`]. They are aggregated per validity range, hence
+    at most one [`ValidityWarning`] is returned per range, which contains the
+    number of evaluations with an influencing factor outside of the range and
+    the farthest outlier.
+     */
+    pub fn take_warnings(&self) -> Vec<ValidityWarning> {
+        return self
+            .warnings
+            .iter()
+            .zip(self.validity_ranges.iter())
+            .filter_map(|(record, range)| record.take(range))
+            .collect();
+    }
+
+    /**
+    Applies the validity policy to `influencing_factors`. The closure `select`
+    returns the index of the factor which is checked against a range. If the
+    factors are modified (clamped), the modified factors are returned.
+     */
+    fn validate<F, S>(
+        &self,
+        influencing_factors: &[F],
+        select: S,
+    ) -> Result<Option<Vec<F>>, EvaluationError>
+    where
+        F: HasQuantity + Clone,
+        S: Fn(&ValidityRange) -> Option<usize>,
+    {
+        let mut clamped: Option<Vec<F>> = None;
+        for (position, range) in self.validity_ranges.iter().enumerate() {
+            let Some(index) = select(range) else {
+                continue;
+            };
+            let Err(err) = range.check(influencing_factors[index].quantity()) else {
+                continue;
+            };
+            match self.policy {
+                ValidityPolicy::Ignore => (),
+                ValidityPolicy::Clamp => {
+                    let factors = clamped.get_or_insert_with(|| influencing_factors.to_vec());
+                    let quantity = factors[index].quantity_mut();
+                    *quantity = range.clamp(*quantity);
+                }
+                ValidityPolicy::Error => return Err(err),
+                ValidityPolicy::Warn => self.warnings[position].add(
+                    range,
+                    1,
+                    influencing_factors[index].quantity().value,
+                ),
+            }
+        }
+        return Ok(clamped);
+    }
+
+    fn validate_unnamed(
+        &self,
+        influencing_factors: &[DynQuantity<f64>],
+    ) -> Result<Option<Vec<DynQuantity<f64>>>, EvaluationError> {
+        return self.validate(influencing_factors, |range| {
+            influencing_factors
+                .iter()
+                .position(|f| f.unit == range.unit())
+        });
+    }
+
+    fn validate_named(
+        &self,
+        influencing_factors: &[InfluencingFactor],
+    ) -> Result<Option<Vec<InfluencingFactor>>, EvaluationError> {
+        return self.validate(influencing_factors, |range| {
+            influencing_factors.iter().position(|f| {
+                f.quantity.unit == range.unit()
+                    && range.input_name().is_none_or(|name| f.name == name)
+            })
         });
     }
 
+    /// Output of the infallible evaluation methods for invalid inputs.
+    fn invalid_output() -> DynQuantity<f64> {
+        return DynQuantity::new(f64::NAN, T::unit_from_type());
+    }
+
     /**
     Forwards the input to the [`QuantityFunction::call`] method of the wrapped
     trait object and asserts that the returned value can be converted to `T`.
@@ -251,7 +986,8 @@ impl<T: IsQuantity> FunctionWrapper<T> {
         }
     }
 
-    // Construction succeeds since the test call is done with an empty slice
+    // Construction succeeds since the default implementation of output_unit
+    // calls the function with an empty slice
     let wrapped_resistance = FunctionWrapper::<ElectricalResistance>::new(Box::new(Resistance {})).expect("units match");
 
     // ... but calling with a quantity results in a panic
@@ -259,38 +995,369 @@ impl<T: IsQuantity> FunctionWrapper<T> {
     ```
      */
     pub fn call(&self, influencing_factors: &[DynQuantity<f64>]) -> T {
-        match T::try_from(self.function.call(influencing_factors).into()) {
+        let output = match self.validate_unnamed(influencing_factors) {
+            Ok(None) => self.function.call(influencing_factors),
+            Ok(Some(clamped)) => self.function.call(&clamped),
+            Err(_) => Self::invalid_output(),
+        };
+        match Self::convert(output, || influencing_factors.to_vec()) {
             Ok(val) => val,
-            Err(_) => {
-                panic!(
-                    "conversion from DynQuantity<f64> to T failed for input {:?}.\n
-                    This means that the QuantityFunction trait object returns
-                    different DynQuantity<f64> depending on the input, which
-                    is a bug in the implementation of the trait object.",
-                    influencing_factors
-                )
-            }
+            Err(err) => panic!("{err}"),
         }
     }
 
     /**
-    Returns the underlying [`QuantityFunction`] trait object.
-     */
-    pub fn inner(&self) -> &dyn QuantityFunction {
-        return &*self.function;
-    }
-}
+    Forwards the input to the [`QuantityFunction::try_call`] method of the
+    wrapped trait object and tries to convert the returned value to `T`. This
+    is the non-panicking version of [`FunctionWrapper::call`]: If the
+    conversion fails, an [`EvaluationError::UnitMismatch`] containing the
+    returned quantity, the expected unit and the influencing factors is
+    returned. Errors returned by the trait object itself (e.g. because an
+    input is outside of the domain of the function) are forwarded.
 
-impl<T: IsQuantity> std::fmt::Debug for FunctionWrapper<T> {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        f.debug_tuple("FunctionWrapper").finish()
-    }
-}
+    # Examples
 
-impl<T: IsQuantity> Clone for FunctionWrapper<T> {
-    fn clone(&self) -> Self {
+    ```
+    use dyn_quantity::{DynQuantity, PredefUnit, Unit};
+    use var_quantity::{EvaluationError, QuantityFunction, FunctionWrapper};
+    use uom::si::f64::{ElectricalResistance};
+
+    // The serde annotations are just here because the doctests of this crate use
+    // the serde feature - they are not needed if the serde feature is disabled.
+    #[derive(Clone, serde::Deserialize, serde::Serialize)]
+    struct Resistance;
+
+    // Again, the macro annotation is just here because of the serde feature
+    #[typetag::serde]
+    impl QuantityFunction for Resistance {
+        fn call(&self, influencing_factors: &[DynQuantity<f64>]) -> DynQuantity<f64> {
+            if influencing_factors.len() == 0 {
+                return DynQuantity::new(1.0, PredefUnit::ElectricResistance);
+            } else {
+                return DynQuantity::new(1.0, PredefUnit::None);
+            }
+        }
+    }
+
+    let wrapped_resistance = FunctionWrapper::<ElectricalResistance>::new(Box::new(Resistance {})).expect("units match");
+    assert!(wrapped_resistance.try_call(&[]).is_ok());
+
+    let factors = [DynQuantity::new(1.0, PredefUnit::None)];
+    let err = wrapped_resistance.try_call(&factors).unwrap_err();
+    assert_eq!(err, EvaluationError::UnitMismatch {
+        output: DynQuantity::new(1.0, PredefUnit::None),
+        expected: PredefUnit::ElectricResistance.into(),
+        influencing_factors: factors.to_vec(),
+    });
+    ```
+     */
+    pub fn try_call(&self, influencing_factors: &[DynQuantity<f64>]) -> Result<T, EvaluationError> {
+        let output = match self.validate_unnamed(influencing_factors)? {
+            None => self.function.try_call(influencing_factors)?,
+            Some(clamped) => self.function.try_call(&clamped)?,
+        };
+        return Self::convert(output, || influencing_factors.to_vec());
+    }
+
+    /**
+    Like [`FunctionWrapper::call`], but forwards the named input to the
+    [`QuantityFunction::call_named`] method of the wrapped trait object.
+     */
+    pub fn call_named(&self, influencing_factors: &[InfluencingFactor]) -> T {
+        let output = match self.validate_named(influencing_factors) {
+            Ok(None) => self.function.call_named(influencing_factors),
+            Ok(Some(clamped)) => self.function.call_named(&clamped),
+            Err(_) => Self::invalid_output(),
+        };
+        match Self::convert(output, || named_quantities(influencing_factors)) {
+            Ok(val) => val,
+            Err(err) => panic!("{err}"),
+        }
+    }
+
+    /**
+    Like [`FunctionWrapper::try_call`], but forwards the named input to the
+    [`QuantityFunction::try_call_named`] method of the wrapped trait object.
+     */
+    pub fn try_call_named(
+        &self,
+        influencing_factors: &[InfluencingFactor],
+    ) -> Result<T, EvaluationError> {
+        let output = match self.validate_named(influencing_factors)? {
+            None => self.function.try_call_named(influencing_factors)?,
+            Some(clamped) => self.function.try_call_named(&clamped)?,
+        };
+        return Self::convert(output, || named_quantities(influencing_factors));
+    }
+
+    /**
+    Forwards the input to the [`QuantityFunction::call_batch`] method of the
+    wrapped trait object. The values written into `output` are given in the SI
+    base units of `T`, which is the output unit of the trait object (this is
+    checked in [`FunctionWrapper::new`], hence the values are not converted
+    individually).
+
+    The [`ValidityPolicy`] is applied per element of the batch: With
+    [`ValidityPolicy::Clamp`], the affected columns are clamped before the
+    evaluation. With [`ValidityPolicy::Error`], the output of each element with
+    an influencing factor outside of its range is NaN. With
+    [`ValidityPolicy::Warn`], each such element is counted in the warning of
+    the range (see [`FunctionWrapper::take_warnings`]).
+
+    # Panics
+
+    Panics if the length of a column in `inputs` differs from that of
+    `output`.
+
+    # Examples
+
+    ```
+    use std::str::FromStr;
+    use dyn_quantity::{DynQuantity, PredefUnit};
+    use uom::si::f64::ElectricalResistance;
+    use var_quantity::{BatchInput, FunctionWrapper, unary::Linear, validity::{ValidityPolicy, ValidityRange}};
+
+    let q = |s: &str| DynQuantity::<f64>::from_str(s).unwrap();
+
+    let fun = Linear::new(q("0.01 ohm/K"), q("1 ohm"))
+        .with_validity(ValidityRange::new(q("200 K"), q("500 K")).expect("valid range"))
+        .expect("units match");
+    let wrapper = FunctionWrapper::<ElectricalResistance>::new(Box::new(fun))
+        .expect("units match")
+        .with_validity_policy(ValidityPolicy::Clamp);
+
+    let mut resistances = [0.0; 3];
+    wrapper.call_batch(
+        &[BatchInput::new(PredefUnit::Temperature, &[100.0, 300.0, 600.0])],
+        &mut resistances,
+    );
+    assert_eq!(resistances, [3.0, 4.0, 6.0]);
+    ```
+     */
+    pub fn call_batch(&self, inputs: &[BatchInput], output: &mut [f64]) {
+        if self.policy == ValidityPolicy::Ignore || self.validity_ranges.is_empty() {
+            return self.function.call_batch(inputs, output);
+        }
+        batch::assert_lengths(inputs, output.len());
+
+        // Analogous to FunctionWrapper::validate, but per column
+        let mut clamped: Vec<Option<Vec<f64>>> = vec![None; inputs.len()];
+        let mut invalid: Vec<usize> = Vec::new();
+        for (position, range) in self.validity_ranges.iter().enumerate() {
+            let unit = range.unit();
+            let Some(index) = inputs.iter().position(|input| input.unit == unit) else {
+                continue;
+            };
+            // Aggregate the warning of the range locally and record it once
+            let mut warning = WarningRecord::default();
+            for (element, value) in inputs[index].values.iter().enumerate() {
+                if range.contains(&DynQuantity::new(*value, unit)) {
+                    continue;
+                }
+                match self.policy {
+                    ValidityPolicy::Ignore => (),
+                    ValidityPolicy::Clamp => {
+                        let column =
+                            clamped[index].get_or_insert_with(|| inputs[index].values.to_vec());
+                        column[element] =
+                            range.clamp(DynQuantity::new(column[element], unit)).value;
+                    }
+                    ValidityPolicy::Error => invalid.push(element),
+                    ValidityPolicy::Warn => warning.add(range, *value),
+                }
+            }
+            warning.merge_into(&self.warnings[position], range);
+        }
+
+        let inputs: Vec<BatchInput> = inputs
+            .iter()
+            .zip(clamped.iter())
+            .map(|(input, column)| match column {
+                Some(values) => BatchInput::new(input.unit, values),
+                None => *input,
+            })
+            .collect();
+        self.function.call_batch(&inputs, output);
+        for element in invalid {
+            output[element] = f64::NAN;
+        }
+    }
+
+    /**
+    Binds the wrapped trait object to `layout` via [`QuantityFunction::bind`]
+    and returns the resulting [`Evaluator`] (see the [`compiled`] module). The
+    values passed to [`Evaluator::eval`] are given in the order and units of
+    `layout` and the output is given in the SI base units of `T`.
+
+    The positions of the factors checked by the [`ValidityPolicy`] are
+    resolved once during binding as well. If the policy is not
+    [`ValidityPolicy::Ignore`], the values are checked on each evaluation
+    analogous to [`FunctionWrapper::call`]: With [`ValidityPolicy::Clamp`],
+    out-of-range values are clamped. With [`ValidityPolicy::Error`], the
+    output is NaN. With [`ValidityPolicy::Warn`], the violation is counted in
+    the warning of the range via atomic operations, hence the evaluator never
+    locks.
+
+    # Examples
+
+    ```
+    use std::str::FromStr;
+    use dyn_quantity::{DynQuantity, PredefUnit};
+    use uom::si::f64::ElectricalResistance;
+    use var_quantity::{FunctionWrapper, Layout, unary::Linear, validity::{ValidityPolicy, ValidityRange}};
+
+    let q = |s: &str| DynQuantity::<f64>::from_str(s).unwrap();
+
+    let fun = Linear::new(q("0.01 ohm/K"), q("1 ohm"))
+        .with_validity(ValidityRange::new(q("200 K"), q("500 K")).expect("valid range"))
+        .expect("units match");
+    let wrapper = FunctionWrapper::<ElectricalResistance>::new(Box::new(fun))
+        .expect("units match")
+        .with_validity_policy(ValidityPolicy::Clamp);
+
+    let evaluator = wrapper.bind(&Layout::new(vec![PredefUnit::Temperature.into()]));
+    assert_eq!(evaluator.eval(&[100.0]), 3.0);
+    assert_eq!(evaluator.eval(&[300.0]), 4.0);
+    assert_eq!(evaluator.eval(&[600.0]), 6.0);
+    ```
+     */
+    pub fn bind(&self, layout: &Layout) -> Box<dyn Evaluator + '_> {
+        let evaluator = self.function.bind(layout);
+        if self.policy == ValidityPolicy::Ignore || self.validity_ranges.is_empty() {
+            return evaluator;
+        }
+
+        // Analogous to FunctionWrapper::validate, but with pre-resolved positions
+        let ranges: Vec<(usize, usize, &ValidityRange)> = self
+            .validity_ranges
+            .iter()
+            .enumerate()
+            .filter_map(|(position, range)| {
+                layout
+                    .position(range.unit(), range.input_name())
+                    .map(|index| (index, position, range))
+            })
+            .collect();
+        // Capture the fields instead of self, since T is not necessarily Sync
+        let policy = self.policy;
+        let warnings = &self.warnings;
+        return Box::new(move |values: &[f64]| {
+            let mut clamped: Option<Vec<f64>> = None;
+            for (index, position, range) in ranges.iter() {
+                let quantity = DynQuantity::new(values[*index], range.unit());
+                if range.contains(&quantity) {
+                    continue;
+                }
+                match policy {
+                    ValidityPolicy::Ignore => (),
+                    ValidityPolicy::Clamp => {
+                        let values = clamped.get_or_insert_with(|| values.to_vec());
+                        values[*index] = range.clamp(quantity).value;
+                    }
+                    ValidityPolicy::Error => return f64::NAN,
+                    ValidityPolicy::Warn => warnings[*position].add(range, 1, quantity.value),
+                }
+            }
+            match clamped {
+                Some(values) => return evaluator.eval(&values),
+                None => return evaluator.eval(values),
+            }
+        });
+    }
+
+    /**
+    Converts the `output` of the wrapped trait object into `T`. The closure
+    `influencing_factors` is only evaluated in case of an error.
+     */
+    fn convert<U, F>(output: DynQuantity<f64>, influencing_factors: F) -> Result<U, EvaluationError>
+    where
+        U: IsQuantity,
+        F: FnOnce() -> Vec<DynQuantity<f64>>,
+    {
+        return U::try_from(output.into()).map_err(|_| EvaluationError::UnitMismatch {
+            output,
+            expected: U::unit_from_type(),
+            influencing_factors: influencing_factors(),
+        });
+    }
+
+    /**
+    Forwards the input to the [`QuantityFunction::derivative`] method of the
+    wrapped trait object (with the unit of `X` as `wrt`) and converts the
+    result to the quotient type of `T` and `X`.
+
+    # Panics
+
+    Panics if the returned derivative cannot be converted, which means that
+    the implementation of [`QuantityFunction::derivative`] returned a wrong
+    unit (see [`FunctionWrapper::call`]).
+     */
+    pub fn derivative<X>(
+        &self,
+        influencing_factors: &[DynQuantity<f64>],
+    ) -> Option<<T as Div<X>>::Output>
+    where
+        T: Div<X>,
+        X: IsQuantity,
+        <T as Div<X>>::Output: IsQuantity,
+    {
+        let output = self
+            .function
+            .derivative(influencing_factors, X::unit_from_type())?;
+        match Self::convert(output, || influencing_factors.to_vec()) {
+            Ok(val) => Some(val),
+            Err(err) => panic!("{err}"),
+        }
+    }
+
+    /**
+    Returns the gradient of the wrapped trait object with respect to all
+    `influencing_factors`, calculated via [`QuantityFunction::call_dual`]. The
+    `i`-th entry is the partial derivative with respect to the `i`-th factor.
+     */
+    pub fn gradient(&self, influencing_factors: &[DynQuantity<f64>]) -> Vec<DynQuantity<f64>> {
+        let output = self
+            .function
+            .call_dual(&DualQuantity::seed(influencing_factors));
+        if output.gradient.is_empty() {
+            return zero_gradient(T::unit_from_type(), influencing_factors);
+        }
+        return output.gradient;
+    }
+
+    /**
+    Returns the units of the influencing factors read by the underlying
+    [`QuantityFunction`] trait object. See [`QuantityFunction::influencing_units`].
+     */
+    pub fn influencing_units(&self) -> Option<Vec<Unit>> {
+        return self.function.influencing_units();
+    }
+
+    /**
+    Returns the underlying [`QuantityFunction`] trait object.
+     */
+    pub fn inner(&self) -> &dyn QuantityFunction {
+        return &*self.function;
+    }
+}
+
+impl<T: IsQuantity> std::fmt::Debug for FunctionWrapper<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("FunctionWrapper").finish()
+    }
+}
+
+impl<T: IsQuantity> Clone for FunctionWrapper<T> {
+    fn clone(&self) -> Self {
         return Self {
             function: dyn_clone::clone_box(&*self.function),
+            policy: self.policy,
+            validity_ranges: self.validity_ranges.clone(),
+            warnings: self
+                .warnings
+                .iter()
+                .map(AtomicWarningRecord::snapshot)
+                .collect(),
             phantom: PhantomData,
         };
     }
@@ -325,14 +1392,17 @@ perspective of the type system, in actual implementations it must be infallible
 statically typed physical quantity (e.g. from the [uom](https://crates.io/crates/uom)
 library), for which [`From<DynQuantity<f64>>`] can obviously not be implemented.
 The conversion is checked once when constructing a [`FunctionWrapper`] from a
-[`QuantityFunction`] trait object by calling [`QuantityFunction::call`] with
-`influencing_factors = &[]`, but of course it is impossible to test all
-potential values for `influencing_factors`.
+[`QuantityFunction`] trait object by comparing the unit declared by
+[`QuantityFunction::output_unit`] with that of `T`, but of course it is
+impossible to test all potential values for `influencing_factors`.
 
 It is therefore up to the provider of the trait object to make sure that the
 [`DynQuantity<f64>`] returned by [`QuantityFunction::call`] always has the same
 [`Unit`]. If this is not the case, the trait object has a bug and the program
-has entered an invalid state, resulting in a [`panic!`].
+has entered an invalid state, resulting in a [`panic!`] when using
+[`VarQuantity::get`]. If panicking is unacceptable (e.g. because the trait
+object has been deserialized from user input), [`VarQuantity::try_get`] returns
+an [`EvaluationError`] instead.
 
 # Examples
 
@@ -421,6 +1491,50 @@ let wrapper = FunctionWrapper::<ElectricalConductance>::new(Box::new(ResistanceF
 assert!(wrapper.is_err());
 ```
 
+## Arithmetic operators
+
+Variable quantities can be combined with the operators `+`, `-`, `*` and `/`.
+The type of the result is derived from the operand types (e.g. a resistivity
+divided by a length is a resistance per area). If both operands are constant,
+the operation is evaluated immediately and the result is a
+[`VarQuantity::Constant`] as well. Otherwise, the operands are combined into a
+function using the types from the [`combinators`] module. Plain quantities
+(including dimensionless `f64` values for `*` and `/`) can be used directly as
+the right-hand operand. As left-hand operand, they need to be converted into a
+[`VarQuantity::Constant`] via [`From`] first.
+
+The result of an operation is a new [`FunctionWrapper`] with the default
+[`ValidityPolicy::Ignore`]: The validity policies of the operands and the
+warnings recorded by them are discarded. The validity ranges of the operands
+are still reported by the combined function, hence a policy set on the result
+via [`FunctionWrapper::with_validity_policy`] applies to all of them.
+
+```
+use std::str::FromStr;
+use dyn_quantity::DynQuantity;
+use uom::si::{f64::*, area::square_meter, electrical_resistance::ohm,
+    electrical_resistivity::ohm_meter, length::meter, thermodynamic_temperature::kelvin};
+use var_quantity::{VarQuantity, unary::Linear};
+
+let rho = VarQuantity::<ElectricalResistivity>::try_from_quantity_function(Linear::new(
+    DynQuantity::from_str("1e-10 ohm*m/K").unwrap(),
+    DynQuantity::from_str("1.7e-8 ohm*m").unwrap(),
+)).expect("units match");
+let length = VarQuantity::from(Length::new::<meter>(2.0));
+let area = VarQuantity::from(Area::new::<square_meter>(1e-6));
+
+// Constants are folded
+let geometry = length / area;
+assert!(matches!(geometry, VarQuantity::Constant(_)));
+
+// Plain quantities and f64 values can be used as right-hand operand
+let rho = rho * 1.0 + ElectricalResistivity::new::<ohm_meter>(0.0);
+
+let resistance: VarQuantity<ElectricalResistance> = rho * geometry;
+let temperature = ThermodynamicTemperature::new::<kelvin>(100.0);
+approx::assert_abs_diff_eq!(resistance.get(&[temperature.into()]).get::<ohm>(), 0.054, epsilon = 1e-12);
+```
+
 # Features
 
 If the `serde` feature is activated, this enum can be serialized and
@@ -460,6 +1574,238 @@ impl<T: IsQuantity> VarQuantity<T> {
         }
     }
 
+    /**
+    Non-panicking version of [`VarQuantity::get`]. Matches against `self` and
+    either returns the contained value (variant [`VarQuantity::Constant`]) or
+    executes the [`FunctionWrapper::try_call`] method of the contained
+    [`FunctionWrapper`] (variant [`VarQuantity::Function`]).
+
+    # Examples
+
+    ```
+    use dyn_quantity::{DynQuantity, PredefUnit};
+    use uom::si::electrical_resistance::ohm;
+    use uom::si::f64::ElectricalResistance;
+    use var_quantity::{EvaluationError, FunctionWrapper, QuantityFunction, VarQuantity};
+
+    #[derive(Clone, serde::Deserialize, serde::Serialize)]
+    struct ResistanceFunction;
+
+    // Buggy implementation: The unit changes depending on the input
+    #[typetag::serde]
+    impl QuantityFunction for ResistanceFunction {
+        fn call(&self, influencing_factors: &[DynQuantity<f64>]) -> DynQuantity<f64> {
+            match influencing_factors.first() {
+                Some(factor) => *factor,
+                None => DynQuantity::new(1.0, PredefUnit::ElectricResistance),
+            }
+        }
+    }
+
+    let qt = VarQuantity::<ElectricalResistance>::try_from_quantity_function(ResistanceFunction {}).expect("units match");
+    assert_eq!(qt.try_get(&[]), Ok(ElectricalResistance::new::<ohm>(1.0)));
+    assert!(matches!(
+        qt.try_get(&[DynQuantity::new(2.0, PredefUnit::Temperature)]),
+        Err(EvaluationError::UnitMismatch { .. })
+    ));
+    ```
+    */
+    pub fn try_get(&self, influencing_factors: &[DynQuantity<f64>]) -> Result<T, EvaluationError> {
+        match self {
+            Self::Constant(val) => Ok(val.clone()),
+            Self::Function(fun) => fun.try_call(influencing_factors),
+        }
+    }
+
+    /**
+    Like [`VarQuantity::get`], but uses named influencing factors (see
+    [`QuantityFunction::call_named`]).
+
+    # Examples
+
+    ```
+    use std::str::FromStr;
+    use dyn_quantity::{DynQuantity, PredefUnit};
+    use uom::si::{f64::{ElectricalResistance, ThermodynamicTemperature}, electrical_resistance::ohm, thermodynamic_temperature::kelvin};
+    use var_quantity::{InfluencingFactor, VarQuantity, unary::FirstOrderTaylor};
+
+    let fot = FirstOrderTaylor::new(
+        DynQuantity::from_str("1 ohm").unwrap(),
+        DynQuantity::from_str("0.5 / K").unwrap(),
+        DynQuantity::from_str("30 K").unwrap(),
+    ).expect("units match").with_input_name("winding_temperature");
+    let qt = VarQuantity::<ElectricalResistance>::try_from_quantity_function(fot).expect("units match");
+
+    let factors = [
+        InfluencingFactor::new("ambient_temperature", ThermodynamicTemperature::new::<kelvin>(20.0)),
+        InfluencingFactor::new("winding_temperature", ThermodynamicTemperature::new::<kelvin>(32.0)),
+    ];
+    assert_eq!(qt.get_named(&factors), ElectricalResistance::new::<ohm>(2.0));
+    ```
+    */
+    pub fn get_named(&self, influencing_factors: &[InfluencingFactor]) -> T {
+        match self {
+            Self::Constant(val) => val.clone(),
+            Self::Function(fun) => fun.call_named(influencing_factors),
+        }
+    }
+
+    /**
+    Like [`VarQuantity::try_get`], but uses named influencing factors (see
+    [`QuantityFunction::call_named`]).
+    */
+    pub fn try_get_named(
+        &self,
+        influencing_factors: &[InfluencingFactor],
+    ) -> Result<T, EvaluationError> {
+        match self {
+            Self::Constant(val) => Ok(val.clone()),
+            Self::Function(fun) => fun.try_call_named(influencing_factors),
+        }
+    }
+
+    /**
+    Returns the partial derivative of `self` with respect to the influencing
+    factor of type `X`, evaluated at `influencing_factors`. The result is
+    statically typed as the quotient of `T` and `X`. For the
+    [`VarQuantity::Constant`] variant, the derivative is always zero, otherwise
+    see [`QuantityFunction::derivative`] and [`FunctionWrapper::derivative`].
+
+    # Examples
+
+    ```
+    use std::str::FromStr;
+    use dyn_quantity::DynQuantity;
+    use uom::si::{
+        f64::{ElectricalResistance, ThermodynamicTemperature},
+        electrical_resistance::ohm,
+        thermodynamic_temperature::kelvin,
+    };
+    use var_quantity::{VarQuantity, unary::Linear};
+
+    let lin = Linear::new(
+        DynQuantity::from_str("0.5 ohm/K").unwrap(),
+        DynQuantity::from_str("1 ohm").unwrap(),
+    );
+    let qt = VarQuantity::<ElectricalResistance>::try_from_quantity_function(lin).expect("units match");
+
+    let temperature = ThermodynamicTemperature::new::<kelvin>(300.0);
+    let derivative = qt.derivative::<ThermodynamicTemperature>(&[temperature.into()]).unwrap();
+    assert_eq!(
+        derivative,
+        ElectricalResistance::new::<ohm>(0.5) / ThermodynamicTemperature::new::<kelvin>(1.0)
+    );
+
+    let constant = VarQuantity::Constant(ElectricalResistance::new::<ohm>(1.0));
+    let derivative = constant.derivative::<ThermodynamicTemperature>(&[temperature.into()]).unwrap();
+    assert_eq!(derivative.value, 0.0);
+    ```
+    */
+    pub fn derivative<X>(
+        &self,
+        influencing_factors: &[DynQuantity<f64>],
+    ) -> Option<<T as Div<X>>::Output>
+    where
+        T: Div<X>,
+        X: IsQuantity,
+        <T as Div<X>>::Output: IsQuantity,
+    {
+        match self {
+            Self::Constant(_) => {
+                let unit = T::unit_from_type() / X::unit_from_type();
+                <T as Div<X>>::Output::try_from(DynQuantity::new(0.0, unit).into()).ok()
+            }
+            Self::Function(fun) => fun.derivative::<X>(influencing_factors),
+        }
+    }
+
+    /**
+    Returns the gradient of `self` with respect to all `influencing_factors`.
+    The `i`-th entry is the partial derivative with respect to the `i`-th
+    factor, its unit is the unit of `T` divided by that of the factor. For the
+    [`VarQuantity::Constant`] variant, all entries are zero, otherwise see
+    [`FunctionWrapper::gradient`].
+
+    # Examples
+
+    ```
+    use std::str::FromStr;
+    use dyn_quantity::DynQuantity;
+    use uom::si::f64::ElectricalResistance;
+    use var_quantity::{VarQuantity, unary::Linear};
+
+    let q = |s: &str| DynQuantity::<f64>::from_str(s).unwrap();
+
+    let lin = Linear::new(q("0.5 ohm/K"), q("1 ohm"));
+    let qt = VarQuantity::<ElectricalResistance>::try_from_quantity_function(lin).expect("units match");
+
+    assert_eq!(qt.gradient(&[q("2 A"), q("300 K")]), vec![q("0 ohm/A"), q("0.5 ohm/K")]);
+    ```
+    */
+    pub fn gradient(&self, influencing_factors: &[DynQuantity<f64>]) -> Vec<DynQuantity<f64>> {
+        match self {
+            Self::Constant(_) => zero_gradient(T::unit_from_type(), influencing_factors),
+            Self::Function(fun) => fun.gradient(influencing_factors),
+        }
+    }
+
+    /**
+    Returns the units of the influencing factors which affect the value of
+    `self`. For the [`VarQuantity::Constant`] variant, this is always an empty
+    vector, otherwise see [`QuantityFunction::influencing_units`]. Returns
+    `None` if the underlying function does not declare its inputs.
+    */
+    pub fn influencing_units(&self) -> Option<Vec<Unit>> {
+        match self {
+            Self::Constant(_) => Some(Vec::new()),
+            Self::Function(fun) => fun.influencing_units(),
+        }
+    }
+
+    /**
+    Returns all units of [`VarQuantity::influencing_units`] for which no
+    matching quantity is contained in `influencing_factors`. This can be used
+    to validate that a caller supplies all factors needed by the underlying
+    function. Returns `None` if the underlying function does not declare its
+    inputs, since the missing factors cannot be determined in this case.
+
+    # Examples
+
+    ```
+    use std::str::FromStr;
+    use dyn_quantity::{DynQuantity, PredefUnit, Unit};
+    use uom::si::f64::ElectricalResistance;
+    use var_quantity::{VarQuantity, unary::Linear};
+
+    let lin = Linear::new(
+        DynQuantity::from_str("0.5 ohm/K").unwrap(),
+        DynQuantity::from_str("1 ohm").unwrap(),
+    );
+    let qt = VarQuantity::<ElectricalResistance>::try_from_quantity_function(lin).expect("units match");
+
+    let current = DynQuantity::new(2.0, PredefUnit::ElectricCurrent);
+    let temperature = DynQuantity::new(300.0, PredefUnit::Temperature);
+
+    assert_eq!(
+        qt.missing_influencing_units(&[current]),
+        Some(vec![Unit::from(PredefUnit::Temperature)])
+    );
+    assert_eq!(qt.missing_influencing_units(&[current, temperature]), Some(vec![]));
+    ```
+    */
+    pub fn missing_influencing_units(
+        &self,
+        influencing_factors: &[DynQuantity<f64>],
+    ) -> Option<Vec<Unit>> {
+        let units = self.influencing_units()?;
+        return Some(
+            units
+                .into_iter()
+                .filter(|unit| !influencing_factors.iter().any(|f| f.unit == *unit))
+                .collect(),
+        );
+    }
+
     /**
     Creates a new [`VarQuantity`] instance if the output [`Unit`] of the given
     function matches that of `T`.
@@ -495,6 +1841,241 @@ impl<T: IsQuantity> From<T> for VarQuantity<T> {
     }
 }
 
+impl<T: IsQuantity + Into<DynQuantity<f64>>> VarQuantity<T> {
+    /**
+    Evaluates `self` for a batch of influencing factors given as columns (see
+    the [`batch`] module) and writes the results into `output`, given in the
+    SI base units of `T`. For the variant [`VarQuantity::Constant`], `output`
+    is filled with the constant value, otherwise this method forwards to
+    [`FunctionWrapper::call_batch`].
+
+    # Panics
+
+    Panics if the length of a column in `inputs` differs from that of
+    `output`.
+
+    # Examples
+
+    ```
+    use dyn_quantity::PredefUnit;
+    use uom::si::{f64::ElectricalResistance, electrical_resistance::milliohm};
+    use var_quantity::{BatchInput, VarQuantity};
+
+    let constant = VarQuantity::Constant(ElectricalResistance::new::<milliohm>(2.0));
+    let mut output = [0.0; 2];
+    constant.get_batch(&[BatchInput::new(PredefUnit::Temperature, &[300.0, 400.0])], &mut output);
+    assert_eq!(output, [0.002, 0.002]);
+    ```
+     */
+    pub fn get_batch(&self, inputs: &[BatchInput], output: &mut [f64]) {
+        match self {
+            Self::Constant(value) => {
+                batch::assert_lengths(inputs, output.len());
+                output.fill(value.clone().into().value);
+            }
+            Self::Function(fun) => fun.call_batch(inputs, output),
+        }
+    }
+
+    /**
+    Binds `self` to the [`Layout`] of the influencing factors and returns an
+    [`Evaluator`] whose output is given in the SI base units of `T` (see the
+    [`compiled`] module). For the variant [`VarQuantity::Constant`], the
+    evaluator always returns the constant value, otherwise this method
+    forwards to [`FunctionWrapper::bind`].
+
+    # Examples
+
+    ```
+    use dyn_quantity::PredefUnit;
+    use uom::si::{f64::ElectricalResistance, electrical_resistance::milliohm};
+    use var_quantity::{Layout, VarQuantity};
+
+    let constant = VarQuantity::Constant(ElectricalResistance::new::<milliohm>(2.0));
+    let evaluator = constant.bind(&Layout::new(vec![PredefUnit::Temperature.into()]));
+    assert_eq!(evaluator.eval(&[300.0]), 0.002);
+    ```
+     */
+    pub fn bind(&self, layout: &Layout) -> Box<dyn Evaluator + '_> {
+        match self {
+            Self::Constant(value) => {
+                let value = value.clone().into().value;
+                return Box::new(move |_: &[f64]| value);
+            }
+            Self::Function(fun) => return fun.bind(layout),
+        }
+    }
+
+    /**
+    Converts `self` into a [`QuantityFunction`] trait object. Constants are
+    wrapped in a [`combinators::Constant`].
+     */
+    fn into_function(self) -> Box<dyn QuantityFunction> {
+        match self {
+            Self::Constant(value) => return Box::new(combinators::Constant::new(value.into())),
+            Self::Function(fun) => return fun.function,
+        }
+    }
+
+    /**
+    Converts `-self` into a [`QuantityFunction`] trait object.
+     */
+    fn into_negated_function(self) -> Box<dyn QuantityFunction> {
+        match self {
+            Self::Constant(value) => {
+                return Box::new(combinators::Constant::new(value.into() * -1.0));
+            }
+            Self::Function(fun) => {
+                return Box::new(combinators::Scaled::new((-1.0).into(), fun.function));
+            }
+        }
+    }
+
+    /**
+    Wraps the result of an arithmetic operation. The output unit of `function`
+    is determined by the operand types, hence it always matches that of `T`.
+    The wrapper uses the default [`ValidityPolicy::Ignore`].
+     */
+    fn from_operation(function: Box<dyn QuantityFunction>) -> Self {
+        return Self::Function(FunctionWrapper::new_unchecked(function));
+    }
+}
+
+impl<T> Add for VarQuantity<T>
+where
+    T: IsQuantity + Into<DynQuantity<f64>> + Add<Output = T>,
+{
+    type Output = VarQuantity<T>;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        if let (Self::Constant(lhs), Self::Constant(rhs)) = (&self, &rhs) {
+            return Self::Constant(lhs.clone() + rhs.clone());
+        }
+        let functions = vec![self.into_function(), rhs.into_function()];
+        return Self::from_operation(Box::new(
+            combinators::Sum::new(functions).expect("both summands have the unit of T"),
+        ));
+    }
+}
+
+impl<T> Sub for VarQuantity<T>
+where
+    T: IsQuantity + Into<DynQuantity<f64>> + Sub<Output = T>,
+{
+    type Output = VarQuantity<T>;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        if let (Self::Constant(lhs), Self::Constant(rhs)) = (&self, &rhs) {
+            return Self::Constant(lhs.clone() - rhs.clone());
+        }
+        let functions = vec![self.into_function(), rhs.into_negated_function()];
+        return Self::from_operation(Box::new(
+            combinators::Sum::new(functions).expect("both summands have the unit of T"),
+        ));
+    }
+}
+
+impl<T, U> Mul<VarQuantity<U>> for VarQuantity<T>
+where
+    T: IsQuantity + Into<DynQuantity<f64>> + Mul<U>,
+    U: IsQuantity + Into<DynQuantity<f64>>,
+    <T as Mul<U>>::Output: IsQuantity + Into<DynQuantity<f64>>,
+{
+    type Output = VarQuantity<<T as Mul<U>>::Output>;
+
+    fn mul(self, rhs: VarQuantity<U>) -> Self::Output {
+        let function: Box<dyn QuantityFunction> = match (self, rhs) {
+            (Self::Constant(lhs), VarQuantity::Constant(rhs)) => {
+                return VarQuantity::Constant(lhs * rhs);
+            }
+            (Self::Constant(lhs), VarQuantity::Function(rhs)) => {
+                Box::new(combinators::Scaled::new(lhs.into(), rhs.function))
+            }
+            (Self::Function(lhs), VarQuantity::Constant(rhs)) => {
+                Box::new(combinators::Scaled::new(rhs.into(), lhs.function))
+            }
+            (Self::Function(lhs), VarQuantity::Function(rhs)) => {
+                Box::new(combinators::Product::new(vec![lhs.function, rhs.function]))
+            }
+        };
+        return VarQuantity::from_operation(function);
+    }
+}
+
+impl<T, U> Div<VarQuantity<U>> for VarQuantity<T>
+where
+    T: IsQuantity + Into<DynQuantity<f64>> + Div<U>,
+    U: IsQuantity + Into<DynQuantity<f64>>,
+    <T as Div<U>>::Output: IsQuantity + Into<DynQuantity<f64>>,
+{
+    type Output = VarQuantity<<T as Div<U>>::Output>;
+
+    fn div(self, rhs: VarQuantity<U>) -> Self::Output {
+        let function: Box<dyn QuantityFunction> = match (self, rhs) {
+            (Self::Constant(lhs), VarQuantity::Constant(rhs)) => {
+                return VarQuantity::Constant(lhs / rhs);
+            }
+            (Self::Function(lhs), VarQuantity::Constant(rhs)) => {
+                let factor = DynQuantity::from(1.0) / rhs.into();
+                Box::new(combinators::Scaled::new(factor, lhs.function))
+            }
+            (lhs, rhs) => Box::new(combinators::Quotient::new(
+                lhs.into_function(),
+                rhs.into_function(),
+            )),
+        };
+        return VarQuantity::from_operation(function);
+    }
+}
+
+impl<T> Add<T> for VarQuantity<T>
+where
+    T: IsQuantity + Into<DynQuantity<f64>> + Add<Output = T>,
+{
+    type Output = VarQuantity<T>;
+
+    fn add(self, rhs: T) -> Self::Output {
+        return self + Self::Constant(rhs);
+    }
+}
+
+impl<T> Sub<T> for VarQuantity<T>
+where
+    T: IsQuantity + Into<DynQuantity<f64>> + Sub<Output = T>,
+{
+    type Output = VarQuantity<T>;
+
+    fn sub(self, rhs: T) -> Self::Output {
+        return self - Self::Constant(rhs);
+    }
+}
+
+impl<T, U> Mul<U> for VarQuantity<T>
+where
+    T: IsQuantity + Into<DynQuantity<f64>> + Mul<U>,
+    U: IsQuantity + Into<DynQuantity<f64>>,
+    <T as Mul<U>>::Output: IsQuantity + Into<DynQuantity<f64>>,
+{
+    type Output = VarQuantity<<T as Mul<U>>::Output>;
+
+    fn mul(self, rhs: U) -> Self::Output {
+        return self * VarQuantity::Constant(rhs);
+    }
+}
+
+impl<T, U> Div<U> for VarQuantity<T>
+where
+    T: IsQuantity + Into<DynQuantity<f64>> + Div<U>,
+    U: IsQuantity + Into<DynQuantity<f64>>,
+    <T as Div<U>>::Output: IsQuantity + Into<DynQuantity<f64>>,
+{
+    type Output = VarQuantity<<T as Div<U>>::Output>;
+
+    fn div(self, rhs: U) -> Self::Output {
+        return self / VarQuantity::Constant(rhs);
+    }
+}
+
 #[cfg(feature = "serde")]
 mod serde_impl {
     use serde::de::DeserializeOwned;
@@ -520,6 +2101,55 @@ mod serde_impl {
         }
     }
 
+    impl<'de, T> serde::Deserialize<'de> for ClampedQuantity<T>
+    where
+        T: QuantityFunction + serde::Deserialize<'de>,
+    {
+        fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+        where
+            D: serde::Deserializer<'de>,
+        {
+            #[derive(serde::Deserialize)]
+            struct ClampedQuantityAlias<T> {
+                #[serde(default)]
+                upper_limit: Option<Limit>,
+                #[serde(default)]
+                lower_limit: Option<Limit>,
+                function: T,
+            }
+
+            let alias = ClampedQuantityAlias::<T>::deserialize(deserializer)?;
+            let unit = alias.function.output_unit();
+            return ClampedQuantity::from_limits(
+                alias.function,
+                alias.lower_limit.map(|limit| limit.into_quantity(unit)),
+                alias.upper_limit.map(|limit| limit.into_quantity(unit)),
+            )
+            .map_err(serde::de::Error::custom);
+        }
+    }
+
+    /**
+    Deserialized output limit of [`ClampedQuantity`] and
+    [`combinators::Clamped`]: Either a raw value in the output unit of the
+    function or a quantity whose unit is checked against the output unit.
+     */
+    #[derive(serde::Deserialize)]
+    #[serde(untagged)]
+    pub(crate) enum Limit {
+        Raw(f64),
+        Quantity(DynQuantity<f64>),
+    }
+
+    impl Limit {
+        pub(crate) fn into_quantity(self, unit: Unit) -> DynQuantity<f64> {
+            match self {
+                Limit::Raw(value) => return DynQuantity::new(value, unit),
+                Limit::Quantity(quantity) => return quantity,
+            }
+        }
+    }
+
     impl<'de, T> serde::Deserialize<'de> for VarQuantity<T>
     where
         T: DeserializeOwned + IsQuantity,
@@ -571,12 +2201,18 @@ A wrapper around a type implementing [`QuantityFunction`] trait object which
 clamps the output of [`QuantityFunction::call`] using the provided upper and
 lower limits.
 
+To clamp the input of a function instead of its output, see
+[`InputClamped`](crate::combinators::InputClamped).
+
 If the `serde` feature is not activated, it implements [`QuantityFunction`]
 in a generic manner and can therefore be used in a [`FunctionWrapper`]. If
 `serde` is activated, it is unfortately not possible to provide a generic
 implementation due to the macro `#[typetag::serde]` not being able to deal with
-generics. As a workaround, it is possible to provide a simple custom
-implementation for each concrete type in your own crate:
+generics. In that case, the easiest option is to box the function and use the
+non-generic [`Clamped`](crate::combinators::Clamped) wrapper instead, which
+works for any [`QuantityFunction`] without further code. Alternatively, it is
+possible to provide a simple custom implementation for each concrete type in
+your own crate:
 
 ```ignore
 #[cfg_attr(feature = "serde", typetag::serde)]
@@ -584,26 +2220,128 @@ impl QuantityFunction for ClampedQuantity<YourTypeHere> {
     fn call(&self, influencing_factors: &[DynQuantity<f64>]) -> DynQuantity<f64> {
         return self.call_clamped(influencing_factors);
     }
+
+    fn output_unit(&self) -> Unit {
+        return self.inner().output_unit();
+    }
+
+    fn influencing_units(&self) -> Option<Vec<Unit>> {
+        return self.inner().influencing_units();
+    }
+
+    fn validity_ranges(&self) -> Vec<ValidityRange> {
+        return self.inner().validity_ranges();
+    }
+
+    fn call_named(&self, influencing_factors: &[InfluencingFactor]) -> DynQuantity<f64> {
+        return self.call_named_clamped(influencing_factors);
+    }
+
+    fn try_call(
+        &self,
+        influencing_factors: &[DynQuantity<f64>],
+    ) -> Result<DynQuantity<f64>, EvaluationError> {
+        return self.try_call_clamped(influencing_factors);
+    }
+
+    fn try_call_named(
+        &self,
+        influencing_factors: &[InfluencingFactor],
+    ) -> Result<DynQuantity<f64>, EvaluationError> {
+        return self.try_call_named_clamped(influencing_factors);
+    }
+
+    fn derivative(
+        &self,
+        influencing_factors: &[DynQuantity<f64>],
+        wrt: Unit,
+    ) -> Option<DynQuantity<f64>> {
+        return self.derivative_clamped(influencing_factors, wrt);
+    }
+
+    fn call_dual(&self, influencing_factors: &[DualQuantity]) -> DualQuantity {
+        return self.call_dual_clamped(influencing_factors);
+    }
+
+    fn call_batch(&self, inputs: &[BatchInput], output: &mut [f64]) {
+        self.call_batch_clamped(inputs, output);
+    }
+
+    fn bind(&self, layout: &Layout) -> Box<dyn Evaluator + '_> {
+        return self.bind_clamped(layout);
+    }
 }
 ```
 
 This approach is used for all the implementors of [`QuantityFunction`] provided
 with this crate.
+
+# Features
+
+This struct can be serialized / deserialized if the `serde` feature is enabled.
+The limits are serialized as raw values in the output unit of the function. A
+missing limit is omitted. When deserializing, raw values are interpreted in
+the output unit as well. Alternatively, the limits can be given as quantities
+(e.g. `10 mOhm`, requires the `from_str` feature), whose unit is checked
+against the output unit of the function (see [`ClampedQuantity::from_limits`]).
+
+```text
+upper_limit: 10 mOhm
+function:
+  Linear:
+    slope: 0.01 mOhm/K
+    base_value: 1 mOhm
+```
  */
 #[derive(Clone)]
-#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", derive(Serialize))]
 pub struct ClampedQuantity<T: QuantityFunction> {
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "is_infinite"))]
     upper_limit: f64,
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "is_infinite"))]
     lower_limit: f64,
     function: T,
 }
 
+/// Used to omit missing limits of a [`ClampedQuantity`] when serializing.
+#[cfg(feature = "serde")]
+fn is_infinite(value: &f64) -> bool {
+    return value.is_infinite();
+}
+
+/**
+Checks the optional output limits of a clamped function against its output
+`unit` and returns them as raw values (infinite if a limit is missing). The
+limits must satisfy the same conditions as those of a [`ValidityRange`], so
+evaluating the clamped function cannot panic in [`f64::clamp`].
+ */
+pub(crate) fn check_limits(
+    unit: Unit,
+    lower_limit: Option<DynQuantity<f64>>,
+    upper_limit: Option<DynQuantity<f64>>,
+) -> Result<(f64, f64), RangeError> {
+    let lower = lower_limit.unwrap_or(DynQuantity::new(f64::NEG_INFINITY, unit));
+    let upper = upper_limit.unwrap_or(DynQuantity::new(f64::INFINITY, unit));
+    for limit in [&lower, &upper] {
+        if limit.unit != unit {
+            return Err(UnitsNotEqual(unit, limit.unit).into());
+        }
+    }
+    ValidityRange::new(lower, upper)?;
+    return Ok((lower.value, upper.value));
+}
+
 impl<T: QuantityFunction> ClampedQuantity<T> {
     /**
     Checks if `upper_limit >= lower_limit` and returns a new instance of
-    [`ClampedQuantity`] if true.
+    [`ClampedQuantity`] if true. The limits are raw values in the output unit
+    of `function` and must not be NaN. See [`ClampedQuantity::from_limits`]
+    for a constructor which checks the units of the limits.
     */
     pub fn new(upper_limit: f64, lower_limit: f64, function: T) -> Result<Self, &'static str> {
+        if upper_limit.is_nan() || lower_limit.is_nan() {
+            return Err("limits must not be NaN");
+        }
         if upper_limit < lower_limit {
             return Err("upper limit must not be smaller than the lower limit");
         }
@@ -614,6 +2352,68 @@ impl<T: QuantityFunction> ClampedQuantity<T> {
         });
     }
 
+    /**
+    Creates a new instance of [`ClampedQuantity`] from limits given as
+    quantities. Either limit can be omitted, in which case the output is only
+    clamped on one side. Fails if the unit of a limit differs from the
+    [`QuantityFunction::output_unit`] of `function`, if a limit is NaN or
+    infinite on the wrong side (see [`ValidityRange::new`]) or if the lower
+    limit is larger than the upper limit. This is also the constructor used
+    when deserializing.
+
+    # Examples
+
+    ```
+    use std::str::FromStr;
+    use dyn_quantity::DynQuantity;
+    use var_quantity::{ClampedQuantity, QuantityFunction, RangeError, unary::Linear};
+
+    let q = |s: &str| DynQuantity::<f64>::from_str(s).unwrap();
+    let lin = Linear::new(q("1 mOhm/K"), q("0 ohm"));
+
+    // Only an upper limit
+    let clamped = ClampedQuantity::from_limits(lin.clone(), None, Some(q("10 mOhm"))).unwrap();
+    assert_eq!(clamped.call(&[q("20 K")]), q("10 mOhm"));
+    assert_eq!(clamped.call(&[q("-20 K")]), q("-20 mOhm"));
+    assert_eq!(clamped.lower_limit(), f64::NEG_INFINITY);
+
+    // The units of the limits must match the output unit
+    assert!(matches!(
+        ClampedQuantity::from_limits(lin.clone(), None, Some(q("10 A"))),
+        Err(RangeError::UnitsNotEqual(_))
+    ));
+    assert!(matches!(
+        ClampedQuantity::from_limits(lin.clone(), Some(q("10 mOhm")), Some(q("5 mOhm"))),
+        Err(RangeError::LowerAboveUpper { .. })
+    ));
+
+    // NaN limits and infinite limits on the wrong side are rejected
+    let nan = DynQuantity::new(f64::NAN, q("1 ohm").unit);
+    assert!(matches!(
+        ClampedQuantity::from_limits(lin.clone(), Some(nan), None),
+        Err(RangeError::NotFinite(_))
+    ));
+    let infinity = DynQuantity::new(f64::INFINITY, q("1 ohm").unit);
+    assert!(matches!(
+        ClampedQuantity::from_limits(lin, Some(infinity), None),
+        Err(RangeError::NotFinite(_))
+    ));
+    ```
+     */
+    pub fn from_limits(
+        function: T,
+        lower_limit: Option<DynQuantity<f64>>,
+        upper_limit: Option<DynQuantity<f64>>,
+    ) -> Result<Self, RangeError> {
+        let (lower_limit, upper_limit) =
+            check_limits(function.output_unit(), lower_limit, upper_limit)?;
+        return Ok(Self {
+            upper_limit,
+            lower_limit,
+            function,
+        });
+    }
+
     /**
     Returns the underlying [`QuantityFunction`].
      */
@@ -628,12 +2428,12 @@ impl<T: QuantityFunction> ClampedQuantity<T> {
         return &self.function;
     }
 
-    /// Returns the upper limit.
+    /// Returns the upper limit (infinite if there is none).
     pub fn upper_limit(&self) -> f64 {
         return self.upper_limit;
     }
 
-    /// Returns the lower limit.
+    /// Returns the lower limit (negative infinite if there is none).
     pub fn lower_limit(&self) -> f64 {
         return self.lower_limit;
     }
@@ -644,7 +2444,111 @@ impl<T: QuantityFunction> ClampedQuantity<T> {
     implementations, see the [`ClampedQuantity`] docstring.
      */
     pub fn call_clamped(&self, influencing_factors: &[DynQuantity<f64>]) -> DynQuantity<f64> {
-        let mut dyn_quantity = self.function.call(influencing_factors);
+        return self.clamp(self.function.call(influencing_factors));
+    }
+
+    /**
+    Clamps the output value of `T::call_named` using the provided upper and
+    lower limits. This function is mainly here to simplify custom
+    [`QuantityFunction`] implementations, see the [`ClampedQuantity`] docstring.
+     */
+    pub fn call_named_clamped(
+        &self,
+        influencing_factors: &[InfluencingFactor],
+    ) -> DynQuantity<f64> {
+        return self.clamp(self.function.call_named(influencing_factors));
+    }
+
+    /**
+    Clamps the output value of `T::try_call` using the provided upper and
+    lower limits. This function is mainly here to simplify custom
+    [`QuantityFunction`] implementations, see the [`ClampedQuantity`] docstring.
+     */
+    pub fn try_call_clamped(
+        &self,
+        influencing_factors: &[DynQuantity<f64>],
+    ) -> Result<DynQuantity<f64>, EvaluationError> {
+        return self
+            .function
+            .try_call(influencing_factors)
+            .map(|q| self.clamp(q));
+    }
+
+    /**
+    Clamps the output value of `T::try_call_named` using the provided upper and
+    lower limits. This function is mainly here to simplify custom
+    [`QuantityFunction`] implementations, see the [`ClampedQuantity`] docstring.
+     */
+    pub fn try_call_named_clamped(
+        &self,
+        influencing_factors: &[InfluencingFactor],
+    ) -> Result<DynQuantity<f64>, EvaluationError> {
+        return self
+            .function
+            .try_call_named(influencing_factors)
+            .map(|q| self.clamp(q));
+    }
+
+    /**
+    Returns the derivative of `T` (see [`QuantityFunction::derivative`]) if the
+    output value of `T::call` is within the limits and zero otherwise. This
+    function is mainly here to simplify custom [`QuantityFunction`]
+    implementations, see the [`ClampedQuantity`] docstring.
+     */
+    pub fn derivative_clamped(
+        &self,
+        influencing_factors: &[DynQuantity<f64>],
+        wrt: Unit,
+    ) -> Option<DynQuantity<f64>> {
+        let output = self.function.call(influencing_factors);
+        if output.value < self.lower_limit || output.value > self.upper_limit {
+            return Some(DynQuantity::new(0.0, output.unit / wrt));
+        }
+        return self.function.derivative(influencing_factors, wrt);
+    }
+
+    /**
+    Returns the output of `T::call_dual` if its value is within the limits and
+    the clamped value as a constant otherwise. This function is mainly here to
+    simplify custom [`QuantityFunction`] implementations, see the
+    [`ClampedQuantity`] docstring.
+     */
+    pub fn call_dual_clamped(&self, influencing_factors: &[DualQuantity]) -> DualQuantity {
+        let output = self.function.call_dual(influencing_factors);
+        if output.value.value < self.lower_limit || output.value.value > self.upper_limit {
+            return DualQuantity::constant(self.clamp(output.value));
+        }
+        return output;
+    }
+
+    /**
+    Forwards to `T::call_batch` and clamps all values of `output`. This
+    function is mainly here to simplify custom [`QuantityFunction`]
+    implementations, see the [`ClampedQuantity`] docstring.
+     */
+    pub fn call_batch_clamped(&self, inputs: &[BatchInput], output: &mut [f64]) {
+        self.function.call_batch(inputs, output);
+        for value in output.iter_mut() {
+            *value = value.clamp(self.lower_limit, self.upper_limit);
+        }
+    }
+
+    /**
+    Binds `T` to `layout` and clamps the values returned by the resulting
+    [`Evaluator`]. This function is mainly here to simplify custom
+    [`QuantityFunction`] implementations, see the [`ClampedQuantity`]
+    docstring.
+     */
+    pub fn bind_clamped(&self, layout: &Layout) -> Box<dyn Evaluator + '_> {
+        let evaluator = self.function.bind(layout);
+        return Box::new(move |values: &[f64]| {
+            evaluator
+                .eval(values)
+                .clamp(self.lower_limit, self.upper_limit)
+        });
+    }
+
+    fn clamp(&self, mut dyn_quantity: DynQuantity<f64>) -> DynQuantity<f64> {
         dyn_quantity.value = dyn_quantity.value.clamp(self.lower_limit, self.upper_limit);
         return dyn_quantity;
     }
@@ -655,6 +2559,56 @@ impl<T: QuantityFunction + Clone> QuantityFunction for ClampedQuantity<T> {
     fn call(&self, influencing_factors: &[DynQuantity<f64>]) -> DynQuantity<f64> {
         return self.call_clamped(influencing_factors);
     }
+
+    fn output_unit(&self) -> Unit {
+        return self.function.output_unit();
+    }
+
+    fn influencing_units(&self) -> Option<Vec<Unit>> {
+        return self.function.influencing_units();
+    }
+
+    fn validity_ranges(&self) -> Vec<ValidityRange> {
+        return self.function.validity_ranges();
+    }
+
+    fn call_named(&self, influencing_factors: &[InfluencingFactor]) -> DynQuantity<f64> {
+        return self.call_named_clamped(influencing_factors);
+    }
+
+    fn try_call(
+        &self,
+        influencing_factors: &[DynQuantity<f64>],
+    ) -> Result<DynQuantity<f64>, EvaluationError> {
+        return self.try_call_clamped(influencing_factors);
+    }
+
+    fn try_call_named(
+        &self,
+        influencing_factors: &[InfluencingFactor],
+    ) -> Result<DynQuantity<f64>, EvaluationError> {
+        return self.try_call_named_clamped(influencing_factors);
+    }
+
+    fn derivative(
+        &self,
+        influencing_factors: &[DynQuantity<f64>],
+        wrt: Unit,
+    ) -> Option<DynQuantity<f64>> {
+        return self.derivative_clamped(influencing_factors, wrt);
+    }
+
+    fn call_dual(&self, influencing_factors: &[DualQuantity]) -> DualQuantity {
+        return self.call_dual_clamped(influencing_factors);
+    }
+
+    fn call_batch(&self, inputs: &[BatchInput], output: &mut [f64]) {
+        self.call_batch_clamped(inputs, output);
+    }
+
+    fn bind(&self, layout: &Layout) -> Box<dyn Evaluator + '_> {
+        return self.bind_clamped(layout);
+    }
 }
 
 /**
@@ -717,3 +2671,139 @@ where
     }
     no_match()
 }
+
+/**
+Selects the quantity of an influencing factor from `influencing_factors`
+for a unary function.
+
+If `name` is given, the first factor with this name and the unit `match_for`
+is selected. Otherwise, the first factor whose unit is `match_for` is selected,
+regardless of its name. If no factor matches, `None` is returned.
+
+The main purpose of this function is to simplify implementing
+[`QuantityFunction::call_named`] for unary functions: The selected factor can
+simply be forwarded to [`QuantityFunction::call`]:
+
+```
+use dyn_quantity::{DynQuantity, PredefUnit, Unit};
+use var_quantity::{select_influencing_factor, InfluencingFactor, QuantityFunction};
+
+// The serde annotations are just here because the doctests of this crate use
+// the serde feature - they are not needed if the serde feature is disabled.
+#[derive(Clone, serde::Serialize, serde::Deserialize)]
+pub struct Doubled {
+    input_name: Option<String>,
+}
+
+// Again, the macro annotation is just here because of the serde feature
+#[typetag::serde]
+impl QuantityFunction for Doubled {
+    fn call(&self, influencing_factors: &[DynQuantity<f64>]) -> DynQuantity<f64> {
+        let x = influencing_factors.first().map(|f| f.value).unwrap_or(0.0);
+        return DynQuantity::new(2.0 * x, Unit::default());
+    }
+
+    fn call_named(&self, influencing_factors: &[InfluencingFactor]) -> DynQuantity<f64> {
+        let selected = select_influencing_factor(
+            influencing_factors,
+            self.input_name.as_deref(),
+            Unit::default(),
+        );
+        return self.call(selected.as_slice());
+    }
+}
+
+let factors = [
+    InfluencingFactor::new("slip", 0.1),
+    InfluencingFactor::new("duty_cycle", 0.4),
+];
+let fun = Doubled { input_name: Some("duty_cycle".to_string()) };
+assert_eq!(fun.call_named(&factors).value, 0.8);
+```
+ */
+pub fn select_influencing_factor(
+    influencing_factors: &[InfluencingFactor],
+    name: Option<&str>,
+    match_for: Unit,
+) -> Option<DynQuantity<f64>> {
+    return influencing_factors
+        .iter()
+        .find(|f| f.quantity.unit == match_for && name.is_none_or(|name| f.name == name))
+        .map(|f| f.quantity);
+}
+
+/**
+A helper function for the [`QuantityFunction::derivative`] implementation of
+unary functions whose input has the unit `match_for`. If `wrt` differs from
+`match_for`, the function does not depend on `wrt` and a zero derivative is
+returned. Otherwise, the first quantity in `influencing_factors` with the unit
+`match_for` is selected and its value is used as argument for `derivative`. If
+no quantity matches, the unary function falls back to a constant default and
+a zero derivative is returned as well. In all cases, the unit of the returned
+quantity is `output_unit / wrt`.
+
+```
+use dyn_quantity::{DynQuantity, PredefUnit, Unit};
+use var_quantity::{filter_unary_derivative, QuantityFunction};
+
+// The serde annotations are just here because the doctests of this crate use
+// the serde feature - they are not needed if the serde feature is disabled.
+#[derive(Clone, serde::Serialize, serde::Deserialize)]
+pub struct Square;
+
+// Again, the macro annotation is just here because of the serde feature
+#[cfg_attr(feature = "serde", typetag::serde)]
+impl QuantityFunction for Square {
+    fn call(&self, influencing_factors: &[DynQuantity<f64>]) -> DynQuantity<f64> {
+        let x = influencing_factors
+            .iter()
+            .find(|f| f.unit == PredefUnit::Length.into())
+            .map(|f| f.value)
+            .unwrap_or(0.0);
+        return DynQuantity::new(x * x, PredefUnit::Area);
+    }
+
+    fn derivative(
+        &self,
+        influencing_factors: &[DynQuantity<f64>],
+        wrt: Unit,
+    ) -> Option<DynQuantity<f64>> {
+        return filter_unary_derivative(
+            influencing_factors,
+            PredefUnit::Length.into(),
+            PredefUnit::Area.into(),
+            wrt,
+            |x| 2.0 * x,
+        );
+    }
+}
+
+let x = DynQuantity::new(3.0, PredefUnit::Length);
+assert_eq!(
+    Square.derivative(&[x], PredefUnit::Length.into()),
+    Some(DynQuantity::new(6.0, PredefUnit::Length))
+);
+assert_eq!(Square.derivative(&[x], PredefUnit::Time.into()).unwrap().value, 0.0);
+```
+ */
+pub fn filter_unary_derivative<F>(
+    influencing_factors: &[DynQuantity<f64>],
+    match_for: Unit,
+    output_unit: Unit,
+    wrt: Unit,
+    derivative: F,
+) -> Option<DynQuantity<f64>>
+where
+    F: FnOnce(f64) -> f64,
+{
+    let unit = output_unit / wrt;
+    if wrt != match_for {
+        return Some(DynQuantity::new(0.0, unit));
+    }
+    let value = influencing_factors
+        .iter()
+        .find(|f| f.unit == match_for)
+        .map(|f| derivative(f.value))
+        .unwrap_or(0.0);
+    return Some(DynQuantity::new(value, unit));
+}
@@ -0,0 +1,596 @@
+/*!
+A multivariate [`Grid`] function (multilinear interpolation on a rectilinear
+grid) which implements [`QuantityFunction`].
+*/
+
+use dyn_quantity::{DynQuantity, Unit, UnitsNotEqual};
+
+use crate::unary::Extrapolation;
+use crate::{BatchInput, EvaluationError, Evaluator, Layout, QuantityFunction, SupportPointsError};
+
+/**
+A table defined on a rectilinear grid which is evaluated by multilinear
+interpolation (bilinear for two axes, trilinear for three axes etc.).
+
+The grid is defined by `N` axes, each of which is a list of strictly increasing
+finite support points with identical units. Each axis is bound to the influencing
+factor with its unit, hence the units of all axes must be distinct. The values
+at the grid points are stored in a flat vector in row-major order (the index of
+the last axis changes fastest), its length must equal the product of the axis
+lengths. All values must be finite and have the same unit, which is the output
+unit of the function. These conditions are checked in the constructor [`Grid::new`].
+
+If no influencing factor matches the unit of an axis, the corresponding input
+is assumed to be zero. The behaviour for inputs outside of an axis is defined
+by the [`Extrapolation`] setting and applied to each axis separately.
+
+# Examples
+
+Core losses depending on flux density and frequency:
+
+```
+use std::str::FromStr;
+use dyn_quantity::DynQuantity;
+use var_quantity::{QuantityFunction, multivariate::Grid, unary::Extrapolation};
+
+let q = |s: &str| DynQuantity::<f64>::from_str(s).unwrap();
+
+let grid = Grid::new(
+    vec![
+        vec![q("0 T"), q("1 T"), q("2 T")],
+        vec![q("50 Hz"), q("100 Hz")],
+    ],
+    vec![
+        q("0 W/kg"), q("0 W/kg"), // 0 T
+        q("1 W/kg"), q("3 W/kg"), // 1 T
+        q("4 W/kg"), q("10 W/kg"), // 2 T
+    ],
+    Extrapolation::Hold,
+).expect("valid grid");
+
+assert_eq!(grid.call(&[q("1 T"), q("50 Hz")]).value, 1.0);
+assert_eq!(grid.call(&[q("75 Hz"), q("1.5 T")]).value, 4.5);
+```
+
+# Features:
+This struct can be serialized / deserialized if the `serde` feature is enabled.
+The `values` are serialized as a flat list. During deserialization, they can
+also be given as nested lists whose nesting depth equals the number of axes:
+
+```
+use std::str::FromStr;
+use indoc::indoc;
+use dyn_quantity::DynQuantity;
+use var_quantity::{QuantityFunction, multivariate::Grid};
+
+let yaml = indoc! {"
+    ---
+    axes:
+      - [0 T, 1 T, 2 T]
+      - [50 Hz, 100 Hz]
+    values:
+      - [0 W/kg, 0 W/kg]
+      - [1 W/kg, 3 W/kg]
+      - [4 W/kg, 10 W/kg]
+    extrapolation: Linear
+    "};
+let grid: Grid = serde_yaml::from_str(yaml).expect("valid grid");
+
+let q = |s: &str| DynQuantity::<f64>::from_str(s).unwrap();
+assert_eq!(grid.call(&[q("1 T"), q("100 Hz")]).value, 3.0);
+assert_eq!(grid.call(&[q("1 T"), q("150 Hz")]).value, 5.0);
+```
+*/
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct Grid {
+    axes: Vec<Vec<DynQuantity<f64>>>,
+    values: Vec<DynQuantity<f64>>,
+    extrapolation: Extrapolation,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    axis_units: Vec<Unit>,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    axis_values: Vec<Vec<f64>>,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    strides: Vec<usize>,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    raw_values: Vec<f64>,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    output_unit: Unit,
+}
+
+impl Grid {
+    /**
+    Checks if the axes and values define a valid grid (see the docstring of
+    [`Grid`]). If this is the case, a new instance of [`Grid`] is returned.
+
+    # Examples
+
+    ```
+    use std::str::FromStr;
+    use dyn_quantity::DynQuantity;
+    use var_quantity::{SupportPointsError, multivariate::Grid, unary::Extrapolation};
+
+    let q = |s: &str| DynQuantity::<f64>::from_str(s).unwrap();
+
+    let axes = vec![
+        vec![q("0 rpm"), q("1000 rpm")],
+        vec![q("0 N*m"), q("10 N*m"), q("20 N*m")],
+    ];
+    let values = vec![q("0.5"), q("0.6"), q("0.7"), q("0.8"), q("0.9"), q("0.95")];
+    assert!(Grid::new(axes.clone(), values.clone(), Extrapolation::Hold).is_ok());
+
+    // Wrong number of values
+    assert_eq!(
+        Grid::new(axes.clone(), values[..5].to_vec(), Extrapolation::Hold),
+        Err(SupportPointsError::LengthMismatch { expected: 6, found: 5 })
+    );
+
+    // Both axes have the same unit
+    let axes = vec![
+        vec![q("0 rpm"), q("1000 rpm")],
+        vec![q("0 rpm"), q("10 rpm"), q("20 rpm")],
+    ];
+    assert!(Grid::new(axes, values, Extrapolation::Hold).is_err());
+    ```
+    */
+    pub fn new(
+        axes: Vec<Vec<DynQuantity<f64>>>,
+        values: Vec<DynQuantity<f64>>,
+        extrapolation: Extrapolation,
+    ) -> Result<Self, SupportPointsError> {
+        let mut axis_units: Vec<Unit> = Vec::with_capacity(axes.len());
+        let mut axis_values = Vec::with_capacity(axes.len());
+        for axis in axes.iter() {
+            if axis.len() < 2 {
+                return Err(SupportPointsError::NotEnoughPoints {
+                    required: 2,
+                    found: axis.len(),
+                });
+            }
+            let unit = axis[0].unit;
+            if axis_units.contains(&unit) {
+                return Err(SupportPointsError::AmbiguousUnit(unit));
+            }
+            let mut raw: Vec<f64> = Vec::with_capacity(axis.len());
+            for (index, point) in axis.iter().enumerate() {
+                if point.unit != unit {
+                    return Err(UnitsNotEqual(unit, point.unit).into());
+                }
+                if !point.value.is_finite() {
+                    return Err(SupportPointsError::NotFinite(index));
+                }
+                if raw.last().is_some_and(|previous| *previous >= point.value) {
+                    return Err(SupportPointsError::NotStrictlyIncreasing(index));
+                }
+                raw.push(point.value);
+            }
+            axis_units.push(unit);
+            axis_values.push(raw);
+        }
+
+        // Row-major order: The index of the last axis changes fastest
+        let mut strides = vec![1; axes.len()];
+        for k in (0..axes.len().saturating_sub(1)).rev() {
+            strides[k] = strides[k + 1] * axes[k + 1].len();
+        }
+        let expected: usize = axes.iter().map(Vec::len).product();
+        if values.len() != expected {
+            return Err(SupportPointsError::LengthMismatch {
+                expected,
+                found: values.len(),
+            });
+        }
+
+        // The check above makes sure that at least one value exists
+        let output_unit = values[0].unit;
+        let mut raw_values = Vec::with_capacity(values.len());
+        for (index, value) in values.iter().enumerate() {
+            if value.unit != output_unit {
+                return Err(UnitsNotEqual(output_unit, value.unit).into());
+            }
+            if !value.value.is_finite() {
+                return Err(SupportPointsError::NotFinite(index));
+            }
+            raw_values.push(value.value);
+        }
+
+        return Ok(Self {
+            axes,
+            values,
+            extrapolation,
+            axis_units,
+            axis_values,
+            strides,
+            raw_values,
+            output_unit,
+        });
+    }
+
+    /**
+    Returns the axes of the grid.
+    */
+    pub fn axes(&self) -> &[Vec<DynQuantity<f64>>] {
+        return self.axes.as_slice();
+    }
+
+    /**
+    Returns the values at the grid points in row-major order.
+    */
+    pub fn values(&self) -> &[DynQuantity<f64>] {
+        return self.values.as_slice();
+    }
+
+    /**
+    Returns the number of support points of each axis.
+    */
+    pub fn shape(&self) -> Vec<usize> {
+        return self.axes.iter().map(Vec::len).collect();
+    }
+
+    /**
+    Returns the [`Extrapolation`] behaviour.
+    */
+    pub fn extrapolation(&self) -> Extrapolation {
+        return self.extrapolation;
+    }
+
+    /**
+    Returns the units of the quantities which influence the variable quantity,
+    in the order of the axes.
+
+    # Examples
+
+    ```
+    use std::str::FromStr;
+    use dyn_quantity::{DynQuantity, PredefUnit, Unit};
+    use var_quantity::{multivariate::Grid, unary::Extrapolation};
+
+    let q = |s: &str| DynQuantity::<f64>::from_str(s).unwrap();
+
+    let grid = Grid::new(
+        vec![vec![q("0 T"), q("1 T")], vec![q("50 Hz"), q("100 Hz")]],
+        vec![q("0 W"), q("0 W"), q("1 W"), q("3 W")],
+        Extrapolation::Hold,
+    ).expect("valid grid");
+    assert_eq!(
+        grid.influencing_factor_units(),
+        &[Unit::from(PredefUnit::MagneticFluxDensity), Unit::from(PredefUnit::Frequency)]
+    );
+    assert_eq!(grid.output_unit(), Unit::from(PredefUnit::Power));
+    ```
+    */
+    pub fn influencing_factor_units(&self) -> &[Unit] {
+        return self.axis_units.as_slice();
+    }
+
+    /**
+    Returns the unit which will be returned from [`QuantityFunction::call`].
+     */
+    pub fn output_unit(&self) -> Unit {
+        return self.output_unit;
+    }
+
+    /**
+    Selects the input for each axis from the influencing factors. Missing
+    inputs are assumed to be zero.
+     */
+    fn inputs(&self, influencing_factors: &[DynQuantity<f64>]) -> Vec<f64> {
+        return self
+            .axis_units
+            .iter()
+            .map(|unit| {
+                influencing_factors
+                    .iter()
+                    .find(|factor| factor.unit == *unit)
+                    .map(|factor| factor.value)
+                    .unwrap_or(0.0)
+            })
+            .collect();
+    }
+
+    /**
+    Interpolates the grid at the given inputs. If `wrt_axis` is given, the
+    partial derivative with respect to the input of this axis is returned
+    instead. Returns the index of the first axis for which the input is outside
+    of the grid if extrapolation is forbidden.
+     */
+    fn interpolate(&self, inputs: &[f64], wrt_axis: Option<usize>) -> Result<f64, usize> {
+        // Segment index and relative position within the segment for each axis
+        let mut cells = Vec::with_capacity(inputs.len());
+        for (k, (axis, x)) in self.axis_values.iter().zip(inputs.iter()).enumerate() {
+            let first = axis[0];
+            let last = axis[axis.len() - 1];
+            let mut x = *x;
+            if x < first || x > last {
+                match self.extrapolation {
+                    Extrapolation::Hold => {
+                        // The held value does not depend on the input
+                        if wrt_axis == Some(k) {
+                            return Ok(0.0);
+                        }
+                        x = x.clamp(first, last);
+                    }
+                    Extrapolation::Linear => (),
+                    Extrapolation::Error => return Err(k),
+                }
+            }
+            let i = axis
+                .partition_point(|xi| *xi <= x)
+                .saturating_sub(1)
+                .min(axis.len() - 2);
+            let h = axis[i + 1] - axis[i];
+            cells.push((i, (x - axis[i]) / h, h));
+        }
+
+        // Weighted sum over the 2^N corners of the cell
+        let mut value = 0.0;
+        for corner in 0..(1usize << cells.len()) {
+            let mut index = 0;
+            let mut weight = 1.0;
+            for (k, (i, t, h)) in cells.iter().enumerate() {
+                let upper = corner & (1 << k) != 0;
+                if upper {
+                    index += (i + 1) * self.strides[k];
+                } else {
+                    index += i * self.strides[k];
+                }
+                weight *= match (wrt_axis == Some(k), upper) {
+                    (false, false) => 1.0 - t,
+                    (false, true) => *t,
+                    (true, false) => -1.0 / h,
+                    (true, true) => 1.0 / h,
+                };
+            }
+            value += weight * self.raw_values[index];
+        }
+        return Ok(value);
+    }
+}
+
+#[cfg_attr(feature = "serde", typetag::serde)]
+impl QuantityFunction for Grid {
+    fn call(&self, influencing_factors: &[DynQuantity<f64>]) -> DynQuantity<f64> {
+        let value = self
+            .interpolate(&self.inputs(influencing_factors), None)
+            .unwrap_or(f64::NAN);
+        return DynQuantity::new(value, self.output_unit);
+    }
+
+    fn call_batch(&self, inputs: &[BatchInput], output: &mut [f64]) {
+        crate::batch::assert_lengths(inputs, output.len());
+
+        // Select the column of each axis only once
+        let columns: Vec<Option<&[f64]>> = self
+            .axis_units
+            .iter()
+            .map(|unit| {
+                inputs
+                    .iter()
+                    .find(|input| input.unit == *unit)
+                    .map(|input| input.values)
+            })
+            .collect();
+        let mut point = vec![0.0; columns.len()];
+        for (index, out) in output.iter_mut().enumerate() {
+            for (coordinate, column) in point.iter_mut().zip(columns.iter()) {
+                *coordinate = column.map_or(0.0, |values| values[index]);
+            }
+            *out = self.interpolate(&point, None).unwrap_or(f64::NAN);
+        }
+    }
+
+    fn bind(&self, layout: &Layout) -> Box<dyn Evaluator + '_> {
+        // Resolve the index of each axis only once
+        let indices: Vec<Option<usize>> = self
+            .axis_units
+            .iter()
+            .map(|unit| layout.position(*unit, None))
+            .collect();
+        return Box::new(move |values: &[f64]| {
+            let point: Vec<f64> = indices
+                .iter()
+                .map(|index| index.map_or(0.0, |index| values[index]))
+                .collect();
+            self.interpolate(&point, None).unwrap_or(f64::NAN)
+        });
+    }
+
+    fn output_unit(&self) -> Unit {
+        return self.output_unit;
+    }
+
+    fn influencing_units(&self) -> Option<Vec<Unit>> {
+        return Some(self.axis_units.clone());
+    }
+
+    fn try_call(
+        &self,
+        influencing_factors: &[DynQuantity<f64>],
+    ) -> Result<DynQuantity<f64>, EvaluationError> {
+        let inputs = self.inputs(influencing_factors);
+        match self.interpolate(&inputs, None) {
+            Ok(value) => return Ok(DynQuantity::new(value, self.output_unit)),
+            Err(k) => {
+                let axis = &self.axes[k];
+                return Err(EvaluationError::InputOutOfRange {
+                    input: DynQuantity::new(inputs[k], self.axis_units[k]),
+                    lower: axis[0],
+                    upper: axis[axis.len() - 1],
+                });
+            }
+        }
+    }
+
+    fn derivative(
+        &self,
+        influencing_factors: &[DynQuantity<f64>],
+        wrt: Unit,
+    ) -> Option<DynQuantity<f64>> {
+        let unit = self.output_unit / wrt;
+        let Some(k) = self
+            .axis_units
+            .iter()
+            .position(|axis_unit| *axis_unit == wrt)
+        else {
+            return Some(DynQuantity::new(0.0, unit));
+        };
+
+        // A missing input is replaced by a constant default
+        if !influencing_factors.iter().any(|factor| factor.unit == wrt) {
+            return Some(DynQuantity::new(0.0, unit));
+        }
+        let value = self
+            .interpolate(&self.inputs(influencing_factors), Some(k))
+            .unwrap_or(f64::NAN);
+        return Some(DynQuantity::new(value, unit));
+    }
+}
+
+#[cfg(feature = "serde")]
+mod serde_impl {
+    use super::*;
+
+    use serde::de::{Deserialize, Deserializer};
+
+    /// Values given either as a flat or as a nested list.
+    #[derive(serde::Deserialize)]
+    #[serde(untagged)]
+    enum NestedValues {
+        List(Vec<NestedValues>),
+        Value(DynQuantity<f64>),
+    }
+
+    /**
+    Flattens `nested` in row-major order and checks that the nesting depth and
+    the length of each list match the `shape` of the grid.
+     */
+    fn flatten(
+        nested: NestedValues,
+        shape: &[usize],
+        flat: &mut Vec<DynQuantity<f64>>,
+    ) -> Result<(), String> {
+        match (nested, shape.split_first()) {
+            (NestedValues::Value(value), None) => flat.push(value),
+            (NestedValues::List(list), Some((len, rest))) => {
+                if list.len() != *len {
+                    return Err(SupportPointsError::LengthMismatch {
+                        expected: *len,
+                        found: list.len(),
+                    }
+                    .to_string());
+                }
+                for item in list {
+                    flatten(item, rest, flat)?;
+                }
+            }
+            _ => {
+                return Err(
+                    "the nesting depth of `values` does not match the number of axes".to_string(),
+                );
+            }
+        }
+        return Ok(());
+    }
+
+    impl<'de> Deserialize<'de> for Grid {
+        fn deserialize<D>(deserializer: D) -> Result<Grid, D::Error>
+        where
+            D: Deserializer<'de>,
+        {
+            #[derive(serde::Deserialize)]
+            struct GridAlias {
+                axes: Vec<Vec<DynQuantity<f64>>>,
+                values: NestedValues,
+                #[serde(default)]
+                extrapolation: Extrapolation,
+            }
+
+            let alias = GridAlias::deserialize(deserializer)?;
+            let shape: Vec<usize> = alias.axes.iter().map(Vec::len).collect();
+
+            // A flat list is accepted as well
+            let values = match alias.values {
+                NestedValues::List(list)
+                    if list
+                        .iter()
+                        .all(|item| matches!(item, NestedValues::Value(_))) =>
+                {
+                    list.into_iter()
+                        .filter_map(|item| match item {
+                            NestedValues::Value(value) => Some(value),
+                            NestedValues::List(_) => None,
+                        })
+                        .collect()
+                }
+                nested => {
+                    let mut flat = Vec::new();
+                    flatten(nested, &shape, &mut flat).map_err(serde::de::Error::custom)?;
+                    flat
+                }
+            };
+            return Self::new(alias.axes, values, alias.extrapolation)
+                .map_err(serde::de::Error::custom);
+        }
+    }
+}
+
+// =============================================================================
+
+#[cfg(feature = "serde")]
+#[cfg_attr(feature = "serde", typetag::serde)]
+impl QuantityFunction for crate::ClampedQuantity<Grid> {
+    fn call(&self, influencing_factors: &[DynQuantity<f64>]) -> DynQuantity<f64> {
+        return self.call_clamped(influencing_factors);
+    }
+
+    fn output_unit(&self) -> Unit {
+        return self.inner().output_unit();
+    }
+
+    fn influencing_units(&self) -> Option<Vec<Unit>> {
+        return self.inner().influencing_units();
+    }
+
+    fn validity_ranges(&self) -> Vec<crate::validity::ValidityRange> {
+        return self.inner().validity_ranges();
+    }
+
+    fn call_named(&self, influencing_factors: &[crate::InfluencingFactor]) -> DynQuantity<f64> {
+        return self.call_named_clamped(influencing_factors);
+    }
+
+    fn try_call(
+        &self,
+        influencing_factors: &[DynQuantity<f64>],
+    ) -> Result<DynQuantity<f64>, EvaluationError> {
+        return self.try_call_clamped(influencing_factors);
+    }
+
+    fn try_call_named(
+        &self,
+        influencing_factors: &[crate::InfluencingFactor],
+    ) -> Result<DynQuantity<f64>, EvaluationError> {
+        return self.try_call_named_clamped(influencing_factors);
+    }
+
+    fn derivative(
+        &self,
+        influencing_factors: &[DynQuantity<f64>],
+        wrt: Unit,
+    ) -> Option<DynQuantity<f64>> {
+        return self.derivative_clamped(influencing_factors, wrt);
+    }
+
+    fn call_dual(&self, influencing_factors: &[crate::DualQuantity]) -> crate::DualQuantity {
+        return self.call_dual_clamped(influencing_factors);
+    }
+
+    fn call_batch(&self, inputs: &[crate::BatchInput], output: &mut [f64]) {
+        self.call_batch_clamped(inputs, output);
+    }
+
+    fn bind(&self, layout: &crate::Layout) -> Box<dyn crate::Evaluator + '_> {
+        return self.bind_clamped(layout);
+    }
+}
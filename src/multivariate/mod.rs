@@ -0,0 +1,8 @@
+/*!
+This module contains multivariate functions (functions of several influencing
+factors) which implement [`QuantityFunction`](crate::QuantityFunction).
+*/
+
+pub mod grid;
+
+pub use grid::Grid;
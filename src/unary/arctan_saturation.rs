@@ -0,0 +1,402 @@
+/*!
+An unary [`ArctanSaturation`] function which implements [`QuantityFunction`].
+*/
+
+use std::f64::consts::FRAC_2_PI;
+
+use dyn_quantity::{DynQuantity, PredefUnit, Unit, UnitsNotEqual};
+
+use crate::{
+    BatchInput, Evaluator, InfluencingFactor, Layout, QuantityFunction, bind_unary,
+    filter_unary_batch, filter_unary_derivative, filter_unary_function, select_influencing_factor,
+    validity::ValidityRange,
+};
+
+pub use super::saturation::{SaturationOutput, VACUUM_PERMEABILITY};
+
+/// Below this argument, `arctan(u) / u` is evaluated via its series expansion.
+const SERIES_LIMIT: f64 = 1e-2;
+
+/**
+An arctan fit of a magnetic saturation curve:
+
+`B = μ0 * H + 2 / π * Js * arctan(H / H0)`
+
+where `μ0` is the [`VACUUM_PERMEABILITY`], `H` is the magnetic field strength,
+`Js` is the saturation polarization (in T) and `H0` is a characteristic field
+strength (in A/m). The initial permeability at `H = 0` is
+`μ0 + 2 / π * Js / H0`.
+
+This struct is meant to be used as a [`QuantityFunction`] trait object. The
+unit of the influencing quantity is [`PredefUnit::MagneticFieldStrength`].
+Depending on the [`SaturationOutput`], either the flux density `B(H)` (default)
+or the permeability `μ(H) = B(H) / H` is returned, see
+[`ArctanSaturation::with_output`].
+
+# Examples
+
+```
+use std::str::FromStr;
+use dyn_quantity::DynQuantity;
+use var_quantity::{QuantityFunction, unary::{ArctanSaturation, SaturationOutput}};
+
+let q = |s: &str| DynQuantity::<f64>::from_str(s).unwrap();
+
+let fun = ArctanSaturation::new(q("1.8 T"), q("200 A/m")).expect("units match");
+
+// At H = H0, the polarization is half of the saturation polarization
+let b = fun.call(&[q("200 A/m")]);
+assert_eq!(b.unit, q("1 T").unit);
+approx::assert_relative_eq!(b.value, 0.9 + 200.0 * 1.25663706212e-6);
+
+// Initial permeability
+let fun = fun.with_output(SaturationOutput::Permeability);
+approx::assert_relative_eq!(
+    fun.call(&[q("0 A/m")]).value,
+    1.25663706212e-6 + 2.0 / std::f64::consts::PI * 1.8 / 200.0
+);
+```
+
+# Features:
+This struct can be serialized / deserialized if the `serde` feature is enabled.
+ */
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct ArctanSaturation {
+    saturation_polarization: DynQuantity<f64>,
+    characteristic_field: DynQuantity<f64>,
+    output: SaturationOutput,
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    input_name: Option<String>,
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    validity: Option<ValidityRange>,
+}
+
+impl ArctanSaturation {
+    /**
+    Checks if `saturation_polarization` is given in T and if
+    `characteristic_field` is given in A/m. If this is the case, a new instance
+    of [`ArctanSaturation`] which returns the flux density is returned.
+    Otherwise, the error contains the expected and the found unit of the first
+    mismatching argument. For a physically meaningful curve, both arguments
+    must be positive.
+
+    # Examples
+
+    ```
+    use std::str::FromStr;
+    use dyn_quantity::DynQuantity;
+    use var_quantity::unary::ArctanSaturation;
+
+    let q = |s: &str| DynQuantity::<f64>::from_str(s).unwrap();
+
+    assert!(ArctanSaturation::new(q("1.8 T"), q("200 A/m")).is_ok());
+    assert!(ArctanSaturation::new(q("1.8 A/m"), q("200 A/m")).is_err());
+    assert!(ArctanSaturation::new(q("1.8 T"), q("200 T")).is_err());
+    ```
+    */
+    pub fn new(
+        saturation_polarization: DynQuantity<f64>,
+        characteristic_field: DynQuantity<f64>,
+    ) -> Result<Self, UnitsNotEqual> {
+        let flux_density = Unit::from(PredefUnit::MagneticFluxDensity);
+        if saturation_polarization.unit != flux_density {
+            return Err(UnitsNotEqual(flux_density, saturation_polarization.unit));
+        }
+        let field_strength = Unit::from(PredefUnit::MagneticFieldStrength);
+        if characteristic_field.unit != field_strength {
+            return Err(UnitsNotEqual(field_strength, characteristic_field.unit));
+        }
+        return Ok(Self {
+            saturation_polarization,
+            characteristic_field,
+            output: SaturationOutput::default(),
+            input_name: None,
+            validity: None,
+        });
+    }
+
+    /**
+    Returns the saturation polarization `Js`.
+    */
+    pub fn saturation_polarization(&self) -> &DynQuantity<f64> {
+        return &self.saturation_polarization;
+    }
+
+    /**
+    Returns the characteristic field strength `H0`.
+    */
+    pub fn characteristic_field(&self) -> &DynQuantity<f64> {
+        return &self.characteristic_field;
+    }
+
+    /**
+    Sets the quantity returned by the function, see [`SaturationOutput`].
+    */
+    pub fn with_output(mut self, output: SaturationOutput) -> Self {
+        self.output = output;
+        return self;
+    }
+
+    /**
+    Returns the quantity returned by the function, see
+    [`ArctanSaturation::with_output`].
+    */
+    pub fn output(&self) -> SaturationOutput {
+        return self.output;
+    }
+
+    /**
+    Returns the unit of the quantity which influences the variable quantity,
+    which is always [`PredefUnit::MagneticFieldStrength`]. If none of the
+    `influencing_factors` in a [`QuantityFunction::call`] matches this item,
+    then `H` is assumed to be zero.
+
+    # Examples
+
+    ```
+    use std::str::FromStr;
+    use dyn_quantity::{DynQuantity, PredefUnit, Unit};
+    use var_quantity::{QuantityFunction, unary::ArctanSaturation};
+
+    let q = |s: &str| DynQuantity::<f64>::from_str(s).unwrap();
+
+    let fun = ArctanSaturation::new(q("1.8 T"), q("200 A/m")).expect("units match");
+    assert_eq!(fun.influencing_factor_unit(), Unit::from(PredefUnit::MagneticFieldStrength));
+    assert_eq!(fun.call(&[q("1 K")]), q("0 T"));
+    ```
+    */
+    pub fn influencing_factor_unit(&self) -> Unit {
+        return PredefUnit::MagneticFieldStrength.into();
+    }
+
+    /**
+    Returns the unit which will be returned from [`QuantityFunction::call`],
+    see [`SaturationOutput::unit`].
+
+    ```
+    use std::str::FromStr;
+    use dyn_quantity::DynQuantity;
+    use var_quantity::{QuantityFunction, unary::{ArctanSaturation, SaturationOutput}};
+
+    let q = |s: &str| DynQuantity::<f64>::from_str(s).unwrap();
+
+    let fun = ArctanSaturation::new(q("1.8 T"), q("200 A/m")).expect("units match");
+    assert_eq!(fun.output_unit(), q("1 T").unit);
+
+    let fun = fun.with_output(SaturationOutput::Permeability);
+    assert_eq!(fun.output_unit(), q("1 T*m/A").unit);
+    ```
+     */
+    pub fn output_unit(&self) -> Unit {
+        return self.output.unit();
+    }
+
+    /**
+    Returns the flux density or the permeability for the field strength `h`.
+     */
+    fn evaluate(&self, h: f64) -> f64 {
+        let js = FRAC_2_PI * self.saturation_polarization.value;
+        let h0 = self.characteristic_field.value;
+        let u = h / h0;
+        match self.output {
+            SaturationOutput::FluxDensity => return VACUUM_PERMEABILITY * h + js * u.atan(),
+            SaturationOutput::Permeability => {
+                return VACUUM_PERMEABILITY + js / h0 * arctan_ratio(u).0;
+            }
+        }
+    }
+
+    /**
+    Returns the derivative of [`ArctanSaturation::evaluate`] with respect to `h`.
+     */
+    fn evaluate_derivative(&self, h: f64) -> f64 {
+        let js = FRAC_2_PI * self.saturation_polarization.value;
+        let h0 = self.characteristic_field.value;
+        let u = h / h0;
+        match self.output {
+            SaturationOutput::FluxDensity => {
+                return VACUUM_PERMEABILITY + js / (h0 * (1.0 + u.powi(2)));
+            }
+            SaturationOutput::Permeability => return js / h0.powi(2) * arctan_ratio(u).1,
+        }
+    }
+}
+
+/**
+Returns `arctan(u) / u` and its derivative.
+ */
+fn arctan_ratio(u: f64) -> (f64, f64) {
+    if u.abs() < SERIES_LIMIT {
+        return (
+            1.0 - u.powi(2) / 3.0 + u.powi(4) / 5.0,
+            -2.0 * u / 3.0 + 4.0 * u.powi(3) / 5.0,
+        );
+    }
+    let value = u.atan() / u;
+    return (value, (1.0 / (1.0 + u.powi(2)) - value) / u);
+}
+
+impl_input_name!(ArctanSaturation);
+impl_validity!(ArctanSaturation);
+
+#[cfg_attr(feature = "serde", typetag::serde)]
+impl QuantityFunction for ArctanSaturation {
+    fn call(&self, influencing_factors: &[DynQuantity<f64>]) -> DynQuantity<f64> {
+        return filter_unary_function(
+            influencing_factors,
+            self.influencing_factor_unit(),
+            |input| DynQuantity::new(self.evaluate(input.value), self.output_unit()),
+            || DynQuantity::new(self.evaluate(0.0), self.output_unit()),
+        );
+    }
+
+    fn call_batch(&self, inputs: &[BatchInput], output: &mut [f64]) {
+        filter_unary_batch(
+            inputs,
+            self.influencing_factor_unit(),
+            output,
+            |input| self.evaluate(input),
+            || self.evaluate(0.0),
+        );
+    }
+
+    fn bind(&self, layout: &Layout) -> Box<dyn Evaluator + '_> {
+        return bind_unary(
+            layout,
+            self.influencing_factor_unit(),
+            self.input_name.as_deref(),
+            |input| self.evaluate(input),
+            || self.evaluate(0.0),
+        );
+    }
+
+    fn output_unit(&self) -> Unit {
+        return ArctanSaturation::output_unit(self);
+    }
+
+    fn influencing_units(&self) -> Option<Vec<Unit>> {
+        return Some(vec![self.influencing_factor_unit()]);
+    }
+
+    fn validity_ranges(&self) -> Vec<ValidityRange> {
+        return super::bind_validity_ranges(
+            self.validity.iter().cloned(),
+            self.input_name.as_deref(),
+        );
+    }
+
+    fn call_named(&self, influencing_factors: &[InfluencingFactor]) -> DynQuantity<f64> {
+        let selected = select_influencing_factor(
+            influencing_factors,
+            self.input_name.as_deref(),
+            self.influencing_factor_unit(),
+        );
+        return self.call(selected.as_slice());
+    }
+
+    fn derivative(
+        &self,
+        influencing_factors: &[DynQuantity<f64>],
+        wrt: Unit,
+    ) -> Option<DynQuantity<f64>> {
+        return filter_unary_derivative(
+            influencing_factors,
+            self.influencing_factor_unit(),
+            self.output_unit(),
+            wrt,
+            |h| self.evaluate_derivative(h),
+        );
+    }
+}
+
+#[cfg(feature = "serde")]
+mod serde_impl {
+    use super::*;
+
+    use serde::de::{Deserialize, Deserializer};
+
+    impl<'de> Deserialize<'de> for ArctanSaturation {
+        fn deserialize<D>(deserializer: D) -> Result<ArctanSaturation, D::Error>
+        where
+            D: Deserializer<'de>,
+        {
+            #[derive(serde::Deserialize)]
+            struct ArctanSaturationAlias {
+                saturation_polarization: DynQuantity<f64>,
+                characteristic_field: DynQuantity<f64>,
+                #[serde(default)]
+                output: SaturationOutput,
+                #[serde(default)]
+                input_name: Option<String>,
+                #[serde(default)]
+                validity: Option<ValidityRange>,
+            }
+
+            let alias = ArctanSaturationAlias::deserialize(deserializer)?;
+            let mut arctan_saturation =
+                Self::new(alias.saturation_polarization, alias.characteristic_field)
+                    .map_err(serde::de::Error::custom)?
+                    .with_output(alias.output);
+            arctan_saturation.input_name = alias.input_name;
+            if let Some(range) = alias.validity {
+                arctan_saturation = arctan_saturation
+                    .with_validity(range)
+                    .map_err(serde::de::Error::custom)?;
+            }
+            return Ok(arctan_saturation);
+        }
+    }
+}
+
+// =============================================================================
+
+#[cfg(feature = "serde")]
+#[cfg_attr(feature = "serde", typetag::serde)]
+impl QuantityFunction for crate::ClampedQuantity<ArctanSaturation> {
+    fn call(&self, influencing_factors: &[DynQuantity<f64>]) -> DynQuantity<f64> {
+        return self.call_clamped(influencing_factors);
+    }
+
+    fn output_unit(&self) -> Unit {
+        return self.inner().output_unit();
+    }
+
+    fn influencing_units(&self) -> Option<Vec<Unit>> {
+        return self.inner().influencing_units();
+    }
+
+    fn validity_ranges(&self) -> Vec<ValidityRange> {
+        return self.inner().validity_ranges();
+    }
+
+    fn call_named(&self, influencing_factors: &[InfluencingFactor]) -> DynQuantity<f64> {
+        return self.call_named_clamped(influencing_factors);
+    }
+
+    fn derivative(
+        &self,
+        influencing_factors: &[DynQuantity<f64>],
+        wrt: Unit,
+    ) -> Option<DynQuantity<f64>> {
+        return self.derivative_clamped(influencing_factors, wrt);
+    }
+
+    fn call_dual(&self, influencing_factors: &[crate::DualQuantity]) -> crate::DualQuantity {
+        return self.call_dual_clamped(influencing_factors);
+    }
+
+    fn call_batch(&self, inputs: &[crate::BatchInput], output: &mut [f64]) {
+        self.call_batch_clamped(inputs, output);
+    }
+
+    fn bind(&self, layout: &crate::Layout) -> Box<dyn crate::Evaluator + '_> {
+        return self.bind_clamped(layout);
+    }
+}
@@ -0,0 +1,472 @@
+/*!
+An unary [`Arrhenius`] function which implements [`QuantityFunction`].
+*/
+
+use dyn_quantity::{DynQuantity, PredefUnit, Unit, UnitsNotEqual};
+
+use crate::{
+    BatchInput, Evaluator, InfluencingFactor, Layout, QuantityFunction, bind_unary,
+    filter_unary_batch, filter_unary_derivative, filter_unary_function, select_influencing_factor,
+    validity::ValidityRange,
+};
+
+/// Boltzmann constant in J/K.
+pub const BOLTZMANN_CONSTANT: f64 = 1.380649e-23;
+
+/// Avogadro constant in 1/mol.
+pub const AVOGADRO_CONSTANT: f64 = 6.02214076e23;
+
+/// Molar gas constant in J/(mol*K).
+pub const GAS_CONSTANT: f64 = BOLTZMANN_CONSTANT * AVOGADRO_CONSTANT;
+
+/// One electronvolt in J.
+pub const ELECTRON_VOLT: f64 = 1.602176634e-19;
+
+/**
+A (modified) Arrhenius function of the temperature `T`:
+
+`y = A * T^n * exp(-Ea / (R * T))`
+
+where `A` is the pre-factor, `Ea` is the activation energy, `R` is the
+[`GAS_CONSTANT`] and `n` is the temperature exponent. This function describes
+e.g. the rate of chemical reactions, diffusion coefficients or the aging of
+electrical insulation. The default temperature exponent is zero (classical
+Arrhenius equation); it can be set via [`Arrhenius::with_temperature_exponent`].
+For example, the Eyring equation `k = kB * T / h * exp(-ΔG / (R * T))` is
+obtained with `n = 1` and `A = kB / h`.
+
+The activation energy can either be given per amount of substance (e.g. in
+J/mol) or per particle as an energy (e.g. in J). In the latter case, the
+[`BOLTZMANN_CONSTANT`] is used instead of the gas constant. Activation
+energies per particle in electronvolt can be given via
+[`Arrhenius::from_electronvolt`].
+
+This struct is meant to be used as a [`QuantityFunction`] trait object. The
+unit of the influencing quantity is [`PredefUnit::Temperature`] and the output
+unit is that of the pre-factor. The temperature is inserted into `T^n` as a
+value in kelvin, hence the pre-factor of a modified Arrhenius function already
+contains the unit `K^-n` implicitly.
+
+# Examples
+
+```
+use std::str::FromStr;
+use dyn_quantity::DynQuantity;
+use var_quantity::{QuantityFunction, unary::Arrhenius};
+
+let q = |s: &str| DynQuantity::<f64>::from_str(s).unwrap();
+
+// Diffusion coefficient D = D0 * exp(-Ea / (R * T))
+let fun = Arrhenius::new(q("2e-5 m^2/s"), q("150 kJ/mol")).expect("energy per amount of substance");
+let diffusion = fun.call(&[q("1000 K")]);
+assert_eq!(diffusion.unit, q("1 m^2/s").unit);
+approx::assert_relative_eq!(diffusion.value, 2.9241e-13, max_relative = 1e-4);
+
+// The same activation energy per particle in electronvolt
+let fun = Arrhenius::from_electronvolt(q("2e-5 m^2/s"), 1.55464);
+approx::assert_relative_eq!(fun.call(&[q("1000 K")]).value, 2.9241e-13, max_relative = 1e-4);
+```
+
+# Features:
+This struct can be serialized / deserialized if the `serde` feature is enabled.
+The activation energy can be given in electronvolt (e.g. `0.7 eV`) during
+deserialization, which is equivalent to [`Arrhenius::from_electronvolt`]. It
+is always serialized in J.
+ */
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct Arrhenius {
+    pre_factor: DynQuantity<f64>,
+    activation_energy: DynQuantity<f64>,
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "is_zero"))]
+    temperature_exponent: f64,
+    // Activation energy divided by the gas or Boltzmann constant in K
+    #[cfg_attr(feature = "serde", serde(skip))]
+    activation_temperature: f64,
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    input_name: Option<String>,
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    validity: Option<ValidityRange>,
+}
+
+impl Arrhenius {
+    /**
+    Checks if `activation_energy` is either an energy per amount of substance
+    or an energy. If this is the case, a new instance of [`Arrhenius`] with
+    the temperature exponent zero is returned. Otherwise, the error contains
+    the unit of an energy per amount of substance and that of
+    `activation_energy`.
+
+    # Examples
+
+    ```
+    use std::str::FromStr;
+    use dyn_quantity::DynQuantity;
+    use var_quantity::unary::Arrhenius;
+
+    let q = |s: &str| DynQuantity::<f64>::from_str(s).unwrap();
+
+    assert!(Arrhenius::new(q("1 / s"), q("80 kJ/mol")).is_ok());
+    assert!(Arrhenius::new(q("1 / s"), q("1e-19 J")).is_ok());
+    assert!(Arrhenius::new(q("1 / s"), q("80 kW")).is_err());
+    ```
+    */
+    pub fn new(
+        pre_factor: DynQuantity<f64>,
+        activation_energy: DynQuantity<f64>,
+    ) -> Result<Self, UnitsNotEqual> {
+        let energy = Unit::from(PredefUnit::Energy);
+        let molar_energy = energy / PredefUnit::AmountOfSubstance.into();
+        let activation_temperature = if activation_energy.unit == molar_energy {
+            activation_energy.value / GAS_CONSTANT
+        } else if activation_energy.unit == energy {
+            activation_energy.value / BOLTZMANN_CONSTANT
+        } else {
+            return Err(UnitsNotEqual(molar_energy, activation_energy.unit));
+        };
+        return Ok(Self {
+            pre_factor,
+            activation_energy,
+            temperature_exponent: 0.0,
+            activation_temperature,
+            input_name: None,
+            validity: None,
+        });
+    }
+
+    /**
+    Returns a new instance of [`Arrhenius`] with the temperature exponent zero
+    whose activation energy per particle is given in electronvolt. The
+    activation energy is stored in J, see [`ELECTRON_VOLT`].
+
+    # Examples
+
+    ```
+    use std::str::FromStr;
+    use dyn_quantity::{DynQuantity, PredefUnit};
+    use var_quantity::unary::{Arrhenius, arrhenius::ELECTRON_VOLT};
+
+    let q = |s: &str| DynQuantity::<f64>::from_str(s).unwrap();
+
+    let fun = Arrhenius::from_electronvolt(q("1 / s"), 0.7);
+    assert_eq!(
+        *fun.activation_energy(),
+        DynQuantity::new(0.7 * ELECTRON_VOLT, PredefUnit::Energy)
+    );
+    ```
+    */
+    pub fn from_electronvolt(pre_factor: DynQuantity<f64>, activation_energy: f64) -> Self {
+        let activation_energy =
+            DynQuantity::new(activation_energy * ELECTRON_VOLT, PredefUnit::Energy);
+        return Self::new(pre_factor, activation_energy).expect("activation energy is an energy");
+    }
+
+    /**
+    Sets the temperature exponent `n` of a modified Arrhenius function.
+
+    # Examples
+
+    ```
+    use std::str::FromStr;
+    use dyn_quantity::DynQuantity;
+    use var_quantity::{QuantityFunction, unary::Arrhenius};
+
+    let q = |s: &str| DynQuantity::<f64>::from_str(s).unwrap();
+
+    // Without activation energy, only T^n remains
+    let fun = Arrhenius::new(q("2 / s"), q("0 J/mol"))
+        .expect("energy per amount of substance")
+        .with_temperature_exponent(1.5);
+    assert_eq!(fun.temperature_exponent(), 1.5);
+    assert_eq!(fun.call(&[q("4 K")]).value, 16.0);
+    ```
+    */
+    pub fn with_temperature_exponent(mut self, exponent: f64) -> Self {
+        self.temperature_exponent = exponent;
+        return self;
+    }
+
+    /**
+    Returns the pre-factor `A`.
+    */
+    pub fn pre_factor(&self) -> &DynQuantity<f64> {
+        return &self.pre_factor;
+    }
+
+    /**
+    Returns the activation energy `Ea`.
+    */
+    pub fn activation_energy(&self) -> &DynQuantity<f64> {
+        return &self.activation_energy;
+    }
+
+    /**
+    Returns the temperature exponent `n`.
+    */
+    pub fn temperature_exponent(&self) -> f64 {
+        return self.temperature_exponent;
+    }
+
+    /**
+    Returns the activation energy divided by the [`GAS_CONSTANT`] (or by the
+    [`BOLTZMANN_CONSTANT`] if the activation energy is given per particle).
+
+    # Examples
+
+    ```
+    use std::str::FromStr;
+    use dyn_quantity::DynQuantity;
+    use var_quantity::unary::{Arrhenius, arrhenius::GAS_CONSTANT};
+
+    let q = |s: &str| DynQuantity::<f64>::from_str(s).unwrap();
+
+    let fun = Arrhenius::new(q("1 / s"), DynQuantity::new(1000.0 * GAS_CONSTANT, q("1 J/mol").unit))
+        .expect("energy per amount of substance");
+    approx::assert_relative_eq!(fun.activation_temperature().value, 1000.0);
+    assert_eq!(fun.activation_temperature().unit, q("1 K").unit);
+    ```
+    */
+    pub fn activation_temperature(&self) -> DynQuantity<f64> {
+        return DynQuantity::new(self.activation_temperature, PredefUnit::Temperature);
+    }
+
+    /**
+    Returns the unit of the quantity which influences the variable quantity,
+    which is always [`PredefUnit::Temperature`]. If none of the
+    `influencing_factors` in a [`QuantityFunction::call`] matches this item,
+    the function returns zero (the limit for `T -> 0 K` and a positive
+    activation energy).
+
+    # Examples
+
+    ```
+    use std::str::FromStr;
+    use dyn_quantity::{DynQuantity, PredefUnit, Unit};
+    use var_quantity::{QuantityFunction, unary::Arrhenius};
+
+    let q = |s: &str| DynQuantity::<f64>::from_str(s).unwrap();
+
+    let fun = Arrhenius::new(q("1 / s"), q("80 kJ/mol")).expect("energy per amount of substance");
+    assert_eq!(fun.influencing_factor_unit(), Unit::from(PredefUnit::Temperature));
+    assert_eq!(fun.call(&[q("1 A")]).value, 0.0);
+    ```
+    */
+    pub fn influencing_factor_unit(&self) -> Unit {
+        return PredefUnit::Temperature.into();
+    }
+
+    /**
+    Returns the unit which will be returned from [`QuantityFunction::call`],
+    which is the unit of the pre-factor.
+     */
+    pub fn output_unit(&self) -> Unit {
+        return self.pre_factor.unit;
+    }
+
+    /// Evaluates the function for a temperature value in kelvin.
+    fn evaluate(&self, temperature: f64) -> f64 {
+        return self.pre_factor.value
+            * temperature.powf(self.temperature_exponent)
+            * (-self.activation_temperature / temperature).exp();
+    }
+}
+
+impl_input_name!(Arrhenius);
+impl_validity!(Arrhenius);
+
+#[cfg_attr(feature = "serde", typetag::serde)]
+impl QuantityFunction for Arrhenius {
+    fn call(&self, influencing_factors: &[DynQuantity<f64>]) -> DynQuantity<f64> {
+        return filter_unary_function(
+            influencing_factors,
+            self.influencing_factor_unit(),
+            |input| DynQuantity::new(self.evaluate(input.value), self.output_unit()),
+            || DynQuantity::new(0.0, self.output_unit()),
+        );
+    }
+
+    fn call_batch(&self, inputs: &[BatchInput], output: &mut [f64]) {
+        filter_unary_batch(
+            inputs,
+            self.influencing_factor_unit(),
+            output,
+            |input| self.evaluate(input),
+            || 0.0,
+        );
+    }
+
+    fn bind(&self, layout: &Layout) -> Box<dyn Evaluator + '_> {
+        return bind_unary(
+            layout,
+            self.influencing_factor_unit(),
+            self.input_name.as_deref(),
+            |input| self.evaluate(input),
+            || 0.0,
+        );
+    }
+
+    fn output_unit(&self) -> Unit {
+        return Arrhenius::output_unit(self);
+    }
+
+    fn influencing_units(&self) -> Option<Vec<Unit>> {
+        return Some(vec![self.influencing_factor_unit()]);
+    }
+
+    fn validity_ranges(&self) -> Vec<ValidityRange> {
+        return super::bind_validity_ranges(
+            self.validity.iter().cloned(),
+            self.input_name.as_deref(),
+        );
+    }
+
+    fn call_named(&self, influencing_factors: &[InfluencingFactor]) -> DynQuantity<f64> {
+        let selected = select_influencing_factor(
+            influencing_factors,
+            self.input_name.as_deref(),
+            self.influencing_factor_unit(),
+        );
+        return self.call(selected.as_slice());
+    }
+
+    fn derivative(
+        &self,
+        influencing_factors: &[DynQuantity<f64>],
+        wrt: Unit,
+    ) -> Option<DynQuantity<f64>> {
+        // dy/dT = y * (n + Ea / (R * T)) / T
+        return filter_unary_derivative(
+            influencing_factors,
+            self.influencing_factor_unit(),
+            self.output_unit(),
+            wrt,
+            |t| {
+                self.evaluate(t) * (self.temperature_exponent + self.activation_temperature / t) / t
+            },
+        );
+    }
+}
+
+/// Used to omit the default temperature exponent when serializing.
+#[cfg(feature = "serde")]
+fn is_zero(value: &f64) -> bool {
+    return *value == 0.0;
+}
+
+#[cfg(feature = "serde")]
+mod serde_impl {
+    use super::*;
+
+    use serde::de::{Deserialize, Deserializer};
+
+    /// The activation energy is either a quantity or a value in electronvolt.
+    #[derive(serde::Deserialize)]
+    #[serde(untagged)]
+    enum ActivationEnergy {
+        Quantity(DynQuantity<f64>),
+        ElectronVolt(String),
+    }
+
+    impl ActivationEnergy {
+        fn into_arrhenius(self, pre_factor: DynQuantity<f64>) -> Result<Arrhenius, String> {
+            match self {
+                ActivationEnergy::Quantity(quantity) => {
+                    return Arrhenius::new(pre_factor, quantity).map_err(|err| err.to_string());
+                }
+                ActivationEnergy::ElectronVolt(string) => {
+                    let value = string
+                        .trim()
+                        .strip_suffix("eV")
+                        .and_then(|value| value.trim().parse::<f64>().ok())
+                        .ok_or_else(|| format!("invalid activation energy {string}"))?;
+                    return Ok(Arrhenius::from_electronvolt(pre_factor, value));
+                }
+            }
+        }
+    }
+
+    impl<'de> Deserialize<'de> for Arrhenius {
+        fn deserialize<D>(deserializer: D) -> Result<Arrhenius, D::Error>
+        where
+            D: Deserializer<'de>,
+        {
+            #[derive(serde::Deserialize)]
+            struct ArrheniusAlias {
+                pre_factor: DynQuantity<f64>,
+                activation_energy: ActivationEnergy,
+                #[serde(default)]
+                temperature_exponent: f64,
+                #[serde(default)]
+                input_name: Option<String>,
+                #[serde(default)]
+                validity: Option<ValidityRange>,
+            }
+
+            let alias = ArrheniusAlias::deserialize(deserializer)?;
+            let mut arrhenius = alias
+                .activation_energy
+                .into_arrhenius(alias.pre_factor)
+                .map_err(serde::de::Error::custom)?
+                .with_temperature_exponent(alias.temperature_exponent);
+            arrhenius.input_name = alias.input_name;
+            if let Some(range) = alias.validity {
+                arrhenius = arrhenius
+                    .with_validity(range)
+                    .map_err(serde::de::Error::custom)?;
+            }
+            return Ok(arrhenius);
+        }
+    }
+}
+
+// =============================================================================
+
+#[cfg(feature = "serde")]
+#[cfg_attr(feature = "serde", typetag::serde)]
+impl QuantityFunction for crate::ClampedQuantity<Arrhenius> {
+    fn call(&self, influencing_factors: &[DynQuantity<f64>]) -> DynQuantity<f64> {
+        return self.call_clamped(influencing_factors);
+    }
+
+    fn output_unit(&self) -> Unit {
+        return self.inner().output_unit();
+    }
+
+    fn influencing_units(&self) -> Option<Vec<Unit>> {
+        return self.inner().influencing_units();
+    }
+
+    fn validity_ranges(&self) -> Vec<ValidityRange> {
+        return self.inner().validity_ranges();
+    }
+
+    fn call_named(&self, influencing_factors: &[InfluencingFactor]) -> DynQuantity<f64> {
+        return self.call_named_clamped(influencing_factors);
+    }
+
+    fn derivative(
+        &self,
+        influencing_factors: &[DynQuantity<f64>],
+        wrt: Unit,
+    ) -> Option<DynQuantity<f64>> {
+        return self.derivative_clamped(influencing_factors, wrt);
+    }
+
+    fn call_dual(&self, influencing_factors: &[crate::DualQuantity]) -> crate::DualQuantity {
+        return self.call_dual_clamped(influencing_factors);
+    }
+
+    fn call_batch(&self, inputs: &[crate::BatchInput], output: &mut [f64]) {
+        self.call_batch_clamped(inputs, output);
+    }
+
+    fn bind(&self, layout: &crate::Layout) -> Box<dyn crate::Evaluator + '_> {
+        return self.bind_clamped(layout);
+    }
+}
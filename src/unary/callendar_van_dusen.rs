@@ -0,0 +1,450 @@
+/*!
+An unary [`CallendarVanDusen`] function which implements [`QuantityFunction`].
+*/
+
+use dyn_quantity::{DynQuantity, PredefUnit, Unit, UnitsNotEqual};
+
+use crate::{
+    BatchInput, Evaluator, InfluencingFactor, Layout, QuantityFunction, bind_unary,
+    filter_unary_batch, filter_unary_derivative, filter_unary_function, select_influencing_factor,
+    validity::ValidityRange,
+};
+
+/// Coefficient `A` of platinum resistance thermometers according to IEC 60751 in 1/K.
+pub const IEC_60751_A: f64 = 3.9083e-3;
+
+/// Coefficient `B` of platinum resistance thermometers according to IEC 60751 in 1/K^2.
+pub const IEC_60751_B: f64 = -5.775e-7;
+
+/// Coefficient `C` of platinum resistance thermometers according to IEC 60751 in 1/K^4.
+pub const IEC_60751_C: f64 = -4.183e-12;
+
+/// Difference between the temperature in kelvin and in degree Celsius.
+const ZERO_CELSIUS: f64 = 273.15;
+
+/**
+The Callendar-Van Dusen equation, which describes the resistance of a platinum
+resistance thermometer (e.g. PT100 or PT1000) as a function of the temperature:
+
+`R = R0 * (1 + A * t + B * t^2 + C * (t - 100 °C) * t^3)`
+
+where `t` is the temperature in degree Celsius and `R0` is the nominal
+resistance at 0 °C. The cubic term with the coefficient `C` only applies below
+0 °C. The coefficients defined by IEC 60751 ([`IEC_60751_A`], [`IEC_60751_B`]
+and [`IEC_60751_C`]) are available via [`CallendarVanDusen::iec_60751`],
+[`CallendarVanDusen::pt100`] and [`CallendarVanDusen::pt1000`].
+
+This struct is meant to be used as a [`QuantityFunction`] trait object. The
+unit of the influencing quantity is [`PredefUnit::Temperature`] and the output
+unit is [`PredefUnit::ElectricResistance`]. Since the influencing quantity is
+given in kelvin, it is converted to degree Celsius internally.
+
+# Examples
+
+```
+use std::str::FromStr;
+use dyn_quantity::DynQuantity;
+use var_quantity::{QuantityFunction, unary::CallendarVanDusen};
+
+let q = |s: &str| DynQuantity::<f64>::from_str(s).unwrap();
+
+let pt100 = CallendarVanDusen::pt100();
+approx::assert_relative_eq!(pt100.call(&[q("273.15 K")]).value, 100.0);
+approx::assert_relative_eq!(pt100.call(&[q("373.15 K")]).value, 138.5055, max_relative = 1e-6);
+approx::assert_relative_eq!(pt100.call(&[q("73.15 K")]).value, 18.5201, max_relative = 1e-5);
+```
+
+IEC 60751 specifies the equation between -200 °C and 850 °C. This range can be
+attached to the function via [`CallendarVanDusen::with_validity`]:
+
+```
+use std::str::FromStr;
+use dyn_quantity::DynQuantity;
+use var_quantity::{QuantityFunction, unary::CallendarVanDusen, validity::ValidityRange};
+
+let q = |s: &str| DynQuantity::<f64>::from_str(s).unwrap();
+
+let range = ValidityRange::new(q("73.15 K"), q("1123.15 K")).expect("valid range");
+let pt1000 = CallendarVanDusen::pt1000().with_validity(range).expect("units match");
+assert_eq!(pt1000.validity_ranges().len(), 1);
+```
+
+# Features:
+This struct can be serialized / deserialized if the `serde` feature is enabled.
+ */
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct CallendarVanDusen {
+    nominal_resistance: DynQuantity<f64>,
+    a: DynQuantity<f64>,
+    b: DynQuantity<f64>,
+    c: DynQuantity<f64>,
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    input_name: Option<String>,
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    validity: Option<ValidityRange>,
+}
+
+impl CallendarVanDusen {
+    /**
+    Checks if `nominal_resistance` is an electrical resistance and if the
+    coefficients `a`, `b` and `c` have the units 1/K, 1/K^2 and 1/K^4. If this
+    is the case, a new instance of [`CallendarVanDusen`] is returned.
+    Otherwise, the error contains the expected and the found unit of the first
+    mismatching argument.
+
+    # Examples
+
+    ```
+    use std::str::FromStr;
+    use dyn_quantity::DynQuantity;
+    use var_quantity::unary::CallendarVanDusen;
+
+    let q = |s: &str| DynQuantity::<f64>::from_str(s).unwrap();
+
+    assert!(CallendarVanDusen::new(q("100 ohm"), q("3.9e-3 / K"), q("-5.8e-7 / K^2"), q("0 / K^4")).is_ok());
+    assert!(CallendarVanDusen::new(q("100 V"), q("3.9e-3 / K"), q("-5.8e-7 / K^2"), q("0 / K^4")).is_err());
+    assert!(CallendarVanDusen::new(q("100 ohm"), q("3.9e-3 / K"), q("-5.8e-7 / K"), q("0 / K^4")).is_err());
+    ```
+    */
+    pub fn new(
+        nominal_resistance: DynQuantity<f64>,
+        a: DynQuantity<f64>,
+        b: DynQuantity<f64>,
+        c: DynQuantity<f64>,
+    ) -> Result<Self, UnitsNotEqual> {
+        let resistance = Unit::from(PredefUnit::ElectricResistance);
+        if nominal_resistance.unit != resistance {
+            return Err(UnitsNotEqual(resistance, nominal_resistance.unit));
+        }
+        let temperature = Unit::from(PredefUnit::Temperature);
+        for (coefficient, exponent) in [(&a, -1), (&b, -2), (&c, -4)] {
+            if coefficient.unit != temperature.powi(exponent) {
+                return Err(UnitsNotEqual(temperature.powi(exponent), coefficient.unit));
+            }
+        }
+        return Ok(Self {
+            nominal_resistance,
+            a,
+            b,
+            c,
+            input_name: None,
+            validity: None,
+        });
+    }
+
+    /**
+    Returns a platinum resistance thermometer with the given nominal resistance
+    at 0 °C and the coefficients defined by IEC 60751. Fails if
+    `nominal_resistance` is not an electrical resistance.
+
+    # Examples
+
+    ```
+    use std::str::FromStr;
+    use dyn_quantity::DynQuantity;
+    use var_quantity::unary::CallendarVanDusen;
+
+    let q = |s: &str| DynQuantity::<f64>::from_str(s).unwrap();
+
+    let pt500 = CallendarVanDusen::iec_60751(q("500 ohm")).expect("resistance");
+    assert_eq!(pt500.nominal_resistance(), &q("500 ohm"));
+    assert!(CallendarVanDusen::iec_60751(q("500 V")).is_err());
+    ```
+    */
+    pub fn iec_60751(nominal_resistance: DynQuantity<f64>) -> Result<Self, UnitsNotEqual> {
+        let temperature = Unit::from(PredefUnit::Temperature);
+        return Self::new(
+            nominal_resistance,
+            DynQuantity::new(IEC_60751_A, temperature.powi(-1)),
+            DynQuantity::new(IEC_60751_B, temperature.powi(-2)),
+            DynQuantity::new(IEC_60751_C, temperature.powi(-4)),
+        );
+    }
+
+    /**
+    Returns a PT100 thermometer (100 Ω at 0 °C) according to IEC 60751.
+    */
+    pub fn pt100() -> Self {
+        return Self::iec_60751_unchecked(100.0);
+    }
+
+    /**
+    Returns a PT1000 thermometer (1000 Ω at 0 °C) according to IEC 60751.
+    */
+    pub fn pt1000() -> Self {
+        return Self::iec_60751_unchecked(1000.0);
+    }
+
+    /// IEC 60751 thermometer with the given nominal resistance in ohm.
+    fn iec_60751_unchecked(nominal_resistance: f64) -> Self {
+        let temperature = Unit::from(PredefUnit::Temperature);
+        return Self {
+            nominal_resistance: DynQuantity::new(
+                nominal_resistance,
+                PredefUnit::ElectricResistance,
+            ),
+            a: DynQuantity::new(IEC_60751_A, temperature.powi(-1)),
+            b: DynQuantity::new(IEC_60751_B, temperature.powi(-2)),
+            c: DynQuantity::new(IEC_60751_C, temperature.powi(-4)),
+            input_name: None,
+            validity: None,
+        };
+    }
+
+    /**
+    Returns the nominal resistance `R0` at 0 °C.
+    */
+    pub fn nominal_resistance(&self) -> &DynQuantity<f64> {
+        return &self.nominal_resistance;
+    }
+
+    /**
+    Returns the linear coefficient `A`.
+    */
+    pub fn a(&self) -> &DynQuantity<f64> {
+        return &self.a;
+    }
+
+    /**
+    Returns the quadratic coefficient `B`.
+    */
+    pub fn b(&self) -> &DynQuantity<f64> {
+        return &self.b;
+    }
+
+    /**
+    Returns the coefficient `C` of the cubic term below 0 °C.
+    */
+    pub fn c(&self) -> &DynQuantity<f64> {
+        return &self.c;
+    }
+
+    /**
+    Returns the unit of the quantity which influences the variable quantity,
+    which is always [`PredefUnit::Temperature`]. If none of the
+    `influencing_factors` in a [`QuantityFunction::call`] matches this item,
+    then the temperature is assumed to be 0 °C and the nominal resistance is
+    returned.
+
+    # Examples
+
+    ```
+    use std::str::FromStr;
+    use dyn_quantity::{DynQuantity, PredefUnit, Unit};
+    use var_quantity::{QuantityFunction, unary::CallendarVanDusen};
+
+    let q = |s: &str| DynQuantity::<f64>::from_str(s).unwrap();
+
+    let fun = CallendarVanDusen::pt100();
+    assert_eq!(fun.influencing_factor_unit(), Unit::from(PredefUnit::Temperature));
+    assert_eq!(fun.call(&[q("1 A")]), q("100 ohm"));
+    ```
+    */
+    pub fn influencing_factor_unit(&self) -> Unit {
+        return PredefUnit::Temperature.into();
+    }
+
+    /**
+    Returns the unit which will be returned from [`QuantityFunction::call`],
+    which is always [`PredefUnit::ElectricResistance`].
+
+    ```
+    use dyn_quantity::{PredefUnit, Unit};
+    use var_quantity::{QuantityFunction, unary::CallendarVanDusen};
+
+    let fun = CallendarVanDusen::pt100();
+    assert_eq!(fun.output_unit(), Unit::from(PredefUnit::ElectricResistance));
+    ```
+     */
+    pub fn output_unit(&self) -> Unit {
+        return self.nominal_resistance.unit;
+    }
+
+    /**
+    Returns the resistance for the temperature `x` in kelvin.
+     */
+    fn evaluate(&self, x: f64) -> f64 {
+        let t = x - ZERO_CELSIUS;
+        let mut factor = 1.0 + self.a.value * t + self.b.value * t.powi(2);
+        if t < 0.0 {
+            factor += self.c.value * (t - 100.0) * t.powi(3);
+        }
+        return self.nominal_resistance.value * factor;
+    }
+
+    /**
+    Returns the derivative of the resistance for the temperature `x` in kelvin.
+     */
+    fn evaluate_derivative(&self, x: f64) -> f64 {
+        let t = x - ZERO_CELSIUS;
+        let mut factor = self.a.value + 2.0 * self.b.value * t;
+        if t < 0.0 {
+            factor += self.c.value * (4.0 * t.powi(3) - 300.0 * t.powi(2));
+        }
+        return self.nominal_resistance.value * factor;
+    }
+}
+
+impl_input_name!(CallendarVanDusen);
+impl_validity!(CallendarVanDusen);
+
+#[cfg_attr(feature = "serde", typetag::serde)]
+impl QuantityFunction for CallendarVanDusen {
+    fn call(&self, influencing_factors: &[DynQuantity<f64>]) -> DynQuantity<f64> {
+        return filter_unary_function(
+            influencing_factors,
+            self.influencing_factor_unit(),
+            |input| DynQuantity::new(self.evaluate(input.value), self.output_unit()),
+            || self.nominal_resistance,
+        );
+    }
+
+    fn call_batch(&self, inputs: &[BatchInput], output: &mut [f64]) {
+        filter_unary_batch(
+            inputs,
+            self.influencing_factor_unit(),
+            output,
+            |input| self.evaluate(input),
+            || self.nominal_resistance.value,
+        );
+    }
+
+    fn bind(&self, layout: &Layout) -> Box<dyn Evaluator + '_> {
+        return bind_unary(
+            layout,
+            self.influencing_factor_unit(),
+            self.input_name.as_deref(),
+            |input| self.evaluate(input),
+            || self.nominal_resistance.value,
+        );
+    }
+
+    fn output_unit(&self) -> Unit {
+        return CallendarVanDusen::output_unit(self);
+    }
+
+    fn influencing_units(&self) -> Option<Vec<Unit>> {
+        return Some(vec![self.influencing_factor_unit()]);
+    }
+
+    fn validity_ranges(&self) -> Vec<ValidityRange> {
+        return super::bind_validity_ranges(
+            self.validity.iter().cloned(),
+            self.input_name.as_deref(),
+        );
+    }
+
+    fn call_named(&self, influencing_factors: &[InfluencingFactor]) -> DynQuantity<f64> {
+        let selected = select_influencing_factor(
+            influencing_factors,
+            self.input_name.as_deref(),
+            self.influencing_factor_unit(),
+        );
+        return self.call(selected.as_slice());
+    }
+
+    fn derivative(
+        &self,
+        influencing_factors: &[DynQuantity<f64>],
+        wrt: Unit,
+    ) -> Option<DynQuantity<f64>> {
+        return filter_unary_derivative(
+            influencing_factors,
+            self.influencing_factor_unit(),
+            self.output_unit(),
+            wrt,
+            |x| self.evaluate_derivative(x),
+        );
+    }
+}
+
+#[cfg(feature = "serde")]
+mod serde_impl {
+    use super::*;
+
+    use serde::de::{Deserialize, Deserializer};
+
+    impl<'de> Deserialize<'de> for CallendarVanDusen {
+        fn deserialize<D>(deserializer: D) -> Result<CallendarVanDusen, D::Error>
+        where
+            D: Deserializer<'de>,
+        {
+            #[derive(serde::Deserialize)]
+            struct CallendarVanDusenAlias {
+                nominal_resistance: DynQuantity<f64>,
+                a: DynQuantity<f64>,
+                b: DynQuantity<f64>,
+                c: DynQuantity<f64>,
+                #[serde(default)]
+                input_name: Option<String>,
+                #[serde(default)]
+                validity: Option<ValidityRange>,
+            }
+
+            let alias = CallendarVanDusenAlias::deserialize(deserializer)?;
+            let mut callendar_van_dusen =
+                Self::new(alias.nominal_resistance, alias.a, alias.b, alias.c)
+                    .map_err(serde::de::Error::custom)?;
+            callendar_van_dusen.input_name = alias.input_name;
+            if let Some(range) = alias.validity {
+                callendar_van_dusen = callendar_van_dusen
+                    .with_validity(range)
+                    .map_err(serde::de::Error::custom)?;
+            }
+            return Ok(callendar_van_dusen);
+        }
+    }
+}
+
+// =============================================================================
+
+#[cfg(feature = "serde")]
+#[cfg_attr(feature = "serde", typetag::serde)]
+impl QuantityFunction for crate::ClampedQuantity<CallendarVanDusen> {
+    fn call(&self, influencing_factors: &[DynQuantity<f64>]) -> DynQuantity<f64> {
+        return self.call_clamped(influencing_factors);
+    }
+
+    fn output_unit(&self) -> Unit {
+        return self.inner().output_unit();
+    }
+
+    fn influencing_units(&self) -> Option<Vec<Unit>> {
+        return self.inner().influencing_units();
+    }
+
+    fn validity_ranges(&self) -> Vec<ValidityRange> {
+        return self.inner().validity_ranges();
+    }
+
+    fn call_named(&self, influencing_factors: &[InfluencingFactor]) -> DynQuantity<f64> {
+        return self.call_named_clamped(influencing_factors);
+    }
+
+    fn derivative(
+        &self,
+        influencing_factors: &[DynQuantity<f64>],
+        wrt: Unit,
+    ) -> Option<DynQuantity<f64>> {
+        return self.derivative_clamped(influencing_factors, wrt);
+    }
+
+    fn call_dual(&self, influencing_factors: &[crate::DualQuantity]) -> crate::DualQuantity {
+        return self.call_dual_clamped(influencing_factors);
+    }
+
+    fn call_batch(&self, inputs: &[crate::BatchInput], output: &mut [f64]) {
+        self.call_batch_clamped(inputs, output);
+    }
+
+    fn bind(&self, layout: &crate::Layout) -> Box<dyn crate::Evaluator + '_> {
+        return self.bind_clamped(layout);
+    }
+}
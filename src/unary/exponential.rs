@@ -4,7 +4,11 @@ An unary [`Exponential`] function which implements [`QuantityFunction`].
 
 use dyn_quantity::{DynQuantity, Unit, UnitsNotEqual};
 
-use crate::{QuantityFunction, filter_unary_function};
+use crate::{
+    BatchInput, Evaluator, InfluencingFactor, Layout, QuantityFunction, bind_unary,
+    filter_unary_batch, filter_unary_derivative, filter_unary_function, select_influencing_factor,
+    validity::ValidityRange,
+};
 
 /**
 An exponential term `amplitude * (exponent * x).exp` which is used to build an
@@ -44,6 +48,16 @@ pub struct Exponential {
     output_unit: Unit,
     #[cfg_attr(feature = "serde", serde(skip))]
     influencing_factor_unit: Unit,
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    input_name: Option<String>,
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    validity: Option<ValidityRange>,
 }
 
 impl Exponential {
@@ -123,6 +137,8 @@ impl Exponential {
             terms,
             output_unit,
             influencing_factor_unit,
+            input_name: None,
+            validity: None,
         });
     }
 
@@ -196,6 +212,9 @@ impl Exponential {
     }
 }
 
+impl_input_name!(Exponential);
+impl_validity!(Exponential);
+
 #[cfg_attr(feature = "serde", typetag::serde)]
 impl QuantityFunction for Exponential {
     fn call(&self, influencing_factors: &[DynQuantity<f64>]) -> DynQuantity<f64> {
@@ -218,6 +237,82 @@ impl QuantityFunction for Exponential {
             },
         );
     }
+
+    fn call_batch(&self, inputs: &[BatchInput], output: &mut [f64]) {
+        filter_unary_batch(
+            inputs,
+            self.influencing_factor_unit,
+            output,
+            |input| {
+                self.terms
+                    .iter()
+                    .map(|t| t.amplitude.value * (t.exponent.value * input).exp())
+                    .sum::<f64>()
+            },
+            || self.terms.iter().map(|t| t.amplitude.value).sum::<f64>(),
+        );
+    }
+
+    fn bind(&self, layout: &Layout) -> Box<dyn Evaluator + '_> {
+        return bind_unary(
+            layout,
+            self.influencing_factor_unit,
+            self.input_name.as_deref(),
+            |input| {
+                self.terms
+                    .iter()
+                    .map(|t| t.amplitude.value * (t.exponent.value * input).exp())
+                    .sum::<f64>()
+            },
+            || self.terms.iter().map(|t| t.amplitude.value).sum::<f64>(),
+        );
+    }
+
+    fn output_unit(&self) -> Unit {
+        return Exponential::output_unit(self);
+    }
+
+    fn influencing_units(&self) -> Option<Vec<Unit>> {
+        if self.terms.is_empty() {
+            return Some(Vec::new());
+        }
+        return Some(vec![self.influencing_factor_unit]);
+    }
+
+    fn validity_ranges(&self) -> Vec<ValidityRange> {
+        return super::bind_validity_ranges(
+            self.validity.iter().cloned(),
+            self.input_name.as_deref(),
+        );
+    }
+
+    fn call_named(&self, influencing_factors: &[InfluencingFactor]) -> DynQuantity<f64> {
+        let selected = select_influencing_factor(
+            influencing_factors,
+            self.input_name.as_deref(),
+            self.influencing_factor_unit(),
+        );
+        return self.call(selected.as_slice());
+    }
+
+    fn derivative(
+        &self,
+        influencing_factors: &[DynQuantity<f64>],
+        wrt: Unit,
+    ) -> Option<DynQuantity<f64>> {
+        return filter_unary_derivative(
+            influencing_factors,
+            self.influencing_factor_unit,
+            self.output_unit,
+            wrt,
+            |x| {
+                self.terms
+                    .iter()
+                    .map(|t| t.amplitude.value * t.exponent.value * (t.exponent.value * x).exp())
+                    .sum::<f64>()
+            },
+        );
+    }
 }
 
 #[cfg(feature = "serde")]
@@ -234,10 +329,21 @@ mod serde_impl {
             #[derive(serde::Deserialize)]
             struct ExponentialAlias {
                 terms: Vec<ExpTerm>,
+                #[serde(default)]
+                input_name: Option<String>,
+                #[serde(default)]
+                validity: Option<ValidityRange>,
             }
 
             let alias = ExponentialAlias::deserialize(deserializer)?;
-            Self::new(alias.terms).map_err(serde::de::Error::custom)
+            let mut exponential = Self::new(alias.terms).map_err(serde::de::Error::custom)?;
+            exponential.input_name = alias.input_name;
+            if let Some(range) = alias.validity {
+                exponential = exponential
+                    .with_validity(range)
+                    .map_err(serde::de::Error::custom)?;
+            }
+            return Ok(exponential);
         }
     }
 }
@@ -250,4 +356,40 @@ impl QuantityFunction for crate::ClampedQuantity<Exponential> {
     fn call(&self, influencing_factors: &[DynQuantity<f64>]) -> DynQuantity<f64> {
         return self.call_clamped(influencing_factors);
     }
+
+    fn output_unit(&self) -> Unit {
+        return self.inner().output_unit();
+    }
+
+    fn influencing_units(&self) -> Option<Vec<Unit>> {
+        return self.inner().influencing_units();
+    }
+
+    fn validity_ranges(&self) -> Vec<ValidityRange> {
+        return self.inner().validity_ranges();
+    }
+
+    fn call_named(&self, influencing_factors: &[InfluencingFactor]) -> DynQuantity<f64> {
+        return self.call_named_clamped(influencing_factors);
+    }
+
+    fn derivative(
+        &self,
+        influencing_factors: &[DynQuantity<f64>],
+        wrt: Unit,
+    ) -> Option<DynQuantity<f64>> {
+        return self.derivative_clamped(influencing_factors, wrt);
+    }
+
+    fn call_dual(&self, influencing_factors: &[crate::DualQuantity]) -> crate::DualQuantity {
+        return self.call_dual_clamped(influencing_factors);
+    }
+
+    fn call_batch(&self, inputs: &[crate::BatchInput], output: &mut [f64]) {
+        self.call_batch_clamped(inputs, output);
+    }
+
+    fn bind(&self, layout: &crate::Layout) -> Box<dyn crate::Evaluator + '_> {
+        return self.bind_clamped(layout);
+    }
 }
@@ -4,7 +4,11 @@ An unary [`FirstOrderTaylor`] function which implements [`QuantityFunction`].
 
 use dyn_quantity::{DynQuantity, Unit, UnitsNotEqual};
 
-use crate::{QuantityFunction, filter_unary_function};
+use crate::{
+    BatchInput, Evaluator, InfluencingFactor, Layout, QuantityFunction, bind_unary,
+    filter_unary_batch, filter_unary_derivative, filter_unary_function, select_influencing_factor,
+    validity::ValidityRange,
+};
 
 /**
 A first order taylor series function defined as:
@@ -45,6 +49,16 @@ pub struct FirstOrderTaylor {
     base_value: DynQuantity<f64>,
     slope: DynQuantity<f64>,
     expansion_point: DynQuantity<f64>,
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    input_name: Option<String>,
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    validity: Option<ValidityRange>,
 }
 
 impl FirstOrderTaylor {
@@ -88,6 +102,8 @@ impl FirstOrderTaylor {
                 base_value,
                 slope,
                 expansion_point,
+                input_name: None,
+                validity: None,
             });
         } else {
             return Err(UnitsNotEqual(expected, found));
@@ -171,6 +187,9 @@ impl FirstOrderTaylor {
     }
 }
 
+impl_input_name!(FirstOrderTaylor);
+impl_validity!(FirstOrderTaylor);
+
 #[cfg_attr(feature = "serde", typetag::serde)]
 impl QuantityFunction for FirstOrderTaylor {
     fn call(&self, influencing_factors: &[DynQuantity<f64>]) -> DynQuantity<f64> {
@@ -186,6 +205,70 @@ impl QuantityFunction for FirstOrderTaylor {
             || self.base_value,
         );
     }
+
+    fn call_batch(&self, inputs: &[BatchInput], output: &mut [f64]) {
+        filter_unary_batch(
+            inputs,
+            self.expansion_point.unit,
+            output,
+            |input| {
+                self.base_value.value
+                    * (1.0 + self.slope.value * (input - self.expansion_point.value))
+            },
+            || self.base_value.value,
+        );
+    }
+
+    fn bind(&self, layout: &Layout) -> Box<dyn Evaluator + '_> {
+        return bind_unary(
+            layout,
+            self.expansion_point.unit,
+            self.input_name.as_deref(),
+            |input| {
+                self.base_value.value
+                    * (1.0 + self.slope.value * (input - self.expansion_point.value))
+            },
+            || self.base_value.value,
+        );
+    }
+
+    fn output_unit(&self) -> Unit {
+        return FirstOrderTaylor::output_unit(self);
+    }
+
+    fn influencing_units(&self) -> Option<Vec<Unit>> {
+        return Some(vec![self.influencing_factor_unit()]);
+    }
+
+    fn validity_ranges(&self) -> Vec<ValidityRange> {
+        return super::bind_validity_ranges(
+            self.validity.iter().cloned(),
+            self.input_name.as_deref(),
+        );
+    }
+
+    fn call_named(&self, influencing_factors: &[InfluencingFactor]) -> DynQuantity<f64> {
+        let selected = select_influencing_factor(
+            influencing_factors,
+            self.input_name.as_deref(),
+            self.influencing_factor_unit(),
+        );
+        return self.call(selected.as_slice());
+    }
+
+    fn derivative(
+        &self,
+        influencing_factors: &[DynQuantity<f64>],
+        wrt: Unit,
+    ) -> Option<DynQuantity<f64>> {
+        return filter_unary_derivative(
+            influencing_factors,
+            self.expansion_point.unit,
+            self.output_unit(),
+            wrt,
+            |_| self.base_value.value * self.slope.value,
+        );
+    }
 }
 
 #[cfg(feature = "serde")]
@@ -204,11 +287,20 @@ mod serde_impl {
                 base_value: DynQuantity<f64>,
                 expansion_point: DynQuantity<f64>,
                 slope: DynQuantity<f64>,
+                #[serde(default)]
+                input_name: Option<String>,
+                #[serde(default)]
+                validity: Option<ValidityRange>,
             }
 
             let alias = FirstOrderTaylorAlias::deserialize(deserializer)?;
-            Self::new(alias.base_value, alias.slope, alias.expansion_point)
-                .map_err(serde::de::Error::custom)
+            let mut fot = Self::new(alias.base_value, alias.slope, alias.expansion_point)
+                .map_err(serde::de::Error::custom)?;
+            fot.input_name = alias.input_name;
+            if let Some(range) = alias.validity {
+                fot = fot.with_validity(range).map_err(serde::de::Error::custom)?;
+            }
+            return Ok(fot);
         }
     }
 }
@@ -221,4 +313,40 @@ impl QuantityFunction for crate::ClampedQuantity<FirstOrderTaylor> {
     fn call(&self, influencing_factors: &[DynQuantity<f64>]) -> DynQuantity<f64> {
         return self.call_clamped(influencing_factors);
     }
+
+    fn output_unit(&self) -> Unit {
+        return self.inner().output_unit();
+    }
+
+    fn influencing_units(&self) -> Option<Vec<Unit>> {
+        return self.inner().influencing_units();
+    }
+
+    fn validity_ranges(&self) -> Vec<ValidityRange> {
+        return self.inner().validity_ranges();
+    }
+
+    fn call_named(&self, influencing_factors: &[InfluencingFactor]) -> DynQuantity<f64> {
+        return self.call_named_clamped(influencing_factors);
+    }
+
+    fn derivative(
+        &self,
+        influencing_factors: &[DynQuantity<f64>],
+        wrt: Unit,
+    ) -> Option<DynQuantity<f64>> {
+        return self.derivative_clamped(influencing_factors, wrt);
+    }
+
+    fn call_dual(&self, influencing_factors: &[crate::DualQuantity]) -> crate::DualQuantity {
+        return self.call_dual_clamped(influencing_factors);
+    }
+
+    fn call_batch(&self, inputs: &[crate::BatchInput], output: &mut [f64]) {
+        self.call_batch_clamped(inputs, output);
+    }
+
+    fn bind(&self, layout: &crate::Layout) -> Box<dyn crate::Evaluator + '_> {
+        return self.bind_clamped(layout);
+    }
 }
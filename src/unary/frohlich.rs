@@ -0,0 +1,435 @@
+/*!
+An unary [`Frohlich`] function which implements [`QuantityFunction`].
+*/
+
+use dyn_quantity::{DynQuantity, PredefUnit, Unit, UnitsNotEqual};
+
+use crate::{
+    BatchInput, Evaluator, InfluencingFactor, Layout, QuantityFunction, bind_unary,
+    filter_unary_batch, filter_unary_derivative, filter_unary_function, select_influencing_factor,
+    validity::ValidityRange,
+};
+
+pub use super::saturation::{SaturationOutput, VACUUM_PERMEABILITY};
+
+/**
+The Frohlich-Kennelly model of a magnetic saturation curve:
+
+`B = μ0 * H + H / (a + b * |H|)`
+
+where `μ0` is the [`VACUUM_PERMEABILITY`], `H` is the magnetic field strength
+and `B` is the magnetic flux density. The second term is the magnetic
+polarization, which approaches the saturation polarization `1 / b` for large
+fields. The initial permeability at `H = 0` is `μ0 + 1 / a`. A model can also
+be created directly from these two quantities via [`Frohlich::from_saturation`].
+
+This struct is meant to be used as a [`QuantityFunction`] trait object. The
+unit of the influencing quantity is [`PredefUnit::MagneticFieldStrength`].
+Depending on the [`SaturationOutput`], either the flux density `B(H)` (default)
+or the permeability `μ(H) = B(H) / H` is returned, see [`Frohlich::with_output`].
+
+# Examples
+
+```
+use std::str::FromStr;
+use dyn_quantity::DynQuantity;
+use var_quantity::{QuantityFunction, unary::{Frohlich, SaturationOutput}};
+
+let q = |s: &str| DynQuantity::<f64>::from_str(s).unwrap();
+
+// Electrical steel with an initial relative permeability of about 5000 and a
+// saturation polarization of 1.8 T
+let mu_i = q("6.28e-3 T*m/A");
+let steel = Frohlich::from_saturation(mu_i, q("1.8 T")).expect("units match");
+
+let b = steel.call(&[q("1e5 A/m")]);
+assert_eq!(b.unit, q("1 T").unit);
+approx::assert_abs_diff_eq!(b.value, 1.9205, epsilon = 1e-4);
+
+// Permeability
+let steel = steel.with_output(SaturationOutput::Permeability);
+approx::assert_relative_eq!(steel.call(&[q("0 A/m")]).value, mu_i.value);
+```
+
+# Features:
+This struct can be serialized / deserialized if the `serde` feature is enabled.
+ */
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct Frohlich {
+    a: DynQuantity<f64>,
+    b: DynQuantity<f64>,
+    output: SaturationOutput,
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    input_name: Option<String>,
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    validity: Option<ValidityRange>,
+}
+
+impl Frohlich {
+    /**
+    Checks if `a` has the unit (A/m)/T and if `b` has the unit 1/T. If this is
+    the case, a new instance of [`Frohlich`] which returns the flux density is
+    returned. Otherwise, the error contains the expected and the found unit of
+    the first mismatching argument. For a physically meaningful curve, `a`
+    must be positive and `b` must not be negative.
+
+    # Examples
+
+    ```
+    use std::str::FromStr;
+    use dyn_quantity::DynQuantity;
+    use var_quantity::unary::Frohlich;
+
+    let q = |s: &str| DynQuantity::<f64>::from_str(s).unwrap();
+
+    assert!(Frohlich::new(q("160 A/(m*T)"), q("0.55 / T")).is_ok());
+    assert!(Frohlich::new(q("160 A/m"), q("0.55 / T")).is_err());
+    assert!(Frohlich::new(q("160 A/(m*T)"), q("0.55 T")).is_err());
+    ```
+    */
+    pub fn new(a: DynQuantity<f64>, b: DynQuantity<f64>) -> Result<Self, UnitsNotEqual> {
+        let flux_density = Unit::from(PredefUnit::MagneticFluxDensity);
+        let a_unit = Unit::from(PredefUnit::MagneticFieldStrength) / flux_density;
+        if a.unit != a_unit {
+            return Err(UnitsNotEqual(a_unit, a.unit));
+        }
+        let b_unit = flux_density.powi(-1);
+        if b.unit != b_unit {
+            return Err(UnitsNotEqual(b_unit, b.unit));
+        }
+        return Ok(Self {
+            a,
+            b,
+            output: SaturationOutput::default(),
+            input_name: None,
+            validity: None,
+        });
+    }
+
+    /**
+    Returns a model with the given initial permeability `μi` (in H/m) and
+    saturation polarization `Js` (in T), i.e. `a = 1 / (μi - μ0)` and
+    `b = 1 / Js`. Fails if the units of the arguments do not match.
+
+    # Examples
+
+    ```
+    use std::str::FromStr;
+    use dyn_quantity::DynQuantity;
+    use var_quantity::unary::Frohlich;
+
+    let q = |s: &str| DynQuantity::<f64>::from_str(s).unwrap();
+
+    let fun = Frohlich::from_saturation(q("6e-3 T*m/A"), q("2 T")).expect("units match");
+    assert_eq!(fun.b(), &q("0.5 / T"));
+    assert!(Frohlich::from_saturation(q("6e-3 T*m/A"), q("2 A/m")).is_err());
+    ```
+    */
+    pub fn from_saturation(
+        initial_permeability: DynQuantity<f64>,
+        saturation_polarization: DynQuantity<f64>,
+    ) -> Result<Self, UnitsNotEqual> {
+        let permeability = SaturationOutput::Permeability.unit();
+        if initial_permeability.unit != permeability {
+            return Err(UnitsNotEqual(permeability, initial_permeability.unit));
+        }
+        let flux_density = Unit::from(PredefUnit::MagneticFluxDensity);
+        if saturation_polarization.unit != flux_density {
+            return Err(UnitsNotEqual(flux_density, saturation_polarization.unit));
+        }
+        return Self::new(
+            DynQuantity::new(
+                1.0 / (initial_permeability.value - VACUUM_PERMEABILITY),
+                permeability.powi(-1),
+            ),
+            DynQuantity::new(1.0 / saturation_polarization.value, flux_density.powi(-1)),
+        );
+    }
+
+    /**
+    Returns the parameter `a`.
+    */
+    pub fn a(&self) -> &DynQuantity<f64> {
+        return &self.a;
+    }
+
+    /**
+    Returns the parameter `b`.
+    */
+    pub fn b(&self) -> &DynQuantity<f64> {
+        return &self.b;
+    }
+
+    /**
+    Sets the quantity returned by the function, see [`SaturationOutput`].
+
+    # Examples
+
+    ```
+    use std::str::FromStr;
+    use dyn_quantity::DynQuantity;
+    use var_quantity::{QuantityFunction, unary::{Frohlich, SaturationOutput}};
+
+    let q = |s: &str| DynQuantity::<f64>::from_str(s).unwrap();
+
+    let fun = Frohlich::new(q("160 A/(m*T)"), q("0.55 / T")).expect("units match");
+    let flux_density = fun.call(&[q("2000 A/m")]);
+
+    let fun = fun.with_output(SaturationOutput::Permeability);
+    assert_eq!(fun.output(), SaturationOutput::Permeability);
+    let permeability = fun.call(&[q("2000 A/m")]);
+    assert_eq!(permeability.unit, q("1 T*m/A").unit);
+    approx::assert_relative_eq!(permeability.value, flux_density.value / 2000.0);
+    ```
+    */
+    pub fn with_output(mut self, output: SaturationOutput) -> Self {
+        self.output = output;
+        return self;
+    }
+
+    /**
+    Returns the quantity returned by the function, see [`Frohlich::with_output`].
+    */
+    pub fn output(&self) -> SaturationOutput {
+        return self.output;
+    }
+
+    /**
+    Returns the unit of the quantity which influences the variable quantity,
+    which is always [`PredefUnit::MagneticFieldStrength`]. If none of the
+    `influencing_factors` in a [`QuantityFunction::call`] matches this item,
+    then `H` is assumed to be zero.
+
+    # Examples
+
+    ```
+    use std::str::FromStr;
+    use dyn_quantity::{DynQuantity, PredefUnit, Unit};
+    use var_quantity::{QuantityFunction, unary::Frohlich};
+
+    let q = |s: &str| DynQuantity::<f64>::from_str(s).unwrap();
+
+    let fun = Frohlich::new(q("160 A/(m*T)"), q("0.55 / T")).expect("units match");
+    assert_eq!(fun.influencing_factor_unit(), Unit::from(PredefUnit::MagneticFieldStrength));
+    assert_eq!(fun.call(&[q("1 K")]), q("0 T"));
+    ```
+    */
+    pub fn influencing_factor_unit(&self) -> Unit {
+        return PredefUnit::MagneticFieldStrength.into();
+    }
+
+    /**
+    Returns the unit which will be returned from [`QuantityFunction::call`],
+    see [`SaturationOutput::unit`].
+
+    ```
+    use std::str::FromStr;
+    use dyn_quantity::DynQuantity;
+    use var_quantity::{QuantityFunction, unary::{Frohlich, SaturationOutput}};
+
+    let q = |s: &str| DynQuantity::<f64>::from_str(s).unwrap();
+
+    let fun = Frohlich::new(q("160 A/(m*T)"), q("0.55 / T")).expect("units match");
+    assert_eq!(fun.output_unit(), q("1 T").unit);
+
+    let fun = fun.with_output(SaturationOutput::Permeability);
+    assert_eq!(fun.output_unit(), q("1 T*m/A").unit);
+    ```
+     */
+    pub fn output_unit(&self) -> Unit {
+        return self.output.unit();
+    }
+
+    /**
+    Returns the flux density or the permeability for the field strength `h`.
+     */
+    fn evaluate(&self, h: f64) -> f64 {
+        let denominator = self.a.value + self.b.value * h.abs();
+        match self.output {
+            SaturationOutput::FluxDensity => return VACUUM_PERMEABILITY * h + h / denominator,
+            SaturationOutput::Permeability => return VACUUM_PERMEABILITY + 1.0 / denominator,
+        }
+    }
+
+    /**
+    Returns the derivative of [`Frohlich::evaluate`] with respect to `h`.
+     */
+    fn evaluate_derivative(&self, h: f64) -> f64 {
+        let denominator = self.a.value + self.b.value * h.abs();
+        match self.output {
+            SaturationOutput::FluxDensity => {
+                return VACUUM_PERMEABILITY + self.a.value / denominator.powi(2);
+            }
+            SaturationOutput::Permeability => {
+                if h == 0.0 {
+                    return 0.0;
+                }
+                return -self.b.value * h.signum() / denominator.powi(2);
+            }
+        }
+    }
+}
+
+impl_input_name!(Frohlich);
+impl_validity!(Frohlich);
+
+#[cfg_attr(feature = "serde", typetag::serde)]
+impl QuantityFunction for Frohlich {
+    fn call(&self, influencing_factors: &[DynQuantity<f64>]) -> DynQuantity<f64> {
+        return filter_unary_function(
+            influencing_factors,
+            self.influencing_factor_unit(),
+            |input| DynQuantity::new(self.evaluate(input.value), self.output_unit()),
+            || DynQuantity::new(self.evaluate(0.0), self.output_unit()),
+        );
+    }
+
+    fn call_batch(&self, inputs: &[BatchInput], output: &mut [f64]) {
+        filter_unary_batch(
+            inputs,
+            self.influencing_factor_unit(),
+            output,
+            |input| self.evaluate(input),
+            || self.evaluate(0.0),
+        );
+    }
+
+    fn bind(&self, layout: &Layout) -> Box<dyn Evaluator + '_> {
+        return bind_unary(
+            layout,
+            self.influencing_factor_unit(),
+            self.input_name.as_deref(),
+            |input| self.evaluate(input),
+            || self.evaluate(0.0),
+        );
+    }
+
+    fn output_unit(&self) -> Unit {
+        return Frohlich::output_unit(self);
+    }
+
+    fn influencing_units(&self) -> Option<Vec<Unit>> {
+        return Some(vec![self.influencing_factor_unit()]);
+    }
+
+    fn validity_ranges(&self) -> Vec<ValidityRange> {
+        return super::bind_validity_ranges(
+            self.validity.iter().cloned(),
+            self.input_name.as_deref(),
+        );
+    }
+
+    fn call_named(&self, influencing_factors: &[InfluencingFactor]) -> DynQuantity<f64> {
+        let selected = select_influencing_factor(
+            influencing_factors,
+            self.input_name.as_deref(),
+            self.influencing_factor_unit(),
+        );
+        return self.call(selected.as_slice());
+    }
+
+    fn derivative(
+        &self,
+        influencing_factors: &[DynQuantity<f64>],
+        wrt: Unit,
+    ) -> Option<DynQuantity<f64>> {
+        return filter_unary_derivative(
+            influencing_factors,
+            self.influencing_factor_unit(),
+            self.output_unit(),
+            wrt,
+            |h| self.evaluate_derivative(h),
+        );
+    }
+}
+
+#[cfg(feature = "serde")]
+mod serde_impl {
+    use super::*;
+
+    use serde::de::{Deserialize, Deserializer};
+
+    impl<'de> Deserialize<'de> for Frohlich {
+        fn deserialize<D>(deserializer: D) -> Result<Frohlich, D::Error>
+        where
+            D: Deserializer<'de>,
+        {
+            #[derive(serde::Deserialize)]
+            struct FrohlichAlias {
+                a: DynQuantity<f64>,
+                b: DynQuantity<f64>,
+                #[serde(default)]
+                output: SaturationOutput,
+                #[serde(default)]
+                input_name: Option<String>,
+                #[serde(default)]
+                validity: Option<ValidityRange>,
+            }
+
+            let alias = FrohlichAlias::deserialize(deserializer)?;
+            let mut frohlich = Self::new(alias.a, alias.b)
+                .map_err(serde::de::Error::custom)?
+                .with_output(alias.output);
+            frohlich.input_name = alias.input_name;
+            if let Some(range) = alias.validity {
+                frohlich = frohlich
+                    .with_validity(range)
+                    .map_err(serde::de::Error::custom)?;
+            }
+            return Ok(frohlich);
+        }
+    }
+}
+
+// =============================================================================
+
+#[cfg(feature = "serde")]
+#[cfg_attr(feature = "serde", typetag::serde)]
+impl QuantityFunction for crate::ClampedQuantity<Frohlich> {
+    fn call(&self, influencing_factors: &[DynQuantity<f64>]) -> DynQuantity<f64> {
+        return self.call_clamped(influencing_factors);
+    }
+
+    fn output_unit(&self) -> Unit {
+        return self.inner().output_unit();
+    }
+
+    fn influencing_units(&self) -> Option<Vec<Unit>> {
+        return self.inner().influencing_units();
+    }
+
+    fn validity_ranges(&self) -> Vec<ValidityRange> {
+        return self.inner().validity_ranges();
+    }
+
+    fn call_named(&self, influencing_factors: &[InfluencingFactor]) -> DynQuantity<f64> {
+        return self.call_named_clamped(influencing_factors);
+    }
+
+    fn derivative(
+        &self,
+        influencing_factors: &[DynQuantity<f64>],
+        wrt: Unit,
+    ) -> Option<DynQuantity<f64>> {
+        return self.derivative_clamped(influencing_factors, wrt);
+    }
+
+    fn call_dual(&self, influencing_factors: &[crate::DualQuantity]) -> crate::DualQuantity {
+        return self.call_dual_clamped(influencing_factors);
+    }
+
+    fn call_batch(&self, inputs: &[crate::BatchInput], output: &mut [f64]) {
+        self.call_batch_clamped(inputs, output);
+    }
+
+    fn bind(&self, layout: &crate::Layout) -> Box<dyn crate::Evaluator + '_> {
+        return self.bind_clamped(layout);
+    }
+}
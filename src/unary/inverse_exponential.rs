@@ -0,0 +1,431 @@
+/*!
+An unary [`InverseExponential`] function which implements [`QuantityFunction`].
+*/
+
+use dyn_quantity::{DynQuantity, Unit, UnitsNotEqual};
+
+use crate::{
+    BatchInput, EvaluationError, Evaluator, InfluencingFactor, Layout, QuantityFunction,
+    bind_unary, filter_unary_batch, filter_unary_derivative, filter_unary_function,
+    select_influencing_factor, validity::ValidityRange,
+};
+
+/**
+An exponential function of the reciprocal input, defined as:
+
+`y = amplitude * exp(coefficient * (1 / x - 1 / reference))`
+
+If no reference point is given (see [`InverseExponential::with_reference`]),
+the term `1 / reference` is omitted, resulting in `y = amplitude *
+exp(coefficient / x)`. The coefficient carries the unit of the influencing
+quantity, hence the exponent is dimensionless. Well-known examples are the
+Beta model of NTC thermistors (`R = R0 * exp(B * (1 / T - 1 / T0))`) and the
+Clausius-Clapeyron relation for the vapour pressure.
+
+# Domain
+
+The function is only defined for positive inputs `x`. For other inputs,
+[`QuantityFunction::call`] returns NaN and [`QuantityFunction::try_call`]
+returns an [`EvaluationError::InputOutOfRange`] with the open interval between
+zero and infinity as range. If none of the influencing factors matches the
+[`InverseExponential::influencing_factor_unit`], the amplitude is returned. This
+is the value at the reference point or, if no reference point is given, the
+limit for `x` approaching infinity.
+
+This struct is meant to be used as a [`QuantityFunction`] trait object.
+
+# Examples
+
+```
+use std::str::FromStr;
+use dyn_quantity::DynQuantity;
+use var_quantity::{QuantityFunction, unary::InverseExponential};
+
+let q = |s: &str| DynQuantity::<f64>::from_str(s).unwrap();
+
+// NTC thermistor with 10 kOhm at 25 °C and B = 3950 K
+let ntc = InverseExponential::new(q("10 kohm"), q("3950 K"))
+    .with_reference(q("298.15 K"))
+    .expect("units match");
+approx::assert_abs_diff_eq!(ntc.call(&[q("298.15 K")]).value, 10e3, epsilon = 1e-9);
+approx::assert_abs_diff_eq!(ntc.call(&[q("373.15 K")]).value, 697.52, epsilon = 1e-2);
+
+// Non-positive inputs are outside of the domain
+assert!(ntc.call(&[q("0 K")]).value.is_nan());
+assert!(ntc.try_call(&[q("-1 K")]).is_err());
+```
+
+# Features:
+This struct can be serialized / deserialized if the `serde` feature is enabled.
+ */
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct InverseExponential {
+    amplitude: DynQuantity<f64>,
+    coefficient: DynQuantity<f64>,
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    reference: Option<DynQuantity<f64>>,
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    input_name: Option<String>,
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    validity: Option<ValidityRange>,
+}
+
+impl InverseExponential {
+    /**
+    Returns a new instance of [`InverseExponential`] without a reference
+    point, i.e. `y = amplitude * exp(coefficient / x)`.
+
+    # Examples
+
+    ```
+    use std::str::FromStr;
+    use dyn_quantity::DynQuantity;
+    use var_quantity::{QuantityFunction, unary::InverseExponential};
+
+    let q = |s: &str| DynQuantity::<f64>::from_str(s).unwrap();
+
+    let fun = InverseExponential::new(q("2 ohm"), q("100 K"));
+    approx::assert_abs_diff_eq!(fun.call(&[q("100 K")]).value, 2.0 * 1f64.exp());
+    ```
+    */
+    pub fn new(amplitude: DynQuantity<f64>, coefficient: DynQuantity<f64>) -> Self {
+        return Self {
+            amplitude,
+            coefficient,
+            reference: None,
+            input_name: None,
+            validity: None,
+        };
+    }
+
+    /**
+    Sets the reference point at which the function returns the amplitude.
+    Fails if the unit of `reference` is not
+    [`InverseExponential::influencing_factor_unit`].
+
+    # Examples
+
+    ```
+    use std::str::FromStr;
+    use dyn_quantity::DynQuantity;
+    use var_quantity::unary::InverseExponential;
+
+    let q = |s: &str| DynQuantity::<f64>::from_str(s).unwrap();
+
+    let fun = InverseExponential::new(q("2 ohm"), q("100 K"));
+    assert!(fun.clone().with_reference(q("300 K")).is_ok());
+    assert!(fun.with_reference(q("300 A")).is_err());
+    ```
+    */
+    pub fn with_reference(mut self, reference: DynQuantity<f64>) -> Result<Self, UnitsNotEqual> {
+        if reference.unit != self.influencing_factor_unit() {
+            return Err(UnitsNotEqual(
+                self.influencing_factor_unit(),
+                reference.unit,
+            ));
+        }
+        self.reference = Some(reference);
+        return Ok(self);
+    }
+
+    /**
+    Returns the `amplitude`.
+    */
+    pub fn amplitude(&self) -> &DynQuantity<f64> {
+        return &self.amplitude;
+    }
+
+    /**
+    Returns the `coefficient`.
+    */
+    pub fn coefficient(&self) -> &DynQuantity<f64> {
+        return &self.coefficient;
+    }
+
+    /**
+    Returns the `reference` point, if any. See
+    [`InverseExponential::with_reference`].
+    */
+    pub fn reference(&self) -> Option<&DynQuantity<f64>> {
+        return self.reference.as_ref();
+    }
+
+    /**
+    Returns the unit of the quantity which influences the variable quantity,
+    which is the unit of the coefficient. If none of the `influencing_factors`
+    in a [`QuantityFunction::call`] matches this item, the amplitude is
+    returned.
+
+    # Examples
+
+    ```
+    use std::str::FromStr;
+    use dyn_quantity::DynQuantity;
+    use var_quantity::{QuantityFunction, unary::InverseExponential};
+
+    let q = |s: &str| DynQuantity::<f64>::from_str(s).unwrap();
+
+    let fun = InverseExponential::new(q("2 ohm"), q("100 K"));
+    assert_eq!(fun.influencing_factor_unit(), q("1 K").unit);
+    assert_eq!(fun.call(&[q("1 A")]), q("2 ohm"));
+    ```
+    */
+    pub fn influencing_factor_unit(&self) -> Unit {
+        return self.coefficient.unit;
+    }
+
+    /**
+    Returns the unit which will be returned from [`QuantityFunction::call`].
+
+    ```
+    use std::str::FromStr;
+    use dyn_quantity::DynQuantity;
+    use var_quantity::{QuantityFunction, unary::InverseExponential};
+
+    let q = |s: &str| DynQuantity::<f64>::from_str(s).unwrap();
+
+    let fun = InverseExponential::new(q("2 ohm"), q("100 K"));
+    assert_eq!(fun.output_unit(), q("1 ohm").unit);
+    ```
+     */
+    pub fn output_unit(&self) -> Unit {
+        return self.amplitude.unit;
+    }
+
+    /**
+    Returns the value of the function for `x` or `None` if `x` is outside of
+    the domain.
+     */
+    fn evaluate(&self, x: f64) -> Option<f64> {
+        if x <= 0.0 {
+            return None;
+        }
+        let reciprocal_reference = self.reference.map_or(0.0, |r| 1.0 / r.value);
+        return Some(
+            self.amplitude.value
+                * (self.coefficient.value * (1.0 / x - reciprocal_reference)).exp(),
+        );
+    }
+}
+
+impl_input_name!(InverseExponential);
+impl_validity!(InverseExponential);
+
+#[cfg_attr(feature = "serde", typetag::serde)]
+impl QuantityFunction for InverseExponential {
+    fn call(&self, influencing_factors: &[DynQuantity<f64>]) -> DynQuantity<f64> {
+        return filter_unary_function(
+            influencing_factors,
+            self.influencing_factor_unit(),
+            |input| {
+                let value = self.evaluate(input.value).unwrap_or(f64::NAN);
+                return DynQuantity::new(value, self.output_unit());
+            },
+            || self.amplitude,
+        );
+    }
+
+    fn call_batch(&self, inputs: &[BatchInput], output: &mut [f64]) {
+        filter_unary_batch(
+            inputs,
+            self.influencing_factor_unit(),
+            output,
+            |input| self.evaluate(input).unwrap_or(f64::NAN),
+            || self.amplitude.value,
+        );
+    }
+
+    fn bind(&self, layout: &Layout) -> Box<dyn Evaluator + '_> {
+        return bind_unary(
+            layout,
+            self.influencing_factor_unit(),
+            self.input_name.as_deref(),
+            |input| self.evaluate(input).unwrap_or(f64::NAN),
+            || self.amplitude.value,
+        );
+    }
+
+    fn output_unit(&self) -> Unit {
+        return InverseExponential::output_unit(self);
+    }
+
+    fn influencing_units(&self) -> Option<Vec<Unit>> {
+        return Some(vec![self.influencing_factor_unit()]);
+    }
+
+    fn validity_ranges(&self) -> Vec<ValidityRange> {
+        return super::bind_validity_ranges(
+            self.validity.iter().cloned(),
+            self.input_name.as_deref(),
+        );
+    }
+
+    fn call_named(&self, influencing_factors: &[InfluencingFactor]) -> DynQuantity<f64> {
+        let selected = select_influencing_factor(
+            influencing_factors,
+            self.input_name.as_deref(),
+            self.influencing_factor_unit(),
+        );
+        return self.call(selected.as_slice());
+    }
+
+    fn try_call(
+        &self,
+        influencing_factors: &[DynQuantity<f64>],
+    ) -> Result<DynQuantity<f64>, EvaluationError> {
+        let input_unit = self.influencing_factor_unit();
+        let Some(input) = influencing_factors.iter().find(|f| f.unit == input_unit) else {
+            return Ok(self.amplitude);
+        };
+        match self.evaluate(input.value) {
+            Some(value) => return Ok(DynQuantity::new(value, self.output_unit())),
+            None => {
+                return Err(EvaluationError::InputOutOfRange {
+                    input: *input,
+                    lower: DynQuantity::new(0.0, input_unit),
+                    upper: DynQuantity::new(f64::INFINITY, input_unit),
+                });
+            }
+        }
+    }
+
+    fn try_call_named(
+        &self,
+        influencing_factors: &[InfluencingFactor],
+    ) -> Result<DynQuantity<f64>, EvaluationError> {
+        let selected = select_influencing_factor(
+            influencing_factors,
+            self.input_name.as_deref(),
+            self.influencing_factor_unit(),
+        );
+        return self.try_call(selected.as_slice());
+    }
+
+    fn derivative(
+        &self,
+        influencing_factors: &[DynQuantity<f64>],
+        wrt: Unit,
+    ) -> Option<DynQuantity<f64>> {
+        return filter_unary_derivative(
+            influencing_factors,
+            self.influencing_factor_unit(),
+            self.output_unit(),
+            wrt,
+            |x| match self.evaluate(x) {
+                Some(value) => -value * self.coefficient.value / x.powi(2),
+                None => f64::NAN,
+            },
+        );
+    }
+}
+
+#[cfg(feature = "serde")]
+mod serde_impl {
+    use super::*;
+
+    use serde::de::{Deserialize, Deserializer};
+
+    impl<'de> Deserialize<'de> for InverseExponential {
+        fn deserialize<D>(deserializer: D) -> Result<InverseExponential, D::Error>
+        where
+            D: Deserializer<'de>,
+        {
+            #[derive(serde::Deserialize)]
+            struct InverseExponentialAlias {
+                amplitude: DynQuantity<f64>,
+                coefficient: DynQuantity<f64>,
+                #[serde(default)]
+                reference: Option<DynQuantity<f64>>,
+                #[serde(default)]
+                input_name: Option<String>,
+                #[serde(default)]
+                validity: Option<ValidityRange>,
+            }
+
+            let alias = InverseExponentialAlias::deserialize(deserializer)?;
+            let mut inverse_exponential = Self::new(alias.amplitude, alias.coefficient);
+            if let Some(reference) = alias.reference {
+                inverse_exponential = inverse_exponential
+                    .with_reference(reference)
+                    .map_err(serde::de::Error::custom)?;
+            }
+            inverse_exponential.input_name = alias.input_name;
+            if let Some(range) = alias.validity {
+                inverse_exponential = inverse_exponential
+                    .with_validity(range)
+                    .map_err(serde::de::Error::custom)?;
+            }
+            return Ok(inverse_exponential);
+        }
+    }
+}
+
+// =============================================================================
+
+#[cfg(feature = "serde")]
+#[cfg_attr(feature = "serde", typetag::serde)]
+impl QuantityFunction for crate::ClampedQuantity<InverseExponential> {
+    fn call(&self, influencing_factors: &[DynQuantity<f64>]) -> DynQuantity<f64> {
+        return self.call_clamped(influencing_factors);
+    }
+
+    fn output_unit(&self) -> Unit {
+        return self.inner().output_unit();
+    }
+
+    fn influencing_units(&self) -> Option<Vec<Unit>> {
+        return self.inner().influencing_units();
+    }
+
+    fn validity_ranges(&self) -> Vec<ValidityRange> {
+        return self.inner().validity_ranges();
+    }
+
+    fn call_named(&self, influencing_factors: &[InfluencingFactor]) -> DynQuantity<f64> {
+        return self.call_named_clamped(influencing_factors);
+    }
+
+    fn try_call(
+        &self,
+        influencing_factors: &[DynQuantity<f64>],
+    ) -> Result<DynQuantity<f64>, EvaluationError> {
+        return self.try_call_clamped(influencing_factors);
+    }
+
+    fn try_call_named(
+        &self,
+        influencing_factors: &[InfluencingFactor],
+    ) -> Result<DynQuantity<f64>, EvaluationError> {
+        return self.try_call_named_clamped(influencing_factors);
+    }
+
+    fn derivative(
+        &self,
+        influencing_factors: &[DynQuantity<f64>],
+        wrt: Unit,
+    ) -> Option<DynQuantity<f64>> {
+        return self.derivative_clamped(influencing_factors, wrt);
+    }
+
+    fn call_dual(&self, influencing_factors: &[crate::DualQuantity]) -> crate::DualQuantity {
+        return self.call_dual_clamped(influencing_factors);
+    }
+
+    fn call_batch(&self, inputs: &[crate::BatchInput], output: &mut [f64]) {
+        self.call_batch_clamped(inputs, output);
+    }
+
+    fn bind(&self, layout: &crate::Layout) -> Box<dyn crate::Evaluator + '_> {
+        return self.bind_clamped(layout);
+    }
+}
@@ -0,0 +1,511 @@
+/*!
+An unary [`Langevin`] function which implements [`QuantityFunction`].
+*/
+
+use dyn_quantity::{DynQuantity, PredefUnit, Unit, UnitsNotEqual};
+
+use crate::{
+    BatchInput, Evaluator, InfluencingFactor, Layout, QuantityFunction, bind_unary,
+    filter_unary_batch, filter_unary_derivative, filter_unary_function, select_influencing_factor,
+    validity::ValidityRange,
+};
+
+pub use super::saturation::{SaturationOutput, VACUUM_PERMEABILITY};
+
+/// Below this argument, the Brillouin function is evaluated via its series expansion.
+const SERIES_LIMIT: f64 = 1e-2;
+
+/**
+A magnetic saturation curve based on the Langevin function
+`L(x) = coth(x) - 1 / x`:
+
+`B = μ0 * (H + Ms * L(H / a))`
+
+where `μ0` is the [`VACUUM_PERMEABILITY`], `H` is the magnetic field strength,
+`Ms` is the saturation magnetization and `a` is a shape parameter (both in
+A/m). The initial permeability at `H = 0` is `μ0 * (1 + Ms / (3 * a))`.
+
+If a total angular momentum quantum number `J` is given via
+[`Langevin::with_total_angular_momentum`], the Langevin function is replaced by
+the Brillouin function
+
+`B_J(x) = (2J + 1) / (2J) * coth((2J + 1) / (2J) * x) - 1 / (2J) * coth(x / (2J))`
+
+which approaches the Langevin function for `J` approaching infinity.
+
+This struct is meant to be used as a [`QuantityFunction`] trait object. The
+unit of the influencing quantity is [`PredefUnit::MagneticFieldStrength`].
+Depending on the [`SaturationOutput`], either the flux density `B(H)` (default)
+or the permeability `μ(H) = B(H) / H` is returned, see [`Langevin::with_output`].
+
+# Examples
+
+```
+use std::str::FromStr;
+use dyn_quantity::DynQuantity;
+use var_quantity::{QuantityFunction, unary::{Langevin, SaturationOutput, langevin::VACUUM_PERMEABILITY}};
+
+let q = |s: &str| DynQuantity::<f64>::from_str(s).unwrap();
+
+let fun = Langevin::new(q("1.6e6 A/m"), q("500 A/m")).expect("units match");
+
+// Saturation
+let b = fun.call(&[q("1e7 A/m")]);
+assert_eq!(b.unit, q("1 T").unit);
+approx::assert_relative_eq!(b.value, VACUUM_PERMEABILITY * (1e7 + 1.6e6), max_relative = 1e-4);
+
+// Initial permeability
+let fun = fun.with_output(SaturationOutput::Permeability);
+approx::assert_relative_eq!(
+    fun.call(&[q("0 A/m")]).value,
+    VACUUM_PERMEABILITY * (1.0 + 1.6e6 / 1500.0)
+);
+```
+
+# Features:
+This struct can be serialized / deserialized if the `serde` feature is enabled.
+ */
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct Langevin {
+    saturation_magnetization: DynQuantity<f64>,
+    a: DynQuantity<f64>,
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    total_angular_momentum: Option<f64>,
+    output: SaturationOutput,
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    input_name: Option<String>,
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    validity: Option<ValidityRange>,
+}
+
+impl Langevin {
+    /**
+    Checks if `saturation_magnetization` and `a` are given in A/m. If this is
+    the case, a new instance of [`Langevin`] which returns the flux density is
+    returned. Otherwise, the error contains A/m and the unit of the first
+    mismatching argument. For a physically meaningful curve, `a` must be
+    positive.
+
+    # Examples
+
+    ```
+    use std::str::FromStr;
+    use dyn_quantity::DynQuantity;
+    use var_quantity::unary::Langevin;
+
+    let q = |s: &str| DynQuantity::<f64>::from_str(s).unwrap();
+
+    assert!(Langevin::new(q("1.6e6 A/m"), q("500 A/m")).is_ok());
+    assert!(Langevin::new(q("2 T"), q("500 A/m")).is_err());
+    ```
+    */
+    pub fn new(
+        saturation_magnetization: DynQuantity<f64>,
+        a: DynQuantity<f64>,
+    ) -> Result<Self, UnitsNotEqual> {
+        let field_strength = Unit::from(PredefUnit::MagneticFieldStrength);
+        for quantity in [&saturation_magnetization, &a] {
+            if quantity.unit != field_strength {
+                return Err(UnitsNotEqual(field_strength, quantity.unit));
+            }
+        }
+        return Ok(Self {
+            saturation_magnetization,
+            a,
+            total_angular_momentum: None,
+            output: SaturationOutput::default(),
+            input_name: None,
+            validity: None,
+        });
+    }
+
+    /**
+    Returns the saturation magnetization `Ms`.
+    */
+    pub fn saturation_magnetization(&self) -> &DynQuantity<f64> {
+        return &self.saturation_magnetization;
+    }
+
+    /**
+    Returns the shape parameter `a`.
+    */
+    pub fn a(&self) -> &DynQuantity<f64> {
+        return &self.a;
+    }
+
+    /**
+    Replaces the Langevin function by the Brillouin function with the total
+    angular momentum quantum number `j`, which must be positive (typically a
+    multiple of 1/2). Otherwise, the function returns NaN.
+
+    # Examples
+
+    ```
+    use std::str::FromStr;
+    use dyn_quantity::DynQuantity;
+    use var_quantity::{QuantityFunction, unary::{Langevin, SaturationOutput, langevin::VACUUM_PERMEABILITY}};
+
+    let q = |s: &str| DynQuantity::<f64>::from_str(s).unwrap();
+
+    // For J = 1/2, the Brillouin function is tanh(x)
+    let fun = Langevin::new(q("1e6 A/m"), q("1000 A/m"))
+        .expect("units match")
+        .with_total_angular_momentum(0.5);
+    assert_eq!(fun.total_angular_momentum(), Some(0.5));
+    approx::assert_relative_eq!(
+        fun.call(&[q("1500 A/m")]).value,
+        VACUUM_PERMEABILITY * (1500.0 + 1e6 * 1.5f64.tanh()),
+        max_relative = 1e-12
+    );
+    ```
+    */
+    pub fn with_total_angular_momentum(mut self, j: f64) -> Self {
+        self.total_angular_momentum = Some(j);
+        return self;
+    }
+
+    /**
+    Returns the total angular momentum quantum number of the Brillouin
+    function, if any. See [`Langevin::with_total_angular_momentum`].
+    */
+    pub fn total_angular_momentum(&self) -> Option<f64> {
+        return self.total_angular_momentum;
+    }
+
+    /**
+    Sets the quantity returned by the function, see [`SaturationOutput`].
+    */
+    pub fn with_output(mut self, output: SaturationOutput) -> Self {
+        self.output = output;
+        return self;
+    }
+
+    /**
+    Returns the quantity returned by the function, see [`Langevin::with_output`].
+    */
+    pub fn output(&self) -> SaturationOutput {
+        return self.output;
+    }
+
+    /**
+    Returns the unit of the quantity which influences the variable quantity,
+    which is always [`PredefUnit::MagneticFieldStrength`]. If none of the
+    `influencing_factors` in a [`QuantityFunction::call`] matches this item,
+    then `H` is assumed to be zero.
+
+    # Examples
+
+    ```
+    use std::str::FromStr;
+    use dyn_quantity::{DynQuantity, PredefUnit, Unit};
+    use var_quantity::{QuantityFunction, unary::Langevin};
+
+    let q = |s: &str| DynQuantity::<f64>::from_str(s).unwrap();
+
+    let fun = Langevin::new(q("1.6e6 A/m"), q("500 A/m")).expect("units match");
+    assert_eq!(fun.influencing_factor_unit(), Unit::from(PredefUnit::MagneticFieldStrength));
+    assert_eq!(fun.call(&[q("1 K")]), q("0 T"));
+    ```
+    */
+    pub fn influencing_factor_unit(&self) -> Unit {
+        return PredefUnit::MagneticFieldStrength.into();
+    }
+
+    /**
+    Returns the unit which will be returned from [`QuantityFunction::call`],
+    see [`SaturationOutput::unit`].
+
+    ```
+    use std::str::FromStr;
+    use dyn_quantity::DynQuantity;
+    use var_quantity::{QuantityFunction, unary::{Langevin, SaturationOutput}};
+
+    let q = |s: &str| DynQuantity::<f64>::from_str(s).unwrap();
+
+    let fun = Langevin::new(q("1.6e6 A/m"), q("500 A/m")).expect("units match");
+    assert_eq!(fun.output_unit(), q("1 T").unit);
+
+    let fun = fun.with_output(SaturationOutput::Permeability);
+    assert_eq!(fun.output_unit(), q("1 T*m/A").unit);
+    ```
+     */
+    pub fn output_unit(&self) -> Unit {
+        return self.output.unit();
+    }
+
+    /**
+    Returns the coefficients `(p, q)` of the Brillouin function
+    `B(x) = p * coth(p * x) - q * coth(q * x)`. The Langevin function
+    corresponds to `p = 1` and `q = 0`.
+     */
+    fn coefficients(&self) -> (f64, f64) {
+        match self.total_angular_momentum {
+            Some(j) => return ((2.0 * j + 1.0) / (2.0 * j), 1.0 / (2.0 * j)),
+            None => return (1.0, 0.0),
+        }
+    }
+
+    /**
+    Returns the Brillouin function `B(x)` and its derivative.
+     */
+    fn brillouin(&self, x: f64) -> (f64, f64) {
+        let (p, q) = self.coefficients();
+        if x.abs() < SERIES_LIMIT {
+            let (s1, s3, s5) = series_coefficients(p, q);
+            return (
+                x * (s1 - x.powi(2) * (s3 - x.powi(2) * s5)),
+                s1 - x.powi(2) * (3.0 * s3 - 5.0 * x.powi(2) * s5),
+            );
+        }
+        return (
+            scaled_coth(p, x) - scaled_coth(q, x),
+            scaled_coth_derivative(p, x) - scaled_coth_derivative(q, x),
+        );
+    }
+
+    /**
+    Returns `B(x) / x` and its derivative.
+     */
+    fn brillouin_ratio(&self, x: f64) -> (f64, f64) {
+        if x.abs() < SERIES_LIMIT {
+            let (p, q) = self.coefficients();
+            let (s1, s3, s5) = series_coefficients(p, q);
+            return (
+                s1 - x.powi(2) * (s3 - x.powi(2) * s5),
+                -2.0 * s3 * x + 4.0 * s5 * x.powi(3),
+            );
+        }
+        let (value, derivative) = self.brillouin(x);
+        return (value / x, (derivative * x - value) / x.powi(2));
+    }
+
+    /**
+    Returns the flux density or the permeability for the field strength `h`.
+     */
+    fn evaluate(&self, h: f64) -> f64 {
+        let ms = self.saturation_magnetization.value;
+        let a = self.a.value;
+        match self.output {
+            SaturationOutput::FluxDensity => {
+                return VACUUM_PERMEABILITY * (h + ms * self.brillouin(h / a).0);
+            }
+            SaturationOutput::Permeability => {
+                return VACUUM_PERMEABILITY * (1.0 + ms / a * self.brillouin_ratio(h / a).0);
+            }
+        }
+    }
+
+    /**
+    Returns the derivative of [`Langevin::evaluate`] with respect to `h`.
+     */
+    fn evaluate_derivative(&self, h: f64) -> f64 {
+        let ms = self.saturation_magnetization.value;
+        let a = self.a.value;
+        match self.output {
+            SaturationOutput::FluxDensity => {
+                return VACUUM_PERMEABILITY * (1.0 + ms / a * self.brillouin(h / a).1);
+            }
+            SaturationOutput::Permeability => {
+                return VACUUM_PERMEABILITY * ms / a.powi(2) * self.brillouin_ratio(h / a).1;
+            }
+        }
+    }
+}
+
+/**
+Returns the coefficients of the series expansion
+`B(x) = s1 * x - s3 * x^3 + s5 * x^5` of the Brillouin function.
+ */
+fn series_coefficients(p: f64, q: f64) -> (f64, f64, f64) {
+    return (
+        (p.powi(2) - q.powi(2)) / 3.0,
+        (p.powi(4) - q.powi(4)) / 45.0,
+        2.0 * (p.powi(6) - q.powi(6)) / 945.0,
+    );
+}
+
+/// Returns `p * coth(p * x)`, which is `1 / x` for `p = 0`.
+fn scaled_coth(p: f64, x: f64) -> f64 {
+    if p == 0.0 {
+        return 1.0 / x;
+    }
+    return p / (p * x).tanh();
+}
+
+/// Returns the derivative of [`scaled_coth`] with respect to `x`.
+fn scaled_coth_derivative(p: f64, x: f64) -> f64 {
+    if p == 0.0 {
+        return -1.0 / x.powi(2);
+    }
+    return -(p / (p * x).sinh()).powi(2);
+}
+
+impl_input_name!(Langevin);
+impl_validity!(Langevin);
+
+#[cfg_attr(feature = "serde", typetag::serde)]
+impl QuantityFunction for Langevin {
+    fn call(&self, influencing_factors: &[DynQuantity<f64>]) -> DynQuantity<f64> {
+        return filter_unary_function(
+            influencing_factors,
+            self.influencing_factor_unit(),
+            |input| DynQuantity::new(self.evaluate(input.value), self.output_unit()),
+            || DynQuantity::new(self.evaluate(0.0), self.output_unit()),
+        );
+    }
+
+    fn call_batch(&self, inputs: &[BatchInput], output: &mut [f64]) {
+        filter_unary_batch(
+            inputs,
+            self.influencing_factor_unit(),
+            output,
+            |input| self.evaluate(input),
+            || self.evaluate(0.0),
+        );
+    }
+
+    fn bind(&self, layout: &Layout) -> Box<dyn Evaluator + '_> {
+        return bind_unary(
+            layout,
+            self.influencing_factor_unit(),
+            self.input_name.as_deref(),
+            |input| self.evaluate(input),
+            || self.evaluate(0.0),
+        );
+    }
+
+    fn output_unit(&self) -> Unit {
+        return Langevin::output_unit(self);
+    }
+
+    fn influencing_units(&self) -> Option<Vec<Unit>> {
+        return Some(vec![self.influencing_factor_unit()]);
+    }
+
+    fn validity_ranges(&self) -> Vec<ValidityRange> {
+        return super::bind_validity_ranges(
+            self.validity.iter().cloned(),
+            self.input_name.as_deref(),
+        );
+    }
+
+    fn call_named(&self, influencing_factors: &[InfluencingFactor]) -> DynQuantity<f64> {
+        let selected = select_influencing_factor(
+            influencing_factors,
+            self.input_name.as_deref(),
+            self.influencing_factor_unit(),
+        );
+        return self.call(selected.as_slice());
+    }
+
+    fn derivative(
+        &self,
+        influencing_factors: &[DynQuantity<f64>],
+        wrt: Unit,
+    ) -> Option<DynQuantity<f64>> {
+        return filter_unary_derivative(
+            influencing_factors,
+            self.influencing_factor_unit(),
+            self.output_unit(),
+            wrt,
+            |h| self.evaluate_derivative(h),
+        );
+    }
+}
+
+#[cfg(feature = "serde")]
+mod serde_impl {
+    use super::*;
+
+    use serde::de::{Deserialize, Deserializer};
+
+    impl<'de> Deserialize<'de> for Langevin {
+        fn deserialize<D>(deserializer: D) -> Result<Langevin, D::Error>
+        where
+            D: Deserializer<'de>,
+        {
+            #[derive(serde::Deserialize)]
+            struct LangevinAlias {
+                saturation_magnetization: DynQuantity<f64>,
+                a: DynQuantity<f64>,
+                #[serde(default)]
+                total_angular_momentum: Option<f64>,
+                #[serde(default)]
+                output: SaturationOutput,
+                #[serde(default)]
+                input_name: Option<String>,
+                #[serde(default)]
+                validity: Option<ValidityRange>,
+            }
+
+            let alias = LangevinAlias::deserialize(deserializer)?;
+            let mut langevin = Self::new(alias.saturation_magnetization, alias.a)
+                .map_err(serde::de::Error::custom)?
+                .with_output(alias.output);
+            langevin.total_angular_momentum = alias.total_angular_momentum;
+            langevin.input_name = alias.input_name;
+            if let Some(range) = alias.validity {
+                langevin = langevin
+                    .with_validity(range)
+                    .map_err(serde::de::Error::custom)?;
+            }
+            return Ok(langevin);
+        }
+    }
+}
+
+// =============================================================================
+
+#[cfg(feature = "serde")]
+#[cfg_attr(feature = "serde", typetag::serde)]
+impl QuantityFunction for crate::ClampedQuantity<Langevin> {
+    fn call(&self, influencing_factors: &[DynQuantity<f64>]) -> DynQuantity<f64> {
+        return self.call_clamped(influencing_factors);
+    }
+
+    fn output_unit(&self) -> Unit {
+        return self.inner().output_unit();
+    }
+
+    fn influencing_units(&self) -> Option<Vec<Unit>> {
+        return self.inner().influencing_units();
+    }
+
+    fn validity_ranges(&self) -> Vec<ValidityRange> {
+        return self.inner().validity_ranges();
+    }
+
+    fn call_named(&self, influencing_factors: &[InfluencingFactor]) -> DynQuantity<f64> {
+        return self.call_named_clamped(influencing_factors);
+    }
+
+    fn derivative(
+        &self,
+        influencing_factors: &[DynQuantity<f64>],
+        wrt: Unit,
+    ) -> Option<DynQuantity<f64>> {
+        return self.derivative_clamped(influencing_factors, wrt);
+    }
+
+    fn call_dual(&self, influencing_factors: &[crate::DualQuantity]) -> crate::DualQuantity {
+        return self.call_dual_clamped(influencing_factors);
+    }
+
+    fn call_batch(&self, inputs: &[crate::BatchInput], output: &mut [f64]) {
+        self.call_batch_clamped(inputs, output);
+    }
+
+    fn bind(&self, layout: &crate::Layout) -> Box<dyn crate::Evaluator + '_> {
+        return self.bind_clamped(layout);
+    }
+}
@@ -4,7 +4,11 @@ An unary [`Linear`] function which implements [`QuantityFunction`].
 
 use dyn_quantity::{DynQuantity, Unit};
 
-use crate::{QuantityFunction, filter_unary_function};
+use crate::{
+    BatchInput, Evaluator, InfluencingFactor, Layout, QuantityFunction, bind_unary,
+    filter_unary_batch, filter_unary_derivative, filter_unary_function, select_influencing_factor,
+    validity::ValidityRange,
+};
 
 /**
 A linear function defined via its `slope` and `base_value`:
@@ -18,10 +22,20 @@ unit of the influencing quantity is `base_value`.unit / slope.unit`.
 This struct can be serialized / deserialized if the `serde` feature is enabled.
 */
 #[derive(Debug, Clone, PartialEq)]
-#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct Linear {
     slope: DynQuantity<f64>,
     base_value: DynQuantity<f64>,
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    input_name: Option<String>,
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    validity: Option<ValidityRange>,
 }
 
 impl Linear {
@@ -37,7 +51,12 @@ impl Linear {
     ```
      */
     pub fn new(slope: DynQuantity<f64>, base_value: DynQuantity<f64>) -> Self {
-        return Self { slope, base_value };
+        return Self {
+            slope,
+            base_value,
+            input_name: None,
+            validity: None,
+        };
     }
 
     /**
@@ -107,6 +126,9 @@ impl Linear {
     }
 }
 
+impl_input_name!(Linear);
+impl_validity!(Linear);
+
 #[cfg_attr(feature = "serde", typetag::serde)]
 impl QuantityFunction for Linear {
     fn call(&self, influencing_factors: &[DynQuantity<f64>]) -> DynQuantity<f64> {
@@ -124,6 +146,98 @@ impl QuantityFunction for Linear {
             || self.base_value,
         );
     }
+
+    fn call_batch(&self, inputs: &[BatchInput], output: &mut [f64]) {
+        filter_unary_batch(
+            inputs,
+            self.influencing_factor_unit(),
+            output,
+            |input| self.base_value.value + self.slope.value * input,
+            || self.base_value.value,
+        );
+    }
+
+    fn bind(&self, layout: &Layout) -> Box<dyn Evaluator + '_> {
+        return bind_unary(
+            layout,
+            self.influencing_factor_unit(),
+            self.input_name.as_deref(),
+            |input| self.base_value.value + self.slope.value * input,
+            || self.base_value.value,
+        );
+    }
+
+    fn output_unit(&self) -> Unit {
+        return Linear::output_unit(self);
+    }
+
+    fn influencing_units(&self) -> Option<Vec<Unit>> {
+        return Some(vec![self.influencing_factor_unit()]);
+    }
+
+    fn validity_ranges(&self) -> Vec<ValidityRange> {
+        return super::bind_validity_ranges(
+            self.validity.iter().cloned(),
+            self.input_name.as_deref(),
+        );
+    }
+
+    fn call_named(&self, influencing_factors: &[InfluencingFactor]) -> DynQuantity<f64> {
+        let selected = select_influencing_factor(
+            influencing_factors,
+            self.input_name.as_deref(),
+            self.influencing_factor_unit(),
+        );
+        return self.call(selected.as_slice());
+    }
+
+    fn derivative(
+        &self,
+        influencing_factors: &[DynQuantity<f64>],
+        wrt: Unit,
+    ) -> Option<DynQuantity<f64>> {
+        return filter_unary_derivative(
+            influencing_factors,
+            self.influencing_factor_unit(),
+            self.output_unit(),
+            wrt,
+            |_| self.slope.value,
+        );
+    }
+}
+
+#[cfg(feature = "serde")]
+mod serde_impl {
+    use super::*;
+
+    use serde::de::{Deserialize, Deserializer};
+
+    impl<'de> Deserialize<'de> for Linear {
+        fn deserialize<D>(deserializer: D) -> Result<Linear, D::Error>
+        where
+            D: Deserializer<'de>,
+        {
+            #[derive(serde::Deserialize)]
+            struct LinearAlias {
+                slope: DynQuantity<f64>,
+                base_value: DynQuantity<f64>,
+                #[serde(default)]
+                input_name: Option<String>,
+                #[serde(default)]
+                validity: Option<ValidityRange>,
+            }
+
+            let alias = LinearAlias::deserialize(deserializer)?;
+            let mut linear = Self::new(alias.slope, alias.base_value);
+            linear.input_name = alias.input_name;
+            if let Some(range) = alias.validity {
+                linear = linear
+                    .with_validity(range)
+                    .map_err(serde::de::Error::custom)?;
+            }
+            return Ok(linear);
+        }
+    }
 }
 
 // =============================================================================
@@ -134,4 +248,40 @@ impl QuantityFunction for crate::ClampedQuantity<Linear> {
     fn call(&self, influencing_factors: &[DynQuantity<f64>]) -> DynQuantity<f64> {
         return self.call_clamped(influencing_factors);
     }
+
+    fn output_unit(&self) -> Unit {
+        return self.inner().output_unit();
+    }
+
+    fn influencing_units(&self) -> Option<Vec<Unit>> {
+        return self.inner().influencing_units();
+    }
+
+    fn validity_ranges(&self) -> Vec<ValidityRange> {
+        return self.inner().validity_ranges();
+    }
+
+    fn call_named(&self, influencing_factors: &[InfluencingFactor]) -> DynQuantity<f64> {
+        return self.call_named_clamped(influencing_factors);
+    }
+
+    fn derivative(
+        &self,
+        influencing_factors: &[DynQuantity<f64>],
+        wrt: Unit,
+    ) -> Option<DynQuantity<f64>> {
+        return self.derivative_clamped(influencing_factors, wrt);
+    }
+
+    fn call_dual(&self, influencing_factors: &[crate::DualQuantity]) -> crate::DualQuantity {
+        return self.call_dual_clamped(influencing_factors);
+    }
+
+    fn call_batch(&self, inputs: &[crate::BatchInput], output: &mut [f64]) {
+        self.call_batch_clamped(inputs, output);
+    }
+
+    fn bind(&self, layout: &crate::Layout) -> Box<dyn crate::Evaluator + '_> {
+        return self.bind_clamped(layout);
+    }
 }
@@ -0,0 +1,405 @@
+/*!
+An unary [`Logarithmic`] function which implements [`QuantityFunction`].
+*/
+
+use dyn_quantity::{DynQuantity, Unit, UnitsNotEqual};
+
+use crate::{
+    BatchInput, EvaluationError, Evaluator, InfluencingFactor, Layout, QuantityFunction,
+    ReferenceError, bind_unary, filter_unary_batch, filter_unary_derivative, filter_unary_function,
+    select_influencing_factor, validity::ValidityRange,
+};
+
+/**
+A logarithmic function defined as:
+
+`y = offset + slope * ln(x / reference)`
+
+The reference point carries the unit of the influencing quantity, hence the
+ratio `x / reference` is dimensionless. Offset and slope share the output unit.
+Since `log_b(z) = ln(z) / ln(b)`, logarithms to other bases (e.g. decibel
+scales with `10 * log10(x / reference)`) are obtained by dividing the slope by
+`ln(b)`.
+
+# Domain
+
+The logarithm is only defined for positive ratios `x / reference`. For other
+inputs, [`QuantityFunction::call`] returns NaN and [`QuantityFunction::try_call`]
+returns an [`EvaluationError::InputOutOfRange`] with the open interval between
+zero and (plus or minus) infinity as range. If none of the influencing factors
+matches the [`Logarithmic::influencing_factor_unit`], the function is
+evaluated at the reference point and returns the offset.
+
+This struct is meant to be used as a [`QuantityFunction`] trait object.
+
+# Examples
+
+```
+use std::str::FromStr;
+use dyn_quantity::DynQuantity;
+use var_quantity::{QuantityFunction, unary::Logarithmic};
+
+let q = |s: &str| DynQuantity::<f64>::from_str(s).unwrap();
+
+// Sound pressure level relative to 20 µPa in dB: L = 20 * log10(p / 20 µPa)
+let level = Logarithmic::new(q("0"), DynQuantity::from(20.0 / 10f64.ln()), q("20e-6 N/m^2"))
+    .expect("units match");
+approx::assert_abs_diff_eq!(level.call(&[q("2 N/m^2")]).value, 100.0, epsilon = 1e-9);
+
+// Non-positive inputs are outside of the domain
+assert!(level.call(&[q("0 N/m^2")]).value.is_nan());
+assert!(level.try_call(&[q("-1 N/m^2")]).is_err());
+```
+
+# Features:
+This struct can be serialized / deserialized if the `serde` feature is enabled.
+ */
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct Logarithmic {
+    offset: DynQuantity<f64>,
+    slope: DynQuantity<f64>,
+    reference: DynQuantity<f64>,
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    input_name: Option<String>,
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    validity: Option<ValidityRange>,
+}
+
+impl Logarithmic {
+    /**
+    Checks if `offset` and `slope` have the same unit and if `reference` is
+    finite and nonzero. If this is the case, a new instance of [`Logarithmic`]
+    is returned.
+
+    # Examples
+
+    ```
+    use std::str::FromStr;
+    use dyn_quantity::DynQuantity;
+    use var_quantity::unary::Logarithmic;
+
+    let q = |s: &str| DynQuantity::<f64>::from_str(s).unwrap();
+
+    assert!(Logarithmic::new(q("2 V"), q("0.1 V"), q("1 A")).is_ok());
+    assert!(Logarithmic::new(q("2 V"), q("0.1 A"), q("1 A")).is_err());
+    assert!(Logarithmic::new(q("2 V"), q("0.1 V"), q("0 A")).is_err());
+    ```
+    */
+    pub fn new(
+        offset: DynQuantity<f64>,
+        slope: DynQuantity<f64>,
+        reference: DynQuantity<f64>,
+    ) -> Result<Self, ReferenceError> {
+        if offset.unit != slope.unit {
+            return Err(UnitsNotEqual(offset.unit, slope.unit).into());
+        }
+        super::check_reference(&reference)?;
+        return Ok(Self {
+            offset,
+            slope,
+            reference,
+            input_name: None,
+            validity: None,
+        });
+    }
+
+    /**
+    Returns the `offset`.
+    */
+    pub fn offset(&self) -> &DynQuantity<f64> {
+        return &self.offset;
+    }
+
+    /**
+    Returns the `slope`.
+    */
+    pub fn slope(&self) -> &DynQuantity<f64> {
+        return &self.slope;
+    }
+
+    /**
+    Returns the `reference` point.
+    */
+    pub fn reference(&self) -> &DynQuantity<f64> {
+        return &self.reference;
+    }
+
+    /**
+    Returns the unit of the quantity which influences the variable quantity,
+    which is the unit of the reference point. If none of the
+    `influencing_factors` in a [`QuantityFunction::call`] matches this item,
+    then `x` is assumed to be the reference point and the offset is returned.
+
+    # Examples
+
+    ```
+    use std::str::FromStr;
+    use dyn_quantity::DynQuantity;
+    use var_quantity::{QuantityFunction, unary::Logarithmic};
+
+    let q = |s: &str| DynQuantity::<f64>::from_str(s).unwrap();
+
+    let fun = Logarithmic::new(q("2 V"), q("0.1 V"), q("1 A")).expect("units match");
+    assert_eq!(fun.influencing_factor_unit(), q("1 A").unit);
+    assert_eq!(fun.call(&[q("1 K")]), q("2 V"));
+    ```
+    */
+    pub fn influencing_factor_unit(&self) -> Unit {
+        return self.reference.unit;
+    }
+
+    /**
+    Returns the unit which will be returned from [`QuantityFunction::call`].
+
+    ```
+    use std::str::FromStr;
+    use dyn_quantity::DynQuantity;
+    use var_quantity::{QuantityFunction, unary::Logarithmic};
+
+    let q = |s: &str| DynQuantity::<f64>::from_str(s).unwrap();
+
+    let fun = Logarithmic::new(q("2 V"), q("0.1 V"), q("1 A")).expect("units match");
+    assert_eq!(fun.output_unit(), q("1 V").unit);
+    ```
+     */
+    pub fn output_unit(&self) -> Unit {
+        return self.offset.unit;
+    }
+
+    /**
+    Returns the value of the function for `x` or `None` if `x` is outside of
+    the domain.
+     */
+    fn evaluate(&self, x: f64) -> Option<f64> {
+        let ratio = x / self.reference.value;
+        if ratio <= 0.0 {
+            return None;
+        }
+        return Some(self.offset.value + self.slope.value * ratio.ln());
+    }
+
+    /// Error for an `input` outside of the domain.
+    fn out_of_domain(&self, input: DynQuantity<f64>) -> EvaluationError {
+        let unit = self.influencing_factor_unit();
+        let bound = DynQuantity::new(f64::INFINITY.copysign(self.reference.value), unit);
+        let zero = DynQuantity::new(0.0, unit);
+        let (lower, upper) = if bound.value > 0.0 {
+            (zero, bound)
+        } else {
+            (bound, zero)
+        };
+        return EvaluationError::InputOutOfRange {
+            input,
+            lower,
+            upper,
+        };
+    }
+}
+
+impl_input_name!(Logarithmic);
+impl_validity!(Logarithmic);
+
+#[cfg_attr(feature = "serde", typetag::serde)]
+impl QuantityFunction for Logarithmic {
+    fn call(&self, influencing_factors: &[DynQuantity<f64>]) -> DynQuantity<f64> {
+        return filter_unary_function(
+            influencing_factors,
+            self.influencing_factor_unit(),
+            |input| {
+                let value = self.evaluate(input.value).unwrap_or(f64::NAN);
+                return DynQuantity::new(value, self.output_unit());
+            },
+            || self.offset,
+        );
+    }
+
+    fn call_batch(&self, inputs: &[BatchInput], output: &mut [f64]) {
+        filter_unary_batch(
+            inputs,
+            self.influencing_factor_unit(),
+            output,
+            |input| self.evaluate(input).unwrap_or(f64::NAN),
+            || self.offset.value,
+        );
+    }
+
+    fn bind(&self, layout: &Layout) -> Box<dyn Evaluator + '_> {
+        return bind_unary(
+            layout,
+            self.influencing_factor_unit(),
+            self.input_name.as_deref(),
+            |input| self.evaluate(input).unwrap_or(f64::NAN),
+            || self.offset.value,
+        );
+    }
+
+    fn output_unit(&self) -> Unit {
+        return Logarithmic::output_unit(self);
+    }
+
+    fn influencing_units(&self) -> Option<Vec<Unit>> {
+        return Some(vec![self.influencing_factor_unit()]);
+    }
+
+    fn validity_ranges(&self) -> Vec<ValidityRange> {
+        return super::bind_validity_ranges(
+            self.validity.iter().cloned(),
+            self.input_name.as_deref(),
+        );
+    }
+
+    fn call_named(&self, influencing_factors: &[InfluencingFactor]) -> DynQuantity<f64> {
+        let selected = select_influencing_factor(
+            influencing_factors,
+            self.input_name.as_deref(),
+            self.influencing_factor_unit(),
+        );
+        return self.call(selected.as_slice());
+    }
+
+    fn try_call(
+        &self,
+        influencing_factors: &[DynQuantity<f64>],
+    ) -> Result<DynQuantity<f64>, EvaluationError> {
+        let input_unit = self.influencing_factor_unit();
+        let Some(input) = influencing_factors.iter().find(|f| f.unit == input_unit) else {
+            return Ok(self.offset);
+        };
+        match self.evaluate(input.value) {
+            Some(value) => return Ok(DynQuantity::new(value, self.output_unit())),
+            None => return Err(self.out_of_domain(*input)),
+        }
+    }
+
+    fn try_call_named(
+        &self,
+        influencing_factors: &[InfluencingFactor],
+    ) -> Result<DynQuantity<f64>, EvaluationError> {
+        let selected = select_influencing_factor(
+            influencing_factors,
+            self.input_name.as_deref(),
+            self.influencing_factor_unit(),
+        );
+        return self.try_call(selected.as_slice());
+    }
+
+    fn derivative(
+        &self,
+        influencing_factors: &[DynQuantity<f64>],
+        wrt: Unit,
+    ) -> Option<DynQuantity<f64>> {
+        return filter_unary_derivative(
+            influencing_factors,
+            self.influencing_factor_unit(),
+            self.output_unit(),
+            wrt,
+            |x| match self.evaluate(x) {
+                Some(_) => self.slope.value / x,
+                None => f64::NAN,
+            },
+        );
+    }
+}
+
+#[cfg(feature = "serde")]
+mod serde_impl {
+    use super::*;
+
+    use serde::de::{Deserialize, Deserializer};
+
+    impl<'de> Deserialize<'de> for Logarithmic {
+        fn deserialize<D>(deserializer: D) -> Result<Logarithmic, D::Error>
+        where
+            D: Deserializer<'de>,
+        {
+            #[derive(serde::Deserialize)]
+            struct LogarithmicAlias {
+                offset: DynQuantity<f64>,
+                slope: DynQuantity<f64>,
+                reference: DynQuantity<f64>,
+                #[serde(default)]
+                input_name: Option<String>,
+                #[serde(default)]
+                validity: Option<ValidityRange>,
+            }
+
+            let alias = LogarithmicAlias::deserialize(deserializer)?;
+            let mut logarithmic = Self::new(alias.offset, alias.slope, alias.reference)
+                .map_err(serde::de::Error::custom)?;
+            logarithmic.input_name = alias.input_name;
+            if let Some(range) = alias.validity {
+                logarithmic = logarithmic
+                    .with_validity(range)
+                    .map_err(serde::de::Error::custom)?;
+            }
+            return Ok(logarithmic);
+        }
+    }
+}
+
+// =============================================================================
+
+#[cfg(feature = "serde")]
+#[cfg_attr(feature = "serde", typetag::serde)]
+impl QuantityFunction for crate::ClampedQuantity<Logarithmic> {
+    fn call(&self, influencing_factors: &[DynQuantity<f64>]) -> DynQuantity<f64> {
+        return self.call_clamped(influencing_factors);
+    }
+
+    fn output_unit(&self) -> Unit {
+        return self.inner().output_unit();
+    }
+
+    fn influencing_units(&self) -> Option<Vec<Unit>> {
+        return self.inner().influencing_units();
+    }
+
+    fn validity_ranges(&self) -> Vec<ValidityRange> {
+        return self.inner().validity_ranges();
+    }
+
+    fn call_named(&self, influencing_factors: &[InfluencingFactor]) -> DynQuantity<f64> {
+        return self.call_named_clamped(influencing_factors);
+    }
+
+    fn try_call(
+        &self,
+        influencing_factors: &[DynQuantity<f64>],
+    ) -> Result<DynQuantity<f64>, EvaluationError> {
+        return self.try_call_clamped(influencing_factors);
+    }
+
+    fn try_call_named(
+        &self,
+        influencing_factors: &[InfluencingFactor],
+    ) -> Result<DynQuantity<f64>, EvaluationError> {
+        return self.try_call_named_clamped(influencing_factors);
+    }
+
+    fn derivative(
+        &self,
+        influencing_factors: &[DynQuantity<f64>],
+        wrt: Unit,
+    ) -> Option<DynQuantity<f64>> {
+        return self.derivative_clamped(influencing_factors, wrt);
+    }
+
+    fn call_dual(&self, influencing_factors: &[crate::DualQuantity]) -> crate::DualQuantity {
+        return self.call_dual_clamped(influencing_factors);
+    }
+
+    fn call_batch(&self, inputs: &[crate::BatchInput], output: &mut [f64]) {
+        self.call_batch_clamped(inputs, output);
+    }
+
+    fn bind(&self, layout: &crate::Layout) -> Box<dyn crate::Evaluator + '_> {
+        return self.bind_clamped(layout);
+    }
+}
@@ -0,0 +1,335 @@
+/*!
+An unary [`Lookup`] function (piecewise-linear interpolation) which implements
+[`QuantityFunction`](crate::QuantityFunction).
+*/
+
+use dyn_quantity::{DynQuantity, Unit};
+
+pub use super::support_points::Extrapolation;
+use super::support_points::{SupportPoints, impl_support_points_function, zip_columns};
+use crate::{SupportPointsError, validity::ValidityRange};
+
+/**
+A lookup table defined via support points `(x_i, y_i)` which is evaluated by
+piecewise-linear interpolation:
+
+`y = y_i + (y_(i+1) - y_i) / (x_(i+1) - x_i) * (x - x_i)` for `x_i <= x <= x_(i+1)`.
+
+This struct is meant to be used as a
+[`QuantityFunction`](crate::QuantityFunction) trait object, e.g. for measured
+material data such as B-H curves or the resistivity of a conductor over
+temperature. The unit of the influencing quantity is that of the `x_i` and
+the output unit is that of the `y_i`. All `x_i` and all `y_i` must have the
+same units respectively and the `x_i` must be strictly increasing; this is
+checked in the constructor [`Lookup::new`]. The interval containing `x` is
+found via binary search.
+
+The behaviour for inputs outside of the range covered by the support points
+is defined by the [`Extrapolation`] setting.
+
+# Examples
+
+```
+use std::str::FromStr;
+use dyn_quantity::DynQuantity;
+use var_quantity::{QuantityFunction, unary::{Extrapolation, Lookup}};
+
+let q = |s: &str| DynQuantity::<f64>::from_str(s).unwrap();
+
+let lookup = Lookup::new(
+    vec![(q("200 K"), q("1 ohm")), (q("300 K"), q("2 ohm")), (q("400 K"), q("4 ohm"))],
+    Extrapolation::Hold,
+).expect("valid support points");
+
+assert_eq!(lookup.call(&[q("250 K")]).value, 1.5);
+assert_eq!(lookup.call(&[q("350 K")]).value, 3.0);
+
+// Hold the last value
+assert_eq!(lookup.call(&[q("500 K")]).value, 4.0);
+```
+
+# Features:
+This struct can be serialized / deserialized if the `serde` feature is enabled.
+Besides the list of `points` (each point is a list of two quantities), the
+deserializer also accepts a compact two-column format where the inputs and
+outputs are given as separate lists `x` and `y`:
+
+```
+use indoc::indoc;
+use dyn_quantity::{DynQuantity, PredefUnit};
+use var_quantity::{QuantityFunction, unary::Lookup};
+
+let yaml = indoc! {"
+    ---
+    x: [200 K, 300 K, 400 K]
+    y: [1 ohm, 2 ohm, 4 ohm]
+    extrapolation: Linear
+    "};
+let lookup: Lookup = serde_yaml::from_str(yaml).expect("valid support points");
+assert_eq!(lookup.call(&[DynQuantity::new(100.0, PredefUnit::Temperature)]).value, 0.0);
+```
+*/
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct Lookup {
+    points: Vec<(DynQuantity<f64>, DynQuantity<f64>)>,
+    extrapolation: Extrapolation,
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    input_name: Option<String>,
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    validity: Option<ValidityRange>,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    support_points: SupportPoints,
+}
+
+impl Lookup {
+    /**
+    Checks if at least two support points are given, if the units of all inputs
+    and outputs are identical respectively, if all values are finite and if
+    the inputs are strictly increasing. If this is the case, a new instance of [`Lookup`] is returned.
+
+    # Examples
+
+    ```
+    use std::str::FromStr;
+    use dyn_quantity::DynQuantity;
+    use var_quantity::{SupportPointsError, unary::{Extrapolation, Lookup}};
+
+    let q = |s: &str| DynQuantity::<f64>::from_str(s).unwrap();
+
+    assert!(Lookup::new(
+        vec![(q("200 K"), q("1 ohm")), (q("300 K"), q("2 ohm"))],
+        Extrapolation::Hold,
+    ).is_ok());
+
+    // Unit mismatch of the second output
+    assert!(Lookup::new(
+        vec![(q("200 K"), q("1 ohm")), (q("300 K"), q("2 A"))],
+        Extrapolation::Hold,
+    ).is_err());
+
+    // Inputs are not strictly increasing
+    assert_eq!(
+        Lookup::new(
+            vec![(q("200 K"), q("1 ohm")), (q("200 K"), q("2 ohm"))],
+            Extrapolation::Hold,
+        ),
+        Err(SupportPointsError::NotStrictlyIncreasing(1))
+    );
+
+    // Not enough points
+    assert!(Lookup::new(vec![(q("200 K"), q("1 ohm"))], Extrapolation::Hold).is_err());
+    ```
+    */
+    pub fn new(
+        points: Vec<(DynQuantity<f64>, DynQuantity<f64>)>,
+        extrapolation: Extrapolation,
+    ) -> Result<Self, SupportPointsError> {
+        let support_points = SupportPoints::new(&points, 2)?;
+        return Ok(Self {
+            points,
+            extrapolation,
+            input_name: None,
+            validity: None,
+            support_points,
+        });
+    }
+
+    /**
+    Creates a new instance of [`Lookup`] from separate columns of inputs `x`
+    and outputs `y`. Both columns must have the same length, afterwards the
+    same checks as in [`Lookup::new`] are performed.
+
+    # Examples
+
+    ```
+    use std::str::FromStr;
+    use dyn_quantity::DynQuantity;
+    use var_quantity::{QuantityFunction, unary::{Extrapolation, Lookup}};
+
+    let q = |s: &str| DynQuantity::<f64>::from_str(s).unwrap();
+
+    let lookup = Lookup::from_columns(
+        vec![q("0 A/m"), q("100 A/m"), q("200 A/m")],
+        vec![q("0 T"), q("1 T"), q("1.5 T")],
+        Extrapolation::Linear,
+    ).expect("valid support points");
+    assert_eq!(lookup.call(&[q("300 A/m")]).value, 2.0);
+
+    // Length mismatch
+    assert!(Lookup::from_columns(
+        vec![q("0 A/m"), q("100 A/m")],
+        vec![q("0 T")],
+        Extrapolation::Linear,
+    ).is_err());
+    ```
+    */
+    pub fn from_columns(
+        x: Vec<DynQuantity<f64>>,
+        y: Vec<DynQuantity<f64>>,
+        extrapolation: Extrapolation,
+    ) -> Result<Self, SupportPointsError> {
+        return Self::new(zip_columns(x, y)?, extrapolation);
+    }
+
+    /**
+    Returns the support points.
+    */
+    pub fn points(&self) -> &[(DynQuantity<f64>, DynQuantity<f64>)] {
+        return self.points.as_slice();
+    }
+
+    /**
+    Returns the [`Extrapolation`] behaviour.
+    */
+    pub fn extrapolation(&self) -> Extrapolation {
+        return self.extrapolation;
+    }
+
+    /**
+    Returns the unit of the quantity which influences the variable quantity.
+    If none of the `influencing_factors` in a
+    [`QuantityFunction::call`](crate::QuantityFunction::call) matches this
+    item, then `x` is assumed to be zero.
+
+    # Examples
+
+    ```
+    use std::str::FromStr;
+    use dyn_quantity::{DynQuantity, PredefUnit, Unit};
+    use var_quantity::unary::{Extrapolation, Lookup};
+
+    let q = |s: &str| DynQuantity::<f64>::from_str(s).unwrap();
+
+    let lookup = Lookup::new(
+        vec![(q("200 K"), q("1 ohm")), (q("300 K"), q("2 ohm"))],
+        Extrapolation::Hold,
+    ).expect("valid support points");
+    assert_eq!(lookup.influencing_factor_unit(), Unit::from(PredefUnit::Temperature));
+    ```
+    */
+    pub fn influencing_factor_unit(&self) -> Unit {
+        return self.support_points.input_unit;
+    }
+
+    /**
+    Returns the unit which will be returned from
+    [`QuantityFunction::call`](crate::QuantityFunction::call).
+
+    ```
+    use std::str::FromStr;
+    use dyn_quantity::{DynQuantity, PredefUnit, Unit};
+    use var_quantity::unary::{Extrapolation, Lookup};
+
+    let q = |s: &str| DynQuantity::<f64>::from_str(s).unwrap();
+
+    let lookup = Lookup::new(
+        vec![(q("200 K"), q("1 ohm")), (q("300 K"), q("2 ohm"))],
+        Extrapolation::Hold,
+    ).expect("valid support points");
+    assert_eq!(lookup.output_unit(), Unit::from(PredefUnit::ElectricResistance));
+    ```
+     */
+    pub fn output_unit(&self) -> Unit {
+        return self.support_points.output_unit;
+    }
+
+    /**
+    Interpolates the support points at `x`. Returns `None` if `x` is outside
+    of the support points and extrapolation is forbidden.
+     */
+    fn interpolate(&self, x: f64) -> Option<f64> {
+        let sp = &self.support_points;
+        if x.is_nan() {
+            return Some(f64::NAN);
+        }
+        if !sp.contains(x) {
+            match self.extrapolation {
+                Extrapolation::Hold => {
+                    if x < sp.x[0] {
+                        return Some(sp.y[0]);
+                    } else {
+                        return Some(sp.y[sp.y.len() - 1]);
+                    }
+                }
+                Extrapolation::Linear => (),
+                Extrapolation::Error => return None,
+            }
+        }
+        let i = sp.segment(x);
+        let slope = (sp.y[i + 1] - sp.y[i]) / (sp.x[i + 1] - sp.x[i]);
+        return Some(sp.y[i] + slope * (x - sp.x[i]));
+    }
+
+    /**
+    Returns the slope of the interpolation at `x`. At a support point, the
+    slope of the segment to the right is used (except for the last support
+    point). Returns `None` if `x` is outside of the support points and
+    extrapolation is forbidden.
+     */
+    fn slope(&self, x: f64) -> Option<f64> {
+        let sp = &self.support_points;
+        if x.is_nan() {
+            return Some(f64::NAN);
+        }
+        if !sp.contains(x) {
+            match self.extrapolation {
+                Extrapolation::Hold => return Some(0.0),
+                Extrapolation::Linear => (),
+                Extrapolation::Error => return None,
+            }
+        }
+        let i = sp.segment(x);
+        return Some((sp.y[i + 1] - sp.y[i]) / (sp.x[i + 1] - sp.x[i]));
+    }
+}
+
+impl_input_name!(Lookup);
+impl_validity!(Lookup);
+
+impl_support_points_function!(Lookup);
+
+#[cfg(feature = "serde")]
+mod serde_impl {
+    use super::*;
+
+    use serde::de::{Deserialize, Deserializer};
+
+    use crate::unary::support_points::PointsAlias;
+
+    impl<'de> Deserialize<'de> for Lookup {
+        fn deserialize<D>(deserializer: D) -> Result<Lookup, D::Error>
+        where
+            D: Deserializer<'de>,
+        {
+            #[derive(serde::Deserialize)]
+            struct LookupAlias {
+                #[serde(flatten)]
+                points: PointsAlias,
+                #[serde(default)]
+                extrapolation: Extrapolation,
+                #[serde(default)]
+                input_name: Option<String>,
+                #[serde(default)]
+                validity: Option<ValidityRange>,
+            }
+
+            let alias = LookupAlias::deserialize(deserializer)?;
+            let mut lookup = Self::new(alias.points.into_points()?, alias.extrapolation)
+                .map_err(serde::de::Error::custom)?;
+            lookup.input_name = alias.input_name;
+            if let Some(range) = alias.validity {
+                lookup = lookup
+                    .with_validity(range)
+                    .map_err(serde::de::Error::custom)?;
+            }
+            return Ok(lookup);
+        }
+    }
+}
@@ -1,13 +1,214 @@
 /*!
 This module contains unary functions which implement [`QuantityFunction`](crate::QuantityFunction).
+
+# Named influencing factors
+
+All unary functions of this module can be bound to an influencing factor with
+a given name via their `with_input_name` method (e.g.
+[`Linear::with_input_name`]). When evaluated via
+[`QuantityFunction::call_named`](crate::QuantityFunction::call_named), only the
+factor with this name and the unit of the function input (see e.g.
+[`Linear::influencing_factor_unit`]) is used. If no such factor exists, the
+function falls back to its default input, just like
+[`QuantityFunction::call`](crate::QuantityFunction::call) without a matching
+factor. The name is ignored by
+[`QuantityFunction::call`](crate::QuantityFunction::call), which still
+selects the first factor with a matching unit.
+
+```
+use std::str::FromStr;
+use dyn_quantity::DynQuantity;
+use var_quantity::{InfluencingFactor, QuantityFunction, unary::Linear};
+
+let fun = Linear::new(
+    DynQuantity::from_str("0.5 ohm/K").unwrap(),
+    DynQuantity::from_str("1 ohm").unwrap(),
+).with_input_name("winding_temperature");
+
+let factors = [
+    InfluencingFactor::new("ambient_temperature", DynQuantity::from_str("20 K").unwrap()),
+    InfluencingFactor::new("winding_temperature", DynQuantity::from_str("40 K").unwrap()),
+];
+assert_eq!(fun.input_name(), Some("winding_temperature"));
+assert_eq!(fun.call_named(&factors), fun.call(&[factors[1].quantity]));
+
+// The unnamed call simply uses the first temperature
+assert_eq!(fun.call(&[factors[0].quantity]).value, 11.0);
+```
+
+# Validity ranges
+
+All unary functions of this module can store the range of their influencing
+factor for which they are valid (e.g. the range covered by the data they have
+been fitted to) via their `with_validity` method (e.g.
+[`Linear::with_validity`]). The range must have the unit of the function
+input. The functions themselves do not enforce the range, they only report it
+via [`QuantityFunction::validity_ranges`](crate::QuantityFunction::validity_ranges)
+(bound to their input name, if any). A [`FunctionWrapper`](crate::FunctionWrapper)
+then handles out-of-range inputs according to its
+[`ValidityPolicy`](crate::validity::ValidityPolicy), see the
+[`validity`](crate::validity) module.
+
+```
+use std::str::FromStr;
+use dyn_quantity::DynQuantity;
+use var_quantity::{QuantityFunction, unary::FirstOrderTaylor, validity::ValidityRange};
+
+let q = |s: &str| DynQuantity::<f64>::from_str(s).unwrap();
+
+// Resistivity of copper, fitted between 200 K and 500 K
+let range = ValidityRange::new(q("200 K"), q("500 K")).expect("valid range");
+let fun = FirstOrderTaylor::new(q("1.68e-8 ohm*m"), q("3.9e-3 1/K"), q("293.15 K"))
+    .expect("units match")
+    .with_validity(range.clone())
+    .expect("units match");
+assert_eq!(fun.validity(), Some(&range));
+assert_eq!(fun.validity_ranges(), vec![range]);
+
+// The range must have the unit of the influencing factor
+let range = ValidityRange::new(q("0 A"), q("10 A")).expect("valid range");
+assert!(fun.with_validity(range).is_err());
+```
 */
 
+use dyn_quantity::DynQuantity;
+
+use crate::{ReferenceError, validity::ValidityRange};
+
+/**
+Implements the `with_input_name` and `input_name` methods for a unary function
+type with an `input_name: Option<String>` field, see the
+[module documentation](self#named-influencing-factors).
+ */
+macro_rules! impl_input_name {
+    ($type:ident) => {
+        impl $type {
+            #[doc = concat!(
+                        "Binds the function to the influencing factor with the given name, see ",
+                        "[named influencing factors](crate::unary#named-influencing-factors). ",
+                        "The unit of the factor must be [`",
+                        stringify!($type),
+                        "::influencing_factor_unit`]."
+                    )]
+            pub fn with_input_name<N: Into<String>>(mut self, name: N) -> Self {
+                self.input_name = Some(name.into());
+                return self;
+            }
+
+            #[doc = concat!(
+                        "Returns the name of the influencing factor this function is bound to, ",
+                        "if any. See [`",
+                        stringify!($type),
+                        "::with_input_name`]."
+                    )]
+            pub fn input_name(&self) -> Option<&str> {
+                return self.input_name.as_deref();
+            }
+        }
+    };
+}
+
+/**
+Implements the `with_validity` and `validity` methods for a unary function type
+with a `validity: Option<ValidityRange>` field, see the
+[module documentation](self#validity-ranges).
+ */
+macro_rules! impl_validity {
+    ($type:ident) => {
+        impl $type {
+            #[doc = concat!(
+                        "Sets the range of the influencing factor for which the function is valid, see ",
+                        "[validity ranges](crate::unary#validity-ranges). ",
+                        "Fails if the unit of the range is not [`",
+                        stringify!($type),
+                        "::influencing_factor_unit`]."
+                    )]
+            pub fn with_validity(
+                mut self,
+                range: crate::validity::ValidityRange,
+            ) -> Result<Self, ::dyn_quantity::UnitsNotEqual> {
+                if range.unit() != self.influencing_factor_unit() {
+                    return Err(::dyn_quantity::UnitsNotEqual(
+                        self.influencing_factor_unit(),
+                        range.unit(),
+                    ));
+                }
+                self.validity = Some(range);
+                return Ok(self);
+            }
+
+            #[doc = concat!(
+                        "Returns the range of the influencing factor for which the function is valid, ",
+                        "if any. See [`",
+                        stringify!($type),
+                        "::with_validity`]."
+                    )]
+            pub fn validity(&self) -> Option<&crate::validity::ValidityRange> {
+                return self.validity.as_ref();
+            }
+        }
+    };
+}
+
+/**
+Binds the `ranges` of a unary function to its `input_name` (if any), which is
+the implementation of [`QuantityFunction::validity_ranges`](crate::QuantityFunction::validity_ranges)
+shared by all types of this module.
+ */
+fn bind_validity_ranges<I>(ranges: I, input_name: Option<&str>) -> Vec<ValidityRange>
+where
+    I: IntoIterator<Item = ValidityRange>,
+{
+    return ranges
+        .into_iter()
+        .map(|range| match input_name {
+            Some(name) => range.with_input_name(name),
+            None => range,
+        })
+        .collect();
+}
+
+/**
+Checks if the `reference` point of a function, which is used as the
+denominator of the ratio `x / reference`, is finite and nonzero.
+ */
+fn check_reference(reference: &DynQuantity<f64>) -> Result<(), ReferenceError> {
+    if reference.value == 0.0 || !reference.value.is_finite() {
+        return Err(ReferenceError::InvalidReference(*reference));
+    }
+    return Ok(());
+}
+
+pub mod arctan_saturation;
+pub mod arrhenius;
+pub mod callendar_van_dusen;
 pub mod exponential;
 pub mod first_order_taylor;
+pub mod frohlich;
+pub mod inverse_exponential;
+pub mod langevin;
 pub mod linear;
+pub mod logarithmic;
+pub mod lookup;
 pub mod polynomial;
+pub mod power_law;
+mod saturation;
+pub mod spline;
+pub mod steinhart_hart;
+mod support_points;
 
+pub use arctan_saturation::ArctanSaturation;
+pub use arrhenius::Arrhenius;
+pub use callendar_van_dusen::CallendarVanDusen;
 pub use exponential::{ExpTerm, Exponential};
 pub use first_order_taylor::FirstOrderTaylor;
+pub use frohlich::{Frohlich, SaturationOutput};
+pub use inverse_exponential::InverseExponential;
+pub use langevin::Langevin;
 pub use linear::Linear;
+pub use logarithmic::Logarithmic;
+pub use lookup::{Extrapolation, Lookup};
 pub use polynomial::Polynomial;
+pub use power_law::{PowerLaw, PowerTerm};
+pub use spline::{Spline, SplineKind};
+pub use steinhart_hart::SteinhartHart;
@@ -4,7 +4,11 @@ An unary [`Polynomial`] function which implements [`QuantityFunction`].
 
 use dyn_quantity::{DynQuantity, Unit, UnitsNotEqual};
 
-use crate::{QuantityFunction, filter_unary_function};
+use crate::{
+    BatchInput, Evaluator, InfluencingFactor, Layout, QuantityFunction, bind_unary,
+    filter_unary_batch, filter_unary_derivative, filter_unary_function, select_influencing_factor,
+    validity::ValidityRange,
+};
 
 /**
 A polynom defined via its coefficients:
@@ -36,6 +40,16 @@ pub struct Polynomial {
     coefficients_val: Vec<f64>,
     #[cfg_attr(feature = "serde", serde(skip_serializing))]
     default_value: DynQuantity<f64>,
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    input_name: Option<String>,
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    validity: Option<ValidityRange>,
 }
 
 impl Polynomial {
@@ -105,6 +119,8 @@ impl Polynomial {
             influencing_factor_unit,
             coefficients_val,
             default_value,
+            input_name: None,
+            validity: None,
         });
     }
 
@@ -174,6 +190,9 @@ impl Polynomial {
     }
 }
 
+impl_input_name!(Polynomial);
+impl_validity!(Polynomial);
+
 #[cfg_attr(feature = "serde", typetag::serde)]
 impl QuantityFunction for Polynomial {
     fn call(&self, influencing_factors: &[DynQuantity<f64>]) -> DynQuantity<f64> {
@@ -188,6 +207,76 @@ impl QuantityFunction for Polynomial {
             || self.default_value,
         );
     }
+
+    fn call_batch(&self, inputs: &[BatchInput], output: &mut [f64]) {
+        filter_unary_batch(
+            inputs,
+            self.influencing_factor_unit,
+            output,
+            |input| horner::eval_polynomial(input, self.coefficients_val.as_slice()).unwrap(),
+            || self.default_value.value,
+        );
+    }
+
+    fn bind(&self, layout: &Layout) -> Box<dyn Evaluator + '_> {
+        return bind_unary(
+            layout,
+            self.influencing_factor_unit,
+            self.input_name.as_deref(),
+            |input| horner::eval_polynomial(input, self.coefficients_val.as_slice()).unwrap(),
+            || self.default_value.value,
+        );
+    }
+
+    fn output_unit(&self) -> Unit {
+        return Polynomial::output_unit(self);
+    }
+
+    fn influencing_units(&self) -> Option<Vec<Unit>> {
+        // A polynomial with less than two coefficients is a constant
+        if self.coefficients.len() < 2 {
+            return Some(Vec::new());
+        }
+        return Some(vec![self.influencing_factor_unit]);
+    }
+
+    fn validity_ranges(&self) -> Vec<ValidityRange> {
+        return super::bind_validity_ranges(
+            self.validity.iter().cloned(),
+            self.input_name.as_deref(),
+        );
+    }
+
+    fn call_named(&self, influencing_factors: &[InfluencingFactor]) -> DynQuantity<f64> {
+        let selected = select_influencing_factor(
+            influencing_factors,
+            self.input_name.as_deref(),
+            self.influencing_factor_unit(),
+        );
+        return self.call(selected.as_slice());
+    }
+
+    fn derivative(
+        &self,
+        influencing_factors: &[DynQuantity<f64>],
+        wrt: Unit,
+    ) -> Option<DynQuantity<f64>> {
+        // Horner scheme for the derivative [(N-1)a, (N-2)b, ...] of [a, b, ...]
+        let n = self.coefficients_val.len();
+        return filter_unary_derivative(
+            influencing_factors,
+            self.influencing_factor_unit,
+            self.output_unit(),
+            wrt,
+            |x| {
+                self.coefficients_val
+                    .iter()
+                    .take(n.saturating_sub(1))
+                    .enumerate()
+                    .fold(0.0, |acc, (i, c)| acc * x + c * (n - 1 - i) as f64)
+            },
+        );
+    }
 }
 
 #[cfg(feature = "serde")]
@@ -204,10 +293,21 @@ mod serde_impl {
             #[derive(serde::Deserialize)]
             struct PolynomialAlias {
                 coefficients: Vec<DynQuantity<f64>>,
+                #[serde(default)]
+                input_name: Option<String>,
+                #[serde(default)]
+                validity: Option<ValidityRange>,
             }
 
             let alias = PolynomialAlias::deserialize(deserializer)?;
-            Self::new(alias.coefficients).map_err(serde::de::Error::custom)
+            let mut polynomial = Self::new(alias.coefficients).map_err(serde::de::Error::custom)?;
+            polynomial.input_name = alias.input_name;
+            if let Some(range) = alias.validity {
+                polynomial = polynomial
+                    .with_validity(range)
+                    .map_err(serde::de::Error::custom)?;
+            }
+            return Ok(polynomial);
         }
     }
 }
@@ -220,4 +320,40 @@ impl QuantityFunction for crate::ClampedQuantity<Polynomial> {
     fn call(&self, influencing_factors: &[DynQuantity<f64>]) -> DynQuantity<f64> {
         return self.call_clamped(influencing_factors);
     }
+
+    fn output_unit(&self) -> Unit {
+        return self.inner().output_unit();
+    }
+
+    fn influencing_units(&self) -> Option<Vec<Unit>> {
+        return self.inner().influencing_units();
+    }
+
+    fn validity_ranges(&self) -> Vec<ValidityRange> {
+        return self.inner().validity_ranges();
+    }
+
+    fn call_named(&self, influencing_factors: &[InfluencingFactor]) -> DynQuantity<f64> {
+        return self.call_named_clamped(influencing_factors);
+    }
+
+    fn derivative(
+        &self,
+        influencing_factors: &[DynQuantity<f64>],
+        wrt: Unit,
+    ) -> Option<DynQuantity<f64>> {
+        return self.derivative_clamped(influencing_factors, wrt);
+    }
+
+    fn call_dual(&self, influencing_factors: &[crate::DualQuantity]) -> crate::DualQuantity {
+        return self.call_dual_clamped(influencing_factors);
+    }
+
+    fn call_batch(&self, inputs: &[crate::BatchInput], output: &mut [f64]) {
+        self.call_batch_clamped(inputs, output);
+    }
+
+    fn bind(&self, layout: &crate::Layout) -> Box<dyn crate::Evaluator + '_> {
+        return self.bind_clamped(layout);
+    }
 }
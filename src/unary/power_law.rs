@@ -0,0 +1,421 @@
+/*!
+An unary [`PowerLaw`] function which implements [`QuantityFunction`].
+*/
+
+use dyn_quantity::{DynQuantity, Unit, UnitsNotEqual};
+
+use crate::{
+    BatchInput, Evaluator, InfluencingFactor, Layout, QuantityFunction, ReferenceError, bind_unary,
+    filter_unary_batch, filter_unary_derivative, filter_unary_function, select_influencing_factor,
+    validity::ValidityRange,
+};
+
+/**
+A power term `coefficient * (x / x0)^exponent` which is used to build a
+[`PowerLaw`] function. The exponent is a real number, since `x / x0` is
+dimensionless.
+ */
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PowerTerm {
+    /// Coefficient of the term.
+    pub coefficient: DynQuantity<f64>,
+    /// Exponent of the term.
+    pub exponent: f64,
+}
+
+/**
+A power law function defined as a sum of [`PowerTerm`]:
+
+`y = ∑ a_n * (x / x0)^k_n`
+
+where `a` is [`PowerTerm::coefficient`], `k` is [`PowerTerm::exponent`] of the
+respective [`PowerTerm`] and `x0` is the reference point of the function.
+
+Since the reference point carries the unit of the influencing quantity, the
+ratio `x / x0` is dimensionless and the exponents can be arbitrary real
+numbers (in contrast to [`Polynomial`](crate::unary::Polynomial), which only
+supports integer powers). This allows to model e.g. Steinmetz-type iron
+losses, Sutherland-like viscosity fits or the AC resistance factor of
+conductors. Multiple terms can be used for fits which are dominated by
+different exponents in different ranges of `x`.
+
+This struct is meant to be used as a [`QuantityFunction`] trait object. The
+unit of the influencing quantity is that of the reference point and the output
+unit is that of the [`PowerTerm::coefficient`]. This means that all terms must
+have the same unit for the coefficient; this is checked in the constructor
+[`PowerLaw::new`]. For negative ratios `x / x0` and non-integer exponents, the
+function returns NaN. If none of the influencing factors matches the
+[`PowerLaw::influencing_factor_unit`], the function is evaluated at the
+reference point and returns the sum of the coefficients.
+
+# Examples
+
+```
+use std::str::FromStr;
+use dyn_quantity::DynQuantity;
+use var_quantity::{QuantityFunction, unary::PowerLaw};
+
+let q = |s: &str| DynQuantity::<f64>::from_str(s).unwrap();
+
+// Hysteresis losses p = 2 W * (f / 50 Hz)^1.3
+let fun = PowerLaw::simple(q("2 W"), q("50 Hz"), 1.3).expect("valid reference point");
+assert_eq!(fun.call(&[q("50 Hz")]), q("2 W"));
+approx::assert_abs_diff_eq!(fun.call(&[q("100 Hz")]).value, 4.9246, epsilon = 1e-4);
+```
+
+# Features:
+This struct can be serialized / deserialized if the `serde` feature is enabled.
+ */
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct PowerLaw {
+    terms: Vec<PowerTerm>,
+    reference: DynQuantity<f64>,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    output_unit: Unit,
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    input_name: Option<String>,
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    validity: Option<ValidityRange>,
+}
+
+impl PowerLaw {
+    /**
+    Checks if the coefficients of all given terms have the same unit and if
+    the reference point `reference` is finite and nonzero. If this is the
+    case, a new instance of [`PowerLaw`] is returned.
+
+    # Examples
+
+    ```
+    use std::str::FromStr;
+    use dyn_quantity::DynQuantity;
+    use var_quantity::{QuantityFunction, unary::{PowerLaw, PowerTerm}};
+
+    let q = |s: &str| DynQuantity::<f64>::from_str(s).unwrap();
+
+    // Hysteresis and eddy current losses
+    let hysteresis = PowerTerm {
+        coefficient: q("2 W"),
+        exponent: 1.3,
+    };
+    let eddy_current = PowerTerm {
+        coefficient: q("1 W"),
+        exponent: 2.0,
+    };
+    let fun = PowerLaw::new(vec![hysteresis, eddy_current], q("50 Hz")).expect("units match");
+    assert_eq!(fun.influencing_factor_unit(), q("1 Hz").unit);
+    assert_eq!(fun.call(&[q("50 Hz")]), q("3 W"));
+
+    // Coefficient mismatch
+    let torque = PowerTerm {
+        coefficient: q("1 Nm"),
+        exponent: 2.0,
+    };
+    assert!(PowerLaw::new(vec![hysteresis, torque], q("50 Hz")).is_err());
+
+    // Invalid reference point
+    assert!(PowerLaw::new(vec![hysteresis], q("0 Hz")).is_err());
+    ```
+    */
+    pub fn new(terms: Vec<PowerTerm>, reference: DynQuantity<f64>) -> Result<Self, ReferenceError> {
+        if let Some(terms) = terms
+            .windows(2)
+            .find(|terms| terms[0].coefficient.unit != terms[1].coefficient.unit)
+        {
+            return Err(UnitsNotEqual(terms[0].coefficient.unit, terms[1].coefficient.unit).into());
+        }
+        super::check_reference(&reference)?;
+        let output_unit = terms
+            .first()
+            .map(|term| term.coefficient.unit)
+            .unwrap_or_default();
+        return Ok(Self {
+            terms,
+            reference,
+            output_unit,
+            input_name: None,
+            validity: None,
+        });
+    }
+
+    /**
+    Returns a power law with a single term `coefficient * (x / reference)^exponent`
+    if `reference` is finite and nonzero.
+
+    # Examples
+
+    ```
+    use std::str::FromStr;
+    use dyn_quantity::DynQuantity;
+    use var_quantity::{QuantityFunction, unary::PowerLaw};
+
+    let q = |s: &str| DynQuantity::<f64>::from_str(s).unwrap();
+
+    // Sutherland-like viscosity fit mu = 1.8e-5 Pa*s * (T / 293 K)^0.7
+    let fun = PowerLaw::simple(q("1.8e-5 N*s/m^2"), q("293 K"), 0.7).expect("valid reference point");
+    assert_eq!(fun.terms().len(), 1);
+    approx::assert_abs_diff_eq!(fun.call(&[q("586 K")]).value, 2.924e-5, epsilon = 1e-8);
+
+    assert!(PowerLaw::simple(q("1.8e-5 N*s/m^2"), q("0 K"), 0.7).is_err());
+    ```
+    */
+    pub fn simple(
+        coefficient: DynQuantity<f64>,
+        reference: DynQuantity<f64>,
+        exponent: f64,
+    ) -> Result<Self, ReferenceError> {
+        let term = PowerTerm {
+            coefficient,
+            exponent,
+        };
+        return Self::new(vec![term], reference);
+    }
+
+    /**
+    Returns a slice of the underlying power terms.
+    */
+    pub fn terms(&self) -> &[PowerTerm] {
+        return &self.terms;
+    }
+
+    /**
+    Returns the reference point `x0`.
+    */
+    pub fn reference(&self) -> &DynQuantity<f64> {
+        return &self.reference;
+    }
+
+    /**
+    Returns the unit of the quantity which influences the variable quantity,
+    which is the unit of the reference point. If none of the
+    `influencing_factors` in a [`QuantityFunction::call`] matches this item,
+    then `x` is assumed to be the reference point and the sum of the
+    coefficients is returned.
+
+    # Examples
+
+    ```
+    use std::str::FromStr;
+    use dyn_quantity::DynQuantity;
+    use var_quantity::{QuantityFunction, unary::PowerLaw};
+
+    let q = |s: &str| DynQuantity::<f64>::from_str(s).unwrap();
+
+    let fun = PowerLaw::simple(q("2 W"), q("50 Hz"), 1.3).expect("valid reference point");
+    assert_eq!(fun.influencing_factor_unit(), q("1 Hz").unit);
+
+    // No match -> x = x0
+    assert_eq!(fun.call(&[q("1 A")]), q("2 W"));
+    ```
+    */
+    pub fn influencing_factor_unit(&self) -> Unit {
+        return self.reference.unit;
+    }
+
+    /**
+    Returns the unit which will be returned from [`QuantityFunction::call`].
+
+    ```
+    use std::str::FromStr;
+    use dyn_quantity::DynQuantity;
+    use var_quantity::{QuantityFunction, unary::PowerLaw};
+
+    let q = |s: &str| DynQuantity::<f64>::from_str(s).unwrap();
+
+    let fun = PowerLaw::simple(q("2 W"), q("50 Hz"), 1.3).expect("valid reference point");
+    assert_eq!(fun.output_unit(), q("1 W").unit);
+    ```
+     */
+    pub fn output_unit(&self) -> Unit {
+        return self.output_unit;
+    }
+
+    /// Evaluates the function for the value of `x`.
+    fn evaluate(&self, input: f64) -> f64 {
+        // Units are already checked during construction - we can simply
+        // calculate with the values directly here
+        let ratio = input / self.reference.value;
+        return self
+            .terms
+            .iter()
+            .map(|t| t.coefficient.value * ratio.powf(t.exponent))
+            .sum::<f64>();
+    }
+
+    /// Returns the value at the reference point, which is used if `x` is missing.
+    fn fallback(&self) -> f64 {
+        return self.terms.iter().map(|t| t.coefficient.value).sum::<f64>();
+    }
+}
+
+impl_input_name!(PowerLaw);
+impl_validity!(PowerLaw);
+
+#[cfg_attr(feature = "serde", typetag::serde)]
+impl QuantityFunction for PowerLaw {
+    fn call(&self, influencing_factors: &[DynQuantity<f64>]) -> DynQuantity<f64> {
+        return filter_unary_function(
+            influencing_factors,
+            self.influencing_factor_unit(),
+            |input| DynQuantity::new(self.evaluate(input.value), self.output_unit),
+            || DynQuantity::new(self.fallback(), self.output_unit),
+        );
+    }
+
+    fn call_batch(&self, inputs: &[BatchInput], output: &mut [f64]) {
+        filter_unary_batch(
+            inputs,
+            self.influencing_factor_unit(),
+            output,
+            |input| self.evaluate(input),
+            || self.fallback(),
+        );
+    }
+
+    fn bind(&self, layout: &Layout) -> Box<dyn Evaluator + '_> {
+        return bind_unary(
+            layout,
+            self.influencing_factor_unit(),
+            self.input_name.as_deref(),
+            |input| self.evaluate(input),
+            || self.fallback(),
+        );
+    }
+
+    fn output_unit(&self) -> Unit {
+        return PowerLaw::output_unit(self);
+    }
+
+    fn influencing_units(&self) -> Option<Vec<Unit>> {
+        if self.terms.is_empty() {
+            return Some(Vec::new());
+        }
+        return Some(vec![self.influencing_factor_unit()]);
+    }
+
+    fn validity_ranges(&self) -> Vec<ValidityRange> {
+        return super::bind_validity_ranges(
+            self.validity.iter().cloned(),
+            self.input_name.as_deref(),
+        );
+    }
+
+    fn call_named(&self, influencing_factors: &[InfluencingFactor]) -> DynQuantity<f64> {
+        let selected = select_influencing_factor(
+            influencing_factors,
+            self.input_name.as_deref(),
+            self.influencing_factor_unit(),
+        );
+        return self.call(selected.as_slice());
+    }
+
+    fn derivative(
+        &self,
+        influencing_factors: &[DynQuantity<f64>],
+        wrt: Unit,
+    ) -> Option<DynQuantity<f64>> {
+        return filter_unary_derivative(
+            influencing_factors,
+            self.influencing_factor_unit(),
+            self.output_unit,
+            wrt,
+            |x| {
+                let ratio = x / self.reference.value;
+                self.terms
+                    .iter()
+                    .map(|t| t.coefficient.value * t.exponent * ratio.powf(t.exponent - 1.0))
+                    .sum::<f64>()
+                    / self.reference.value
+            },
+        );
+    }
+}
+
+#[cfg(feature = "serde")]
+mod serde_impl {
+    use super::*;
+
+    use serde::de::{Deserialize, Deserializer};
+
+    impl<'de> Deserialize<'de> for PowerLaw {
+        fn deserialize<D>(deserializer: D) -> Result<PowerLaw, D::Error>
+        where
+            D: Deserializer<'de>,
+        {
+            #[derive(serde::Deserialize)]
+            struct PowerLawAlias {
+                terms: Vec<PowerTerm>,
+                reference: DynQuantity<f64>,
+                #[serde(default)]
+                input_name: Option<String>,
+                #[serde(default)]
+                validity: Option<ValidityRange>,
+            }
+
+            let alias = PowerLawAlias::deserialize(deserializer)?;
+            let mut power_law =
+                Self::new(alias.terms, alias.reference).map_err(serde::de::Error::custom)?;
+            power_law.input_name = alias.input_name;
+            if let Some(range) = alias.validity {
+                power_law = power_law
+                    .with_validity(range)
+                    .map_err(serde::de::Error::custom)?;
+            }
+            return Ok(power_law);
+        }
+    }
+}
+
+// =============================================================================
+
+#[cfg(feature = "serde")]
+#[cfg_attr(feature = "serde", typetag::serde)]
+impl QuantityFunction for crate::ClampedQuantity<PowerLaw> {
+    fn call(&self, influencing_factors: &[DynQuantity<f64>]) -> DynQuantity<f64> {
+        return self.call_clamped(influencing_factors);
+    }
+
+    fn output_unit(&self) -> Unit {
+        return self.inner().output_unit();
+    }
+
+    fn influencing_units(&self) -> Option<Vec<Unit>> {
+        return self.inner().influencing_units();
+    }
+
+    fn validity_ranges(&self) -> Vec<ValidityRange> {
+        return self.inner().validity_ranges();
+    }
+
+    fn call_named(&self, influencing_factors: &[InfluencingFactor]) -> DynQuantity<f64> {
+        return self.call_named_clamped(influencing_factors);
+    }
+
+    fn derivative(
+        &self,
+        influencing_factors: &[DynQuantity<f64>],
+        wrt: Unit,
+    ) -> Option<DynQuantity<f64>> {
+        return self.derivative_clamped(influencing_factors, wrt);
+    }
+
+    fn call_dual(&self, influencing_factors: &[crate::DualQuantity]) -> crate::DualQuantity {
+        return self.call_dual_clamped(influencing_factors);
+    }
+
+    fn call_batch(&self, inputs: &[crate::BatchInput], output: &mut [f64]) {
+        self.call_batch_clamped(inputs, output);
+    }
+
+    fn bind(&self, layout: &crate::Layout) -> Box<dyn crate::Evaluator + '_> {
+        return self.bind_clamped(layout);
+    }
+}
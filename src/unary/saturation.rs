@@ -0,0 +1,46 @@
+/*!
+Crate-internal helper for the magnetic saturation models
+[`Frohlich`](super::Frohlich), [`Langevin`](super::Langevin) and
+[`ArctanSaturation`](super::ArctanSaturation).
+*/
+
+use dyn_quantity::{PredefUnit, Unit};
+
+/// Vacuum permeability in H/m.
+pub const VACUUM_PERMEABILITY: f64 = 1.25663706212e-6;
+
+/**
+Quantity returned by a magnetic saturation model for a given magnetic field
+strength `H`, see e.g. [`Frohlich`](super::Frohlich).
+
+# Features:
+This enum can be serialized / deserialized if the `serde` feature is enabled.
+ */
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum SaturationOutput {
+    /// The magnetic flux density `B(H)` in T.
+    #[default]
+    FluxDensity,
+    /**
+    The (secant) permeability `μ(H) = B(H) / H` in H/m. For `H = 0`, the
+    initial permeability (the slope of `B(H)` at the origin) is returned.
+     */
+    Permeability,
+}
+
+impl SaturationOutput {
+    /**
+    Returns the unit of the quantity, i.e. [`PredefUnit::MagneticFluxDensity`]
+    or the unit of a permeability.
+     */
+    pub fn unit(&self) -> Unit {
+        match self {
+            SaturationOutput::FluxDensity => return PredefUnit::MagneticFluxDensity.into(),
+            SaturationOutput::Permeability => {
+                return Unit::from(PredefUnit::MagneticFluxDensity)
+                    / PredefUnit::MagneticFieldStrength.into();
+            }
+        }
+    }
+}
@@ -0,0 +1,517 @@
+/*!
+An unary [`Spline`] function (smooth piecewise-cubic interpolation) which
+implements [`QuantityFunction`](crate::QuantityFunction).
+*/
+
+use dyn_quantity::{DynQuantity, Unit};
+
+use super::support_points::{
+    Extrapolation, SupportPoints, impl_support_points_function, zip_columns,
+};
+use crate::{SupportPointsError, validity::ValidityRange};
+
+/**
+Method used by a [`Spline`] to calculate the slopes at the support points.
+
+# Features:
+This enum can be serialized / deserialized if the `serde` feature is enabled.
+ */
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum SplineKind {
+    /**
+    Natural cubic spline: The first and second derivatives are continuous and
+    the second derivative is zero at the first and last support point. May
+    overshoot between the support points.
+     */
+    Natural,
+    /**
+    Piecewise cubic Hermite interpolating polynomial (Fritsch-Carlson): The
+    first derivative is continuous and the interpolation preserves the
+    monotonicity of the support points, i.e. it does not overshoot.
+     */
+    Pchip,
+    /**
+    Akima spline: The first derivative is continuous and the slopes are
+    calculated from the neighbouring segments only, which avoids wiggles
+    caused by outliers.
+     */
+    Akima,
+}
+
+/**
+A smooth interpolation of support points `(x_i, y_i)` with piecewise cubic
+polynomials. In contrast to the piecewise-linear [`Lookup`](super::Lookup),
+the first derivative of the function is continuous, which is e.g. important
+for Newton solvers working with B-H or loss curves. The slopes at the support
+points are calculated according to the [`SplineKind`].
+
+This struct is meant to be used as a
+[`QuantityFunction`](crate::QuantityFunction) trait object. The unit of the
+influencing quantity is that of the `x_i` and the output unit is that of the
+`y_i`. All `x_i` and all `y_i` must have the same units
+respectively and the `x_i` must be strictly increasing; this is checked in the
+constructor [`Spline::new`].
+
+The behaviour for inputs outside of the range covered by the support points
+is defined by the [`Extrapolation`] setting. For [`Extrapolation::Linear`], the
+function is continued with the slope at the first / last support point.
+
+# Examples
+
+```
+use std::str::FromStr;
+use dyn_quantity::DynQuantity;
+use var_quantity::{QuantityFunction, unary::{Extrapolation, Spline, SplineKind}};
+
+let q = |s: &str| DynQuantity::<f64>::from_str(s).unwrap();
+
+let points = vec![
+    (q("0 A/m"), q("0 T")),
+    (q("100 A/m"), q("1 T")),
+    (q("200 A/m"), q("1.5 T")),
+    (q("400 A/m"), q("1.7 T")),
+];
+let spline = Spline::new(points, SplineKind::Pchip, Extrapolation::Hold)
+    .expect("valid support points");
+
+// The support points are interpolated exactly
+assert_eq!(spline.call(&[q("100 A/m")]).value, 1.0);
+
+// Monotone in between
+let y = spline.call(&[q("300 A/m")]).value;
+assert!(y > 1.5 && y < 1.7);
+```
+
+# Features:
+This struct can be serialized / deserialized if the `serde` feature is enabled.
+Only the support points are serialized, the slopes are recalculated during
+deserialization. Like [`Lookup`](super::Lookup), the deserializer accepts either
+a list of `points` or the two columns `x` and `y`:
+
+```
+use indoc::indoc;
+use dyn_quantity::{DynQuantity, PredefUnit};
+use var_quantity::{QuantityFunction, unary::Spline};
+
+let yaml = indoc! {"
+    ---
+    x: [0 A/m, 100 A/m, 200 A/m]
+    y: [0 T, 1 T, 1.5 T]
+    kind: Natural
+    "};
+let spline: Spline = serde_yaml::from_str(yaml).expect("valid support points");
+assert_eq!(
+    spline.call(&[DynQuantity::new(200.0, PredefUnit::MagneticFieldStrength)]).value,
+    1.5
+);
+```
+*/
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct Spline {
+    points: Vec<(DynQuantity<f64>, DynQuantity<f64>)>,
+    kind: SplineKind,
+    extrapolation: Extrapolation,
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    input_name: Option<String>,
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    validity: Option<ValidityRange>,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    support_points: SupportPoints,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    slopes: Vec<f64>,
+}
+
+impl Spline {
+    /**
+    Checks if at least two support points are given, if the units of all inputs
+    and outputs are identical respectively, if all values are finite and if
+    the inputs are strictly increasing. If this is the case, the slopes at the support points are
+    calculated and a new instance of [`Spline`] is returned.
+
+    # Examples
+
+    ```
+    use std::str::FromStr;
+    use dyn_quantity::DynQuantity;
+    use var_quantity::{SupportPointsError, unary::{Extrapolation, Spline, SplineKind}};
+
+    let q = |s: &str| DynQuantity::<f64>::from_str(s).unwrap();
+
+    assert!(Spline::new(
+        vec![(q("200 K"), q("1 ohm")), (q("300 K"), q("2 ohm"))],
+        SplineKind::Akima,
+        Extrapolation::Hold,
+    ).is_ok());
+
+    // Unit mismatch of the second input
+    assert!(Spline::new(
+        vec![(q("200 K"), q("1 ohm")), (q("300 s"), q("2 ohm"))],
+        SplineKind::Akima,
+        Extrapolation::Hold,
+    ).is_err());
+
+    // Inputs are not strictly increasing
+    assert_eq!(
+        Spline::new(
+            vec![(q("300 K"), q("1 ohm")), (q("200 K"), q("2 ohm"))],
+            SplineKind::Akima,
+            Extrapolation::Hold,
+        ),
+        Err(SupportPointsError::NotStrictlyIncreasing(1))
+    );
+    ```
+    */
+    pub fn new(
+        points: Vec<(DynQuantity<f64>, DynQuantity<f64>)>,
+        kind: SplineKind,
+        extrapolation: Extrapolation,
+    ) -> Result<Self, SupportPointsError> {
+        let support_points = SupportPoints::new(&points, 2)?;
+        let slopes = match kind {
+            SplineKind::Natural => natural_slopes(&support_points.x, &support_points.y),
+            SplineKind::Pchip => pchip_slopes(&support_points.x, &support_points.y),
+            SplineKind::Akima => akima_slopes(&support_points.x, &support_points.y),
+        };
+        return Ok(Self {
+            points,
+            kind,
+            extrapolation,
+            input_name: None,
+            validity: None,
+            support_points,
+            slopes,
+        });
+    }
+
+    /**
+    Creates a new instance of [`Spline`] from separate columns of inputs `x`
+    and outputs `y`. Both columns must have the same length, afterwards the
+    same checks as in [`Spline::new`] are performed.
+    */
+    pub fn from_columns(
+        x: Vec<DynQuantity<f64>>,
+        y: Vec<DynQuantity<f64>>,
+        kind: SplineKind,
+        extrapolation: Extrapolation,
+    ) -> Result<Self, SupportPointsError> {
+        return Self::new(zip_columns(x, y)?, kind, extrapolation);
+    }
+
+    /**
+    Returns the support points.
+    */
+    pub fn points(&self) -> &[(DynQuantity<f64>, DynQuantity<f64>)] {
+        return self.points.as_slice();
+    }
+
+    /**
+    Returns the [`SplineKind`].
+    */
+    pub fn kind(&self) -> SplineKind {
+        return self.kind;
+    }
+
+    /**
+    Returns the [`Extrapolation`] behaviour.
+    */
+    pub fn extrapolation(&self) -> Extrapolation {
+        return self.extrapolation;
+    }
+
+    /**
+    Returns the unit of the quantity which influences the variable quantity.
+    If none of the `influencing_factors` in a
+    [`QuantityFunction::call`](crate::QuantityFunction::call) matches this
+    item, then `x` is assumed to be zero.
+
+    # Examples
+
+    ```
+    use std::str::FromStr;
+    use dyn_quantity::{DynQuantity, PredefUnit, Unit};
+    use var_quantity::unary::{Extrapolation, Spline, SplineKind};
+
+    let q = |s: &str| DynQuantity::<f64>::from_str(s).unwrap();
+
+    let spline = Spline::new(
+        vec![(q("200 K"), q("1 ohm")), (q("300 K"), q("2 ohm"))],
+        SplineKind::Natural,
+        Extrapolation::Hold,
+    ).expect("valid support points");
+    assert_eq!(spline.influencing_factor_unit(), Unit::from(PredefUnit::Temperature));
+    ```
+    */
+    pub fn influencing_factor_unit(&self) -> Unit {
+        return self.support_points.input_unit;
+    }
+
+    /**
+    Returns the unit which will be returned from
+    [`QuantityFunction::call`](crate::QuantityFunction::call).
+
+    ```
+    use std::str::FromStr;
+    use dyn_quantity::{DynQuantity, PredefUnit, Unit};
+    use var_quantity::unary::{Extrapolation, Spline, SplineKind};
+
+    let q = |s: &str| DynQuantity::<f64>::from_str(s).unwrap();
+
+    let spline = Spline::new(
+        vec![(q("200 K"), q("1 ohm")), (q("300 K"), q("2 ohm"))],
+        SplineKind::Natural,
+        Extrapolation::Hold,
+    ).expect("valid support points");
+    assert_eq!(spline.output_unit(), Unit::from(PredefUnit::ElectricResistance));
+    ```
+     */
+    pub fn output_unit(&self) -> Unit {
+        return self.support_points.output_unit;
+    }
+
+    /**
+    Evaluates the spline at `x`. Returns `None` if `x` is outside of the
+    support points and extrapolation is forbidden.
+     */
+    fn interpolate(&self, x: f64) -> Option<f64> {
+        let sp = &self.support_points;
+        let last = sp.x.len() - 1;
+        if x.is_nan() {
+            return Some(f64::NAN);
+        }
+        if !sp.contains(x) {
+            let i = if x < sp.x[0] { 0 } else { last };
+            match self.extrapolation {
+                Extrapolation::Hold => return Some(sp.y[i]),
+                Extrapolation::Linear => return Some(sp.y[i] + self.slopes[i] * (x - sp.x[i])),
+                Extrapolation::Error => return None,
+            }
+        }
+
+        // Cubic Hermite polynomial on the segment [x_i, x_(i+1)]
+        let i = sp.segment(x);
+        let h = sp.x[i + 1] - sp.x[i];
+        let t = (x - sp.x[i]) / h;
+        let t2 = t * t;
+        let t3 = t2 * t;
+        return Some(
+            (2.0 * t3 - 3.0 * t2 + 1.0) * sp.y[i]
+                + (t3 - 2.0 * t2 + t) * h * self.slopes[i]
+                + (-2.0 * t3 + 3.0 * t2) * sp.y[i + 1]
+                + (t3 - t2) * h * self.slopes[i + 1],
+        );
+    }
+
+    /**
+    Evaluates the first derivative of the spline at `x`. Returns `None` if `x`
+    is outside of the support points and extrapolation is forbidden.
+     */
+    fn slope(&self, x: f64) -> Option<f64> {
+        let sp = &self.support_points;
+        let last = sp.x.len() - 1;
+        if x.is_nan() {
+            return Some(f64::NAN);
+        }
+        if !sp.contains(x) {
+            match self.extrapolation {
+                Extrapolation::Hold => return Some(0.0),
+                Extrapolation::Linear => {
+                    return Some(if x < sp.x[0] {
+                        self.slopes[0]
+                    } else {
+                        self.slopes[last]
+                    });
+                }
+                Extrapolation::Error => return None,
+            }
+        }
+
+        // Derivative of the cubic Hermite polynomial on the segment [x_i, x_(i+1)]
+        let i = sp.segment(x);
+        let h = sp.x[i + 1] - sp.x[i];
+        let t = (x - sp.x[i]) / h;
+        let t2 = t * t;
+        return Some(
+            (6.0 * t2 - 6.0 * t) * sp.y[i] / h
+                + (3.0 * t2 - 4.0 * t + 1.0) * self.slopes[i]
+                + (-6.0 * t2 + 6.0 * t) * sp.y[i + 1] / h
+                + (3.0 * t2 - 2.0 * t) * self.slopes[i + 1],
+        );
+    }
+}
+
+/// Slopes of the segments between the support points.
+fn secants(x: &[f64], y: &[f64]) -> Vec<f64> {
+    return x
+        .windows(2)
+        .zip(y.windows(2))
+        .map(|(x, y)| (y[1] - y[0]) / (x[1] - x[0]))
+        .collect();
+}
+
+/**
+Slopes of a natural cubic spline at the support points. The second derivatives
+`m_i` are obtained by solving the tridiagonal system with the Thomas algorithm
+and afterwards converted into first derivatives.
+ */
+fn natural_slopes(x: &[f64], y: &[f64]) -> Vec<f64> {
+    let n = x.len();
+    let h: Vec<f64> = x.windows(2).map(|x| x[1] - x[0]).collect();
+    let delta = secants(x, y);
+
+    // Second derivatives, m_0 = m_(n-1) = 0
+    let mut m = vec![0.0; n];
+    if n > 2 {
+        // Forward elimination over the interior points 1..n-1
+        let mut c_prime = vec![0.0; n];
+        let mut d_prime = vec![0.0; n];
+        for i in 1..n - 1 {
+            let a = h[i - 1];
+            let b = 2.0 * (h[i - 1] + h[i]);
+            let c = h[i];
+            let d = 6.0 * (delta[i] - delta[i - 1]);
+            let denominator = b - a * c_prime[i - 1];
+            c_prime[i] = c / denominator;
+            d_prime[i] = (d - a * d_prime[i - 1]) / denominator;
+        }
+
+        // Back substitution
+        for i in (1..n - 1).rev() {
+            m[i] = d_prime[i] - c_prime[i] * m[i + 1];
+        }
+    }
+
+    let mut slopes: Vec<f64> = (0..n - 1)
+        .map(|i| delta[i] - h[i] * (2.0 * m[i] + m[i + 1]) / 6.0)
+        .collect();
+    slopes.push(delta[n - 2] + h[n - 2] * (m[n - 2] + 2.0 * m[n - 1]) / 6.0);
+    return slopes;
+}
+
+/**
+Slopes of a monotone piecewise cubic Hermite interpolation according to
+Fritsch and Carlson. The interior slopes are the weighted harmonic mean of the
+adjacent secants (or zero at local extrema), the end slopes are calculated
+with a shape-preserving three-point formula.
+ */
+fn pchip_slopes(x: &[f64], y: &[f64]) -> Vec<f64> {
+    let n = x.len();
+    let h: Vec<f64> = x.windows(2).map(|x| x[1] - x[0]).collect();
+    let delta = secants(x, y);
+
+    if n == 2 {
+        return vec![delta[0]; 2];
+    }
+
+    let mut slopes = vec![0.0; n];
+    for i in 1..n - 1 {
+        if delta[i - 1] * delta[i] > 0.0 {
+            let w1 = 2.0 * h[i] + h[i - 1];
+            let w2 = h[i] + 2.0 * h[i - 1];
+            slopes[i] = (w1 + w2) / (w1 / delta[i - 1] + w2 / delta[i]);
+        }
+    }
+
+    let end_slope = |h0: f64, h1: f64, d0: f64, d1: f64| {
+        let slope = ((2.0 * h0 + h1) * d0 - h0 * d1) / (h0 + h1);
+        if slope.signum() != d0.signum() || d0 == 0.0 {
+            return 0.0;
+        } else if d0.signum() != d1.signum() && slope.abs() > 3.0 * d0.abs() {
+            return 3.0 * d0;
+        } else {
+            return slope;
+        }
+    };
+    slopes[0] = end_slope(h[0], h[1], delta[0], delta[1]);
+    slopes[n - 1] = end_slope(h[n - 2], h[n - 3], delta[n - 2], delta[n - 3]);
+    return slopes;
+}
+
+/**
+Slopes of an Akima spline. The secants are extended by two virtual segments
+at each end and the slope at a support point is a weighted average of the
+adjacent secants.
+ */
+fn akima_slopes(x: &[f64], y: &[f64]) -> Vec<f64> {
+    let n = x.len();
+    let delta = secants(x, y);
+
+    if n == 2 {
+        return vec![delta[0]; 2];
+    }
+
+    // Extended secants: m[k + 2] = delta[k]
+    let mut m = Vec::with_capacity(n + 3);
+    let m_minus_1 = 2.0 * delta[0] - delta[1];
+    m.push(2.0 * m_minus_1 - delta[0]);
+    m.push(m_minus_1);
+    m.extend_from_slice(&delta);
+    let m_n = 2.0 * delta[n - 2] - delta[n - 3];
+    m.push(m_n);
+    m.push(2.0 * m_n - delta[n - 2]);
+
+    return (0..n)
+        .map(|i| {
+            let w1 = (m[i + 3] - m[i + 2]).abs();
+            let w2 = (m[i + 1] - m[i]).abs();
+            if w1 + w2 == 0.0 {
+                return 0.5 * (m[i + 1] + m[i + 2]);
+            } else {
+                return (w1 * m[i + 1] + w2 * m[i + 2]) / (w1 + w2);
+            }
+        })
+        .collect();
+}
+
+impl_input_name!(Spline);
+impl_validity!(Spline);
+
+impl_support_points_function!(Spline);
+
+#[cfg(feature = "serde")]
+mod serde_impl {
+    use super::*;
+
+    use serde::de::{Deserialize, Deserializer};
+
+    use crate::unary::support_points::PointsAlias;
+
+    impl<'de> Deserialize<'de> for Spline {
+        fn deserialize<D>(deserializer: D) -> Result<Spline, D::Error>
+        where
+            D: Deserializer<'de>,
+        {
+            #[derive(serde::Deserialize)]
+            struct SplineAlias {
+                #[serde(flatten)]
+                points: PointsAlias,
+                kind: SplineKind,
+                #[serde(default)]
+                extrapolation: Extrapolation,
+                #[serde(default)]
+                input_name: Option<String>,
+                #[serde(default)]
+                validity: Option<ValidityRange>,
+            }
+
+            let alias = SplineAlias::deserialize(deserializer)?;
+            let mut spline =
+                Self::new(alias.points.into_points()?, alias.kind, alias.extrapolation)
+                    .map_err(serde::de::Error::custom)?;
+            spline.input_name = alias.input_name;
+            if let Some(range) = alias.validity {
+                spline = spline
+                    .with_validity(range)
+                    .map_err(serde::de::Error::custom)?;
+            }
+            return Ok(spline);
+        }
+    }
+}
@@ -0,0 +1,460 @@
+/*!
+An unary [`SteinhartHart`] function which implements [`QuantityFunction`].
+*/
+
+use dyn_quantity::{DynQuantity, PredefUnit, Unit, UnitsNotEqual};
+
+use crate::{
+    BatchInput, EvaluationError, Evaluator, InfluencingFactor, Layout, QuantityFunction,
+    bind_unary, filter_unary_batch, filter_unary_derivative, filter_unary_function,
+    select_influencing_factor, validity::ValidityRange,
+};
+
+/**
+The Steinhart-Hart equation, which describes the temperature of a thermistor
+(e.g. an NTC) as a function of its resistance `R`:
+
+`1 / T = A + B * ln(R) + C * ln(R)^3`
+
+where `R` is inserted as a value in ohm and the coefficients `A`, `B` and `C`
+have the unit 1/K. Thermistors which are specified by their nominal
+resistance and Beta value can be created via [`SteinhartHart::from_beta`].
+
+This struct is meant to be used as a [`QuantityFunction`] trait object. The
+unit of the influencing quantity is [`PredefUnit::ElectricResistance`], hence
+a measured sensor resistance can be used directly as influencing factor. The
+output unit is [`PredefUnit::Temperature`].
+
+# Domain
+
+The logarithm is only defined for positive resistances. For other inputs,
+[`QuantityFunction::call`] returns NaN and [`QuantityFunction::try_call`]
+returns an [`EvaluationError::InputOutOfRange`] with the open interval between
+zero and infinity as range. If none of the influencing factors matches the
+[`SteinhartHart::influencing_factor_unit`], the resistance is assumed to be
+zero and the function therefore returns NaN as well.
+
+# Examples
+
+```
+use std::str::FromStr;
+use dyn_quantity::DynQuantity;
+use var_quantity::{QuantityFunction, unary::SteinhartHart};
+
+let q = |s: &str| DynQuantity::<f64>::from_str(s).unwrap();
+
+// Typical coefficients of a 10 kOhm NTC
+let ntc = SteinhartHart::new(
+    q("1.009249522e-3 / K"),
+    q("2.378405444e-4 / K"),
+    q("2.019202697e-7 / K"),
+)
+.expect("units match");
+// Approximately 25 °C at the nominal resistance
+approx::assert_abs_diff_eq!(ntc.call(&[q("10 kohm")]).value, 298.15, epsilon = 0.5);
+
+// Non-positive resistances are outside of the domain
+assert!(ntc.call(&[q("0 ohm")]).value.is_nan());
+assert!(ntc.try_call(&[q("-1 ohm")]).is_err());
+```
+
+# Features:
+This struct can be serialized / deserialized if the `serde` feature is enabled.
+ */
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct SteinhartHart {
+    a: DynQuantity<f64>,
+    b: DynQuantity<f64>,
+    c: DynQuantity<f64>,
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    input_name: Option<String>,
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    validity: Option<ValidityRange>,
+}
+
+impl SteinhartHart {
+    /**
+    Checks if the coefficients `a`, `b` and `c` have the unit 1/K. If this is
+    the case, a new instance of [`SteinhartHart`] is returned. Otherwise, the
+    error contains 1/K and the unit of the first mismatching coefficient.
+
+    # Examples
+
+    ```
+    use std::str::FromStr;
+    use dyn_quantity::DynQuantity;
+    use var_quantity::unary::SteinhartHart;
+
+    let q = |s: &str| DynQuantity::<f64>::from_str(s).unwrap();
+
+    assert!(SteinhartHart::new(q("1e-3 / K"), q("2e-4 / K"), q("2e-7 / K")).is_ok());
+    assert!(SteinhartHart::new(q("1e-3 / K"), q("2e-4 / K"), q("2e-7 / ohm")).is_err());
+    ```
+    */
+    pub fn new(
+        a: DynQuantity<f64>,
+        b: DynQuantity<f64>,
+        c: DynQuantity<f64>,
+    ) -> Result<Self, UnitsNotEqual> {
+        let expected = Unit::from(PredefUnit::Temperature).powi(-1);
+        for coefficient in [&a, &b, &c] {
+            if coefficient.unit != expected {
+                return Err(UnitsNotEqual(expected, coefficient.unit));
+            }
+        }
+        return Ok(Self {
+            a,
+            b,
+            c,
+            input_name: None,
+            validity: None,
+        });
+    }
+
+    /**
+    Returns the Steinhart-Hart equation of a thermistor which is specified by
+    its resistance `r0` at the temperature `t0` and the Beta value `beta`:
+
+    `1 / T = 1 / T0 + 1 / Beta * ln(R / R0)`
+
+    This corresponds to the coefficients `A = 1 / T0 - ln(R0) / Beta`,
+    `B = 1 / Beta` and `C = 0`. Fails if `r0` is not an electrical resistance
+    or if `t0` or `beta` is not a temperature.
+
+    # Examples
+
+    ```
+    use std::str::FromStr;
+    use dyn_quantity::DynQuantity;
+    use var_quantity::{QuantityFunction, unary::SteinhartHart};
+
+    let q = |s: &str| DynQuantity::<f64>::from_str(s).unwrap();
+
+    let ntc = SteinhartHart::from_beta(q("10 kohm"), q("298.15 K"), q("3950 K")).expect("units match");
+    approx::assert_relative_eq!(ntc.call(&[q("10 kohm")]).value, 298.15);
+    assert_eq!(ntc.c(), &q("0 / K"));
+
+    assert!(SteinhartHart::from_beta(q("10 kohm"), q("298.15 K"), q("3950 ohm")).is_err());
+    ```
+    */
+    pub fn from_beta(
+        r0: DynQuantity<f64>,
+        t0: DynQuantity<f64>,
+        beta: DynQuantity<f64>,
+    ) -> Result<Self, UnitsNotEqual> {
+        let resistance = Unit::from(PredefUnit::ElectricResistance);
+        if r0.unit != resistance {
+            return Err(UnitsNotEqual(resistance, r0.unit));
+        }
+        let temperature = Unit::from(PredefUnit::Temperature);
+        for quantity in [&t0, &beta] {
+            if quantity.unit != temperature {
+                return Err(UnitsNotEqual(temperature, quantity.unit));
+            }
+        }
+        let inverse_temperature = temperature.powi(-1);
+        return Self::new(
+            DynQuantity::new(
+                1.0 / t0.value - r0.value.ln() / beta.value,
+                inverse_temperature,
+            ),
+            DynQuantity::new(1.0 / beta.value, inverse_temperature),
+            DynQuantity::new(0.0, inverse_temperature),
+        );
+    }
+
+    /**
+    Returns the constant coefficient `A`.
+    */
+    pub fn a(&self) -> &DynQuantity<f64> {
+        return &self.a;
+    }
+
+    /**
+    Returns the linear coefficient `B`.
+    */
+    pub fn b(&self) -> &DynQuantity<f64> {
+        return &self.b;
+    }
+
+    /**
+    Returns the cubic coefficient `C`.
+    */
+    pub fn c(&self) -> &DynQuantity<f64> {
+        return &self.c;
+    }
+
+    /**
+    Returns the unit of the quantity which influences the variable quantity,
+    which is always [`PredefUnit::ElectricResistance`]. If none of the
+    `influencing_factors` in a [`QuantityFunction::call`] matches this item,
+    then the resistance is assumed to be zero, which is outside of the domain.
+
+    # Examples
+
+    ```
+    use std::str::FromStr;
+    use dyn_quantity::{DynQuantity, PredefUnit, Unit};
+    use var_quantity::{QuantityFunction, unary::SteinhartHart};
+
+    let q = |s: &str| DynQuantity::<f64>::from_str(s).unwrap();
+
+    let fun = SteinhartHart::from_beta(q("10 kohm"), q("298.15 K"), q("3950 K")).expect("units match");
+    assert_eq!(fun.influencing_factor_unit(), Unit::from(PredefUnit::ElectricResistance));
+    assert!(fun.call(&[q("300 K")]).value.is_nan());
+    ```
+    */
+    pub fn influencing_factor_unit(&self) -> Unit {
+        return PredefUnit::ElectricResistance.into();
+    }
+
+    /**
+    Returns the unit which will be returned from [`QuantityFunction::call`],
+    which is always [`PredefUnit::Temperature`].
+
+    ```
+    use std::str::FromStr;
+    use dyn_quantity::{DynQuantity, PredefUnit, Unit};
+    use var_quantity::{QuantityFunction, unary::SteinhartHart};
+
+    let q = |s: &str| DynQuantity::<f64>::from_str(s).unwrap();
+
+    let fun = SteinhartHart::from_beta(q("10 kohm"), q("298.15 K"), q("3950 K")).expect("units match");
+    assert_eq!(fun.output_unit(), Unit::from(PredefUnit::Temperature));
+    ```
+     */
+    pub fn output_unit(&self) -> Unit {
+        return PredefUnit::Temperature.into();
+    }
+
+    /**
+    Returns the temperature for the resistance `x` in ohm or `None` if `x` is
+    outside of the domain.
+     */
+    fn evaluate(&self, x: f64) -> Option<f64> {
+        if x <= 0.0 {
+            return None;
+        }
+        let ln = x.ln();
+        return Some(1.0 / (self.a.value + self.b.value * ln + self.c.value * ln.powi(3)));
+    }
+}
+
+impl_input_name!(SteinhartHart);
+impl_validity!(SteinhartHart);
+
+#[cfg_attr(feature = "serde", typetag::serde)]
+impl QuantityFunction for SteinhartHart {
+    fn call(&self, influencing_factors: &[DynQuantity<f64>]) -> DynQuantity<f64> {
+        return filter_unary_function(
+            influencing_factors,
+            self.influencing_factor_unit(),
+            |input| {
+                let value = self.evaluate(input.value).unwrap_or(f64::NAN);
+                return DynQuantity::new(value, self.output_unit());
+            },
+            || DynQuantity::new(f64::NAN, self.output_unit()),
+        );
+    }
+
+    fn call_batch(&self, inputs: &[BatchInput], output: &mut [f64]) {
+        filter_unary_batch(
+            inputs,
+            self.influencing_factor_unit(),
+            output,
+            |input| self.evaluate(input).unwrap_or(f64::NAN),
+            || f64::NAN,
+        );
+    }
+
+    fn bind(&self, layout: &Layout) -> Box<dyn Evaluator + '_> {
+        return bind_unary(
+            layout,
+            self.influencing_factor_unit(),
+            self.input_name.as_deref(),
+            |input| self.evaluate(input).unwrap_or(f64::NAN),
+            || f64::NAN,
+        );
+    }
+
+    fn output_unit(&self) -> Unit {
+        return SteinhartHart::output_unit(self);
+    }
+
+    fn influencing_units(&self) -> Option<Vec<Unit>> {
+        return Some(vec![self.influencing_factor_unit()]);
+    }
+
+    fn validity_ranges(&self) -> Vec<ValidityRange> {
+        return super::bind_validity_ranges(
+            self.validity.iter().cloned(),
+            self.input_name.as_deref(),
+        );
+    }
+
+    fn call_named(&self, influencing_factors: &[InfluencingFactor]) -> DynQuantity<f64> {
+        let selected = select_influencing_factor(
+            influencing_factors,
+            self.input_name.as_deref(),
+            self.influencing_factor_unit(),
+        );
+        return self.call(selected.as_slice());
+    }
+
+    fn try_call(
+        &self,
+        influencing_factors: &[DynQuantity<f64>],
+    ) -> Result<DynQuantity<f64>, EvaluationError> {
+        let input_unit = self.influencing_factor_unit();
+        let input = influencing_factors
+            .iter()
+            .find(|f| f.unit == input_unit)
+            .cloned()
+            .unwrap_or(DynQuantity::new(0.0, input_unit));
+        match self.evaluate(input.value) {
+            Some(value) => return Ok(DynQuantity::new(value, self.output_unit())),
+            None => {
+                return Err(EvaluationError::InputOutOfRange {
+                    input,
+                    lower: DynQuantity::new(0.0, input_unit),
+                    upper: DynQuantity::new(f64::INFINITY, input_unit),
+                });
+            }
+        }
+    }
+
+    fn try_call_named(
+        &self,
+        influencing_factors: &[InfluencingFactor],
+    ) -> Result<DynQuantity<f64>, EvaluationError> {
+        let selected = select_influencing_factor(
+            influencing_factors,
+            self.input_name.as_deref(),
+            self.influencing_factor_unit(),
+        );
+        return self.try_call(selected.as_slice());
+    }
+
+    fn derivative(
+        &self,
+        influencing_factors: &[DynQuantity<f64>],
+        wrt: Unit,
+    ) -> Option<DynQuantity<f64>> {
+        return filter_unary_derivative(
+            influencing_factors,
+            self.influencing_factor_unit(),
+            self.output_unit(),
+            wrt,
+            |x| match self.evaluate(x) {
+                Some(t) => {
+                    let ln = x.ln();
+                    -t.powi(2) * (self.b.value + 3.0 * self.c.value * ln.powi(2)) / x
+                }
+                None => f64::NAN,
+            },
+        );
+    }
+}
+
+#[cfg(feature = "serde")]
+mod serde_impl {
+    use super::*;
+
+    use serde::de::{Deserialize, Deserializer};
+
+    impl<'de> Deserialize<'de> for SteinhartHart {
+        fn deserialize<D>(deserializer: D) -> Result<SteinhartHart, D::Error>
+        where
+            D: Deserializer<'de>,
+        {
+            #[derive(serde::Deserialize)]
+            struct SteinhartHartAlias {
+                a: DynQuantity<f64>,
+                b: DynQuantity<f64>,
+                c: DynQuantity<f64>,
+                #[serde(default)]
+                input_name: Option<String>,
+                #[serde(default)]
+                validity: Option<ValidityRange>,
+            }
+
+            let alias = SteinhartHartAlias::deserialize(deserializer)?;
+            let mut steinhart_hart =
+                Self::new(alias.a, alias.b, alias.c).map_err(serde::de::Error::custom)?;
+            steinhart_hart.input_name = alias.input_name;
+            if let Some(range) = alias.validity {
+                steinhart_hart = steinhart_hart
+                    .with_validity(range)
+                    .map_err(serde::de::Error::custom)?;
+            }
+            return Ok(steinhart_hart);
+        }
+    }
+}
+
+// =============================================================================
+
+#[cfg(feature = "serde")]
+#[cfg_attr(feature = "serde", typetag::serde)]
+impl QuantityFunction for crate::ClampedQuantity<SteinhartHart> {
+    fn call(&self, influencing_factors: &[DynQuantity<f64>]) -> DynQuantity<f64> {
+        return self.call_clamped(influencing_factors);
+    }
+
+    fn output_unit(&self) -> Unit {
+        return self.inner().output_unit();
+    }
+
+    fn influencing_units(&self) -> Option<Vec<Unit>> {
+        return self.inner().influencing_units();
+    }
+
+    fn validity_ranges(&self) -> Vec<ValidityRange> {
+        return self.inner().validity_ranges();
+    }
+
+    fn call_named(&self, influencing_factors: &[InfluencingFactor]) -> DynQuantity<f64> {
+        return self.call_named_clamped(influencing_factors);
+    }
+
+    fn try_call(
+        &self,
+        influencing_factors: &[DynQuantity<f64>],
+    ) -> Result<DynQuantity<f64>, EvaluationError> {
+        return self.try_call_clamped(influencing_factors);
+    }
+
+    fn try_call_named(
+        &self,
+        influencing_factors: &[InfluencingFactor],
+    ) -> Result<DynQuantity<f64>, EvaluationError> {
+        return self.try_call_named_clamped(influencing_factors);
+    }
+
+    fn derivative(
+        &self,
+        influencing_factors: &[DynQuantity<f64>],
+        wrt: Unit,
+    ) -> Option<DynQuantity<f64>> {
+        return self.derivative_clamped(influencing_factors, wrt);
+    }
+
+    fn call_dual(&self, influencing_factors: &[crate::DualQuantity]) -> crate::DualQuantity {
+        return self.call_dual_clamped(influencing_factors);
+    }
+
+    fn call_batch(&self, inputs: &[crate::BatchInput], output: &mut [f64]) {
+        self.call_batch_clamped(inputs, output);
+    }
+
+    fn bind(&self, layout: &crate::Layout) -> Box<dyn crate::Evaluator + '_> {
+        return self.bind_clamped(layout);
+    }
+}
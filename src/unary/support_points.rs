@@ -0,0 +1,408 @@
+/*!
+Crate-internal helper for unary functions which are defined via a set of
+support points (e.g. [`Lookup`](super::Lookup) or [`Spline`](super::Spline)).
+*/
+
+use dyn_quantity::{DynQuantity, Unit, UnitsNotEqual};
+
+use crate::{SupportPointsError, validity::ValidityRange};
+
+/**
+Behaviour of a function defined via support points for inputs outside of the
+range covered by the support points, see e.g. [`Lookup`](super::Lookup) and
+[`Spline`](super::Spline). A NaN input is not covered by the support points,
+but always results in NaN regardless of the extrapolation.
+
+# Features:
+This enum can be serialized / deserialized if the `serde` feature is enabled.
+ */
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Extrapolation {
+    /// Hold the value of the first / last support point constant.
+    #[default]
+    Hold,
+    /// Continue the first / last segment of the function linearly.
+    Linear,
+    /**
+    Inputs outside of the support points are an error.
+    [`QuantityFunction::try_call`](crate::QuantityFunction::try_call) returns
+    an [`EvaluationError::InputOutOfRange`](crate::EvaluationError::InputOutOfRange).
+    The infallible methods [`QuantityFunction::call`](crate::QuantityFunction::call),
+    [`QuantityFunction::call_batch`](crate::QuantityFunction::call_batch) and
+    the evaluator returned by [`QuantityFunction::bind`](crate::QuantityFunction::bind)
+    cannot report an error and return NaN instead.
+
+    To handle such inputs without NaN values, [`Lookup`](super::Lookup) and
+    [`Spline`](super::Spline) additionally report the range covered by the
+    support points via
+    [`QuantityFunction::validity_ranges`](crate::QuantityFunction::validity_ranges).
+    Hence, the [`ValidityPolicy`](crate::validity::ValidityPolicy) of a
+    [`FunctionWrapper`](crate::FunctionWrapper) applies to them, e.g.
+    [`ValidityPolicy::Clamp`](crate::validity::ValidityPolicy::Clamp) clamps
+    the input to the support points before evaluating the function.
+     */
+    Error,
+}
+
+/**
+Unit-checked support points split into their raw input and output values.
+ */
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct SupportPoints {
+    pub(crate) x: Vec<f64>,
+    pub(crate) y: Vec<f64>,
+    pub(crate) input_unit: Unit,
+    pub(crate) output_unit: Unit,
+}
+
+impl SupportPoints {
+    /**
+    Checks that at least `required` points are given, that all inputs / outputs
+    have the same unit and are finite and that the inputs are strictly
+    increasing.
+     */
+    pub(crate) fn new(
+        points: &[(DynQuantity<f64>, DynQuantity<f64>)],
+        required: usize,
+    ) -> Result<Self, SupportPointsError> {
+        if points.len() < required {
+            return Err(SupportPointsError::NotEnoughPoints {
+                required,
+                found: points.len(),
+            });
+        }
+
+        // The check above makes sure that at least one point exists
+        let (first_x, first_y) = points[0];
+        let mut x = Vec::with_capacity(points.len());
+        let mut y = Vec::with_capacity(points.len());
+        for (index, (xi, yi)) in points.iter().enumerate() {
+            if xi.unit != first_x.unit {
+                return Err(UnitsNotEqual(first_x.unit, xi.unit).into());
+            }
+            if yi.unit != first_y.unit {
+                return Err(UnitsNotEqual(first_y.unit, yi.unit).into());
+            }
+            if !xi.value.is_finite() || !yi.value.is_finite() {
+                return Err(SupportPointsError::NotFinite(index));
+            }
+            if x.last().is_some_and(|previous| *previous >= xi.value) {
+                return Err(SupportPointsError::NotStrictlyIncreasing(index));
+            }
+            x.push(xi.value);
+            y.push(yi.value);
+        }
+
+        return Ok(Self {
+            x,
+            y,
+            input_unit: first_x.unit,
+            output_unit: first_y.unit,
+        });
+    }
+
+    /**
+    Returns the index `i` of the segment `[x_i, x_(i+1)]` containing `x`. Inputs
+    outside of the support points are assigned to the first / last segment.
+     */
+    pub(crate) fn segment(&self, x: f64) -> usize {
+        let last_segment = self.x.len().saturating_sub(2);
+        return self
+            .x
+            .partition_point(|xi| *xi <= x)
+            .saturating_sub(1)
+            .min(last_segment);
+    }
+
+    /// Returns true if `x` is within the range covered by the support points.
+    pub(crate) fn contains(&self, x: f64) -> bool {
+        // The constructor makes sure that at least one point exists
+        return x >= self.x[0] && x <= self.x[self.x.len() - 1];
+    }
+
+    /// Returns the smallest input as a quantity.
+    pub(crate) fn lower(&self) -> DynQuantity<f64> {
+        return DynQuantity::new(self.x[0], self.input_unit);
+    }
+
+    /// Returns the largest input as a quantity.
+    pub(crate) fn upper(&self) -> DynQuantity<f64> {
+        return DynQuantity::new(self.x[self.x.len() - 1], self.input_unit);
+    }
+
+    /**
+    Returns the range covered by the support points if inputs outside of it
+    are an error, see [`Extrapolation::Error`].
+     */
+    pub(crate) fn extrapolation_range(
+        &self,
+        extrapolation: Extrapolation,
+    ) -> Option<ValidityRange> {
+        if extrapolation != Extrapolation::Error {
+            return None;
+        }
+        // The support points are finite and strictly increasing, hence the
+        // construction cannot fail.
+        return ValidityRange::new(self.lower(), self.upper()).ok();
+    }
+}
+
+/// A support point `(x_i, y_i)`.
+pub(crate) type Point = (DynQuantity<f64>, DynQuantity<f64>);
+
+/**
+Joins separate columns of inputs `x` and outputs `y` into support points.
+Fails if the columns do not have the same length.
+ */
+pub(crate) fn zip_columns(
+    x: Vec<DynQuantity<f64>>,
+    y: Vec<DynQuantity<f64>>,
+) -> Result<Vec<Point>, SupportPointsError> {
+    if x.len() != y.len() {
+        return Err(SupportPointsError::LengthMismatch {
+            expected: x.len(),
+            found: y.len(),
+        });
+    }
+    return Ok(x.into_iter().zip(y).collect());
+}
+
+/**
+Serialized representation of support points, which are given either as a
+list of `points` or as separate columns `x` and `y`. Meant to be flattened
+into the deserialization alias of a function defined via support points.
+ */
+#[cfg(feature = "serde")]
+#[derive(serde::Deserialize)]
+pub(crate) struct PointsAlias {
+    #[serde(default)]
+    points: Option<Vec<Point>>,
+    #[serde(default)]
+    x: Option<Vec<DynQuantity<f64>>>,
+    #[serde(default)]
+    y: Option<Vec<DynQuantity<f64>>>,
+}
+
+#[cfg(feature = "serde")]
+impl PointsAlias {
+    /// Returns the support points, see [`zip_columns`].
+    pub(crate) fn into_points<E: serde::de::Error>(self) -> Result<Vec<Point>, E> {
+        match (self.points, self.x, self.y) {
+            (Some(points), None, None) => return Ok(points),
+            (None, Some(x), Some(y)) => return zip_columns(x, y).map_err(E::custom),
+            _ => {
+                return Err(E::custom(
+                    "expected either the field `points` or the fields `x` and `y`",
+                ));
+            }
+        }
+    }
+}
+
+/**
+Implements [`QuantityFunction`](crate::QuantityFunction) for a function `$type`
+defined via support points (and for [`ClampedQuantity<$type>`](crate::ClampedQuantity)
+if the `serde` feature is enabled). The type must have the fields
+`support_points: SupportPoints`, `extrapolation: Extrapolation`,
+`input_name: Option<String>` and `validity: Option<ValidityRange>` and the
+methods `interpolate` and `slope` (`fn(&self, f64) -> Option<f64>`), which
+return `None` for inputs outside of the support points if extrapolation is
+forbidden.
+ */
+macro_rules! impl_support_points_function {
+    ($type:ident) => {
+        #[cfg_attr(feature = "serde", typetag::serde)]
+        impl crate::QuantityFunction for $type {
+            fn call(
+                &self,
+                influencing_factors: &[::dyn_quantity::DynQuantity<f64>],
+            ) -> ::dyn_quantity::DynQuantity<f64> {
+                let output_unit = self.support_points.output_unit;
+                return crate::filter_unary_function(
+                    influencing_factors,
+                    self.support_points.input_unit,
+                    |input| {
+                        let value = self.interpolate(input.value).unwrap_or(f64::NAN);
+                        ::dyn_quantity::DynQuantity::new(value, output_unit)
+                    },
+                    || {
+                        let value = self.interpolate(0.0).unwrap_or(f64::NAN);
+                        ::dyn_quantity::DynQuantity::new(value, output_unit)
+                    },
+                );
+            }
+
+            fn call_batch(&self, inputs: &[crate::BatchInput], output: &mut [f64]) {
+                crate::filter_unary_batch(
+                    inputs,
+                    self.support_points.input_unit,
+                    output,
+                    |input| self.interpolate(input).unwrap_or(f64::NAN),
+                    || self.interpolate(0.0).unwrap_or(f64::NAN),
+                );
+            }
+
+            fn bind(&self, layout: &crate::Layout) -> Box<dyn crate::Evaluator + '_> {
+                return crate::bind_unary(
+                    layout,
+                    self.support_points.input_unit,
+                    self.input_name.as_deref(),
+                    |input| self.interpolate(input).unwrap_or(f64::NAN),
+                    || self.interpolate(0.0).unwrap_or(f64::NAN),
+                );
+            }
+
+            fn output_unit(&self) -> ::dyn_quantity::Unit {
+                return self.support_points.output_unit;
+            }
+
+            fn influencing_units(&self) -> Option<Vec<::dyn_quantity::Unit>> {
+                return Some(vec![self.support_points.input_unit]);
+            }
+
+            fn validity_ranges(&self) -> Vec<crate::validity::ValidityRange> {
+                return crate::unary::bind_validity_ranges(
+                    self.validity
+                        .iter()
+                        .cloned()
+                        .chain(self.support_points.extrapolation_range(self.extrapolation)),
+                    self.input_name.as_deref(),
+                );
+            }
+
+            fn call_named(
+                &self,
+                influencing_factors: &[crate::InfluencingFactor],
+            ) -> ::dyn_quantity::DynQuantity<f64> {
+                let selected = crate::select_influencing_factor(
+                    influencing_factors,
+                    self.input_name.as_deref(),
+                    self.support_points.input_unit,
+                );
+                return self.call(selected.as_slice());
+            }
+
+            fn try_call(
+                &self,
+                influencing_factors: &[::dyn_quantity::DynQuantity<f64>],
+            ) -> Result<::dyn_quantity::DynQuantity<f64>, crate::EvaluationError> {
+                let sp = &self.support_points;
+                let input = influencing_factors
+                    .iter()
+                    .find(|f| f.unit == sp.input_unit)
+                    .cloned()
+                    .unwrap_or(::dyn_quantity::DynQuantity::new(0.0, sp.input_unit));
+                match self.interpolate(input.value) {
+                    Some(value) => {
+                        return Ok(::dyn_quantity::DynQuantity::new(value, sp.output_unit));
+                    }
+                    None => {
+                        return Err(crate::EvaluationError::InputOutOfRange {
+                            input,
+                            lower: sp.lower(),
+                            upper: sp.upper(),
+                        });
+                    }
+                }
+            }
+
+            fn try_call_named(
+                &self,
+                influencing_factors: &[crate::InfluencingFactor],
+            ) -> Result<::dyn_quantity::DynQuantity<f64>, crate::EvaluationError> {
+                let selected = crate::select_influencing_factor(
+                    influencing_factors,
+                    self.input_name.as_deref(),
+                    self.support_points.input_unit,
+                );
+                return self.try_call(selected.as_slice());
+            }
+
+            fn derivative(
+                &self,
+                influencing_factors: &[::dyn_quantity::DynQuantity<f64>],
+                wrt: ::dyn_quantity::Unit,
+            ) -> Option<::dyn_quantity::DynQuantity<f64>> {
+                return crate::filter_unary_derivative(
+                    influencing_factors,
+                    self.support_points.input_unit,
+                    self.support_points.output_unit,
+                    wrt,
+                    |x| self.slope(x).unwrap_or(f64::NAN),
+                );
+            }
+        }
+
+        // =====================================================================
+
+        #[cfg(feature = "serde")]
+        #[cfg_attr(feature = "serde", typetag::serde)]
+        impl crate::QuantityFunction for crate::ClampedQuantity<$type> {
+            fn call(
+                &self,
+                influencing_factors: &[::dyn_quantity::DynQuantity<f64>],
+            ) -> ::dyn_quantity::DynQuantity<f64> {
+                return self.call_clamped(influencing_factors);
+            }
+
+            fn output_unit(&self) -> ::dyn_quantity::Unit {
+                return self.inner().output_unit();
+            }
+
+            fn influencing_units(&self) -> Option<Vec<::dyn_quantity::Unit>> {
+                return self.inner().influencing_units();
+            }
+
+            fn validity_ranges(&self) -> Vec<crate::validity::ValidityRange> {
+                return self.inner().validity_ranges();
+            }
+
+            fn call_named(
+                &self,
+                influencing_factors: &[crate::InfluencingFactor],
+            ) -> ::dyn_quantity::DynQuantity<f64> {
+                return self.call_named_clamped(influencing_factors);
+            }
+
+            fn try_call(
+                &self,
+                influencing_factors: &[::dyn_quantity::DynQuantity<f64>],
+            ) -> Result<::dyn_quantity::DynQuantity<f64>, crate::EvaluationError> {
+                return self.try_call_clamped(influencing_factors);
+            }
+
+            fn try_call_named(
+                &self,
+                influencing_factors: &[crate::InfluencingFactor],
+            ) -> Result<::dyn_quantity::DynQuantity<f64>, crate::EvaluationError> {
+                return self.try_call_named_clamped(influencing_factors);
+            }
+
+            fn derivative(
+                &self,
+                influencing_factors: &[::dyn_quantity::DynQuantity<f64>],
+                wrt: ::dyn_quantity::Unit,
+            ) -> Option<::dyn_quantity::DynQuantity<f64>> {
+                return self.derivative_clamped(influencing_factors, wrt);
+            }
+
+            fn call_dual(
+                &self,
+                influencing_factors: &[crate::DualQuantity],
+            ) -> crate::DualQuantity {
+                return self.call_dual_clamped(influencing_factors);
+            }
+
+            fn call_batch(&self, inputs: &[crate::BatchInput], output: &mut [f64]) {
+                self.call_batch_clamped(inputs, output);
+            }
+
+            fn bind(&self, layout: &crate::Layout) -> Box<dyn crate::Evaluator + '_> {
+                return self.bind_clamped(layout);
+            }
+        }
+    };
+}
+
+pub(crate) use impl_support_points_function;
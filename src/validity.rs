@@ -0,0 +1,453 @@
+/*!
+This module contains the [`ValidityRange`] of an influencing factor and the
+[`ValidityPolicy`] which defines how a [`FunctionWrapper`](crate::FunctionWrapper)
+handles influencing factors outside of that range.
+
+Many functions are fitted to measurements or datasheet values which only cover
+a certain range of inputs. For example, a [`FirstOrderTaylor`](crate::unary::FirstOrderTaylor)
+approximation of the resistivity of copper might only be valid from 200 K to
+500 K. The unary functions of this crate can store such a range via their
+`with_validity` method (e.g. [`Linear::with_validity`](crate::unary::Linear::with_validity)).
+The ranges are reported by [`QuantityFunction::validity_ranges`](crate::QuantityFunction::validity_ranges)
+and are checked by a [`FunctionWrapper`](crate::FunctionWrapper) according to its
+[`ValidityPolicy`].
+
+# Examples
+
+```
+use std::str::FromStr;
+use dyn_quantity::DynQuantity;
+use uom::si::{f64::ElectricalResistance, electrical_resistance::ohm};
+use var_quantity::{
+    EvaluationError, FunctionWrapper, unary::Linear, validity::{ValidityPolicy, ValidityRange},
+};
+
+let q = |s: &str| DynQuantity::<f64>::from_str(s).unwrap();
+
+let fun = Linear::new(q("0.01 ohm/K"), q("1 ohm"))
+    .with_validity(ValidityRange::new(q("200 K"), q("500 K")).expect("valid range"))
+    .expect("units match");
+
+// By default, the range is ignored
+let wrapper = FunctionWrapper::<ElectricalResistance>::new(Box::new(fun)).expect("units match");
+assert_eq!(wrapper.call(&[q("600 K")]).get::<ohm>(), 7.0);
+
+// Clamp the input to the range
+let clamped = wrapper.clone().with_validity_policy(ValidityPolicy::Clamp);
+assert_eq!(clamped.call(&[q("600 K")]).get::<ohm>(), 6.0);
+
+// Return an error via the fallible evaluation methods
+let strict = wrapper.clone().with_validity_policy(ValidityPolicy::Error);
+assert_eq!(
+    strict.try_call(&[q("600 K")]),
+    Err(EvaluationError::InputOutOfRange {
+        input: q("600 K"),
+        lower: q("200 K"),
+        upper: q("500 K")
+    })
+);
+
+// Evaluate normally, but record a warning
+let warn = wrapper.with_validity_policy(ValidityPolicy::Warn);
+assert_eq!(warn.call(&[q("600 K")]).get::<ohm>(), 7.0);
+assert_eq!(warn.call(&[q("700 K")]).get::<ohm>(), 8.0);
+let warnings = warn.take_warnings();
+assert_eq!(warnings.len(), 1);
+assert_eq!(warnings[0].count(), 2);
+assert_eq!(warnings[0].worst_input(), &q("700 K"));
+assert!(warn.take_warnings().is_empty());
+```
+*/
+
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+
+use dyn_quantity::{DynQuantity, Unit, UnitsNotEqual};
+
+use crate::{EvaluationError, error::RangeError};
+
+/**
+The closed range `[lower, upper]` of an influencing factor for which a
+function is valid.
+
+A range can optionally be bound to an influencing factor with a given name
+(see [`ValidityRange::with_input_name`]). The unary functions of this crate
+bind the ranges they report via
+[`QuantityFunction::validity_ranges`](crate::QuantityFunction::validity_ranges)
+to their own input name, so named evaluations check the correct factor.
+
+# Features:
+This struct can be serialized / deserialized if the `serde` feature is enabled.
+The input name is not serialized, since it is taken from the function which
+stores the range. The limits are checked during deserialization as well.
+
+```text
+lower: 200 K
+upper: 500 K
+```
+*/
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct ValidityRange {
+    lower: DynQuantity<f64>,
+    upper: DynQuantity<f64>,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    input_name: Option<String>,
+}
+
+impl ValidityRange {
+    /**
+    Checks if `lower` and `upper` have the same unit, if they are finite and
+    if `lower` is not larger than `upper`. If this is the case, a new instance
+    of [`ValidityRange`] is returned. One-sided ranges can be created by
+    setting `lower` to negative infinity or `upper` to positive infinity.
+
+    # Examples
+
+    ```
+    use std::str::FromStr;
+    use dyn_quantity::DynQuantity;
+    use var_quantity::{error::RangeError, validity::ValidityRange};
+
+    let q = |s: &str| DynQuantity::<f64>::from_str(s).unwrap();
+
+    assert!(ValidityRange::new(q("200 K"), q("500 K")).is_ok());
+    assert!(matches!(
+        ValidityRange::new(q("200 K"), q("500 A")),
+        Err(RangeError::UnitsNotEqual(_))
+    ));
+    assert_eq!(
+        ValidityRange::new(q("500 K"), q("200 K")),
+        Err(RangeError::LowerAboveUpper { lower: q("500 K"), upper: q("200 K") })
+    );
+
+    // One-sided range
+    let upper = DynQuantity::new(f64::INFINITY, q("1 K").unit);
+    assert!(ValidityRange::new(q("200 K"), upper).is_ok());
+    assert_eq!(
+        ValidityRange::new(upper, upper),
+        Err(RangeError::NotFinite(upper))
+    );
+    ```
+     */
+    pub fn new(lower: DynQuantity<f64>, upper: DynQuantity<f64>) -> Result<Self, RangeError> {
+        if lower.unit != upper.unit {
+            return Err(UnitsNotEqual(lower.unit, upper.unit).into());
+        }
+        if lower.value.is_nan() || lower.value == f64::INFINITY {
+            return Err(RangeError::NotFinite(lower));
+        }
+        if upper.value.is_nan() || upper.value == f64::NEG_INFINITY {
+            return Err(RangeError::NotFinite(upper));
+        }
+        if lower.value > upper.value {
+            return Err(RangeError::LowerAboveUpper { lower, upper });
+        }
+        return Ok(Self {
+            lower,
+            upper,
+            input_name: None,
+        });
+    }
+
+    /**
+    Binds the range to the influencing factor with the given name. When
+    checked against named influencing factors, only the factor with this name
+    (and the unit [`ValidityRange::unit`]) is checked. Otherwise, the first
+    factor with a matching unit is checked.
+     */
+    pub fn with_input_name<N: Into<String>>(mut self, name: N) -> Self {
+        self.input_name = Some(name.into());
+        return self;
+    }
+
+    /**
+    Returns the name of the influencing factor this range is bound to, if any.
+    See [`ValidityRange::with_input_name`].
+     */
+    pub fn input_name(&self) -> Option<&str> {
+        return self.input_name.as_deref();
+    }
+
+    /**
+    Returns the lower limit.
+     */
+    pub fn lower(&self) -> &DynQuantity<f64> {
+        return &self.lower;
+    }
+
+    /**
+    Returns the upper limit.
+     */
+    pub fn upper(&self) -> &DynQuantity<f64> {
+        return &self.upper;
+    }
+
+    /**
+    Returns the unit of the influencing factor this range applies to.
+     */
+    pub fn unit(&self) -> Unit {
+        return self.lower.unit;
+    }
+
+    /**
+    Returns whether `quantity` is within the range. Quantities with a
+    different unit or a NaN value are never contained.
+     */
+    pub fn contains(&self, quantity: &DynQuantity<f64>) -> bool {
+        return quantity.unit == self.unit()
+            && quantity.value >= self.lower.value
+            && quantity.value <= self.upper.value;
+    }
+
+    /**
+    Clamps the value of `quantity` to the range. Quantities with a different
+    unit are returned unchanged.
+     */
+    pub fn clamp(&self, quantity: DynQuantity<f64>) -> DynQuantity<f64> {
+        if quantity.unit != self.unit() {
+            return quantity;
+        }
+        return DynQuantity::new(
+            quantity.value.clamp(self.lower.value, self.upper.value),
+            quantity.unit,
+        );
+    }
+
+    /**
+    Returns an [`EvaluationError::InputOutOfRange`] if `quantity` is not
+    within the range.
+     */
+    pub fn check(&self, quantity: &DynQuantity<f64>) -> Result<(), EvaluationError> {
+        if self.contains(quantity) {
+            return Ok(());
+        }
+        return Err(EvaluationError::InputOutOfRange {
+            input: *quantity,
+            lower: self.lower,
+            upper: self.upper,
+        });
+    }
+
+    /**
+    Returns how far `value` (given in the unit of the range) lies outside of
+    the range. Contained values yield zero, NaN yields infinity.
+     */
+    pub(crate) fn excess(&self, value: f64) -> f64 {
+        if value.is_nan() {
+            return f64::INFINITY;
+        }
+        return (self.lower.value - value)
+            .max(value - self.upper.value)
+            .max(0.0);
+    }
+}
+
+/**
+Defines how a [`FunctionWrapper`](crate::FunctionWrapper) handles influencing
+factors which are outside of the [`ValidityRange`]s reported by the wrapped
+function. See the [module documentation](crate::validity) for an example.
+
+# Features:
+This enum can be serialized / deserialized if the `serde` feature is enabled.
+*/
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ValidityPolicy {
+    /// The validity ranges are not checked (default).
+    #[default]
+    Ignore,
+    /// Influencing factors outside of their range are clamped to it before
+    /// evaluating the function.
+    Clamp,
+    /**
+    The fallible evaluation methods (e.g. [`FunctionWrapper::try_call`](crate::FunctionWrapper::try_call))
+    return an [`EvaluationError::InputOutOfRange`]. The infallible methods
+    return a NaN value instead, analogous to a [`Lookup`](crate::unary::Lookup)
+    table which rejects extrapolation.
+     */
+    Error,
+    /**
+    The function is evaluated normally, but the violation is recorded as a
+    [`ValidityWarning`]. The warnings can be retrieved via [`FunctionWrapper::take_warnings`](crate::FunctionWrapper::take_warnings).
+     */
+    Warn,
+}
+
+/**
+Warning recorded by a [`FunctionWrapper`](crate::FunctionWrapper) with
+[`ValidityPolicy::Warn`] for evaluations in which an influencing factor was
+outside of a [`ValidityRange`].
+
+The warnings are aggregated per range: Instead of one entry per offending
+evaluation, a single warning stores the number of such evaluations and the
+input which was farthest outside of the range. Hence the memory used for the
+warnings is bounded by the number of ranges, regardless of how often the
+wrapper is evaluated.
+ */
+#[derive(Debug, Clone, PartialEq)]
+pub struct ValidityWarning {
+    range: ValidityRange,
+    count: usize,
+    worst_input: DynQuantity<f64>,
+}
+
+impl ValidityWarning {
+    /**
+    Returns the range which was violated.
+     */
+    pub fn range(&self) -> &ValidityRange {
+        return &self.range;
+    }
+
+    /**
+    Returns the number of evaluations with an influencing factor outside of
+    [`ValidityWarning::range`].
+     */
+    pub fn count(&self) -> usize {
+        return self.count;
+    }
+
+    /**
+    Returns the influencing factor which was farthest outside of
+    [`ValidityWarning::range`]. NaN inputs are considered to be the farthest.
+     */
+    pub fn worst_input(&self) -> &DynQuantity<f64> {
+        return &self.worst_input;
+    }
+
+    /**
+    Returns the [`EvaluationError::InputOutOfRange`] of
+    [`ValidityWarning::worst_input`].
+     */
+    pub fn error(&self) -> EvaluationError {
+        return EvaluationError::InputOutOfRange {
+            input: self.worst_input,
+            lower: self.range.lower,
+            upper: self.range.upper,
+        };
+    }
+}
+
+/**
+Aggregated out-of-range evaluations of a single [`ValidityRange`], used to
+collect the violations of e.g. a batch locally before they are added to the
+[`AtomicWarningRecord`] of the range.
+ */
+#[derive(Debug, Clone, Copy, Default)]
+pub(crate) struct WarningRecord {
+    count: usize,
+    worst: f64,
+}
+
+impl WarningRecord {
+    /// Adds an evaluation with the out-of-range `value`.
+    pub(crate) fn add(&mut self, range: &ValidityRange, value: f64) {
+        if self.count == 0 || range.excess(value) > range.excess(self.worst) {
+            self.worst = value;
+        }
+        self.count += 1;
+    }
+
+    /// Adds the evaluations of `self` to `shared`, if there are any.
+    pub(crate) fn merge_into(&self, shared: &AtomicWarningRecord, range: &ValidityRange) {
+        if self.count > 0 {
+            shared.add(range, self.count, self.worst);
+        }
+    }
+}
+
+/**
+Lock-free counterpart of [`WarningRecord`], which is shared between all
+evaluations of a [`FunctionWrapper`](crate::FunctionWrapper) (including the
+evaluators returned by [`FunctionWrapper::bind`](crate::FunctionWrapper::bind)).
+Concurrent evaluations are counted exactly. Only the worst input of an
+evaluation which runs concurrently to [`AtomicWarningRecord::take`] may be
+reported with the neighbouring warning.
+ */
+#[derive(Debug)]
+pub(crate) struct AtomicWarningRecord {
+    count: AtomicUsize,
+    // Bits of the worst input. If no evaluation has been recorded yet, this
+    // is a value within the range, so any outlier replaces it.
+    worst: AtomicU64,
+}
+
+impl AtomicWarningRecord {
+    pub(crate) fn new(range: &ValidityRange) -> Self {
+        return Self {
+            count: AtomicUsize::new(0),
+            worst: AtomicU64::new(Self::initial_worst(range).to_bits()),
+        };
+    }
+
+    fn initial_worst(range: &ValidityRange) -> f64 {
+        if range.lower.value.is_finite() {
+            return range.lower.value;
+        }
+        if range.upper.value.is_finite() {
+            return range.upper.value;
+        }
+        return 0.0;
+    }
+
+    /// Adds `count` evaluations whose farthest outlier is `worst`.
+    pub(crate) fn add(&self, range: &ValidityRange, count: usize, worst: f64) {
+        let excess = range.excess(worst);
+        // The closure returns None if the stored value is already worse, which
+        // is not an error
+        let _ = self
+            .worst
+            .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |current| {
+                (excess > range.excess(f64::from_bits(current))).then_some(worst.to_bits())
+            });
+        self.count.fetch_add(count, Ordering::Relaxed);
+    }
+
+    /// Returns the warning for `range` and resets the record.
+    pub(crate) fn take(&self, range: &ValidityRange) -> Option<ValidityWarning> {
+        let count = self.count.swap(0, Ordering::Relaxed);
+        if count == 0 {
+            return None;
+        }
+        let worst = self
+            .worst
+            .swap(Self::initial_worst(range).to_bits(), Ordering::Relaxed);
+        return Some(ValidityWarning {
+            range: range.clone(),
+            count,
+            worst_input: DynQuantity::new(f64::from_bits(worst), range.unit()),
+        });
+    }
+
+    /// Creates a copy of the record, which is not synchronized with concurrent
+    /// evaluations.
+    pub(crate) fn snapshot(&self) -> Self {
+        return Self {
+            count: AtomicUsize::new(self.count.load(Ordering::Relaxed)),
+            worst: AtomicU64::new(self.worst.load(Ordering::Relaxed)),
+        };
+    }
+}
+
+#[cfg(feature = "serde")]
+mod serde_impl {
+    use super::*;
+
+    use serde::de::{Deserialize, Deserializer};
+
+    impl<'de> Deserialize<'de> for ValidityRange {
+        fn deserialize<D>(deserializer: D) -> Result<ValidityRange, D::Error>
+        where
+            D: Deserializer<'de>,
+        {
+            #[derive(serde::Deserialize)]
+            struct ValidityRangeAlias {
+                lower: DynQuantity<f64>,
+                upper: DynQuantity<f64>,
+            }
+
+            let alias = ValidityRangeAlias::deserialize(deserializer)?;
+            return Self::new(alias.lower, alias.upper).map_err(serde::de::Error::custom);
+        }
+    }
+}
@@ -0,0 +1,276 @@
+use std::str::FromStr;
+
+use dyn_quantity::{DynQuantity, Unit};
+use uom::si::{electrical_resistance::ohm, f64::*};
+use var_quantity::{
+    BatchInput, ClampedQuantity, EvaluationError, FunctionWrapper, QuantityFunction, VarQuantity,
+    combinators::*,
+    expression::Expression,
+    multivariate::Grid,
+    unary::*,
+    validity::{ValidityPolicy, ValidityRange},
+};
+
+fn q(s: &str) -> DynQuantity<f64> {
+    return DynQuantity::from_str(s).unwrap();
+}
+
+fn unit(s: &str) -> Unit {
+    return q(s).unit;
+}
+
+const TEMPERATURES: [f64; 6] = [-50.0, 0.0, 150.0, 293.15, 500.0, 1200.0];
+const CURRENTS: [f64; 6] = [0.0, 1.0, 2.0, 5.0, 10.0, 20.0];
+
+/// Asserts that `call_batch` yields the same values as `call` per element.
+fn check_batch(fun: &dyn QuantityFunction) {
+    let currents = BatchInput::new(unit("1 A"), &CURRENTS);
+    let temperatures = BatchInput::new(unit("1 K"), &TEMPERATURES);
+
+    for inputs in [
+        vec![temperatures],
+        vec![currents, temperatures],
+        vec![temperatures, currents],
+        vec![currents],
+        vec![],
+    ] {
+        let mut output = [0.0; 6];
+        fun.call_batch(&inputs, &mut output);
+        for (index, value) in output.iter().enumerate() {
+            let factors: Vec<DynQuantity<f64>> = inputs
+                .iter()
+                .map(|input| DynQuantity::new(input.values[index], input.unit))
+                .collect();
+            let expected = fun.call(&factors).value;
+            if expected.is_nan() {
+                assert!(value.is_nan());
+                continue;
+            }
+            approx::assert_abs_diff_eq!(
+                *value,
+                expected,
+                epsilon = 1e-12 * expected.abs().max(1.0)
+            );
+        }
+    }
+}
+
+fn resistance() -> FirstOrderTaylor {
+    return FirstOrderTaylor::new(q("1 ohm"), q("0.004 1/K"), q("300 K")).unwrap();
+}
+
+fn support_points() -> Vec<(DynQuantity<f64>, DynQuantity<f64>)> {
+    return vec![
+        (q("0 K"), q("1 ohm")),
+        (q("300 K"), q("1.5 ohm")),
+        (q("600 K"), q("2.5 ohm")),
+        (q("900 K"), q("2.8 ohm")),
+    ];
+}
+
+#[test]
+fn test_unary_functions() {
+    check_batch(&Linear::new(q("0.01 ohm/K"), q("1 ohm")));
+    check_batch(&resistance());
+    check_batch(&Polynomial::new(vec![q("1e-5 ohm/K^2"), q("-0.007 ohm/K"), q("2 ohm")]).unwrap());
+    check_batch(&Polynomial::new(vec![q("2 ohm")]).unwrap());
+    check_batch(
+        &Exponential::new(vec![
+            ExpTerm {
+                amplitude: q("1 ohm"),
+                exponent: q("0.001 1/K"),
+            },
+            ExpTerm {
+                amplitude: q("2 ohm"),
+                exponent: q("-0.002 1/K"),
+            },
+        ])
+        .unwrap(),
+    );
+    check_batch(
+        &Arrhenius::new(q("1e6 ohm"), q("20 kJ/mol"))
+            .unwrap()
+            .with_temperature_exponent(-1.5),
+    );
+    check_batch(&PowerLaw::simple(q("2 ohm"), q("300 K"), 1.7).unwrap());
+    check_batch(&Logarithmic::new(q("1 ohm"), q("0.2 ohm"), q("300 K")).unwrap());
+    check_batch(
+        &InverseExponential::new(q("10 kohm"), q("3950 K"))
+            .with_reference(q("298.15 K"))
+            .unwrap(),
+    );
+    check_batch(&CallendarVanDusen::pt100());
+    check_batch(&Frohlich::new(q("160 A/(m*T)"), q("0.55 / T")).unwrap());
+    check_batch(&Langevin::new(q("1.6e6 A/m"), q("500 A/m")).unwrap());
+    check_batch(
+        &ArctanSaturation::new(q("1.8 T"), q("200 A/m"))
+            .unwrap()
+            .with_output(SaturationOutput::Permeability),
+    );
+    check_batch(&SteinhartHart::from_beta(q("10 kohm"), q("298.15 K"), q("3950 K")).unwrap());
+    for extrapolation in [
+        Extrapolation::Hold,
+        Extrapolation::Linear,
+        Extrapolation::Error,
+    ] {
+        check_batch(&Lookup::new(support_points(), extrapolation).unwrap());
+        check_batch(&Spline::new(support_points(), SplineKind::Natural, extrapolation).unwrap());
+    }
+
+    // Clamped variants
+    check_batch(&ClampedQuantity::new(2.0, 1.1, Linear::new(q("0.01 ohm/K"), q("1 ohm"))).unwrap());
+    check_batch(&ClampedQuantity::new(2.0, 0.5, resistance()).unwrap());
+}
+
+#[test]
+fn test_combinators() {
+    let current = || Box::new(Linear::new(q("0.1 1/A"), q("1")));
+    check_batch(&Sum::new(vec![Box::new(resistance()), Box::new(resistance())]).unwrap());
+    check_batch(&Product::new(vec![Box::new(resistance()), current()]));
+    check_batch(&Quotient::new(Box::new(resistance()), current()));
+    check_batch(&Scaled::new(q("2 A"), Box::new(resistance())));
+    check_batch(&Constant::new(q("2 ohm")));
+    check_batch(
+        &Clamped::new(Box::new(resistance()), Some(q("0.5 ohm")), Some(q("2 ohm"))).unwrap(),
+    );
+    check_batch(&InputClamped::new(Box::new(resistance()), q("200 K"), q("500 K")).unwrap());
+
+    // Composition: Temperature as a function of the current
+    let temperature = Linear::new(q("10 K/A"), q("300 K"));
+    check_batch(&Compose::new(Box::new(resistance()), Box::new(temperature)).unwrap());
+
+    check_batch(
+        &Expression::new(
+            "1 ohm + 0.01 ohm/K * T + 0.1 ohm/A * I",
+            [("T", q("0 K")), ("I", q("0 A"))],
+        )
+        .unwrap(),
+    );
+    // Missing columns fall back to the default values of the variables
+    check_batch(
+        &Expression::new(
+            "1 ohm + 0.01 ohm/K * T * I / (2 A)",
+            [("T", q("300 K")), ("I", q("3 A"))],
+        )
+        .unwrap(),
+    );
+}
+
+#[test]
+fn test_grid() {
+    for extrapolation in [
+        Extrapolation::Hold,
+        Extrapolation::Linear,
+        Extrapolation::Error,
+    ] {
+        check_batch(
+            &Grid::new(
+                vec![
+                    vec![q("0 K"), q("300 K"), q("600 K")],
+                    vec![q("0 A"), q("10 A")],
+                ],
+                vec![
+                    q("1 ohm"),
+                    q("2 ohm"),
+                    q("3 ohm"),
+                    q("5 ohm"),
+                    q("4 ohm"),
+                    q("8 ohm"),
+                ],
+                extrapolation,
+            )
+            .unwrap(),
+        );
+    }
+}
+
+#[test]
+#[should_panic]
+fn test_length_mismatch() {
+    let mut output = [0.0; 2];
+    resistance().call_batch(&[BatchInput::new(unit("1 K"), &[300.0])], &mut output);
+}
+
+#[test]
+fn test_function_wrapper() {
+    let fun = resistance()
+        .with_validity(ValidityRange::new(q("200 K"), q("500 K")).unwrap())
+        .unwrap();
+    let wrapper = FunctionWrapper::<ElectricalResistance>::new(Box::new(fun)).unwrap();
+    let inputs = [BatchInput::new(unit("1 K"), &TEMPERATURES)];
+    let expected =
+        |temperatures: [f64; 6]| temperatures.map(|t| resistance().call(&[q("1 K") * t]).value);
+    let mut output = [0.0; 6];
+
+    // Ignore
+    wrapper.call_batch(&inputs, &mut output);
+    assert_eq!(output, expected(TEMPERATURES));
+
+    // Clamp
+    let clamp = wrapper.clone().with_validity_policy(ValidityPolicy::Clamp);
+    clamp.call_batch(&inputs, &mut output);
+    assert_eq!(
+        output,
+        expected(TEMPERATURES.map(|t| t.clamp(200.0, 500.0)))
+    );
+
+    // Error
+    let error = wrapper.clone().with_validity_policy(ValidityPolicy::Error);
+    error.call_batch(&inputs, &mut output);
+    for (value, temperature) in output.iter().zip(TEMPERATURES) {
+        assert_eq!(value.is_nan(), !(200.0..=500.0).contains(&temperature));
+    }
+
+    // Warn
+    let warn = wrapper.with_validity_policy(ValidityPolicy::Warn);
+    warn.call_batch(&inputs, &mut output);
+    assert_eq!(output, expected(TEMPERATURES));
+    let warnings = warn.take_warnings();
+    assert_eq!(warnings.len(), 1);
+    assert_eq!(warnings[0].count(), 4);
+    assert_eq!(warnings[0].worst_input(), &q("1200 K"));
+    assert_eq!(
+        warnings[0].error(),
+        EvaluationError::InputOutOfRange {
+            input: q("1200 K"),
+            lower: q("200 K"),
+            upper: q("500 K")
+        }
+    );
+    assert!(warn.take_warnings().is_empty());
+
+    // Warnings of multiple batches and single evaluations are aggregated
+    warn.call_batch(&inputs, &mut output);
+    warn.call_batch(&inputs, &mut output);
+    warn.call(&[q("2000 K")]);
+    let warnings = warn.take_warnings();
+    assert_eq!(warnings.len(), 1);
+    assert_eq!(warnings[0].count(), 9);
+    assert_eq!(warnings[0].worst_input(), &q("2000 K"));
+}
+
+#[test]
+fn test_var_quantity() {
+    let inputs = [BatchInput::new(unit("1 K"), &TEMPERATURES)];
+    let mut output = [0.0; 6];
+
+    let function =
+        VarQuantity::<ElectricalResistance>::try_from_quantity_function(resistance()).unwrap();
+    function.get_batch(&inputs, &mut output);
+    assert_eq!(
+        output,
+        TEMPERATURES.map(|t| resistance().call(&[q("1 K") * t]).value)
+    );
+
+    // Quantities built via operators use the batch implementations of the combinators
+    let sum = function.clone() + function;
+    sum.get_batch(&inputs, &mut output);
+    assert_eq!(
+        output,
+        TEMPERATURES.map(|t| 2.0 * resistance().call(&[q("1 K") * t]).value)
+    );
+
+    let constant = VarQuantity::Constant(ElectricalResistance::new::<ohm>(3.0));
+    constant.get_batch(&inputs, &mut output);
+    assert_eq!(output, [3.0; 6]);
+}
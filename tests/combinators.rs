@@ -0,0 +1,521 @@
+use std::str::FromStr;
+
+use dyn_quantity::{DynQuantity, PredefUnit, Unit, UnitsNotEqual};
+use indoc::indoc;
+use uom::si::{
+    electrical_resistivity::ohm_meter, f64::*, frequency::hertz, thermodynamic_temperature::kelvin,
+};
+use var_quantity::{
+    ClampedQuantity, CompositionError, DualQuantity, EvaluationError, FunctionWrapper,
+    InfluencingFactor, QuantityFunction, VarQuantity,
+    combinators::*,
+    expression::Expression,
+    unary::{Extrapolation, Linear, Lookup, Polynomial},
+};
+
+fn q(s: &str) -> DynQuantity<f64> {
+    return DynQuantity::from_str(s).unwrap();
+}
+
+/// Resistivity depending on the temperature times a skin effect factor
+/// depending on the frequency.
+fn resistivity() -> Product {
+    let base = Linear::new(q("1e-10 ohm*m/K"), q("1.7e-8 ohm*m"));
+    let skin_effect = Polynomial::new(vec![q("1e-6 1/Hz^2"), q("0 1/Hz"), q("1")]).unwrap();
+    return Product::new(vec![Box::new(base), Box::new(skin_effect)]);
+}
+
+/// Compares the exact derivatives of `fun` with central differences.
+fn check_derivatives(fun: &dyn QuantityFunction, factors: &[DynQuantity<f64>]) {
+    for (index, factor) in factors.iter().enumerate() {
+        let derivative = fun.derivative(factors, factor.unit).unwrap();
+        assert_eq!(derivative.unit, fun.output_unit() / factor.unit);
+
+        let step = 1e-6 * factor.value.abs().max(1.0);
+        let mut upper = factors.to_vec();
+        upper[index].value += step;
+        let mut lower = factors.to_vec();
+        lower[index].value -= step;
+        let expected = (fun.call(&upper).value - fun.call(&lower).value) / (2.0 * step);
+        approx::assert_abs_diff_eq!(
+            derivative.value,
+            expected,
+            epsilon = 1e-6 * expected.abs().max(1e-12)
+        );
+
+        // call_dual yields the same derivative
+        let dual = fun.call_dual(&DualQuantity::seed(factors));
+        assert_eq!(dual.value, fun.call(factors));
+        approx::assert_abs_diff_eq!(
+            dual.gradient[index].value,
+            derivative.value,
+            epsilon = 1e-12 * derivative.value.abs().max(1e-12)
+        );
+    }
+
+    // Missing factor
+    let derivative = fun.derivative(&factors[1..], factors[0].unit).unwrap();
+    assert_eq!(derivative.value, 0.0);
+}
+
+#[test]
+fn test_sum() {
+    let sum = Sum::new(vec![
+        Box::new(Linear::new(q("0.5 ohm/K"), q("1 ohm"))),
+        Box::new(Linear::new(q("2 ohm/A"), q("3 ohm"))),
+    ])
+    .unwrap();
+    assert_eq!(
+        sum.output_unit(),
+        Unit::from(PredefUnit::ElectricResistance)
+    );
+    assert_eq!(
+        sum.influencing_units(),
+        Some(vec![
+            Unit::from(PredefUnit::Temperature),
+            Unit::from(PredefUnit::ElectricCurrent)
+        ])
+    );
+    assert_eq!(sum.call(&[q("2 K"), q("1 A")]), q("7 ohm"));
+    assert_eq!(
+        format!("{:?}", sum),
+        format!(
+            "Sum {{ output_unit: {:?}, functions: 2, .. }}",
+            sum.output_unit()
+        )
+    );
+    assert_eq!(sum.call(&[]), q("4 ohm"));
+    check_derivatives(&sum, &[q("2 K"), q("1 A")]);
+
+    // Empty sum
+    let empty = Sum::new(Vec::new()).unwrap();
+    assert_eq!(empty.call(&[q("2 K")]), q("0"));
+
+    // Unit mismatch
+    assert_eq!(
+        Sum::new(vec![
+            Box::new(Linear::new(q("0.5 ohm/K"), q("1 ohm"))),
+            Box::new(Linear::new(q("2 A/K"), q("3 A"))),
+        ])
+        .unwrap_err(),
+        UnitsNotEqual(
+            Unit::from(PredefUnit::ElectricResistance),
+            Unit::from(PredefUnit::ElectricCurrent)
+        )
+    );
+}
+
+#[test]
+fn test_product() {
+    let product = resistivity();
+    assert_eq!(product.output_unit(), q("1 ohm*m").unit);
+    assert_eq!(
+        product.influencing_units(),
+        Some(vec![
+            Unit::from(PredefUnit::Temperature),
+            Unit::from(PredefUnit::Frequency)
+        ])
+    );
+    approx::assert_abs_diff_eq!(
+        product.call(&[q("100 K"), q("1000 Hz")]).value,
+        2.7e-8 * 2.0,
+        epsilon = 1e-20
+    );
+    check_derivatives(&product, &[q("100 K"), q("1000 Hz")]);
+
+    assert_eq!(
+        format!("{:?}", product),
+        format!(
+            "Product {{ output_unit: {:?}, functions: 2, .. }}",
+            product.output_unit()
+        )
+    );
+
+    // Empty product
+    assert_eq!(Product::new(Vec::new()).call(&[]), q("1"));
+
+    // Wrapped in a VarQuantity
+    let wrapper = FunctionWrapper::<ElectricalResistivity>::new(Box::new(product)).unwrap();
+    let qt = VarQuantity::Function(wrapper);
+    let rho = qt.get(&[
+        ThermodynamicTemperature::new::<kelvin>(100.0).into(),
+        Frequency::new::<hertz>(1000.0).into(),
+    ]);
+    approx::assert_abs_diff_eq!(rho.get::<ohm_meter>(), 5.4e-8, epsilon = 1e-20);
+}
+
+#[test]
+fn test_quotient_and_scaled() {
+    let quotient = Quotient::new(
+        Box::new(Linear::new(q("2 A/s"), q("1 A"))),
+        Box::new(Linear::new(q("0.01 m^2/K"), q("0.5 m^2"))),
+    );
+    assert_eq!(quotient.output_unit(), q("1 A/m^2").unit);
+    assert_eq!(quotient.call(&[q("3 s"), q("50 K")]), q("7 A/m^2"));
+    check_derivatives(&quotient, &[q("3 s"), q("50 K")]);
+
+    let scaled = Scaled::new(q("-2 m^2"), Box::new(quotient));
+    assert_eq!(scaled.output_unit(), q("1 A").unit);
+    assert_eq!(scaled.call(&[q("3 s"), q("50 K")]), q("-14 A"));
+    check_derivatives(&scaled, &[q("3 s"), q("50 K")]);
+}
+
+#[test]
+fn test_named_and_fallible() {
+    let lookup = Lookup::new(
+        vec![(q("0 K"), q("1")), (q("100 K"), q("2"))],
+        Extrapolation::Error,
+    )
+    .unwrap()
+    .with_input_name("winding");
+    let base = Linear::new(q("0 ohm/A"), q("2 ohm"));
+    let product = Product::new(vec![Box::new(base), Box::new(lookup)]);
+
+    let factors = [
+        InfluencingFactor::new("ambient", q("300 K")),
+        InfluencingFactor::new("winding", q("50 K")),
+    ];
+    assert_eq!(product.call_named(&factors), q("3 ohm"));
+    assert_eq!(product.try_call_named(&factors), Ok(q("3 ohm")));
+
+    // The error of the inner function is propagated
+    assert!(product.call(&[q("300 K")]).value.is_nan());
+    assert_eq!(
+        product.try_call(&[q("300 K")]),
+        Err(EvaluationError::InputOutOfRange {
+            input: q("300 K"),
+            lower: q("0 K"),
+            upper: q("100 K")
+        })
+    );
+}
+
+#[test]
+fn test_compose() {
+    // Temperature depending on the losses and the ambient temperature
+    let temperature = Expression::new(
+        "ambient + 0.1 K/W * losses",
+        [("ambient", q("1 K")), ("losses", q("1 W"))],
+    )
+    .unwrap();
+    // Thermal conductivity depending on the temperature and the frequency
+    let conductivity = Product::new(vec![
+        Box::new(Linear::new(q("-0.1 W/(m*K^2)"), q("400 W/(m*K)"))),
+        Box::new(Linear::new(q("0.001 1/Hz"), q("1"))),
+    ]);
+    let fun = Compose::new(Box::new(conductivity), Box::new(temperature)).unwrap();
+    assert_eq!(fun.output_unit(), q("1 W/(m*K)").unit);
+    assert_eq!(
+        fun.influencing_units(),
+        Some(vec![
+            Unit::from(PredefUnit::Temperature),
+            Unit::from(PredefUnit::Power),
+            Unit::from(PredefUnit::Frequency)
+        ])
+    );
+
+    // The ambient temperature of 300 K is replaced by the computed temperature
+    // of 400 K before evaluating the conductivity
+    let factors = [q("300 K"), q("1000 W"), q("1000 Hz")];
+    approx::assert_abs_diff_eq!(fun.call(&factors).value, 720.0, epsilon = 1e-9);
+    check_derivatives(&fun, &factors);
+
+    // The outer function must consume the output unit of the inner function
+    let current = Linear::new(q("1 A/W"), q("0 A"));
+    let conductivity = Linear::new(q("-0.1 W/(m*K^2)"), q("400 W/(m*K)"));
+    assert_eq!(
+        Compose::new(Box::new(conductivity), Box::new(current)).unwrap_err(),
+        CompositionError::NotConsumed {
+            unit: Unit::from(PredefUnit::ElectricCurrent),
+            influencing_units: vec![Unit::from(PredefUnit::Temperature)]
+        }
+    );
+}
+
+#[test]
+fn test_compose_named_and_fallible() {
+    let temperature = Lookup::new(
+        vec![(q("0 W"), q("300 K")), (q("1000 W"), q("400 K"))],
+        Extrapolation::Error,
+    )
+    .unwrap();
+    let conductivity =
+        Linear::new(q("-0.1 W/(m*K^2)"), q("400 W/(m*K)")).with_input_name("winding_temperature");
+    let fun = Compose::new(Box::new(conductivity), Box::new(temperature))
+        .unwrap()
+        .with_output_name("winding_temperature");
+
+    let factors = [
+        InfluencingFactor::new("winding_temperature", q("20 K")),
+        InfluencingFactor::new("losses", q("500 W")),
+    ];
+    assert_eq!(fun.call_named(&factors), q("365 W/(m*K)"));
+    assert_eq!(fun.try_call_named(&factors), Ok(q("365 W/(m*K)")));
+
+    // Only the factor with the output name is replaced
+    let ambient =
+        Linear::new(q("-0.1 W/(m*K^2)"), q("400 W/(m*K)")).with_input_name("ambient_temperature");
+    let temperature = Linear::new(q("0.1 K/W"), q("300 K"));
+    let named = Compose::new(Box::new(ambient), Box::new(temperature.clone()))
+        .unwrap()
+        .with_output_name("winding_temperature");
+    let factors_with_ambient = [
+        InfluencingFactor::new("ambient_temperature", q("20 K")),
+        InfluencingFactor::new("winding_temperature", q("50 K")),
+        InfluencingFactor::new("losses", q("500 W")),
+    ];
+    assert_eq!(named.call_named(&factors_with_ambient), q("398 W/(m*K)"));
+
+    // Without an output name, the outer function is evaluated without names
+    let conductivity =
+        Linear::new(q("-0.1 W/(m*K^2)"), q("400 W/(m*K)")).with_input_name("winding_temperature");
+    let unnamed = Compose::new(Box::new(conductivity), Box::new(temperature)).unwrap();
+    assert_eq!(unnamed.output_name(), None);
+    assert_eq!(unnamed.call_named(&factors), q("365 W/(m*K)"));
+    assert_eq!(unnamed.try_call_named(&factors), Ok(q("365 W/(m*K)")));
+
+    // The error of the inner function is propagated
+    assert_eq!(
+        fun.try_call(&[q("2000 W")]),
+        Err(EvaluationError::InputOutOfRange {
+            input: q("2000 W"),
+            lower: q("0 W"),
+            upper: q("1000 W")
+        })
+    );
+}
+
+#[test]
+fn test_input_clamped() {
+    // Parabola with its minimum at 350 K, fitted between 200 K and 500 K
+    let fit = Polynomial::new(vec![q("1e-5 ohm/K^2"), q("-0.007 ohm/K"), q("2 ohm")]).unwrap();
+    let fun = InputClamped::new(Box::new(fit.clone()), q("200 K"), q("500 K")).unwrap();
+    assert_eq!(fun.output_unit(), fit.output_unit());
+    assert_eq!(fun.influencing_units(), fit.influencing_units());
+
+    // Inside the limits, the function is unchanged
+    let factors = [q("400 K"), q("1 A")];
+    assert_eq!(fun.call(&factors), fit.call(&factors));
+    check_derivatives(&fun, &factors);
+
+    // Outside of the limits, the curve is held flat. Clamping the output
+    // instead would let the parabola rise again up to the output limit.
+    assert_eq!(fun.call(&[q("600 K")]), fit.call(&[q("500 K")]));
+    assert_eq!(fun.call(&[q("100 K")]), fit.call(&[q("200 K")]));
+    assert!(fit.call(&[q("100 K")]).value > fit.call(&[q("200 K")]).value);
+    assert_eq!(
+        fun.derivative(&[q("600 K")], q("1 K").unit),
+        Some(q("0 ohm/K"))
+    );
+    let dual = fun.call_dual(&DualQuantity::seed(&[q("600 K")]));
+    assert_eq!(dual.value, fun.call(&[q("600 K")]));
+    assert!(dual.gradient.iter().all(|g| g.value == 0.0));
+
+    assert!(format!("{:?}", fun).starts_with(&format!(
+        "InputClamped {{ output_unit: {:?}, lower_limit: ",
+        fit.output_unit()
+    )));
+
+    // Fallible evaluation
+    assert_eq!(fun.try_call(&[q("600 K")]), Ok(fun.call(&[q("600 K")])));
+
+    // Only the named factor is clamped
+    let named = InputClamped::new(
+        Box::new(fit.clone().with_input_name("winding")),
+        q("200 K"),
+        q("500 K"),
+    )
+    .unwrap()
+    .with_input_name("winding");
+    let factors = [
+        InfluencingFactor::new("ambient", q("100 K")),
+        InfluencingFactor::new("winding", q("600 K")),
+    ];
+    assert_eq!(named.call_named(&factors), fit.call(&[q("500 K")]));
+    assert_eq!(named.try_call_named(&factors), Ok(fit.call(&[q("500 K")])));
+
+    // Invalid limits
+    assert!(InputClamped::new(Box::new(fit.clone()), q("200 K"), q("500 A")).is_err());
+    assert!(InputClamped::new(Box::new(fit.clone()), q("500 K"), q("200 K")).is_err());
+    let nan = DynQuantity::new(f64::NAN, PredefUnit::Temperature);
+    assert!(InputClamped::new(Box::new(fit.clone()), nan, q("500 K")).is_err());
+    assert!(InputClamped::new(Box::new(fit.clone()), q("200 K"), nan).is_err());
+
+    // One-sided limits
+    let infinity = DynQuantity::new(f64::INFINITY, PredefUnit::Temperature);
+    let fun = InputClamped::new(Box::new(fit.clone()), q("200 K"), infinity).unwrap();
+    assert_eq!(fun.call(&[q("100 K")]), fit.call(&[q("200 K")]));
+    assert_eq!(fun.call(&[q("600 K")]), fit.call(&[q("600 K")]));
+}
+
+#[test]
+fn test_clamped() {
+    let sum = Sum::new(vec![
+        Box::new(Linear::new(q("1 ohm/K"), q("0 ohm"))),
+        Box::new(Linear::new(q("1 ohm/K"), q("0 ohm"))),
+    ])
+    .unwrap();
+    assert!(ClampedQuantity::new(f64::NAN, 0.0, sum.clone()).is_err());
+    let clamped = ClampedQuantity::new(10.0, 0.0, sum).unwrap();
+    assert_eq!(clamped.call(&[q("2 K")]), q("4 ohm"));
+    assert_eq!(clamped.call(&[q("20 K")]), q("10 ohm"));
+    assert_eq!(
+        clamped
+            .derivative(&[q("20 K")], q("1 K").unit)
+            .unwrap()
+            .value,
+        0.0
+    );
+
+    // The non-generic wrapper behaves identically
+    let inner = Linear::new(q("2 ohm/K"), q("0 ohm"));
+    let clamped =
+        Clamped::new(Box::new(inner.clone()), Some(q("0 ohm")), Some(q("10 ohm"))).unwrap();
+    assert_eq!(clamped.output_unit(), inner.output_unit());
+    assert_eq!(clamped.influencing_units(), inner.influencing_units());
+    assert_eq!(clamped.call(&[q("2 K")]), q("4 ohm"));
+    assert_eq!(clamped.call(&[q("20 K")]), q("10 ohm"));
+    assert_eq!(clamped.call(&[q("-20 K")]), q("0 ohm"));
+    assert_eq!(clamped.try_call(&[q("20 K")]), Ok(q("10 ohm")));
+    check_derivatives(&clamped, &[q("2 K")]);
+    assert_eq!(
+        clamped.derivative(&[q("20 K")], q("1 K").unit),
+        Some(q("0 ohm/K"))
+    );
+    let dual = clamped.call_dual(&DualQuantity::seed(&[q("20 K")]));
+    assert_eq!(dual.value, q("10 ohm"));
+    assert!(dual.gradient.iter().all(|g| g.value == 0.0));
+
+    assert_eq!(
+        format!("{:?}", clamped),
+        format!(
+            "Clamped {{ output_unit: {:?}, lower_limit: 0.0, upper_limit: 10.0, .. }}",
+            inner.output_unit()
+        )
+    );
+
+    // One-sided limits
+    let clamped = Clamped::new(Box::new(inner.clone()), None, Some(q("10 ohm"))).unwrap();
+    assert_eq!(clamped.lower_limit(), f64::NEG_INFINITY);
+    assert_eq!(clamped.call(&[q("-20 K")]), q("-40 ohm"));
+
+    // Invalid limits
+    assert!(Clamped::new(Box::new(inner.clone()), Some(q("0 A")), None).is_err());
+    assert!(Clamped::new(Box::new(inner), Some(q("10 ohm")), Some(q("0 ohm"))).is_err());
+}
+
+#[test]
+fn test_serde() {
+    let yaml = indoc! {"
+        ---
+        Sum:
+          functions:
+            - Linear:
+                slope: 1e-10 ohm*m/K
+                base_value: 1.7e-8 ohm*m
+            - Scaled:
+                factor: 1e-8 ohm*m
+                function:
+                  Quotient:
+                    numerator:
+                      Linear:
+                        slope: 1 1/Hz
+                        base_value: 0
+                    denominator:
+                      Linear:
+                        slope: 0 1/K
+                        base_value: 1000
+        "};
+    let fun: Box<dyn QuantityFunction> = serde_yaml::from_str(yaml).unwrap();
+    let factors = [q("100 K"), q("1000 Hz")];
+    approx::assert_abs_diff_eq!(fun.call(&factors).value, 3.7e-8, epsilon = 1e-20);
+
+    // Round trip
+    let serialized = serde_yaml::to_string(&fun).unwrap();
+    let deserialized: Box<dyn QuantityFunction> = serde_yaml::from_str(&serialized).unwrap();
+    assert_eq!(deserialized.call(&factors), fun.call(&factors));
+
+    let product: Box<dyn QuantityFunction> = Box::new(resistivity());
+    let serialized = serde_yaml::to_string(&product).unwrap();
+    let deserialized: Box<dyn QuantityFunction> = serde_yaml::from_str(&serialized).unwrap();
+    assert_eq!(deserialized.call(&factors), product.call(&factors));
+
+    // The units of a sum are checked during deserialization
+    let yaml = indoc! {"
+        ---
+        Sum:
+          functions:
+            - Linear:
+                slope: 1 ohm/K
+                base_value: 1 ohm
+            - Linear:
+                slope: 1 A/K
+                base_value: 1 A
+        "};
+    assert!(serde_yaml::from_str::<Box<dyn QuantityFunction>>(yaml).is_err());
+
+    // Composition
+    let yaml = indoc! {"
+        ---
+        Compose:
+          outer:
+            Linear:
+              slope: -0.1 W/(m*K^2)
+              base_value: 400 W/(m*K)
+          inner:
+            Linear:
+              slope: 0.1 K/W
+              base_value: 300 K
+          output_name: winding_temperature
+        "};
+    let fun: Box<dyn QuantityFunction> = serde_yaml::from_str(yaml).unwrap();
+    assert_eq!(fun.call(&[q("1000 W")]), q("360 W/(m*K)"));
+    let serialized = serde_yaml::to_string(&fun).unwrap();
+    assert!(serialized.contains("output_name: winding_temperature"));
+    let deserialized: Box<dyn QuantityFunction> = serde_yaml::from_str(&serialized).unwrap();
+    assert_eq!(deserialized.call(&[q("1000 W")]), q("360 W/(m*K)"));
+
+    // The outer function must consume the output of the inner function
+    let yaml = indoc! {"
+        ---
+        Compose:
+          outer:
+            Linear:
+              slope: 1 ohm/K
+              base_value: 1 ohm
+          inner:
+            Linear:
+              slope: 1 A/W
+              base_value: 0 A
+        "};
+    assert!(serde_yaml::from_str::<Box<dyn QuantityFunction>>(yaml).is_err());
+
+    // Input clamping
+    let yaml = indoc! {"
+        ---
+        InputClamped:
+          function:
+            Polynomial:
+              coefficients: [1e-5 ohm/K^2, -0.007 ohm/K, 2 ohm]
+          lower_limit: 200 K
+          upper_limit: 500 K
+        "};
+    let fun: Box<dyn QuantityFunction> = serde_yaml::from_str(yaml).unwrap();
+    assert_eq!(fun.call(&[q("600 K")]), fun.call(&[q("500 K")]));
+    let serialized = serde_yaml::to_string(&fun).unwrap();
+    let deserialized: Box<dyn QuantityFunction> = serde_yaml::from_str(&serialized).unwrap();
+    assert_eq!(deserialized.call(&[q("600 K")]), fun.call(&[q("600 K")]));
+
+    // The limits are checked during deserialization
+    let yaml = indoc! {"
+        ---
+        InputClamped:
+          function:
+            Linear:
+              slope: 1 ohm/K
+              base_value: 1 ohm
+          lower_limit: 500 K
+          upper_limit: 200 K
+        "};
+    assert!(serde_yaml::from_str::<Box<dyn QuantityFunction>>(yaml).is_err());
+}
@@ -0,0 +1,355 @@
+use std::str::FromStr;
+
+use dyn_quantity::{DynQuantity, Unit};
+use uom::si::{electrical_resistance::ohm, f64::*};
+use var_quantity::{
+    ClampedQuantity, EvaluationError, FunctionWrapper, InfluencingFactor, Layout, QuantityFunction,
+    VarQuantity,
+    combinators::*,
+    expression::Expression,
+    multivariate::Grid,
+    unary::*,
+    validity::{ValidityPolicy, ValidityRange},
+};
+
+fn q(s: &str) -> DynQuantity<f64> {
+    return DynQuantity::from_str(s).unwrap();
+}
+
+fn unit(s: &str) -> Unit {
+    return q(s).unit;
+}
+
+const TEMPERATURES: [f64; 6] = [-50.0, 0.0, 150.0, 293.15, 500.0, 1200.0];
+const CURRENTS: [f64; 6] = [0.0, 1.0, 2.0, 5.0, 10.0, 20.0];
+
+fn assert_same(value: f64, expected: f64) {
+    if expected.is_nan() {
+        assert!(value.is_nan());
+    } else {
+        approx::assert_abs_diff_eq!(value, expected, epsilon = 1e-12 * expected.abs().max(1.0));
+    }
+}
+
+/// Asserts that the bound evaluator yields the same values as `call`.
+fn check_bind(fun: &dyn QuantityFunction) {
+    for units in [
+        vec![unit("1 K")],
+        vec![unit("1 A"), unit("1 K")],
+        vec![unit("1 K"), unit("1 A")],
+        vec![unit("1 A")],
+        vec![],
+    ] {
+        let layout = Layout::new(units.clone());
+        let evaluator = fun.bind(&layout);
+        for (temperature, current) in TEMPERATURES.iter().zip(CURRENTS.iter()) {
+            let values: Vec<f64> = units
+                .iter()
+                .map(|u| match *u == unit("1 K") {
+                    true => *temperature,
+                    false => *current,
+                })
+                .collect();
+            let factors: Vec<DynQuantity<f64>> = units
+                .iter()
+                .zip(values.iter())
+                .map(|(u, v)| DynQuantity::new(*v, *u))
+                .collect();
+            assert_same(evaluator.eval(&values), fun.call(&factors).value);
+        }
+    }
+}
+
+fn resistance() -> FirstOrderTaylor {
+    return FirstOrderTaylor::new(q("1 ohm"), q("0.004 1/K"), q("300 K")).unwrap();
+}
+
+fn support_points() -> Vec<(DynQuantity<f64>, DynQuantity<f64>)> {
+    return vec![
+        (q("0 K"), q("1 ohm")),
+        (q("300 K"), q("1.5 ohm")),
+        (q("600 K"), q("2.5 ohm")),
+        (q("900 K"), q("2.8 ohm")),
+    ];
+}
+
+#[test]
+fn test_unary_functions() {
+    check_bind(&Linear::new(q("0.01 ohm/K"), q("1 ohm")));
+    check_bind(&resistance());
+    check_bind(&Polynomial::new(vec![q("1e-5 ohm/K^2"), q("-0.007 ohm/K"), q("2 ohm")]).unwrap());
+    check_bind(
+        &Exponential::new(vec![ExpTerm {
+            amplitude: q("1 ohm"),
+            exponent: q("0.001 1/K"),
+        }])
+        .unwrap(),
+    );
+    check_bind(
+        &Arrhenius::new(q("1e6 ohm"), q("20 kJ/mol"))
+            .unwrap()
+            .with_temperature_exponent(-1.5),
+    );
+    check_bind(&PowerLaw::simple(q("2 ohm"), q("300 K"), 1.7).unwrap());
+    check_bind(&Logarithmic::new(q("1 ohm"), q("0.2 ohm"), q("300 K")).unwrap());
+    check_bind(
+        &InverseExponential::new(q("10 kohm"), q("3950 K"))
+            .with_reference(q("298.15 K"))
+            .unwrap(),
+    );
+    check_bind(&CallendarVanDusen::pt100());
+    check_bind(&Frohlich::new(q("160 A/(m*T)"), q("0.55 / T")).unwrap());
+    check_bind(&Langevin::new(q("1.6e6 A/m"), q("500 A/m")).unwrap());
+    check_bind(
+        &ArctanSaturation::new(q("1.8 T"), q("200 A/m"))
+            .unwrap()
+            .with_output(SaturationOutput::Permeability),
+    );
+    check_bind(&SteinhartHart::from_beta(q("10 kohm"), q("298.15 K"), q("3950 K")).unwrap());
+    for extrapolation in [
+        Extrapolation::Hold,
+        Extrapolation::Linear,
+        Extrapolation::Error,
+    ] {
+        check_bind(&Lookup::new(support_points(), extrapolation).unwrap());
+        check_bind(&Spline::new(support_points(), SplineKind::Natural, extrapolation).unwrap());
+    }
+
+    // Clamped variants
+    check_bind(&ClampedQuantity::new(2.0, 1.1, Linear::new(q("0.01 ohm/K"), q("1 ohm"))).unwrap());
+    check_bind(&ClampedQuantity::new(2.0, 0.5, resistance()).unwrap());
+}
+
+#[test]
+fn test_combinators() {
+    let current = || Box::new(Linear::new(q("0.1 1/A"), q("1")));
+    check_bind(&Sum::new(vec![Box::new(resistance()), Box::new(resistance())]).unwrap());
+    check_bind(&Product::new(vec![Box::new(resistance()), current()]));
+    check_bind(&Quotient::new(Box::new(resistance()), current()));
+    check_bind(&Scaled::new(q("2 A"), Box::new(resistance())));
+    check_bind(&Constant::new(q("2 ohm")));
+    check_bind(
+        &Clamped::new(Box::new(resistance()), Some(q("0.5 ohm")), Some(q("2 ohm"))).unwrap(),
+    );
+    check_bind(&InputClamped::new(Box::new(resistance()), q("200 K"), q("500 K")).unwrap());
+
+    // Composition: Temperature as a function of the current
+    let temperature = Linear::new(q("10 K/A"), q("300 K"));
+    check_bind(&Compose::new(Box::new(resistance()), Box::new(temperature)).unwrap());
+
+    check_bind(
+        &Expression::new(
+            "1 ohm + 0.01 ohm/K * T + 0.1 ohm/A * I",
+            [("T", q("0 K")), ("I", q("0 A"))],
+        )
+        .unwrap(),
+    );
+    // Missing factors fall back to the default values of the variables
+    check_bind(
+        &Expression::new(
+            "1 ohm + 0.01 ohm/K * T * I / (2 A)",
+            [("T", q("300 K")), ("I", q("3 A"))],
+        )
+        .unwrap(),
+    );
+}
+
+#[test]
+fn test_grid() {
+    for extrapolation in [
+        Extrapolation::Hold,
+        Extrapolation::Linear,
+        Extrapolation::Error,
+    ] {
+        check_bind(
+            &Grid::new(
+                vec![
+                    vec![q("0 K"), q("300 K"), q("600 K")],
+                    vec![q("0 A"), q("10 A")],
+                ],
+                vec![
+                    q("1 ohm"),
+                    q("2 ohm"),
+                    q("3 ohm"),
+                    q("5 ohm"),
+                    q("4 ohm"),
+                    q("8 ohm"),
+                ],
+                extrapolation,
+            )
+            .unwrap(),
+        );
+    }
+}
+
+#[test]
+fn test_named_layout() {
+    // Difference between the winding and the ambient temperature
+    let winding = Linear::new(q("1"), q("0 K")).with_input_name("winding");
+    let ambient = Linear::new(q("-1"), q("0 K")).with_input_name("ambient");
+    let fun = Sum::new(vec![Box::new(winding), Box::new(ambient)]).unwrap();
+
+    let layout = Layout::named([("ambient", unit("1 K")), ("winding", unit("1 K"))]);
+    let evaluator = fun.bind(&layout);
+    let factors = [
+        InfluencingFactor::new("ambient", q("300 K")),
+        InfluencingFactor::new("winding", q("400 K")),
+    ];
+    assert_eq!(evaluator.eval(&[300.0, 400.0]), 100.0);
+    assert_eq!(
+        evaluator.eval(&[300.0, 400.0]),
+        fun.call_named(&factors).value
+    );
+
+    // Without names, the first temperature is used for both terms
+    let evaluator = fun.bind(&Layout::new(vec![unit("1 K"), unit("1 K")]));
+    assert_eq!(evaluator.eval(&[300.0, 400.0]), 0.0);
+
+    // Expressions select their variables by name as well
+    let fun = Expression::new(
+        "winding - ambient",
+        [("ambient", q("0 K")), ("winding", q("0 K"))],
+    )
+    .unwrap();
+    let evaluator = fun.bind(&layout);
+    assert_eq!(evaluator.eval(&[300.0, 400.0]), 100.0);
+    assert_eq!(
+        evaluator.eval(&[300.0, 400.0]),
+        fun.call_named(&factors).value
+    );
+
+    // The clamped input is selected by name
+    let winding = Linear::new(q("1"), q("0 K")).with_input_name("winding");
+    let clamped = InputClamped::new(Box::new(winding), q("350 K"), q("500 K"))
+        .unwrap()
+        .with_input_name("winding");
+    let evaluator = clamped.bind(&layout);
+    assert_eq!(evaluator.eval(&[300.0, 400.0]), 400.0);
+    assert_eq!(evaluator.eval(&[400.0, 300.0]), 350.0);
+    assert_eq!(
+        evaluator.eval(&[400.0, 300.0]),
+        clamped
+            .call_named(&[
+                InfluencingFactor::new("ambient", q("400 K")),
+                InfluencingFactor::new("winding", q("300 K")),
+            ])
+            .value
+    );
+
+    // The output of the inner function replaces the factor with its name
+    let heating = Linear::new(q("2"), q("0 K")).with_input_name("ambient");
+    let composed = Compose::new(Box::new(fun.clone()), Box::new(heating))
+        .unwrap()
+        .with_output_name("winding");
+    let evaluator = composed.bind(&layout);
+    assert_eq!(evaluator.eval(&[300.0, 400.0]), 300.0);
+    assert_eq!(
+        evaluator.eval(&[300.0, 400.0]),
+        composed.call_named(&factors).value
+    );
+
+    // Variables without a matching name use their default value
+    let layout = Layout::named([("ambient", unit("1 K")), ("coolant", unit("1 K"))]);
+    let factors = [
+        InfluencingFactor::new("ambient", q("300 K")),
+        InfluencingFactor::new("coolant", q("400 K")),
+    ];
+    let evaluator = fun.bind(&layout);
+    assert_eq!(evaluator.eval(&[300.0, 400.0]), -300.0);
+    assert_eq!(
+        evaluator.eval(&[300.0, 400.0]),
+        fun.call_named(&factors).value
+    );
+}
+
+#[test]
+fn test_function_wrapper() {
+    let fun = resistance()
+        .with_validity(ValidityRange::new(q("200 K"), q("500 K")).unwrap())
+        .unwrap();
+    let wrapper = FunctionWrapper::<ElectricalResistance>::new(Box::new(fun)).unwrap();
+    let layout = Layout::new(vec![unit("1 A"), unit("1 K")]);
+    let expected = |temperature: f64| resistance().call(&[q("1 K") * temperature]).value;
+
+    // Ignore
+    let evaluator = wrapper.bind(&layout);
+    for temperature in TEMPERATURES {
+        assert_eq!(evaluator.eval(&[1.0, temperature]), expected(temperature));
+    }
+
+    // Clamp
+    let clamp = wrapper.clone().with_validity_policy(ValidityPolicy::Clamp);
+    let evaluator = clamp.bind(&layout);
+    for temperature in TEMPERATURES {
+        assert_eq!(
+            evaluator.eval(&[1.0, temperature]),
+            expected(temperature.clamp(200.0, 500.0))
+        );
+    }
+
+    // Error
+    let error = wrapper.clone().with_validity_policy(ValidityPolicy::Error);
+    let evaluator = error.bind(&layout);
+    for temperature in TEMPERATURES {
+        assert_eq!(
+            evaluator.eval(&[1.0, temperature]).is_nan(),
+            !(200.0..=500.0).contains(&temperature)
+        );
+    }
+
+    // Warn
+    let warn = wrapper.clone().with_validity_policy(ValidityPolicy::Warn);
+    let evaluator = warn.bind(&layout);
+    for temperature in TEMPERATURES {
+        assert_eq!(evaluator.eval(&[1.0, temperature]), expected(temperature));
+    }
+    drop(evaluator);
+    let warnings = warn.take_warnings();
+    assert_eq!(warnings.len(), 1);
+    assert_eq!(warnings[0].count(), 4);
+    assert_eq!(warnings[0].worst_input(), &q("1200 K"));
+    assert_eq!(
+        warnings[0].error(),
+        EvaluationError::InputOutOfRange {
+            input: q("1200 K"),
+            lower: q("200 K"),
+            upper: q("500 K")
+        }
+    );
+    assert!(warn.take_warnings().is_empty());
+
+    // Concurrent evaluations are counted exactly
+    let evaluator = warn.bind(&layout);
+    std::thread::scope(|scope| {
+        for thread in 0..4 {
+            let evaluator = &evaluator;
+            scope.spawn(move || {
+                for step in 0..1000 {
+                    evaluator.eval(&[1.0, 600.0 + (thread * 1000 + step) as f64]);
+                }
+            });
+        }
+    });
+    drop(evaluator);
+    let warnings = warn.take_warnings();
+    assert_eq!(warnings[0].count(), 4000);
+    assert_eq!(warnings[0].worst_input(), &q("4599 K"));
+}
+
+#[test]
+fn test_var_quantity() {
+    let layout = Layout::new(vec![unit("1 K")]);
+
+    let function =
+        VarQuantity::<ElectricalResistance>::try_from_quantity_function(resistance()).unwrap();
+    let evaluator = function.bind(&layout);
+    for temperature in TEMPERATURES {
+        assert_eq!(
+            evaluator.eval(&[temperature]),
+            function.get(&[q("1 K") * temperature]).get::<ohm>()
+        );
+    }
+
+    let constant = VarQuantity::Constant(ElectricalResistance::new::<ohm>(3.0));
+    assert_eq!(constant.bind(&layout).eval(&[300.0]), 3.0);
+}
@@ -0,0 +1,343 @@
+use std::str::FromStr;
+
+use dyn_quantity::{DynQuantity, PredefUnit, Unit};
+use serde::{Deserialize, Serialize};
+use uom::si::{
+    electrical_resistance::ohm, f64::*, magnetic_flux_density::tesla,
+    thermodynamic_temperature::kelvin,
+};
+use var_quantity::{ClampedQuantity, QuantityFunction, VarQuantity, multivariate::Grid, unary::*};
+
+fn q(s: &str) -> DynQuantity<f64> {
+    return DynQuantity::from_str(s).unwrap();
+}
+
+/// Central difference of `fun` at `x` as reference for the exact derivatives.
+fn finite_difference(fun: &dyn QuantityFunction, x: DynQuantity<f64>) -> f64 {
+    let step = 1e-6 * x.value.abs().max(1.0);
+    let upper = fun.call(&[DynQuantity::new(x.value + step, x.unit)]).value;
+    let lower = fun.call(&[DynQuantity::new(x.value - step, x.unit)]).value;
+    return (upper - lower) / (2.0 * step);
+}
+
+fn check_unary(fun: &dyn QuantityFunction, inputs: &[DynQuantity<f64>]) {
+    let input_unit = fun.influencing_units().expect("declared units")[0];
+    let expected_unit = fun.output_unit() / input_unit;
+    for x in inputs {
+        let derivative = fun.derivative(&[*x], input_unit).unwrap();
+        assert_eq!(derivative.unit, expected_unit);
+        approx::assert_abs_diff_eq!(
+            derivative.value,
+            finite_difference(fun, *x),
+            epsilon = 1e-6 * derivative.value.abs().max(1.0)
+        );
+
+        // The function does not depend on another unit
+        let other = fun.derivative(&[*x], PredefUnit::Force.into()).unwrap();
+        assert_eq!(other.value, 0.0);
+        assert_eq!(
+            other.unit,
+            fun.output_unit() / Unit::from(PredefUnit::Force)
+        );
+    }
+
+    // Missing input -> constant default
+    assert_eq!(fun.derivative(&[], input_unit).unwrap().value, 0.0);
+}
+
+#[test]
+fn test_unary_derivatives() {
+    let temperatures = [q("-20 K"), q("0 K"), q("35 K"), q("120 K")];
+
+    let linear = Linear::new(q("0.5 ohm/K"), q("1 ohm"));
+    check_unary(&linear, &temperatures);
+    assert_eq!(
+        linear.derivative(&[q("10 K")], PredefUnit::Temperature.into()),
+        Some(q("0.5 ohm/K"))
+    );
+
+    let fot = FirstOrderTaylor::new(q("2 ohm"), q("0.004 / K"), q("293 K")).unwrap();
+    check_unary(&fot, &temperatures);
+
+    let polynomial = Polynomial::new(vec![q("0.01 ohm/K^2"), q("-0.5 ohm/K"), q("3 ohm")]).unwrap();
+    check_unary(&polynomial, &temperatures);
+    assert_eq!(
+        polynomial
+            .derivative(&[q("10 K")], PredefUnit::Temperature.into())
+            .unwrap()
+            .value,
+        -0.3
+    );
+
+    // Constant polynomial
+    let constant = Polynomial::new(vec![q("3 ohm")]).unwrap();
+    assert_eq!(
+        constant
+            .derivative(&[q("2")], Unit::default())
+            .unwrap()
+            .value,
+        0.0
+    );
+
+    let exponential = Exponential::new(vec![
+        ExpTerm {
+            amplitude: q("2 ohm"),
+            exponent: q("0.01 / K"),
+        },
+        ExpTerm {
+            amplitude: q("-1 ohm"),
+            exponent: q("-0.02 / K"),
+        },
+    ])
+    .unwrap();
+    check_unary(&exponential, &temperatures);
+
+    let arrhenius = Arrhenius::new(q("1e6 ohm"), q("20 kJ/mol"))
+        .unwrap()
+        .with_temperature_exponent(-1.5);
+    check_unary(&arrhenius, &[q("250 K"), q("300 K"), q("420 K")]);
+
+    let power_law = PowerLaw::new(
+        vec![
+            PowerTerm {
+                coefficient: q("1 ohm"),
+                exponent: 0.5,
+            },
+            PowerTerm {
+                coefficient: q("0.1 ohm"),
+                exponent: 2.3,
+            },
+        ],
+        q("300 K"),
+    )
+    .unwrap();
+    check_unary(&power_law, &[q("35 K"), q("120 K"), q("600 K")]);
+
+    let logarithmic = Logarithmic::new(q("1 ohm"), q("0.2 ohm"), q("300 K")).unwrap();
+    check_unary(&logarithmic, &[q("35 K"), q("120 K"), q("600 K")]);
+
+    let inverse_exponential = InverseExponential::new(q("10 kohm"), q("3950 K"))
+        .with_reference(q("298.15 K"))
+        .unwrap();
+    check_unary(&inverse_exponential, &[q("250 K"), q("300 K"), q("420 K")]);
+
+    check_unary(
+        &CallendarVanDusen::pt1000(),
+        &[q("100 K"), q("250 K"), q("300 K"), q("900 K")],
+    );
+
+    let steinhart_hart =
+        SteinhartHart::new(q("1.009e-3 / K"), q("2.378e-4 / K"), q("2.019e-7 / K")).unwrap();
+    check_unary(
+        &steinhart_hart,
+        &[q("500 ohm"), q("10 kohm"), q("200 kohm")],
+    );
+
+    let points = vec![
+        (q("0 K"), q("1 ohm")),
+        (q("50 K"), q("2 ohm")),
+        (q("100 K"), q("4 ohm")),
+    ];
+    let inside = [q("10 K"), q("35 K"), q("60 K"), q("99 K")];
+
+    let lookup = Lookup::new(points.clone(), Extrapolation::Linear).unwrap();
+    check_unary(&lookup, &inside);
+    assert_eq!(
+        lookup
+            .derivative(&[q("150 K")], PredefUnit::Temperature.into())
+            .unwrap()
+            .value,
+        0.04
+    );
+
+    for kind in [SplineKind::Natural, SplineKind::Pchip, SplineKind::Akima] {
+        let spline = Spline::new(points.clone(), kind, Extrapolation::Linear).unwrap();
+        check_unary(&spline, &inside);
+        check_unary(&spline, &[q("-10 K"), q("150 K")]);
+
+        // Hold -> zero derivative outside of the support points
+        let spline = Spline::new(points.clone(), kind, Extrapolation::Hold).unwrap();
+        assert_eq!(
+            spline
+                .derivative(&[q("150 K")], PredefUnit::Temperature.into())
+                .unwrap()
+                .value,
+            0.0
+        );
+
+        // Error -> NaN outside of the support points
+        let spline = Spline::new(points.clone(), kind, Extrapolation::Error).unwrap();
+        assert!(
+            spline
+                .derivative(&[q("150 K")], PredefUnit::Temperature.into())
+                .unwrap()
+                .value
+                .is_nan()
+        );
+    }
+}
+
+#[test]
+fn test_saturation_derivatives() {
+    // Includes fields in the range of the series expansions
+    let fields = [q("-3000 A/m"), q("1 A/m"), q("100 A/m"), q("2000 A/m")];
+    for output in [
+        SaturationOutput::FluxDensity,
+        SaturationOutput::Permeability,
+    ] {
+        let functions: Vec<Box<dyn QuantityFunction>> = vec![
+            Box::new(
+                Frohlich::new(q("160 A/(m*T)"), q("0.55 / T"))
+                    .unwrap()
+                    .with_output(output),
+            ),
+            Box::new(
+                Langevin::new(q("1.6e6 A/m"), q("500 A/m"))
+                    .unwrap()
+                    .with_output(output),
+            ),
+            Box::new(
+                Langevin::new(q("1.6e6 A/m"), q("500 A/m"))
+                    .unwrap()
+                    .with_total_angular_momentum(1.5)
+                    .with_output(output),
+            ),
+            Box::new(
+                ArctanSaturation::new(q("1.8 T"), q("200 A/m"))
+                    .unwrap()
+                    .with_output(output),
+            ),
+        ];
+        for fun in functions.iter() {
+            check_unary(fun.as_ref(), &fields);
+
+            // The derivatives are small compared to the absolute tolerance of
+            // check_unary, hence compare them relatively
+            for h in [q("-3000 A/m"), q("100 A/m"), q("2000 A/m")] {
+                let derivative = fun.derivative(&[h], h.unit).unwrap().value;
+                approx::assert_relative_eq!(
+                    derivative,
+                    finite_difference(fun.as_ref(), h),
+                    max_relative = 1e-5
+                );
+            }
+        }
+    }
+}
+
+#[test]
+fn test_clamped_derivative() {
+    let linear = Linear::new(q("0.5 ohm/K"), q("1 ohm"));
+    let clamped = ClampedQuantity::new(3.0, 0.0, linear).unwrap();
+    let wrt = Unit::from(PredefUnit::Temperature);
+
+    assert_eq!(clamped.derivative(&[q("2 K")], wrt).unwrap().value, 0.5);
+    assert_eq!(clamped.derivative(&[q("-4 K")], wrt).unwrap().value, 0.0);
+    assert_eq!(clamped.derivative(&[q("6 K")], wrt).unwrap().value, 0.0);
+    assert_eq!(
+        clamped.derivative(&[q("6 K")], wrt).unwrap().unit,
+        Unit::from(PredefUnit::ElectricResistance) / wrt
+    );
+}
+
+#[test]
+fn test_grid_derivative() {
+    let grid = Grid::new(
+        vec![
+            vec![q("0 T"), q("1 T"), q("2 T")],
+            vec![q("50 Hz"), q("100 Hz")],
+        ],
+        vec![q("0 W"), q("0 W"), q("1 W"), q("3 W"), q("4 W"), q("10 W")],
+        Extrapolation::Hold,
+    )
+    .unwrap();
+    let b = Unit::from(PredefUnit::MagneticFluxDensity);
+    let f = Unit::from(PredefUnit::Frequency);
+
+    // Within the cell [1 T, 2 T] x [50 Hz, 100 Hz]:
+    // p = 1 + 3(B-1) + (2 + 4(B-1)) (f - 50) / 50
+    let factors = [q("1.5 T"), q("75 Hz")];
+    let dp_db = grid.derivative(&factors, b).unwrap();
+    assert_eq!(dp_db.value, 5.0);
+    assert_eq!(dp_db.unit, Unit::from(PredefUnit::Power) / b);
+    assert_eq!(grid.derivative(&factors, f).unwrap().value, 0.08);
+
+    // Independent of other units
+    let wrt = Unit::from(PredefUnit::Temperature);
+    assert_eq!(grid.derivative(&factors, wrt).unwrap().value, 0.0);
+
+    // Held axis
+    assert_eq!(
+        grid.derivative(&[q("1.5 T"), q("200 Hz")], f)
+            .unwrap()
+            .value,
+        0.0
+    );
+    assert_eq!(
+        grid.derivative(&[q("1.5 T"), q("200 Hz")], b)
+            .unwrap()
+            .value,
+        7.0
+    );
+}
+
+#[test]
+fn test_finite_difference_fallback() {
+    #[derive(Serialize, Deserialize, Clone)]
+    struct Cubic;
+
+    #[typetag::serde]
+    impl QuantityFunction for Cubic {
+        fn call(&self, influencing_factors: &[DynQuantity<f64>]) -> DynQuantity<f64> {
+            let x = influencing_factors
+                .iter()
+                .find(|f| f.unit == PredefUnit::MagneticFluxDensity.into())
+                .map(|f| f.value)
+                .unwrap_or(0.0);
+            return DynQuantity::new(x.powi(3), PredefUnit::ElectricResistance);
+        }
+    }
+
+    let b = MagneticFluxDensity::new::<tesla>(2.0);
+    let factors = [q("300 K"), b.into()];
+    let derivative = Cubic
+        .derivative(&factors, PredefUnit::MagneticFluxDensity.into())
+        .unwrap();
+    approx::assert_abs_diff_eq!(derivative.value, 12.0, epsilon = 1e-6);
+
+    // Factor not given
+    assert!(
+        Cubic
+            .derivative(&factors, PredefUnit::Frequency.into())
+            .is_none()
+    );
+}
+
+#[test]
+fn test_var_quantity_derivative() {
+    let fun = Polynomial::new(vec![q("0.01 ohm/K^2"), q("-0.5 ohm/K"), q("3 ohm")]).unwrap();
+    let qt = VarQuantity::<ElectricalResistance>::try_from_quantity_function(fun).unwrap();
+
+    let temperature = ThermodynamicTemperature::new::<kelvin>(10.0);
+    let derivative = qt
+        .derivative::<ThermodynamicTemperature>(&[temperature.into()])
+        .unwrap();
+    approx::assert_abs_diff_eq!(
+        (derivative * ThermodynamicTemperature::new::<kelvin>(1.0)).get::<ohm>(),
+        -0.3,
+        epsilon = 1e-12
+    );
+
+    // Derivative with respect to a quantity the function does not depend on
+    let derivative = qt
+        .derivative::<MagneticFluxDensity>(&[temperature.into()])
+        .unwrap();
+    assert_eq!(derivative.value, 0.0);
+
+    // Constant
+    let qt = VarQuantity::Constant(ElectricalResistance::new::<ohm>(2.0));
+    let derivative = qt
+        .derivative::<ThermodynamicTemperature>(&[temperature.into()])
+        .unwrap();
+    assert_eq!(derivative.value, 0.0);
+}
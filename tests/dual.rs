@@ -0,0 +1,159 @@
+use std::str::FromStr;
+
+use dyn_quantity::{DynQuantity, PredefUnit, Unit};
+use serde::{Deserialize, Serialize};
+use uom::si::f64::Power;
+use var_quantity::{
+    ClampedQuantity, DualQuantity, QuantityFunction, VarQuantity, multivariate::Grid, unary::*,
+};
+
+fn q(s: &str) -> DynQuantity<f64> {
+    return DynQuantity::from_str(s).unwrap();
+}
+
+#[test]
+fn test_dual_arithmetic() {
+    let seeds = DualQuantity::seed(&[q("2 m"), q("4 s")]);
+    let (x, t) = (seeds[0].clone(), seeds[1].clone());
+
+    // v = x / t
+    let v = x.clone() / t.clone();
+    assert_eq!(v.value, q("0.5 m/s"));
+    assert_eq!(v.gradient, vec![q("0.25 1/s"), q("-0.125 m/s^2")]);
+
+    // x² - 3 m * x
+    let y = x
+        .powi(2)
+        .try_sub(&(DualQuantity::constant(q("3 m")) * x.clone()))
+        .unwrap();
+    assert_eq!(y.value, q("-2 m^2"));
+    assert_eq!(y.gradient, vec![q("1 m"), q("0 m^2/s")]);
+
+    // Adding quantities with different units fails
+    assert!(x.try_add(&t).is_err());
+
+    // Transcendental functions require dimensionless inputs
+    assert!(x.try_exp().is_err());
+    assert!(x.try_ln().is_err());
+    let ratio = x.clone() / DualQuantity::constant(q("1 m"));
+    let exp = ratio.try_exp().unwrap();
+    assert_eq!(exp.gradient[0].value, 2.0f64.exp());
+    assert_eq!(
+        exp.gradient[0].unit,
+        Unit::from(PredefUnit::Length).powi(-1)
+    );
+    let ln = ratio.try_ln().unwrap();
+    assert_eq!(ln.gradient[0].value, 0.5);
+    let sqrt = ratio.try_powf(0.5).unwrap();
+    approx::assert_abs_diff_eq!(sqrt.gradient[0].value, 0.5 / 2.0f64.sqrt(), epsilon = 1e-12);
+
+    // Scalar operations and negation
+    let z = -(x.clone() * 2.0) / 4.0;
+    assert_eq!(z.value, q("-1 m"));
+    assert_eq!(z.gradient[0].value, -0.5);
+
+    // Constants have no gradient
+    let c = DualQuantity::constant(q("3 m")) * DualQuantity::constant(q("2 m"));
+    assert!(c.gradient.is_empty());
+}
+
+#[test]
+fn test_call_dual_builtin() {
+    // Built-in functions use their exact derivatives
+    let fun = Polynomial::new(vec![q("0.25 ohm/K^2"), q("-0.5 ohm/K"), q("3 ohm")]).unwrap();
+    let output = fun.call_dual(&DualQuantity::seed(&[q("2 A"), q("2 K")]));
+    assert_eq!(output.value, q("3 ohm"));
+    assert_eq!(output.gradient, vec![q("0 ohm/A"), q("0.5 ohm/K")]);
+
+    // Multivariate functions
+    let grid = Grid::new(
+        vec![
+            vec![q("0 T"), q("1 T"), q("2 T")],
+            vec![q("50 Hz"), q("100 Hz")],
+        ],
+        vec![q("0 W"), q("0 W"), q("1 W"), q("3 W"), q("4 W"), q("10 W")],
+        Extrapolation::Hold,
+    )
+    .unwrap();
+    let output = grid.call_dual(&DualQuantity::seed(&[q("75 Hz"), q("1.5 T")]));
+    assert_eq!(output.value, q("4.5 W"));
+    assert_eq!(output.gradient, vec![q("0.08 W/Hz"), q("5 W/T")]);
+
+    // Clamped functions have a zero gradient outside of the limits
+    let clamped = ClampedQuantity::new(3.0, 0.0, Linear::new(q("0.5 ohm/K"), q("1 ohm"))).unwrap();
+    let output = clamped.call_dual(&DualQuantity::seed(&[q("2 K")]));
+    assert_eq!(output.gradient, vec![q("0.5 ohm/K")]);
+    let output = clamped.call_dual(&DualQuantity::seed(&[q("10 K")]));
+    assert_eq!(output.value, q("3 ohm"));
+    assert!(output.gradient.is_empty());
+}
+
+/// Resistance of a winding whose temperature rise is proportional to the
+/// current squared: R(I, T) = R_0(T + k * I²), where R_0 is a built-in
+/// function of temperature.
+#[derive(Clone, Serialize, Deserialize)]
+struct SelfHeatingWinding {
+    cold_resistance: Linear,
+}
+
+#[typetag::serde]
+impl QuantityFunction for SelfHeatingWinding {
+    fn call(&self, influencing_factors: &[DynQuantity<f64>]) -> DynQuantity<f64> {
+        let factors: Vec<DualQuantity> = influencing_factors
+            .iter()
+            .cloned()
+            .map(DualQuantity::constant)
+            .collect();
+        return self.call_dual(&factors).value;
+    }
+
+    fn call_dual(&self, influencing_factors: &[DualQuantity]) -> DualQuantity {
+        let select = |unit: PredefUnit| {
+            influencing_factors
+                .iter()
+                .find(|f| f.value.unit == unit.into())
+                .cloned()
+                .unwrap_or(DualQuantity::constant(DynQuantity::new(0.0, unit)))
+        };
+        let current = select(PredefUnit::ElectricCurrent);
+        let temperature = select(PredefUnit::Temperature);
+        let k = DualQuantity::constant(q("2 K/A^2"));
+        let winding_temperature = temperature.try_add(&(k * current.powi(2))).unwrap();
+        return self.cold_resistance.call_dual(&[winding_temperature]);
+    }
+}
+
+#[test]
+fn test_call_dual_custom() {
+    let fun = SelfHeatingWinding {
+        cold_resistance: Linear::new(q("0.5 ohm/K"), q("1 ohm")),
+    };
+
+    // R = 1 ohm + 0.5 ohm/K * (T + 2 K/A² * I²)
+    // dR/dI = 0.5 ohm/K * 4 K/A² * I = 2 ohm/A² * I
+    let output = fun.call_dual(&DualQuantity::seed(&[q("3 A"), q("10 K")]));
+    assert_eq!(output.value, q("15 ohm"));
+    assert_eq!(output.gradient, vec![q("6 ohm/A"), q("0.5 ohm/K")]);
+
+    // Clamping a custom function
+    let clamped = ClampedQuantity::new(10.0, 0.0, fun.clone()).unwrap();
+    let output = clamped.call_dual_clamped(&DualQuantity::seed(&[q("3 A"), q("10 K")]));
+    assert_eq!(output.value, q("10 ohm"));
+    assert!(output.gradient.is_empty());
+}
+
+#[test]
+fn test_var_quantity_gradient() {
+    let losses = Polynomial::new(vec![q("2 W/T^2"), q("0 W/T"), q("0 W")]).unwrap();
+    let qt = VarQuantity::<Power>::try_from_quantity_function(losses).unwrap();
+    assert_eq!(
+        qt.gradient(&[q("50 Hz"), q("1.5 T")]),
+        vec![q("0 W/Hz"), q("6 W/T")]
+    );
+
+    let qt = VarQuantity::<Power>::Constant(Power::new::<uom::si::power::watt>(2.0));
+    assert_eq!(
+        qt.gradient(&[q("50 Hz"), q("1.5 T")]),
+        vec![q("0 W/Hz"), q("0 W/T")]
+    );
+}
@@ -0,0 +1,282 @@
+use std::str::FromStr;
+
+use dyn_quantity::{DynQuantity, PredefUnit, Unit};
+use uom::si::{electrical_resistivity::ohm_meter, f64::*, thermodynamic_temperature::kelvin};
+use var_quantity::{
+    DualQuantity, ExpressionError, FunctionWrapper, InfluencingFactor, QuantityFunction,
+    VarQuantity, expression::Expression,
+};
+
+fn q(s: &str) -> DynQuantity<f64> {
+    return DynQuantity::from_str(s).unwrap();
+}
+
+fn no_variables() -> Vec<(String, DynQuantity<f64>)> {
+    return Vec::new();
+}
+
+#[test]
+fn test_resistivity() {
+    let rho = Expression::new(
+        "1.7e-8 ohm*m * (1 + 3.9e-3/K * (T - 293.15 K))",
+        [("T", q("293.15 K"))],
+    )
+    .unwrap();
+    assert_eq!(rho.output_unit(), q("1 ohm*m").unit);
+    assert_eq!(
+        rho.influencing_units(),
+        Some(vec![Unit::from(PredefUnit::Temperature)])
+    );
+
+    let wrapper = FunctionWrapper::<ElectricalResistivity>::new(Box::new(rho)).unwrap();
+    let qt = VarQuantity::Function(wrapper);
+    let temperature = ThermodynamicTemperature::new::<kelvin>(373.15);
+    approx::assert_abs_diff_eq!(
+        qt.get(&[temperature.into()]).get::<ohm_meter>(),
+        1.7e-8 * (1.0 + 3.9e-3 * 80.0),
+        epsilon = 1e-20
+    );
+
+    // Derivative with respect to the temperature
+    let derivative = qt.gradient(&[temperature.into()]);
+    approx::assert_abs_diff_eq!(derivative[0].value, 1.7e-8 * 3.9e-3, epsilon = 1e-20);
+    assert_eq!(derivative[0].unit, q("1 ohm*m/K").unit);
+}
+
+#[test]
+fn test_operators_and_functions() {
+    let eval = |s: &str| Expression::new(s, no_variables()).unwrap().call(&[]);
+
+    assert_eq!(eval("1 + 2 * 3"), q("7"));
+    assert_eq!(eval("(1 + 2) * 3"), q("9"));
+    assert_eq!(eval("2 - 3 - 4"), q("-5"));
+    assert_eq!(eval("12 / 3 / 2"), q("2"));
+    assert_eq!(eval("-2^2"), q("-4"));
+    assert_eq!(eval("2^3^2"), q("512"));
+    assert_eq!(eval("2^-1"), q("0.5"));
+    assert_eq!(eval("2 m^2 * 3"), q("6 m^2"));
+    assert_eq!(eval("(2 m)^2"), q("4 m^2"));
+    assert_eq!(eval("1e3 mm"), q("1 m"));
+    assert_eq!(eval("20 °C"), q("293.15 K"));
+    assert_eq!(eval("sqrt(16 m^2)"), q("4 m"));
+    assert_eq!(eval("abs(-3 A)"), q("3 A"));
+    assert_eq!(eval("min(2 s, 3 s)"), q("2 s"));
+    assert_eq!(eval("max(2 s, 3 s)"), q("3 s"));
+    assert_eq!(eval("pow(2 m, 3)"), q("8 m^3"));
+    assert_eq!(eval("exp(0) + ln(1)"), q("1"));
+    approx::assert_abs_diff_eq!(eval("pow(2, 0.5)").value, 2f64.sqrt());
+    approx::assert_abs_diff_eq!(eval("2 * pi").value, std::f64::consts::TAU);
+}
+
+#[test]
+fn test_variables() {
+    let fun = Expression::new(
+        "k * (T_winding - T_ambient)",
+        [
+            ("k", q("2 W/K")),
+            ("T_winding", q("300 K")),
+            ("T_ambient", q("290 K")),
+        ],
+    )
+    .unwrap();
+    assert_eq!(fun.output_unit(), Unit::from(PredefUnit::Power));
+
+    // Defaults
+    assert_eq!(fun.call(&[]), q("20 W"));
+
+    // The unnamed call uses the first temperature for both variables
+    assert_eq!(fun.call(&[q("320 K"), q("3 W/K")]), q("0 W"));
+
+    // Named call
+    let factors = [
+        InfluencingFactor::new("T_ambient", q("280 K")),
+        InfluencingFactor::new("T_winding", q("330 K")),
+    ];
+    assert_eq!(fun.call_named(&factors), q("100 W"));
+
+    // Variable with a wrong unit falls back to the default
+    let factors = [InfluencingFactor::new("T_winding", q("330 A"))];
+    assert_eq!(fun.call_named(&factors), q("20 W"));
+}
+
+#[test]
+fn test_dimensional_analysis() {
+    let vars = [("T", q("300 K")), ("t", q("1 s")), ("x", q("2"))];
+    let err = |s: &str| Expression::new(s, vars).unwrap_err();
+
+    assert_eq!(
+        err("T + 1 m"),
+        ExpressionError::UnitsNotEqual {
+            position: 2,
+            left: Unit::from(PredefUnit::Temperature),
+            right: Unit::from(PredefUnit::Length)
+        }
+    );
+    assert_eq!(
+        err("min(T, t)"),
+        ExpressionError::UnitsNotEqual {
+            position: 0,
+            left: Unit::from(PredefUnit::Temperature),
+            right: Unit::from(PredefUnit::Time)
+        }
+    );
+    assert_eq!(
+        err("ln(T)"),
+        ExpressionError::NotDimensionless {
+            position: 0,
+            unit: Unit::from(PredefUnit::Temperature)
+        }
+    );
+    assert_eq!(
+        err("x^t"),
+        ExpressionError::NotDimensionless {
+            position: 1,
+            unit: Unit::from(PredefUnit::Time)
+        }
+    );
+    assert_eq!(
+        err("T^x"),
+        ExpressionError::InvalidExponent {
+            position: 1,
+            unit: Unit::from(PredefUnit::Temperature)
+        }
+    );
+    assert_eq!(
+        err("pow(t, 0.5)"),
+        ExpressionError::InvalidExponent {
+            position: 0,
+            unit: Unit::from(PredefUnit::Time)
+        }
+    );
+    assert!(matches!(
+        err("sqrt(t)"),
+        ExpressionError::InvalidRoot { position: 0, .. }
+    ));
+
+    // Dimensionless bases can be raised to arbitrary powers
+    assert!(Expression::new("x^x + pow(T / (1 K), 0.5)", vars).is_ok());
+
+    // Integer exponents of dimensional bases are fine
+    assert!(Expression::new("(T / t)^2 * t^(4 / 2)", vars).is_ok());
+}
+
+#[test]
+fn test_syntax_errors() {
+    let err = |s: &str| Expression::new(s, [("x", q("1"))]).unwrap_err();
+
+    assert!(matches!(
+        err("1 +"),
+        ExpressionError::Syntax { position: 3, .. }
+    ));
+    assert!(matches!(
+        err("(1 + x"),
+        ExpressionError::Syntax { position: 6, .. }
+    ));
+    assert!(matches!(
+        err("2 x"),
+        ExpressionError::Syntax { position: 2, .. }
+    ));
+    assert!(matches!(
+        err("1 $ 2"),
+        ExpressionError::Syntax { position: 2, .. }
+    ));
+    assert!(matches!(
+        err("exp(x, x)"),
+        ExpressionError::Syntax { position: 0, .. }
+    ));
+    assert_eq!(
+        err("3 foo"),
+        ExpressionError::UnknownIdentifier {
+            position: 2,
+            identifier: "foo".to_string()
+        }
+    );
+    assert!(
+        err("1 +")
+            .to_string()
+            .contains("unexpected end of expression")
+    );
+}
+
+#[test]
+fn test_derivatives() {
+    let fun = Expression::new(
+        "a * exp(-t / (2 s)) + sqrt(a * 1 ohm) * abs(t / (1 s)) + pow(x, x) * 1 ohm - max(a, 1 ohm)",
+        [("a", q("3 ohm")), ("t", q("1 s")), ("x", q("1.5"))],
+    )
+    .unwrap();
+    let factors = [q("2 ohm"), q("0.5 s"), q("1.2")];
+
+    // Exact derivatives vs. finite differences
+    for (index, factor) in factors.iter().enumerate() {
+        let derivative = fun.derivative(&factors, factor.unit).unwrap();
+        assert_eq!(derivative.unit, fun.output_unit() / factor.unit);
+
+        let step = 1e-6;
+        let mut upper = factors;
+        upper[index].value += step;
+        let mut lower = factors;
+        lower[index].value -= step;
+        let expected = (fun.call(&upper).value - fun.call(&lower).value) / (2.0 * step);
+        approx::assert_abs_diff_eq!(derivative.value, expected, epsilon = 1e-6);
+    }
+
+    // Missing or unrelated factors
+    assert_eq!(
+        fun.derivative(&factors[..2], Unit::default())
+            .unwrap()
+            .value,
+        0.0
+    );
+    assert_eq!(
+        fun.derivative(&factors, PredefUnit::Force.into())
+            .unwrap()
+            .value,
+        0.0
+    );
+
+    // call_dual yields the same gradient
+    let output = fun.call_dual(&DualQuantity::seed(&factors));
+    assert_eq!(output.value, fun.call(&factors));
+    for (gradient, factor) in output.gradient.iter().zip(factors.iter()) {
+        assert_eq!(*gradient, fun.derivative(&factors, factor.unit).unwrap());
+    }
+
+    // A constant expression has no gradient
+    let constant = Expression::new("2 ohm", no_variables()).unwrap();
+    assert!(
+        constant
+            .call_dual(&DualQuantity::seed(&factors))
+            .gradient
+            .is_empty()
+    );
+}
+
+#[test]
+fn test_serde() {
+    let fun = Expression::new(
+        "1.7e-8 ohm*m * (1 + 3.9e-3/K * (T - 293.15 K))",
+        [("T", q("293.15 K"))],
+    )
+    .unwrap();
+
+    // The original string is preserved
+    let yaml = serde_yaml::to_string(&fun).unwrap();
+    assert!(yaml.contains("expression: 1.7e-8 ohm*m * (1 + 3.9e-3/K * (T - 293.15 K))"));
+    let deserialized: Expression = serde_yaml::from_str(&yaml).unwrap();
+    assert_eq!(deserialized, fun);
+    assert_eq!(deserialized.expression(), fun.expression());
+
+    // As trait object
+    let boxed: Box<dyn QuantityFunction> = Box::new(fun.clone());
+    let yaml = serde_yaml::to_string(&boxed).unwrap();
+    let deserialized: Box<dyn QuantityFunction> = serde_yaml::from_str(&yaml).unwrap();
+    assert_eq!(
+        deserialized.call(&[q("393.15 K")]),
+        fun.call(&[q("393.15 K")])
+    );
+
+    // Invalid expressions are rejected during deserialization
+    let result: Result<Expression, _> = serde_yaml::from_str("---\n1 K + 1 m");
+    assert!(result.is_err());
+}
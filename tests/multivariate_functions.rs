@@ -0,0 +1,270 @@
+use dyn_quantity::{DynQuantity, PredefUnit};
+use indoc::indoc;
+use var_quantity::{
+    EvaluationError, QuantityFunction, SupportPointsError, multivariate::*, unary::Extrapolation,
+};
+
+fn b(v: f64) -> DynQuantity<f64> {
+    return DynQuantity::new(v, PredefUnit::MagneticFluxDensity);
+}
+
+fn f(v: f64) -> DynQuantity<f64> {
+    return DynQuantity::new(v, PredefUnit::Frequency);
+}
+
+fn t(v: f64) -> DynQuantity<f64> {
+    return DynQuantity::new(v, PredefUnit::Temperature);
+}
+
+fn p(v: f64) -> DynQuantity<f64> {
+    return DynQuantity::new(v, PredefUnit::Power);
+}
+
+#[test]
+fn test_grid_bilinear() {
+    let axes = vec![vec![b(0.0), b(1.0), b(2.0)], vec![f(50.0), f(100.0)]];
+    let values = vec![p(0.0), p(0.0), p(1.0), p(3.0), p(4.0), p(10.0)];
+
+    for extrapolation in [
+        Extrapolation::Hold,
+        Extrapolation::Linear,
+        Extrapolation::Error,
+    ] {
+        let fun = Grid::new(axes.clone(), values.clone(), extrapolation).unwrap();
+        assert_eq!(fun.shape(), vec![3, 2]);
+        assert_eq!(fun.output_unit(), PredefUnit::Power.into());
+        assert_eq!(
+            fun.influencing_units(),
+            Some(vec![
+                PredefUnit::MagneticFluxDensity.into(),
+                PredefUnit::Frequency.into()
+            ])
+        );
+
+        // Grid points
+        assert_eq!(fun.call(&[b(0.0), f(50.0)]).value, 0.0);
+        assert_eq!(fun.call(&[b(1.0), f(100.0)]).value, 3.0);
+        assert_eq!(fun.call(&[b(2.0), f(50.0)]).value, 4.0);
+        assert_eq!(fun.call(&[b(2.0), f(100.0)]).value, 10.0);
+
+        // Order of the influencing factors does not matter, other factors are ignored
+        assert_eq!(fun.call(&[t(300.0), f(100.0), b(1.0)]).value, 3.0);
+
+        // Interpolation along the axes and within a cell
+        assert_eq!(fun.call(&[b(0.5), f(100.0)]).value, 1.5);
+        assert_eq!(fun.call(&[b(1.0), f(75.0)]).value, 2.0);
+        assert_eq!(fun.call(&[b(1.5), f(75.0)]).value, 4.5);
+        assert_eq!(fun.try_call(&[b(1.5), f(75.0)]).unwrap().value, 4.5);
+    }
+
+    // Hold
+    let fun = Grid::new(axes.clone(), values.clone(), Extrapolation::Hold).unwrap();
+    assert_eq!(fun.call(&[b(3.0), f(100.0)]).value, 10.0);
+    assert_eq!(fun.call(&[b(1.0), f(200.0)]).value, 3.0);
+
+    // Missing frequency -> 0 Hz, which is held at 50 Hz
+    assert_eq!(fun.call(&[b(1.0)]).value, 1.0);
+
+    // Linear
+    let fun = Grid::new(axes.clone(), values.clone(), Extrapolation::Linear).unwrap();
+    assert_eq!(fun.call(&[b(3.0), f(100.0)]).value, 17.0);
+    assert_eq!(fun.call(&[b(1.0), f(150.0)]).value, 5.0);
+    assert_eq!(fun.call(&[b(3.0), f(150.0)]).value, 27.0);
+
+    // Error
+    let fun = Grid::new(axes.clone(), values.clone(), Extrapolation::Error).unwrap();
+    assert!(fun.call(&[b(1.0), f(150.0)]).value.is_nan());
+    assert_eq!(
+        fun.try_call(&[b(1.0), f(150.0)]),
+        Err(EvaluationError::InputOutOfRange {
+            input: f(150.0),
+            lower: f(50.0),
+            upper: f(100.0),
+        })
+    );
+}
+
+#[test]
+fn test_grid_trilinear() {
+    // f(B, f, T) = B + f / 100 + T / 1000 is reproduced exactly
+    let bs = [0.0, 1.0];
+    let fs = [0.0, 100.0, 200.0];
+    let ts = [0.0, 1000.0];
+    let mut values = Vec::new();
+    for bv in bs {
+        for fv in fs {
+            for tv in ts {
+                values.push(p(bv + fv / 100.0 + tv / 1000.0));
+            }
+        }
+    }
+    let fun = Grid::new(
+        vec![
+            bs.into_iter().map(b).collect(),
+            fs.into_iter().map(f).collect(),
+            ts.into_iter().map(t).collect(),
+        ],
+        values,
+        Extrapolation::Linear,
+    )
+    .unwrap();
+    assert_eq!(fun.shape(), vec![2, 3, 2]);
+    approx::assert_abs_diff_eq!(
+        fun.call(&[b(0.25), f(150.0), t(500.0)]).value,
+        2.25,
+        epsilon = 1e-12
+    );
+    approx::assert_abs_diff_eq!(
+        fun.call(&[b(2.0), f(-100.0), t(2000.0)]).value,
+        3.0,
+        epsilon = 1e-12
+    );
+}
+
+#[test]
+fn test_grid_invalid() {
+    let values = vec![p(0.0), p(0.0), p(1.0), p(3.0)];
+
+    // Axis with only one point
+    assert_eq!(
+        Grid::new(
+            vec![vec![b(0.0)], vec![f(50.0), f(100.0)]],
+            values[..2].to_vec(),
+            Extrapolation::Hold
+        ),
+        Err(SupportPointsError::NotEnoughPoints {
+            required: 2,
+            found: 1
+        })
+    );
+
+    // Axis not strictly increasing
+    assert_eq!(
+        Grid::new(
+            vec![vec![b(0.0), b(1.0)], vec![f(100.0), f(50.0)]],
+            values.clone(),
+            Extrapolation::Hold
+        ),
+        Err(SupportPointsError::NotStrictlyIncreasing(1))
+    );
+
+    // Non-finite axis points and values
+    assert_eq!(
+        Grid::new(
+            vec![vec![b(0.0), b(1.0)], vec![f(50.0), f(f64::NAN)]],
+            values.clone(),
+            Extrapolation::Hold
+        ),
+        Err(SupportPointsError::NotFinite(1))
+    );
+    assert_eq!(
+        Grid::new(
+            vec![vec![b(f64::NEG_INFINITY), b(1.0)], vec![f(50.0), f(100.0)]],
+            values.clone(),
+            Extrapolation::Hold
+        ),
+        Err(SupportPointsError::NotFinite(0))
+    );
+    assert_eq!(
+        Grid::new(
+            vec![vec![b(0.0), b(1.0)], vec![f(50.0), f(100.0)]],
+            vec![p(0.0), p(0.0), p(f64::NAN), p(3.0)],
+            Extrapolation::Hold
+        ),
+        Err(SupportPointsError::NotFinite(2))
+    );
+
+    // Inconsistent units of an axis
+    assert!(
+        Grid::new(
+            vec![vec![b(0.0), f(1.0)], vec![f(50.0), f(100.0)]],
+            values.clone(),
+            Extrapolation::Hold
+        )
+        .is_err()
+    );
+
+    // Two axes with the same unit
+    assert_eq!(
+        Grid::new(
+            vec![vec![b(0.0), b(1.0)], vec![b(2.0), b(3.0)]],
+            values.clone(),
+            Extrapolation::Hold
+        ),
+        Err(SupportPointsError::AmbiguousUnit(
+            PredefUnit::MagneticFluxDensity.into()
+        ))
+    );
+
+    // Inconsistent units of the values
+    assert!(
+        Grid::new(
+            vec![vec![b(0.0), b(1.0)], vec![f(50.0), f(100.0)]],
+            vec![p(0.0), p(0.0), p(1.0), t(3.0)],
+            Extrapolation::Hold
+        )
+        .is_err()
+    );
+}
+
+#[test]
+fn test_grid_serde() {
+    // Nested values
+    let yaml = indoc! {"
+    ---
+    axes:
+      - [0 T, 1 T, 2 T]
+      - [50 Hz, 100 Hz]
+    values:
+      - [0 W, 0 W]
+      - [1 W, 3 W]
+      - [4 W, 10 W]
+    extrapolation: Error
+    "};
+    let fun: Grid = serde_yaml::from_str(yaml).unwrap();
+    assert_eq!(fun.extrapolation(), Extrapolation::Error);
+    assert_eq!(fun.call(&[b(1.5), f(75.0)]).value, 4.5);
+    assert!(fun.try_call(&[b(1.5), f(150.0)]).is_err());
+
+    // Flat values are serialized and accepted as well
+    let serialized = serde_yaml::to_string(&fun).unwrap();
+    let deserialized: Grid = serde_yaml::from_str(&serialized).unwrap();
+    assert_eq!(fun, deserialized);
+
+    // Trait object
+    let yaml = indoc! {"
+    ---
+    Grid:
+      axes:
+        - [0 T, 1 T]
+        - [50 Hz, 100 Hz]
+      values: [0 W, 0 W, 1 W, 3 W]
+    "};
+    let fun: Box<dyn QuantityFunction> = serde_yaml::from_str(yaml).unwrap();
+    assert_eq!(fun.call(&[b(1.0), f(75.0)]).value, 2.0);
+
+    // Wrong length of a nested list
+    let yaml = indoc! {"
+    ---
+    axes:
+      - [0 T, 1 T, 2 T]
+      - [50 Hz, 100 Hz]
+    values:
+      - [0 W, 0 W]
+      - [1 W, 3 W, 5 W]
+      - [4 W, 10 W]
+    "};
+    assert!(serde_yaml::from_str::<Grid>(yaml).is_err());
+
+    // Wrong nesting depth
+    let yaml = indoc! {"
+    ---
+    axes:
+      - [0 T, 1 T]
+      - [50 Hz, 100 Hz]
+    values:
+      - [[0 W, 0 W], [1 W, 3 W]]
+      - [[0 W, 0 W], [1 W, 3 W]]
+    "};
+    assert!(serde_yaml::from_str::<Grid>(yaml).is_err());
+}
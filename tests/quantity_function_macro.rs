@@ -0,0 +1,159 @@
+use std::str::FromStr;
+
+use dyn_quantity::{DynQuantity, PredefUnit, Unit};
+use indoc::indoc;
+use serde::{Deserialize, Serialize};
+use uom::si::{f64::*, power::watt};
+use var_quantity::{
+    FunctionWrapper, InfluencingFactor, QuantityFunction, VarQuantity, quantity_function,
+};
+
+fn q(s: &str) -> DynQuantity<f64> {
+    return DynQuantity::from_str(s).unwrap();
+}
+
+// Steinmetz equation: p = k * f^alpha * B^beta
+#[derive(Clone, Serialize, Deserialize)]
+struct Steinmetz {
+    k: f64,
+    alpha: f64,
+    beta: f64,
+}
+
+#[quantity_function(output = "W", inputs(b = "T", f(unit = "Hz", default = 50.0)))]
+impl Steinmetz {
+    fn losses(&self, b: f64, f: f64) -> f64 {
+        return self.k * f.powf(self.alpha) * b.powf(self.beta);
+    }
+}
+
+// Prefixed units: The arguments are in kHz and the output is in mW
+#[derive(Clone, Serialize, Deserialize)]
+struct Prefixed;
+
+#[quantity_function(output = "mW", inputs(f = "kHz"))]
+impl Prefixed {
+    fn power(&self, f: f64) -> f64 {
+        return 2.0 * f;
+    }
+}
+
+// Two arguments with the same unit
+#[derive(Clone, Serialize, Deserialize)]
+struct Gradient;
+
+#[quantity_function(
+    output = "K/m",
+    inputs(hot = "K", cold = "K", length(unit = "m", default = 1.0))
+)]
+impl Gradient {
+    fn gradient(&self, hot: f64, cold: f64, length: f64) -> f64 {
+        return (hot - cold) / length;
+    }
+}
+
+#[test]
+fn test_units_and_defaults() {
+    let fun = Steinmetz {
+        k: 2.0,
+        alpha: 2.0,
+        beta: 2.0,
+    };
+    assert_eq!(fun.output_unit(), Unit::from(PredefUnit::Power));
+    assert_eq!(
+        fun.influencing_units(),
+        Some(vec![
+            Unit::from(PredefUnit::MagneticFluxDensity),
+            Unit::from(PredefUnit::Frequency)
+        ])
+    );
+    assert_eq!(fun.call(&[q("20 Hz"), q("1.2 T")]), q("1152 W"));
+
+    // The flux density defaults to zero, the frequency to 50 Hz
+    assert_eq!(fun.call(&[q("20 Hz")]), q("0 W"));
+    assert_eq!(fun.call(&[q("1 T")]), q("5000 W"));
+
+    // Factors with other units are ignored
+    assert_eq!(fun.call(&[q("1 A"), q("1 T")]), q("5000 W"));
+
+    // The default derivative implementation works with the generated impl
+    let derivative = fun
+        .derivative(&[q("1 T"), q("20 Hz")], q("1 T").unit)
+        .unwrap();
+    assert_eq!(
+        derivative.unit,
+        Unit::from(PredefUnit::Power) / PredefUnit::MagneticFluxDensity.into()
+    );
+    approx::assert_abs_diff_eq!(derivative.value, 1600.0, epsilon = 1e-6);
+
+    // Prefixes
+    let fun = Prefixed;
+    assert_eq!(fun.output_unit(), Unit::from(PredefUnit::Power));
+    approx::assert_abs_diff_eq!(fun.call(&[q("3 kHz")]).value, 6e-3, epsilon = 1e-15);
+}
+
+#[test]
+fn test_same_unit() {
+    let fun = Gradient;
+    assert_eq!(
+        fun.influencing_units(),
+        Some(vec![
+            Unit::from(PredefUnit::Temperature),
+            Unit::from(PredefUnit::Length)
+        ])
+    );
+
+    // The temperatures are taken in order
+    assert_eq!(fun.call(&[q("400 K"), q("2 m"), q("300 K")]), q("50 K/m"));
+    assert_eq!(fun.call(&[q("400 K")]), q("400 K/m"));
+
+    // Named factors are selected by the argument name
+    let factors = [
+        InfluencingFactor::new("cold", q("300 K")),
+        InfluencingFactor::new("hot", q("400 K")),
+    ];
+    assert_eq!(fun.call_named(&factors), q("100 K/m"));
+
+    // Without matching names, the arguments fall back to their defaults
+    let factors = [
+        InfluencingFactor::new("a", q("300 K")),
+        InfluencingFactor::new("hot", q("400 K")),
+        InfluencingFactor::new("length", q("2 m")),
+    ];
+    assert_eq!(fun.call_named(&factors), q("200 K/m"));
+    let factors = [
+        InfluencingFactor::new("a", q("300 K")),
+        InfluencingFactor::new("b", q("400 K")),
+    ];
+    assert_eq!(fun.call_named(&factors), q("0 K/m"));
+}
+
+#[test]
+fn test_serde() {
+    let yaml = indoc! {"
+        ---
+        Steinmetz:
+          k: 2.0
+          alpha: 2.0
+          beta: 2.0
+        "};
+    let quantity: VarQuantity<Power> = serde_yaml::from_str(yaml).unwrap();
+    assert_eq!(
+        quantity.get(&[q("1.2 T"), q("20 Hz")]).get::<watt>(),
+        1152.0
+    );
+
+    // Round trip
+    let fun: Box<dyn QuantityFunction> = Box::new(Steinmetz {
+        k: 1.0,
+        alpha: 1.5,
+        beta: 2.0,
+    });
+    let serialized = serde_yaml::to_string(&fun).unwrap();
+    let deserialized: Box<dyn QuantityFunction> = serde_yaml::from_str(&serialized).unwrap();
+    let factors = [q("1 T"), q("100 Hz")];
+    assert_eq!(deserialized.call(&factors), fun.call(&factors));
+
+    // The output unit is checked by the wrapper
+    assert!(FunctionWrapper::<Power>::new(Box::new(Gradient)).is_err());
+}
@@ -157,3 +157,228 @@ fn test_serialize_and_deserialize_var_resistance() {
         );
     }
 }
+
+#[test]
+fn test_deserialize_lookup_out_of_range() {
+    let string = indoc! {"
+        ---
+        Lookup:
+            x: [200 K, 300 K]
+            y: [1 Ohm, 2 Ohm]
+            extrapolation: Error
+        "};
+
+    let var_quantity: VarQuantity<ElectricalResistance> = serde_yaml::from_str(string).unwrap();
+
+    let valid = [ThermodynamicTemperature::new::<kelvin>(250.0).into()];
+    assert_eq!(var_quantity.get(&valid).get::<ohm>(), 1.5);
+    assert_eq!(var_quantity.try_get(&valid).unwrap().get::<ohm>(), 1.5);
+
+    let invalid = [ThermodynamicTemperature::new::<kelvin>(350.0).into()];
+    assert!(var_quantity.get(&invalid).get::<ohm>().is_nan());
+    assert!(matches!(
+        var_quantity.try_get(&invalid),
+        Err(EvaluationError::InputOutOfRange { .. })
+    ));
+}
+
+#[test]
+fn test_deserialize_support_points_formats() {
+    let string = indoc! {"
+        ---
+        Spline:
+            points: [[200 K, 1 Ohm], [300 K, 2 Ohm], [400 K, 4 Ohm]]
+            kind: Pchip
+        "};
+    let points: Box<dyn QuantityFunction> = serde_yaml::from_str(string).unwrap();
+    let string = indoc! {"
+        ---
+        Spline:
+            x: [200 K, 300 K, 400 K]
+            y: [1 Ohm, 2 Ohm, 4 Ohm]
+            kind: Pchip
+        "};
+    let columns: Box<dyn QuantityFunction> = serde_yaml::from_str(string).unwrap();
+    let temperature = [ThermodynamicTemperature::new::<kelvin>(250.0).into()];
+    assert_eq!(points.call(&temperature), columns.call(&temperature));
+
+    // Both formats at once or columns of different lengths
+    let string = indoc! {"
+        ---
+        Spline:
+            points: [[200 K, 1 Ohm], [300 K, 2 Ohm]]
+            x: [200 K, 300 K]
+            y: [1 Ohm, 2 Ohm]
+            kind: Pchip
+        "};
+    assert!(serde_yaml::from_str::<Box<dyn QuantityFunction>>(string).is_err());
+    let string = indoc! {"
+        ---
+        Lookup:
+            x: [200 K, 300 K]
+            y: [1 Ohm]
+        "};
+    assert!(serde_yaml::from_str::<Box<dyn QuantityFunction>>(string).is_err());
+
+    // Round trip of a clamped spline
+    let clamped = ClampedQuantity::new(
+        3.0,
+        1.5,
+        unary::Spline::from_columns(
+            vec![
+                DynQuantity::new(200.0, PredefUnit::Temperature),
+                DynQuantity::new(300.0, PredefUnit::Temperature),
+                DynQuantity::new(400.0, PredefUnit::Temperature),
+            ],
+            vec![
+                DynQuantity::new(1.0, PredefUnit::ElectricResistance),
+                DynQuantity::new(2.0, PredefUnit::ElectricResistance),
+                DynQuantity::new(4.0, PredefUnit::ElectricResistance),
+            ],
+            unary::SplineKind::Pchip,
+            unary::Extrapolation::Hold,
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    let serialized = serde_yaml::to_string(&clamped).unwrap();
+    let deserialized: ClampedQuantity<unary::Spline> = serde_yaml::from_str(&serialized).unwrap();
+    for value in [100.0, 250.0, 500.0] {
+        let temperature = [DynQuantity::new(value, PredefUnit::Temperature)];
+        assert_eq!(deserialized.call(&temperature), clamped.call(&temperature));
+    }
+}
+
+#[test]
+fn test_clamped_quantity_limits() {
+    let yaml = indoc! {"
+        ---
+        upper_limit: 10 mOhm
+        function:
+          slope: 1 mOhm/K
+          base_value: 0 Ohm
+        "};
+    let clamped: ClampedQuantity<unary::Linear> = serde_yaml::from_str(yaml).unwrap();
+    assert_eq!(clamped.upper_limit(), 0.01);
+    assert_eq!(clamped.lower_limit(), f64::NEG_INFINITY);
+    let temperature = ThermodynamicTemperature::new::<kelvin>(20.0);
+    assert_eq!(clamped.call(&[temperature.into()]).value, 0.01);
+
+    // Missing limits are not serialized
+    let serialized = serde_yaml::to_string(&clamped).unwrap();
+    assert!(serialized.contains("upper_limit: 0.01"));
+    assert!(!serialized.contains("lower_limit"));
+    let deserialized: ClampedQuantity<unary::Linear> = serde_yaml::from_str(&serialized).unwrap();
+    assert_eq!(deserialized.upper_limit(), 0.01);
+    assert_eq!(deserialized.lower_limit(), f64::NEG_INFINITY);
+
+    // Raw values are interpreted in the output unit
+    let yaml = indoc! {"
+        ---
+        upper_limit: 0.01
+        lower_limit: 0.001
+        function:
+          slope: 1 mOhm/K
+          base_value: 0 Ohm
+        "};
+    let clamped: ClampedQuantity<unary::Linear> = serde_yaml::from_str(yaml).unwrap();
+    assert_eq!(clamped.lower_limit(), 0.001);
+
+    // Wrong unit
+    let yaml = indoc! {"
+        ---
+        upper_limit: 10 mA
+        function:
+          slope: 1 mOhm/K
+          base_value: 0 Ohm
+        "};
+    assert!(serde_yaml::from_str::<ClampedQuantity<unary::Linear>>(yaml).is_err());
+
+    // Lower limit above upper limit
+    let yaml = indoc! {"
+        ---
+        upper_limit: 1 mOhm
+        lower_limit: 10 mOhm
+        function:
+          slope: 1 mOhm/K
+          base_value: 0 Ohm
+        "};
+    assert!(serde_yaml::from_str::<ClampedQuantity<unary::Linear>>(yaml).is_err());
+}
+
+#[test]
+fn test_clamped_quantity_unit_limits() {
+    let temperature = ThermodynamicTemperature::new::<kelvin>(20.0);
+
+    // Raw values and quantities can be mixed
+    let yaml = indoc! {"
+        ---
+        upper_limit: 0.01
+        lower_limit: 1 mOhm
+        function:
+          slope: 1 mOhm/K
+          base_value: 0 Ohm
+        "};
+    let clamped: ClampedQuantity<unary::Linear> = serde_yaml::from_str(yaml).unwrap();
+    assert_eq!(clamped.upper_limit(), 0.01);
+    assert_eq!(clamped.lower_limit(), 0.001);
+    assert_eq!(clamped.call(&[temperature.into()]).value, 0.01);
+
+    // A quantity without a unit is not a raw value and must match the unit
+    let yaml = indoc! {"
+        ---
+        upper_limit: '0.01'
+        function:
+          slope: 1 mOhm/K
+          base_value: 0 Ohm
+        "};
+    assert!(serde_yaml::from_str::<ClampedQuantity<unary::Linear>>(yaml).is_err());
+
+    // NaN limits are rejected
+    let yaml = indoc! {"
+        ---
+        upper_limit: .nan
+        function:
+          slope: 1 mOhm/K
+          base_value: 0 Ohm
+        "};
+    assert!(serde_yaml::from_str::<ClampedQuantity<unary::Linear>>(yaml).is_err());
+}
+
+#[test]
+fn test_clamped_custom_function() {
+    // A user-defined function can be clamped without implementing
+    // QuantityFunction for ClampedQuantity<VariableResistance>
+    let yaml = indoc! {"
+        ---
+        Clamped:
+          upper_limit: 3 Ohm
+          function:
+            VariableResistance:
+              base_value: 2.0
+        "};
+    let clamped: Box<dyn QuantityFunction> = serde_yaml::from_str(yaml).unwrap();
+    let factors = [ThermodynamicTemperature::new::<kelvin>(10.0).into()];
+    assert_eq!(clamped.call(&factors).value, 2.0);
+    let factors = [ThermodynamicTemperature::new::<kelvin>(20.0).into()];
+    assert_eq!(clamped.call(&factors).value, 3.0);
+
+    // Round trip
+    let serialized = serde_yaml::to_string(&clamped).unwrap();
+    assert!(serialized.contains("upper_limit: 3"));
+    assert!(!serialized.contains("lower_limit"));
+    let deserialized: VarQuantity<ElectricalResistance> =
+        serde_yaml::from_str(&serialized).unwrap();
+    assert_eq!(deserialized.get(&factors).get::<ohm>(), 3.0);
+
+    // Wrong unit
+    let yaml = indoc! {"
+        ---
+        Clamped:
+          upper_limit: 3 A
+          function:
+            VariableResistance:
+              base_value: 2.0
+        "};
+    assert!(serde_yaml::from_str::<Box<dyn QuantityFunction>>(yaml).is_err());
+}
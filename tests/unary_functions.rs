@@ -1,7 +1,16 @@
+use std::str::FromStr;
+
 use approx;
-use dyn_quantity::{DynQuantity, PredefUnit, Unit};
+use dyn_quantity::{DynQuantity, PredefUnit, Unit, UnitsNotEqual};
 use indoc::indoc;
-use var_quantity::{QuantityFunction, unary::*};
+use var_quantity::{
+    EvaluationError, InfluencingFactor, QuantityFunction, ReferenceError, SupportPointsError,
+    unary::{
+        arrhenius::{AVOGADRO_CONSTANT, BOLTZMANN_CONSTANT, ELECTRON_VOLT, GAS_CONSTANT},
+        frohlich::VACUUM_PERMEABILITY,
+        *,
+    },
+};
 
 #[test]
 fn test_exponential() {
@@ -158,6 +167,795 @@ fn test_linear() {
     }
 }
 
+#[test]
+fn test_lookup() {
+    let temperature = |v: f64| DynQuantity::new(v, PredefUnit::Temperature);
+    let resistance = |v: f64| DynQuantity::new(v, PredefUnit::ElectricResistance);
+    let points = vec![
+        (temperature(200.0), resistance(1.0)),
+        (temperature(300.0), resistance(2.0)),
+        (temperature(400.0), resistance(4.0)),
+        (temperature(500.0), resistance(5.0)),
+    ];
+
+    // Interpolation is independent from the extrapolation behaviour
+    for extrapolation in [
+        Extrapolation::Hold,
+        Extrapolation::Linear,
+        Extrapolation::Error,
+    ] {
+        let fun = Lookup::new(points.clone(), extrapolation).unwrap();
+        assert_eq!(fun.call(&[temperature(200.0)]).value, 1.0);
+        assert_eq!(fun.call(&[temperature(250.0)]).value, 1.5);
+        assert_eq!(fun.call(&[temperature(300.0)]).value, 2.0);
+        assert_eq!(fun.call(&[temperature(375.0)]).value, 3.5);
+        assert_eq!(fun.call(&[temperature(450.0)]).value, 4.5);
+        assert_eq!(fun.call(&[temperature(500.0)]).value, 5.0);
+        assert_eq!(fun.try_call(&[temperature(450.0)]).unwrap().value, 4.5);
+        assert_eq!(
+            fun.call(&[temperature(450.0)]).unit,
+            PredefUnit::ElectricResistance.into()
+        );
+
+        // NaN inputs are never extrapolated
+        assert!(fun.call(&[temperature(f64::NAN)]).value.is_nan());
+    }
+
+    // Hold
+    let fun = Lookup::new(points.clone(), Extrapolation::Hold).unwrap();
+    assert_eq!(fun.call(&[temperature(100.0)]).value, 1.0);
+    assert_eq!(fun.call(&[temperature(600.0)]).value, 5.0);
+
+    // Input type does not match influencing quantity -> x = 0
+    assert_eq!(fun.call(&[2.0.into()]).value, 1.0);
+
+    // Linear
+    let fun = Lookup::new(points.clone(), Extrapolation::Linear).unwrap();
+    assert_eq!(fun.call(&[temperature(100.0)]).value, 0.0);
+    assert_eq!(fun.call(&[temperature(600.0)]).value, 6.0);
+
+    // Error
+    let fun = Lookup::new(points.clone(), Extrapolation::Error).unwrap();
+    assert!(fun.call(&[temperature(100.0)]).value.is_nan());
+    assert!(fun.call(&[temperature(600.0)]).value.is_nan());
+    assert_eq!(
+        fun.try_call(&[temperature(600.0)]),
+        Err(EvaluationError::InputOutOfRange {
+            input: temperature(600.0),
+            lower: temperature(200.0),
+            upper: temperature(500.0),
+        })
+    );
+
+    // Named input
+    let fun = fun.with_input_name("winding_temperature");
+    let factors = [
+        InfluencingFactor::new("ambient_temperature", temperature(600.0)),
+        InfluencingFactor::new("winding_temperature", temperature(250.0)),
+    ];
+    assert_eq!(fun.call_named(&factors).value, 1.5);
+    assert_eq!(fun.try_call_named(&factors).unwrap().value, 1.5);
+    assert!(fun.try_call(&[temperature(600.0)]).is_err());
+
+    // Unit mismatch
+    assert!(
+        Lookup::new(
+            vec![
+                (temperature(200.0), resistance(1.0)),
+                (DynQuantity::new(300.0, PredefUnit::Time), resistance(2.0)),
+            ],
+            Extrapolation::Hold
+        )
+        .is_err()
+    );
+
+    // Non-finite support points
+    for value in [f64::NAN, f64::INFINITY] {
+        assert_eq!(
+            Lookup::new(
+                vec![
+                    (temperature(200.0), resistance(1.0)),
+                    (temperature(value), resistance(2.0)),
+                ],
+                Extrapolation::Hold
+            ),
+            Err(SupportPointsError::NotFinite(1))
+        );
+        assert_eq!(
+            Lookup::new(
+                vec![
+                    (temperature(200.0), resistance(value)),
+                    (temperature(300.0), resistance(2.0)),
+                ],
+                Extrapolation::Hold
+            ),
+            Err(SupportPointsError::NotFinite(0))
+        );
+    }
+}
+
+#[test]
+fn test_spline() {
+    let h = |v: f64| DynQuantity::new(v, PredefUnit::MagneticFieldStrength);
+    let b = |v: f64| DynQuantity::new(v, PredefUnit::MagneticFluxDensity);
+    let points = vec![
+        (h(0.0), b(0.0)),
+        (h(100.0), b(1.0)),
+        (h(200.0), b(1.5)),
+        (h(400.0), b(1.7)),
+        (h(800.0), b(1.8)),
+    ];
+
+    for kind in [SplineKind::Natural, SplineKind::Pchip, SplineKind::Akima] {
+        let fun = Spline::new(points.clone(), kind, Extrapolation::Hold).unwrap();
+        assert_eq!(fun.kind(), kind);
+        assert_eq!(
+            fun.influencing_factor_unit(),
+            PredefUnit::MagneticFieldStrength.into()
+        );
+        assert_eq!(fun.output_unit(), PredefUnit::MagneticFluxDensity.into());
+        assert!(fun.call(&[h(f64::NAN)]).value.is_nan());
+
+        // Support points are interpolated exactly
+        for (x, y) in points.iter() {
+            approx::assert_abs_diff_eq!(fun.call(&[*x]).value, y.value, epsilon = 1e-12);
+        }
+
+        // Continuity at the support points
+        for (x, y) in points.iter().skip(1).take(3) {
+            let left = fun.call(&[h(x.value - 1e-6)]).value;
+            let right = fun.call(&[h(x.value + 1e-6)]).value;
+            approx::assert_abs_diff_eq!(left, y.value, epsilon = 1e-5);
+            approx::assert_abs_diff_eq!(right, y.value, epsilon = 1e-5);
+        }
+
+        // Hold
+        assert_eq!(fun.call(&[h(-100.0)]).value, 0.0);
+        assert_eq!(fun.call(&[h(1000.0)]).value, 1.8);
+
+        // Error
+        let fun = Spline::new(points.clone(), kind, Extrapolation::Error).unwrap();
+        assert!(fun.call(&[h(1000.0)]).value.is_nan());
+        assert_eq!(
+            fun.try_call(&[h(1000.0)]),
+            Err(EvaluationError::InputOutOfRange {
+                input: h(1000.0),
+                lower: h(0.0),
+                upper: h(800.0),
+            })
+        );
+
+        // Two points result in a straight line
+        let fun = Spline::new(points[..2].to_vec(), kind, Extrapolation::Linear).unwrap();
+        approx::assert_abs_diff_eq!(fun.call(&[h(50.0)]).value, 0.5, epsilon = 1e-12);
+        approx::assert_abs_diff_eq!(fun.call(&[h(200.0)]).value, 2.0, epsilon = 1e-12);
+    }
+
+    // The natural spline has a vanishing second derivative at the end points,
+    // hence linear extrapolation continues the curve smoothly
+    let fun = Spline::new(points.clone(), SplineKind::Natural, Extrapolation::Linear).unwrap();
+    let d = 1e-3;
+    let slope_inside = (fun.call(&[h(800.0)]).value - fun.call(&[h(800.0 - d)]).value) / d;
+    let slope_outside = (fun.call(&[h(800.0 + d)]).value - fun.call(&[h(800.0)]).value) / d;
+    approx::assert_abs_diff_eq!(slope_inside, slope_outside, epsilon = 1e-6);
+
+    // PCHIP does not overshoot for monotone data, which is evaluated on a fine
+    // grid
+    {
+        let fun = Spline::new(points.clone(), SplineKind::Pchip, Extrapolation::Hold).unwrap();
+        let mut previous = fun.call(&[h(0.0)]).value;
+        for i in 1..=800 {
+            let value = fun.call(&[h(i as f64)]).value;
+            assert!(value >= previous);
+            assert!(value <= 1.8);
+            previous = value;
+        }
+    }
+
+    // PCHIP produces flat sections for plateaus
+    let plateau = vec![
+        (h(0.0), b(0.0)),
+        (h(1.0), b(1.0)),
+        (h(2.0), b(1.0)),
+        (h(3.0), b(2.0)),
+    ];
+    let fun = Spline::new(plateau, SplineKind::Pchip, Extrapolation::Hold).unwrap();
+    assert_eq!(fun.call(&[h(1.5)]).value, 1.0);
+
+    // Named input
+    let fun = Spline::new(points.clone(), SplineKind::Akima, Extrapolation::Hold)
+        .unwrap()
+        .with_input_name("h");
+    let factors = [
+        InfluencingFactor::new("h_max", h(800.0)),
+        InfluencingFactor::new("h", h(100.0)),
+    ];
+    approx::assert_abs_diff_eq!(fun.call_named(&factors).value, 1.0, epsilon = 1e-12);
+
+    // Unit mismatch
+    assert!(
+        Spline::new(
+            vec![
+                (h(0.0), b(0.0)),
+                (h(1.0), DynQuantity::new(1.0, PredefUnit::None))
+            ],
+            SplineKind::Natural,
+            Extrapolation::Hold
+        )
+        .is_err()
+    );
+}
+
+#[test]
+fn test_clamped_spline() {
+    let h = |v: f64| DynQuantity::new(v, PredefUnit::MagneticFieldStrength);
+    let b = |v: f64| DynQuantity::new(v, PredefUnit::MagneticFluxDensity);
+    let spline = Spline::new(
+        vec![(h(0.0), b(0.0)), (h(1.0), b(1.0)), (h(2.0), b(4.0))],
+        SplineKind::Natural,
+        Extrapolation::Linear,
+    )
+    .unwrap();
+    let fun = var_quantity::ClampedQuantity::new(3.0, 0.5, spline).unwrap();
+    assert_eq!(fun.call(&[h(-1.0)]).value, 0.5);
+    assert_eq!(fun.call(&[h(1.0)]).value, 1.0);
+    assert_eq!(fun.call(&[h(2.0)]).value, 3.0);
+
+    // Serialization roundtrip
+    let serialized = serde_yaml::to_string(&fun).unwrap();
+    let fun: var_quantity::ClampedQuantity<Spline> = serde_yaml::from_str(&serialized).unwrap();
+    assert_eq!(fun.call(&[h(-1.0)]).value, 0.5);
+    assert_eq!(fun.call(&[h(1.0)]).value, 1.0);
+    assert_eq!(fun.call(&[h(2.0)]).value, 3.0);
+}
+
+#[test]
+fn test_arrhenius() {
+    let q = |s: &str| DynQuantity::<f64>::from_str(s).unwrap();
+    let expected = |temperature: f64| 2e-5 * (-150e3 / (GAS_CONSTANT * temperature)).exp();
+
+    // Activation energy per amount of substance
+    let fun = Arrhenius::new(q("2e-5 m^2/s"), q("150 kJ/mol")).unwrap();
+    assert_eq!(fun.output_unit(), q("1 m^2/s").unit);
+    assert_eq!(
+        fun.influencing_units(),
+        Some(vec![Unit::from(PredefUnit::Temperature)])
+    );
+    for temperature in [300.0, 800.0, 1500.0] {
+        approx::assert_relative_eq!(
+            fun.call(&[q("1 K") * temperature]).value,
+            expected(temperature),
+            max_relative = 1e-12
+        );
+    }
+
+    // The same activation energy per particle yields the same result
+    let per_particle = DynQuantity::new(150e3 / AVOGADRO_CONSTANT, PredefUnit::Energy);
+    let fun_per_particle = Arrhenius::new(q("2e-5 m^2/s"), per_particle).unwrap();
+    approx::assert_relative_eq!(
+        fun_per_particle.activation_temperature().value,
+        fun.activation_temperature().value,
+        max_relative = 1e-12
+    );
+
+    // 1 eV per particle corresponds to 96.485 kJ/mol
+    let ev = Arrhenius::from_electronvolt(1.0.into(), 1.0);
+    assert_eq!(
+        ev,
+        Arrhenius::new(
+            1.0.into(),
+            DynQuantity::new(ELECTRON_VOLT, PredefUnit::Energy)
+        )
+        .unwrap()
+    );
+    let molar = Arrhenius::new(1.0.into(), q("96485.33212 J/mol")).unwrap();
+    approx::assert_relative_eq!(
+        ev.call(&[q("400 K")]).value,
+        molar.call(&[q("400 K")]).value,
+        max_relative = 1e-9
+    );
+
+    // Modified Arrhenius function
+    let fun = fun.with_temperature_exponent(-0.5);
+    approx::assert_relative_eq!(
+        fun.call(&[q("900 K")]).value,
+        expected(900.0) / 30.0,
+        max_relative = 1e-12
+    );
+
+    // Missing temperature
+    assert_eq!(fun.call(&[q("2 A")]), q("0 m^2/s"));
+
+    // Invalid activation energies
+    assert!(Arrhenius::new(1.0.into(), q("1 J/K")).is_err());
+    assert!(Arrhenius::new(1.0.into(), q("1 W/mol")).is_err());
+}
+
+#[test]
+fn test_power_law() {
+    let q = |s: &str| DynQuantity::<f64>::from_str(s).unwrap();
+
+    // Single term with fractional exponent
+    let fun = PowerLaw::simple(q("2 W"), q("50 Hz"), 1.3).unwrap();
+    assert_eq!(fun.output_unit(), Unit::from(PredefUnit::Power));
+    assert_eq!(
+        fun.influencing_units(),
+        Some(vec![Unit::from(PredefUnit::Frequency)])
+    );
+    assert_eq!(fun.call(&[q("50 Hz")]), q("2 W"));
+    approx::assert_relative_eq!(
+        fun.call(&[q("20 Hz")]).value,
+        2.0 * 0.4f64.powf(1.3),
+        max_relative = 1e-12
+    );
+
+    // Prefixed reference point
+    let fun = PowerLaw::simple(q("2 W"), q("1 kHz"), 0.5).unwrap();
+    approx::assert_relative_eq!(fun.call(&[q("4 kHz")]).value, 4.0, max_relative = 1e-12);
+
+    // Sum of terms, e.g. for piecewise fits
+    let fun = PowerLaw::new(
+        vec![
+            PowerTerm {
+                coefficient: q("1 ohm"),
+                exponent: 0.0,
+            },
+            PowerTerm {
+                coefficient: q("0.5 ohm"),
+                exponent: 2.5,
+            },
+        ],
+        q("1 kHz"),
+    )
+    .unwrap();
+    approx::assert_relative_eq!(
+        fun.call(&[q("2 kHz")]).value,
+        1.0 + 0.5 * 2.0f64.powf(2.5),
+        max_relative = 1e-12
+    );
+
+    // Missing input -> x = x0
+    assert_eq!(fun.call(&[]), q("1.5 ohm"));
+
+    // Negative ratio with fractional exponent
+    assert!(fun.call(&[q("-2 kHz")]).value.is_nan());
+
+    // Mismatched coefficients
+    assert!(
+        PowerLaw::new(
+            vec![
+                PowerTerm {
+                    coefficient: q("1 ohm"),
+                    exponent: 0.0,
+                },
+                PowerTerm {
+                    coefficient: q("1 W"),
+                    exponent: 1.0,
+                },
+            ],
+            q("1 Hz"),
+        )
+        .is_err()
+    );
+
+    // Invalid reference points
+    for reference in [
+        q("0 Hz"),
+        q("-0 Hz"),
+        DynQuantity::new(f64::NAN, PredefUnit::Frequency),
+        DynQuantity::new(f64::INFINITY, PredefUnit::Frequency),
+    ] {
+        assert!(matches!(
+            PowerLaw::simple(q("2 W"), reference, 1.3),
+            Err(ReferenceError::InvalidReference(_))
+        ));
+    }
+
+    // Without terms, the function is zero and does not depend on any factor
+    let fun = PowerLaw::new(Vec::new(), q("1 Hz")).unwrap();
+    assert_eq!(fun.call(&[q("2 Hz")]), q("0"));
+    assert_eq!(fun.influencing_units(), Some(vec![]));
+}
+
+#[test]
+fn test_logarithmic() {
+    let q = |s: &str| DynQuantity::<f64>::from_str(s).unwrap();
+
+    let fun = Logarithmic::new(q("1 V"), q("0.05 V"), q("1 mA")).unwrap();
+    assert_eq!(fun.output_unit(), Unit::from(PredefUnit::ElectricVoltage));
+    assert_eq!(
+        fun.influencing_units(),
+        Some(vec![Unit::from(PredefUnit::ElectricCurrent)])
+    );
+    assert_eq!(fun.call(&[q("1 mA")]), q("1 V"));
+    approx::assert_relative_eq!(
+        fun.call(&[q("1 A")]).value,
+        1.0 + 0.05 * 1000f64.ln(),
+        max_relative = 1e-12
+    );
+
+    // Missing input -> reference point
+    assert_eq!(fun.call(&[]), q("1 V"));
+    assert_eq!(fun.try_call(&[]), Ok(q("1 V")));
+
+    // Non-positive inputs
+    assert!(fun.call(&[q("0 A")]).value.is_nan());
+    assert!(fun.call(&[q("-1 A")]).value.is_nan());
+    assert_eq!(
+        fun.try_call(&[q("-1 A")]),
+        Err(EvaluationError::InputOutOfRange {
+            input: q("-1 A"),
+            lower: q("0 A"),
+            upper: DynQuantity::new(f64::INFINITY, q("1 A").unit),
+        })
+    );
+    let factors = [InfluencingFactor::new("current", q("0 A"))];
+    assert!(
+        fun.clone()
+            .with_input_name("current")
+            .try_call_named(&factors)
+            .is_err()
+    );
+
+    // Negative reference point -> only negative inputs are valid
+    let fun = Logarithmic::new(q("1 V"), q("0.05 V"), q("-1 mA")).unwrap();
+    approx::assert_relative_eq!(
+        fun.call(&[q("-1 A")]).value,
+        1.0 + 0.05 * 1000f64.ln(),
+        max_relative = 1e-12
+    );
+    assert_eq!(
+        fun.try_call(&[q("1 A")]),
+        Err(EvaluationError::InputOutOfRange {
+            input: q("1 A"),
+            lower: DynQuantity::new(f64::NEG_INFINITY, q("1 A").unit),
+            upper: q("0 A"),
+        })
+    );
+
+    // Mismatched offset and slope
+    assert!(matches!(
+        Logarithmic::new(q("1 V"), q("0.05 A"), q("1 mA")),
+        Err(ReferenceError::UnitsNotEqual(_))
+    ));
+
+    // Invalid reference points
+    for reference in [
+        q("0 mA"),
+        DynQuantity::new(f64::NAN, PredefUnit::ElectricCurrent),
+        DynQuantity::new(f64::NEG_INFINITY, PredefUnit::ElectricCurrent),
+    ] {
+        assert!(matches!(
+            Logarithmic::new(q("1 V"), q("0.05 V"), reference),
+            Err(ReferenceError::InvalidReference(_))
+        ));
+    }
+}
+
+#[test]
+fn test_inverse_exponential() {
+    let q = |s: &str| DynQuantity::<f64>::from_str(s).unwrap();
+
+    // Without reference point
+    let fun = InverseExponential::new(q("2 ohm"), q("300 K"));
+    assert_eq!(
+        fun.output_unit(),
+        Unit::from(PredefUnit::ElectricResistance)
+    );
+    assert_eq!(
+        fun.influencing_units(),
+        Some(vec![Unit::from(PredefUnit::Temperature)])
+    );
+    assert_eq!(fun.reference(), None);
+    approx::assert_relative_eq!(
+        fun.call(&[q("150 K")]).value,
+        2.0 * 2f64.exp(),
+        max_relative = 1e-12
+    );
+
+    // Beta model
+    let fun = fun.with_reference(q("300 K")).unwrap();
+    assert_eq!(fun.reference(), Some(&q("300 K")));
+    assert_eq!(fun.call(&[q("300 K")]), q("2 ohm"));
+    approx::assert_relative_eq!(
+        fun.call(&[q("150 K")]).value,
+        2.0 * 1f64.exp(),
+        max_relative = 1e-12
+    );
+
+    // Missing input -> amplitude
+    assert_eq!(fun.call(&[]), q("2 ohm"));
+    assert_eq!(fun.try_call(&[]), Ok(q("2 ohm")));
+
+    // Non-positive inputs
+    assert!(fun.call(&[q("0 K")]).value.is_nan());
+    assert_eq!(
+        fun.try_call(&[q("-10 K")]),
+        Err(EvaluationError::InputOutOfRange {
+            input: q("-10 K"),
+            lower: q("0 K"),
+            upper: DynQuantity::new(f64::INFINITY, q("1 K").unit),
+        })
+    );
+
+    // Reference point with wrong unit
+    assert!(
+        InverseExponential::new(q("2 ohm"), q("300 K"))
+            .with_reference(q("300 A"))
+            .is_err()
+    );
+}
+
+#[test]
+fn test_callendar_van_dusen() {
+    let q = |s: &str| DynQuantity::<f64>::from_str(s).unwrap();
+
+    let fun = CallendarVanDusen::pt100();
+    assert_eq!(
+        fun.output_unit(),
+        Unit::from(PredefUnit::ElectricResistance)
+    );
+    assert_eq!(
+        fun.influencing_units(),
+        Some(vec![Unit::from(PredefUnit::Temperature)])
+    );
+    assert_eq!(fun, CallendarVanDusen::iec_60751(q("100 ohm")).unwrap());
+
+    // Reference values of IEC 60751
+    for (celsius, resistance) in [
+        (-200.0, 18.52),
+        (-100.0, 60.26),
+        (0.0, 100.0),
+        (100.0, 138.51),
+        (400.0, 247.09),
+        (850.0, 390.48),
+    ] {
+        let temperature = DynQuantity::new(celsius + 273.15, PredefUnit::Temperature);
+        approx::assert_abs_diff_eq!(fun.call(&[temperature]).value, resistance, epsilon = 5e-3);
+    }
+
+    // Missing input -> 0 °C
+    assert_eq!(fun.call(&[]), q("100 ohm"));
+
+    // Custom coefficients without the cubic term
+    let fun =
+        CallendarVanDusen::new(q("1 kohm"), q("4e-3 / K"), q("0 / K^2"), q("0 / K^4")).unwrap();
+    approx::assert_relative_eq!(
+        fun.call(&[q("223.15 K")]).value,
+        800.0,
+        max_relative = 1e-12
+    );
+
+    // Unit checks
+    assert_eq!(
+        CallendarVanDusen::new(q("1 kohm"), q("4e-3 / K"), q("0 / K^2"), q("0 / K^3")),
+        Err(UnitsNotEqual(q("1 / K^4").unit, q("1 / K^3").unit))
+    );
+    assert!(CallendarVanDusen::iec_60751(q("100 W")).is_err());
+}
+
+#[test]
+fn test_steinhart_hart() {
+    let q = |s: &str| DynQuantity::<f64>::from_str(s).unwrap();
+
+    let fun = SteinhartHart::new(q("1.4e-3 / K"), q("2.37e-4 / K"), q("9.9e-8 / K")).unwrap();
+    assert_eq!(fun.output_unit(), Unit::from(PredefUnit::Temperature));
+    assert_eq!(
+        fun.influencing_units(),
+        Some(vec![Unit::from(PredefUnit::ElectricResistance)])
+    );
+    let ln = 5000f64.ln();
+    approx::assert_relative_eq!(
+        fun.call(&[q("5 kohm")]).value,
+        1.0 / (1.4e-3 + 2.37e-4 * ln + 9.9e-8 * ln.powi(3)),
+        max_relative = 1e-12
+    );
+
+    // Beta model
+    let fun = SteinhartHart::from_beta(q("10 kohm"), q("298.15 K"), q("3950 K")).unwrap();
+    approx::assert_relative_eq!(
+        fun.call(&[q("10 kohm")]).value,
+        298.15,
+        max_relative = 1e-12
+    );
+    approx::assert_relative_eq!(
+        fun.call(&[q("697.52 ohm")]).value,
+        373.15,
+        max_relative = 1e-5
+    );
+
+    // Non-positive and missing inputs
+    assert!(fun.call(&[q("0 ohm")]).value.is_nan());
+    assert!(fun.call(&[]).value.is_nan());
+    assert_eq!(
+        fun.try_call(&[]),
+        Err(EvaluationError::InputOutOfRange {
+            input: q("0 ohm"),
+            lower: q("0 ohm"),
+            upper: DynQuantity::new(f64::INFINITY, PredefUnit::ElectricResistance),
+        })
+    );
+    let factors = [InfluencingFactor::new("ntc", q("-5 ohm"))];
+    assert!(fun.with_input_name("ntc").try_call_named(&factors).is_err());
+
+    // Unit checks
+    assert!(SteinhartHart::new(q("1e-3 / K"), q("2e-4"), q("1e-7 / K")).is_err());
+    assert!(SteinhartHart::from_beta(q("10 kohm"), q("25"), q("3950 K")).is_err());
+    assert!(SteinhartHart::from_beta(q("10 kA"), q("298.15 K"), q("3950 K")).is_err());
+}
+
+#[test]
+fn test_frohlich() {
+    let q = |s: &str| DynQuantity::<f64>::from_str(s).unwrap();
+
+    let fun = Frohlich::new(q("160 A/(m*T)"), q("0.55 / T")).unwrap();
+    assert_eq!(fun.output(), SaturationOutput::FluxDensity);
+    assert_eq!(
+        fun.output_unit(),
+        Unit::from(PredefUnit::MagneticFluxDensity)
+    );
+    assert_eq!(
+        fun.influencing_units(),
+        Some(vec![Unit::from(PredefUnit::MagneticFieldStrength)])
+    );
+    approx::assert_relative_eq!(
+        fun.call(&[q("1000 A/m")]).value,
+        VACUUM_PERMEABILITY * 1000.0 + 1000.0 / (160.0 + 550.0),
+        max_relative = 1e-12
+    );
+
+    // Odd function
+    assert_eq!(
+        fun.call(&[q("-1000 A/m")]).value,
+        -fun.call(&[q("1000 A/m")]).value
+    );
+
+    // Saturation: the polarization approaches 1 / b
+    let h = 1e9;
+    let polarization = fun.call(&[q("1e9 A/m")]).value - VACUUM_PERMEABILITY * h;
+    approx::assert_relative_eq!(polarization, 1.0 / 0.55, max_relative = 1e-6);
+
+    // Missing input -> H = 0
+    assert_eq!(fun.call(&[]), q("0 T"));
+
+    // Permeability
+    let fun = fun.with_output(SaturationOutput::Permeability);
+    assert_eq!(
+        fun.output_unit(),
+        Unit::from(PredefUnit::MagneticFluxDensity) / PredefUnit::MagneticFieldStrength.into()
+    );
+    approx::assert_relative_eq!(
+        fun.call(&[]).value,
+        VACUUM_PERMEABILITY + 1.0 / 160.0,
+        max_relative = 1e-12
+    );
+    approx::assert_relative_eq!(
+        fun.call(&[q("-1000 A/m")]).value,
+        VACUUM_PERMEABILITY + 1.0 / (160.0 + 550.0),
+        max_relative = 1e-12
+    );
+
+    // Initial permeability and saturation polarization
+    let fun = Frohlich::from_saturation(q("6e-3 T*m/A"), q("2 T")).unwrap();
+    approx::assert_relative_eq!(
+        fun.a().value,
+        1.0 / (6e-3 - VACUUM_PERMEABILITY),
+        max_relative = 1e-12
+    );
+
+    // Unit checks
+    assert_eq!(
+        Frohlich::new(q("160 A/(m*T)"), q("0.55 / A")),
+        Err(UnitsNotEqual(q("1 / T").unit, q("1 / A").unit))
+    );
+    assert!(Frohlich::from_saturation(q("6e-3"), q("2 T")).is_err());
+}
+
+#[test]
+fn test_langevin() {
+    let q = |s: &str| DynQuantity::<f64>::from_str(s).unwrap();
+    let langevin = |x: f64| 1.0 / x.tanh() - 1.0 / x;
+
+    let fun = Langevin::new(q("1.6e6 A/m"), q("500 A/m")).unwrap();
+    assert_eq!(
+        fun.output_unit(),
+        Unit::from(PredefUnit::MagneticFluxDensity)
+    );
+    assert_eq!(
+        fun.influencing_units(),
+        Some(vec![Unit::from(PredefUnit::MagneticFieldStrength)])
+    );
+    assert_eq!(fun.total_angular_momentum(), None);
+    approx::assert_relative_eq!(
+        fun.call(&[q("750 A/m")]).value,
+        VACUUM_PERMEABILITY * (750.0 + 1.6e6 * langevin(1.5)),
+        max_relative = 1e-12
+    );
+    assert_eq!(fun.call(&[]), q("0 T"));
+
+    // The series expansion for small fields is continuous to the exact function
+    let permeability = fun.clone().with_output(SaturationOutput::Permeability);
+    for output in [fun.clone(), permeability.clone()] {
+        let below = output.call(&[q("4.9999 A/m")]).value;
+        let above = output.call(&[q("5.0001 A/m")]).value;
+        approx::assert_relative_eq!(below, above, max_relative = 1e-4);
+    }
+    approx::assert_relative_eq!(
+        permeability.call(&[q("5.0001 A/m")]).value,
+        VACUUM_PERMEABILITY * (1.0 + 1.6e6 / 500.0 * langevin(0.0100002) / 0.0100002),
+        max_relative = 1e-9
+    );
+    approx::assert_relative_eq!(
+        permeability.call(&[q("0 A/m")]).value,
+        VACUUM_PERMEABILITY * (1.0 + 1.6e6 / 1500.0),
+        max_relative = 1e-12
+    );
+
+    // Brillouin function with J = 1/2 -> tanh
+    let fun = fun.with_total_angular_momentum(0.5);
+    for h in [1.0, 4.0, 400.0, -2000.0] {
+        approx::assert_relative_eq!(
+            fun.call(&[q("1 A/m") * h]).value,
+            VACUUM_PERMEABILITY * (h + 1.6e6 * (h / 500.0).tanh()),
+            max_relative = 1e-12
+        );
+    }
+
+    // Large J -> Langevin function
+    let fun = fun.with_total_angular_momentum(1e6);
+    approx::assert_relative_eq!(
+        fun.call(&[q("750 A/m")]).value,
+        VACUUM_PERMEABILITY * (750.0 + 1.6e6 * langevin(1.5)),
+        max_relative = 1e-6
+    );
+
+    // Unit checks
+    assert!(Langevin::new(q("1.6e6 A/m"), q("500 T")).is_err());
+}
+
+#[test]
+fn test_arctan_saturation() {
+    let q = |s: &str| DynQuantity::<f64>::from_str(s).unwrap();
+    let frac_2_pi = std::f64::consts::FRAC_2_PI;
+
+    let fun = ArctanSaturation::new(q("1.8 T"), q("200 A/m")).unwrap();
+    assert_eq!(
+        fun.output_unit(),
+        Unit::from(PredefUnit::MagneticFluxDensity)
+    );
+    assert_eq!(
+        fun.influencing_units(),
+        Some(vec![Unit::from(PredefUnit::MagneticFieldStrength)])
+    );
+    approx::assert_relative_eq!(
+        fun.call(&[q("-600 A/m")]).value,
+        -VACUUM_PERMEABILITY * 600.0 - frac_2_pi * 1.8 * 3f64.atan(),
+        max_relative = 1e-12
+    );
+    assert_eq!(fun.call(&[]), q("0 T"));
+
+    // Permeability, including the series expansion for small fields
+    let fun = fun.with_output(SaturationOutput::Permeability);
+    for h in [0.5, 1.9999, 2.0001, 600.0] {
+        approx::assert_relative_eq!(
+            fun.call(&[q("1 A/m") * h]).value,
+            VACUUM_PERMEABILITY + frac_2_pi * 1.8 * (h / 200.0).atan() / h,
+            max_relative = 1e-12
+        );
+    }
+    approx::assert_relative_eq!(
+        fun.call(&[q("0 A/m")]).value,
+        VACUUM_PERMEABILITY + frac_2_pi * 1.8 / 200.0,
+        max_relative = 1e-12
+    );
+
+    // Unit checks
+    assert_eq!(
+        ArctanSaturation::new(q("1.8 A/m"), q("200 A/m")),
+        Err(UnitsNotEqual(q("1 T").unit, q("1 A/m").unit))
+    );
+}
+
 #[test]
 fn test_polynomial() {
     // No units
@@ -226,6 +1024,100 @@ fn test_polynomial() {
     }
 }
 
+#[test]
+fn test_influencing_units() {
+    let current = Unit::from(PredefUnit::ElectricCurrent);
+    let per_ampere = current.powi(-1);
+
+    let fun: Box<dyn QuantityFunction> = Box::new(
+        Exponential::new(vec![ExpTerm {
+            amplitude: DynQuantity::new(2.0, PredefUnit::ElectricVoltage),
+            exponent: DynQuantity::new(2.0, per_ampere),
+        }])
+        .unwrap(),
+    );
+    assert_eq!(fun.influencing_units(), Some(vec![current]));
+
+    let fun: Box<dyn QuantityFunction> = Box::new(
+        FirstOrderTaylor::new(
+            DynQuantity::new(2.5, PredefUnit::Power),
+            DynQuantity::new(2.0, per_ampere),
+            DynQuantity::new(0.5, PredefUnit::ElectricCurrent),
+        )
+        .unwrap(),
+    );
+    assert_eq!(fun.influencing_units(), Some(vec![current]));
+
+    let fun: Box<dyn QuantityFunction> = Box::new(Linear::new(
+        DynQuantity::new(2.0, PredefUnit::ElectricVoltage),
+        DynQuantity::new(0.5, PredefUnit::Power),
+    ));
+    assert_eq!(fun.influencing_units(), Some(vec![current]));
+
+    let fun: Box<dyn QuantityFunction> = Box::new(
+        Polynomial::new(vec![
+            DynQuantity::new(3.0, PredefUnit::Area),
+            DynQuantity::new(2.0, PredefUnit::Volume),
+        ])
+        .unwrap(),
+    );
+    assert_eq!(
+        fun.influencing_units(),
+        Some(vec![Unit::from(PredefUnit::Length)])
+    );
+
+    // Constant functions do not have any influencing factors
+    let fun: Box<dyn QuantityFunction> =
+        Box::new(Polynomial::new(vec![DynQuantity::new(2.0, PredefUnit::Volume)]).unwrap());
+    assert_eq!(fun.influencing_units(), Some(vec![]));
+    let fun: Box<dyn QuantityFunction> = Box::new(Exponential::new(vec![]).unwrap());
+    assert_eq!(fun.influencing_units(), Some(vec![]));
+}
+
+#[test]
+fn test_named_influencing_factors() {
+    // Two dimensionless inputs which cannot be distinguished by their unit
+    let factors = [
+        InfluencingFactor::new("slip", 0.5),
+        InfluencingFactor::new("duty_cycle", 2.0),
+    ];
+
+    let fun = Linear::new(0.5.into(), (-3.0).into());
+    assert_eq!(fun.call_named(&factors).value, -2.75);
+    let fun = fun.with_input_name("duty_cycle");
+    assert_eq!(fun.call_named(&factors).value, -2.0);
+
+    let fun = Polynomial::new(vec![3.0.into(), 2.0.into()])
+        .unwrap()
+        .with_input_name("duty_cycle");
+    assert_eq!(fun.call_named(&factors).value, 8.0);
+
+    let fun = FirstOrderTaylor::new(2.5.into(), 2.0.into(), 0.5.into())
+        .unwrap()
+        .with_input_name("duty_cycle");
+    assert_eq!(fun.call_named(&factors).value, 10.0);
+
+    let fun = Exponential::new(vec![ExpTerm {
+        amplitude: 2.0.into(),
+        exponent: 0.0.into(),
+    }])
+    .unwrap()
+    .with_input_name("duty_cycle");
+    assert_eq!(fun.call_named(&factors).value, 2.0);
+
+    // No factor with the bound name -> default value
+    let fun = Linear::new(0.5.into(), (-3.0).into()).with_input_name("speed");
+    assert_eq!(fun.call_named(&factors).value, -3.0);
+
+    // A factor with the bound name, but the wrong unit -> default value
+    let fun = Linear::new(
+        DynQuantity::new(2.0, PredefUnit::ElectricVoltage),
+        DynQuantity::new(0.5, PredefUnit::Power),
+    )
+    .with_input_name("slip");
+    assert_eq!(fun.call_named(&factors).value, 0.5);
+}
+
 // =================================================
 // Serde
 
@@ -577,3 +1469,503 @@ fn test_polynomial_serde() {
         );
     }
 }
+
+#[test]
+fn test_arrhenius_serde() {
+    let q = |s: &str| DynQuantity::<f64>::from_str(s).unwrap();
+
+    let yaml = indoc! {"
+        ---
+        pre_factor: 1e13 1/s
+        activation_energy: 0.7 eV
+        temperature_exponent: 1.0
+        input_name: hot_spot
+        "};
+    let fun: Arrhenius = serde_yaml::from_str(yaml).unwrap();
+    assert_eq!(fun.input_name(), Some("hot_spot"));
+    assert_eq!(fun.temperature_exponent(), 1.0);
+    assert_eq!(
+        fun,
+        Arrhenius::from_electronvolt(q("1e13 1/s"), 0.7)
+            .with_temperature_exponent(1.0)
+            .with_input_name("hot_spot")
+    );
+    assert_eq!(
+        *fun.activation_energy(),
+        DynQuantity::new(0.7 * ELECTRON_VOLT, PredefUnit::Energy)
+    );
+    let expected = fun.call(&[q("400 K")]).value;
+    approx::assert_relative_eq!(
+        expected,
+        1e13 * 400.0 * (-0.7 * ELECTRON_VOLT / (BOLTZMANN_CONSTANT * 400.0)).exp(),
+        max_relative = 1e-12
+    );
+
+    // Serialize again and deserialize, then run the tests again
+    let serialized = serde_yaml::to_string(&fun).unwrap();
+    assert!(!serialized.contains("activation_temperature"));
+    let deserialized: Arrhenius = serde_yaml::from_str(&serialized).unwrap();
+    assert_eq!(fun, deserialized);
+
+    // Activation energy per amount of substance, no temperature exponent
+    let yaml = indoc! {"
+        ---
+        pre_factor: 2.0
+        activation_energy: 50 kJ/mol
+        "};
+    let fun: Arrhenius = serde_yaml::from_str(yaml).unwrap();
+    assert_eq!(fun.temperature_exponent(), 0.0);
+    let serialized = serde_yaml::to_string(&fun).unwrap();
+    assert!(!serialized.contains("temperature_exponent"));
+
+    // Invalid activation energies
+    let yaml = indoc! {"
+        ---
+        pre_factor: 2.0
+        activation_energy: 50 kW
+        "};
+    assert!(serde_yaml::from_str::<Arrhenius>(yaml).is_err());
+    let yaml = indoc! {"
+        ---
+        pre_factor: 2.0
+        activation_energy: 0.7 keV
+        "};
+    assert!(serde_yaml::from_str::<Arrhenius>(yaml).is_err());
+}
+
+#[test]
+fn test_power_law_serde() {
+    let q = |s: &str| DynQuantity::<f64>::from_str(s).unwrap();
+
+    let yaml = indoc! {"
+        ---
+        terms:
+          - coefficient: 2 W
+            exponent: 1.3
+          - coefficient: 1 W
+            exponent: 2.0
+        reference: 50 Hz
+        input_name: frequency
+        "};
+    let fun: PowerLaw = serde_yaml::from_str(yaml).unwrap();
+    assert_eq!(fun.input_name(), Some("frequency"));
+    assert_eq!(fun.reference(), &q("50 Hz"));
+    assert_eq!(fun.call(&[q("50 Hz")]), q("3 W"));
+
+    // Serialize again and deserialize
+    let serialized = serde_yaml::to_string(&fun).unwrap();
+    assert!(!serialized.contains("output_unit"));
+    let deserialized: PowerLaw = serde_yaml::from_str(&serialized).unwrap();
+    assert_eq!(fun, deserialized);
+
+    // Mismatched coefficients
+    let yaml = indoc! {"
+        ---
+        terms:
+          - coefficient: 2 W
+            exponent: 1.3
+          - coefficient: 1 A
+            exponent: 2.0
+        reference: 50 Hz
+        "};
+    assert!(serde_yaml::from_str::<PowerLaw>(yaml).is_err());
+
+    // The reference point is required
+    let yaml = indoc! {"
+        ---
+        terms:
+          - coefficient: 2 W
+            exponent: 1.3
+        "};
+    assert!(serde_yaml::from_str::<PowerLaw>(yaml).is_err());
+
+    // Invalid reference point
+    let yaml = indoc! {"
+        ---
+        terms:
+          - coefficient: 2 W
+            exponent: 1.3
+        reference: 0 Hz
+        "};
+    assert!(serde_yaml::from_str::<PowerLaw>(yaml).is_err());
+}
+
+#[test]
+fn test_logarithmic_serde() {
+    let q = |s: &str| DynQuantity::<f64>::from_str(s).unwrap();
+
+    let yaml = indoc! {"
+        ---
+        offset: 1 V
+        slope: 0.05 V
+        reference: 1 mA
+        input_name: diode_current
+        "};
+    let fun: Logarithmic = serde_yaml::from_str(yaml).unwrap();
+    assert_eq!(fun.input_name(), Some("diode_current"));
+    assert_eq!(fun.call(&[q("1 mA")]), q("1 V"));
+
+    // Serialize again and deserialize
+    let serialized = serde_yaml::to_string(&fun).unwrap();
+    let deserialized: Logarithmic = serde_yaml::from_str(&serialized).unwrap();
+    assert_eq!(fun, deserialized);
+
+    // Mismatched offset and slope
+    let yaml = indoc! {"
+        ---
+        offset: 1 V
+        slope: 0.05 A
+        reference: 1 mA
+        "};
+    assert!(serde_yaml::from_str::<Logarithmic>(yaml).is_err());
+
+    // Invalid reference point
+    let yaml = indoc! {"
+        ---
+        offset: 1 V
+        slope: 0.05 V
+        reference: 0 mA
+        "};
+    assert!(serde_yaml::from_str::<Logarithmic>(yaml).is_err());
+}
+
+#[test]
+fn test_inverse_exponential_serde() {
+    let q = |s: &str| DynQuantity::<f64>::from_str(s).unwrap();
+
+    let yaml = indoc! {"
+        ---
+        amplitude: 10 kohm
+        coefficient: 3950 K
+        reference: 298.15 K
+        "};
+    let fun: InverseExponential = serde_yaml::from_str(yaml).unwrap();
+    assert_eq!(fun.call(&[q("298.15 K")]), q("10 kohm"));
+
+    // Serialize again and deserialize
+    let serialized = serde_yaml::to_string(&fun).unwrap();
+    let deserialized: InverseExponential = serde_yaml::from_str(&serialized).unwrap();
+    assert_eq!(fun, deserialized);
+
+    // The reference point is optional
+    let yaml = indoc! {"
+        ---
+        amplitude: 10 kohm
+        coefficient: 3950 K
+        "};
+    let fun: InverseExponential = serde_yaml::from_str(yaml).unwrap();
+    assert_eq!(fun.reference(), None);
+    assert!(!serde_yaml::to_string(&fun).unwrap().contains("reference"));
+
+    // Reference point with wrong unit
+    let yaml = indoc! {"
+        ---
+        amplitude: 10 kohm
+        coefficient: 3950 K
+        reference: 25 A
+        "};
+    assert!(serde_yaml::from_str::<InverseExponential>(yaml).is_err());
+}
+
+#[test]
+fn test_callendar_van_dusen_serde() {
+    let yaml = indoc! {"
+        ---
+        nominal_resistance: 100 ohm
+        a: 3.9083e-3 / K
+        b: -5.775e-7 / K^2
+        c: -4.183e-12 / K^4
+        "};
+    let fun: CallendarVanDusen = serde_yaml::from_str(yaml).unwrap();
+    let pt100 = CallendarVanDusen::pt100();
+    assert_eq!(fun.nominal_resistance(), pt100.nominal_resistance());
+    approx::assert_relative_eq!(fun.c().value, pt100.c().value, max_relative = 1e-12);
+
+    // Serialize again and deserialize
+    let serialized = serde_yaml::to_string(&fun).unwrap();
+    let deserialized: CallendarVanDusen = serde_yaml::from_str(&serialized).unwrap();
+    assert_eq!(fun, deserialized);
+
+    // Wrong unit of a coefficient
+    let yaml = indoc! {"
+        ---
+        nominal_resistance: 100 ohm
+        a: 3.9083e-3 / K
+        b: -5.775e-7 / K
+        c: -4.183e-12 / K^4
+        "};
+    assert!(serde_yaml::from_str::<CallendarVanDusen>(yaml).is_err());
+}
+
+#[test]
+fn test_steinhart_hart_serde() {
+    let yaml = indoc! {"
+        ---
+        a: 1.009249522e-3 / K
+        b: 2.378405444e-4 / K
+        c: 2.019202697e-7 / K
+        input_name: ntc
+        "};
+    let fun: SteinhartHart = serde_yaml::from_str(yaml).unwrap();
+    assert_eq!(fun.input_name(), Some("ntc"));
+
+    // Serialize again and deserialize
+    let serialized = serde_yaml::to_string(&fun).unwrap();
+    let deserialized: SteinhartHart = serde_yaml::from_str(&serialized).unwrap();
+    assert_eq!(fun, deserialized);
+
+    // Wrong unit of a coefficient
+    let yaml = indoc! {"
+        ---
+        a: 1.009249522e-3 / K
+        b: 2.378405444e-4 / K
+        c: 2.019202697e-7
+        "};
+    assert!(serde_yaml::from_str::<SteinhartHart>(yaml).is_err());
+}
+
+#[test]
+fn test_saturation_serde() {
+    let q = |s: &str| DynQuantity::<f64>::from_str(s).unwrap();
+
+    let yaml = indoc! {"
+        ---
+        a: 160 A/(m*T)
+        b: 0.55 / T
+        output: Permeability
+        input_name: stator_yoke
+        "};
+    let fun: Frohlich = serde_yaml::from_str(yaml).unwrap();
+    assert_eq!(fun.output(), SaturationOutput::Permeability);
+    assert_eq!(fun.input_name(), Some("stator_yoke"));
+    let serialized = serde_yaml::to_string(&fun).unwrap();
+    assert_eq!(fun, serde_yaml::from_str::<Frohlich>(&serialized).unwrap());
+
+    // The flux density is the default output
+    let yaml = indoc! {"
+        ---
+        saturation_magnetization: 1.6e6 A/m
+        a: 500 A/m
+        total_angular_momentum: 1.5
+        "};
+    let fun: Langevin = serde_yaml::from_str(yaml).unwrap();
+    assert_eq!(fun.output(), SaturationOutput::FluxDensity);
+    assert_eq!(fun.total_angular_momentum(), Some(1.5));
+    let serialized = serde_yaml::to_string(&fun).unwrap();
+    assert_eq!(fun, serde_yaml::from_str::<Langevin>(&serialized).unwrap());
+
+    let yaml = indoc! {"
+        ---
+        saturation_polarization: 1.8 T
+        characteristic_field: 200 A/m
+        "};
+    let fun: ArctanSaturation = serde_yaml::from_str(yaml).unwrap();
+    assert_eq!(fun.saturation_polarization(), &q("1.8 T"));
+    let serialized = serde_yaml::to_string(&fun).unwrap();
+    assert_eq!(
+        fun,
+        serde_yaml::from_str::<ArctanSaturation>(&serialized).unwrap()
+    );
+
+    // Parameters with wrong units
+    let yaml = indoc! {"
+        ---
+        a: 160 A/m
+        b: 0.55 / T
+        "};
+    assert!(serde_yaml::from_str::<Frohlich>(yaml).is_err());
+    let yaml = indoc! {"
+        ---
+        saturation_magnetization: 1.6 T
+        a: 500 A/m
+        "};
+    assert!(serde_yaml::from_str::<Langevin>(yaml).is_err());
+    let yaml = indoc! {"
+        ---
+        saturation_polarization: 1.8 T
+        characteristic_field: 200 T
+        "};
+    assert!(serde_yaml::from_str::<ArctanSaturation>(yaml).is_err());
+}
+
+#[test]
+fn test_input_name_serde() {
+    let yaml = indoc! {"
+        ---
+        base_value: 2.5 W
+        slope: 2.0 / A
+        expansion_point: 0.5 A
+        input_name: phase_current
+        "};
+    let fun: FirstOrderTaylor = serde_yaml::from_str(yaml).unwrap();
+    assert_eq!(fun.input_name(), Some("phase_current"));
+
+    let factors = [
+        InfluencingFactor::new(
+            "field_current",
+            DynQuantity::new(0.0, PredefUnit::ElectricCurrent),
+        ),
+        InfluencingFactor::new(
+            "phase_current",
+            DynQuantity::new(1.5, PredefUnit::ElectricCurrent),
+        ),
+    ];
+    assert_eq!(fun.call_named(&factors).value, 7.5);
+
+    // Serialize again and deserialize, then run the tests again
+    let serialized = serde_yaml::to_string(&fun).unwrap();
+    let fun: FirstOrderTaylor = serde_yaml::from_str(&serialized).unwrap();
+    assert_eq!(fun.input_name(), Some("phase_current"));
+    assert_eq!(fun.call_named(&factors).value, 7.5);
+
+    // Without binding, the input name is not serialized
+    let fun = Linear::new(0.5.into(), (-3.0).into());
+    let serialized = serde_yaml::to_string(&fun).unwrap();
+    assert!(!serialized.contains("input_name"));
+
+    // Binding of other unary functions
+    let yaml = indoc! {"
+        ---
+        coefficients:
+          - 3.0
+          - 2.0
+        input_name: duty_cycle
+        "};
+    let fun: Polynomial = serde_yaml::from_str(yaml).unwrap();
+    assert_eq!(fun.input_name(), Some("duty_cycle"));
+
+    let yaml = indoc! {"
+        ---
+        terms:
+          - amplitude: 2.0
+            exponent: 0.0
+        input_name: duty_cycle
+        "};
+    let fun: Exponential = serde_yaml::from_str(yaml).unwrap();
+    assert_eq!(fun.input_name(), Some("duty_cycle"));
+}
+
+#[test]
+fn test_lookup_serde() {
+    // Points
+    {
+        let yaml = indoc! {"
+        ---
+        points:
+          - [0 A/m, 0 T]
+          - [100 A/m, 1 T]
+          - [200 A/m, 1.5 T]
+        extrapolation: Error
+        "};
+        let fun: Lookup = serde_yaml::from_str(yaml).unwrap();
+        let h = |v: f64| DynQuantity::new(v, PredefUnit::MagneticFieldStrength);
+
+        assert_eq!(fun.extrapolation(), Extrapolation::Error);
+        assert_eq!(fun.call(&[h(50.0)]).value, 0.5);
+        assert_eq!(fun.call(&[h(150.0)]).value, 1.25);
+        assert!(fun.try_call(&[h(250.0)]).is_err());
+
+        // Serialize again and deserialize, then run the tests again
+        let serialized = serde_yaml::to_string(&fun).unwrap();
+        let fun: Lookup = serde_yaml::from_str(&serialized).unwrap();
+
+        assert_eq!(fun.extrapolation(), Extrapolation::Error);
+        assert_eq!(fun.call(&[h(50.0)]).value, 0.5);
+        assert_eq!(fun.call(&[h(150.0)]).value, 1.25);
+        assert!(fun.try_call(&[h(250.0)]).is_err());
+    }
+
+    // Columns, default extrapolation
+    {
+        let yaml = indoc! {"
+        ---
+        x: [1.0, 2.0, 3.0]
+        y: [10.0, 20.0, 10.0]
+        "};
+        let fun: Lookup = serde_yaml::from_str(yaml).unwrap();
+        assert_eq!(fun.extrapolation(), Extrapolation::Hold);
+        assert_eq!(fun.call(&[2.5.into()]).value, 15.0);
+        assert_eq!(fun.call(&[5.0.into()]).value, 10.0);
+    }
+
+    // Invalid inputs
+    {
+        // Not strictly increasing
+        let yaml = indoc! {"
+        ---
+        x: [1.0, 3.0, 2.0]
+        y: [10.0, 20.0, 10.0]
+        "};
+        assert!(serde_yaml::from_str::<Lookup>(yaml).is_err());
+
+        // Length mismatch
+        let yaml = indoc! {"
+        ---
+        x: [1.0, 2.0, 3.0]
+        y: [10.0, 20.0]
+        "};
+        assert!(serde_yaml::from_str::<Lookup>(yaml).is_err());
+
+        // Both formats
+        let yaml = indoc! {"
+        ---
+        points:
+          - [1.0, 10.0]
+          - [2.0, 20.0]
+        x: [1.0, 2.0]
+        y: [10.0, 20.0]
+        "};
+        assert!(serde_yaml::from_str::<Lookup>(yaml).is_err());
+    }
+}
+
+#[test]
+fn test_spline_serde() {
+    let yaml = indoc! {"
+        ---
+        points:
+          - [0 A/m, 0 T]
+          - [100 A/m, 1 T]
+          - [200 A/m, 1.5 T]
+          - [400 A/m, 1.7 T]
+        kind: Akima
+        extrapolation: Linear
+        input_name: h
+        "};
+    let fun: Spline = serde_yaml::from_str(yaml).unwrap();
+    let h = |v: f64| DynQuantity::new(v, PredefUnit::MagneticFieldStrength);
+
+    assert_eq!(fun.kind(), SplineKind::Akima);
+    assert_eq!(fun.extrapolation(), Extrapolation::Linear);
+    assert_eq!(fun.input_name(), Some("h"));
+    let expected: Vec<f64> = [50.0, 150.0, 300.0, 500.0]
+        .into_iter()
+        .map(|x| fun.call(&[h(x)]).value)
+        .collect();
+
+    // Only the support points are serialized, the slopes are recalculated
+    let serialized = serde_yaml::to_string(&fun).unwrap();
+    assert!(!serialized.contains("slopes"));
+    let deserialized: Spline = serde_yaml::from_str(&serialized).unwrap();
+    assert_eq!(fun, deserialized);
+    for (x, y) in [50.0, 150.0, 300.0, 500.0].into_iter().zip(expected) {
+        assert_eq!(deserialized.call(&[h(x)]).value, y);
+    }
+
+    // The kind must be given
+    let yaml = indoc! {"
+        ---
+        x: [1.0, 2.0, 3.0]
+        y: [10.0, 20.0, 10.0]
+        "};
+    assert!(serde_yaml::from_str::<Spline>(yaml).is_err());
+
+    // Not strictly increasing
+    let yaml = indoc! {"
+        ---
+        x: [1.0, 3.0, 2.0]
+        y: [10.0, 20.0, 10.0]
+        kind: Pchip
+        "};
+    assert!(serde_yaml::from_str::<Spline>(yaml).is_err());
+}
@@ -0,0 +1,402 @@
+use std::str::FromStr;
+
+use dyn_quantity::{DynQuantity, PredefUnit, Unit, UnitsNotEqual};
+use indoc::indoc;
+use uom::si::{electrical_resistance::ohm, f64::*};
+use var_quantity::{
+    ClampedQuantity, EvaluationError, FunctionWrapper, InfluencingFactor, QuantityFunction,
+    RangeError, VarQuantity,
+    combinators::{Compose, Product, Sum},
+    unary::{
+        ExpTerm, Exponential, Extrapolation, FirstOrderTaylor, Linear, Lookup, Polynomial, Spline,
+        SplineKind,
+    },
+    validity::{ValidityPolicy, ValidityRange},
+};
+
+fn q(s: &str) -> DynQuantity<f64> {
+    return DynQuantity::from_str(s).unwrap();
+}
+
+fn range(lower: &str, upper: &str) -> ValidityRange {
+    return ValidityRange::new(q(lower), q(upper)).unwrap();
+}
+
+/// Resistance of a copper conductor, fitted between 200 K and 500 K.
+fn resistance() -> FirstOrderTaylor {
+    return FirstOrderTaylor::new(q("1 ohm"), q("0.004 1/K"), q("300 K"))
+        .unwrap()
+        .with_validity(range("200 K", "500 K"))
+        .unwrap();
+}
+
+fn wrapper(policy: ValidityPolicy) -> FunctionWrapper<ElectricalResistance> {
+    return FunctionWrapper::new(Box::new(resistance()))
+        .unwrap()
+        .with_validity_policy(policy);
+}
+
+#[test]
+fn test_validity_range() {
+    let r = range("200 K", "500 K");
+    assert_eq!(r.unit(), Unit::from(PredefUnit::Temperature));
+    assert!(r.contains(&q("200 K")));
+    assert!(r.contains(&q("500 K")));
+    assert!(!r.contains(&q("600 K")));
+    assert!(!r.contains(&q("300 A")));
+    assert!(!r.contains(&DynQuantity::new(f64::NAN, PredefUnit::Temperature)));
+    assert_eq!(r.clamp(q("100 K")), q("200 K"));
+    assert_eq!(r.clamp(q("600 A")), q("600 A"));
+    assert_eq!(
+        r.check(&q("600 K")),
+        Err(EvaluationError::InputOutOfRange {
+            input: q("600 K"),
+            lower: q("200 K"),
+            upper: q("500 K")
+        })
+    );
+
+    // Degenerate range
+    assert!(ValidityRange::new(q("300 K"), q("300 K")).is_ok());
+
+    // Non-finite limits are only allowed for one-sided ranges
+    let kelvin = |value: f64| DynQuantity::new(value, PredefUnit::Temperature);
+    let one_sided = ValidityRange::new(kelvin(f64::NEG_INFINITY), q("500 K")).unwrap();
+    assert!(one_sided.contains(&q("-1000 K")));
+    assert!(!one_sided.contains(&q("600 K")));
+    assert_eq!(one_sided.clamp(q("600 K")), q("500 K"));
+    assert!(ValidityRange::new(q("200 K"), kelvin(f64::INFINITY)).is_ok());
+    assert!(ValidityRange::new(kelvin(f64::NEG_INFINITY), kelvin(f64::INFINITY)).is_ok());
+    for (lower, upper) in [
+        (kelvin(f64::NAN), q("500 K")),
+        (q("200 K"), kelvin(f64::NAN)),
+        (kelvin(f64::INFINITY), kelvin(f64::INFINITY)),
+        (kelvin(f64::NEG_INFINITY), kelvin(f64::NEG_INFINITY)),
+    ] {
+        assert!(matches!(
+            ValidityRange::new(lower, upper),
+            Err(RangeError::NotFinite(_))
+        ));
+    }
+}
+
+#[test]
+fn test_unary_functions() {
+    let temperature = range("200 K", "500 K");
+    let functions: Vec<Box<dyn QuantityFunction>> = vec![
+        Box::new(
+            Linear::new(q("0.5 ohm/K"), q("1 ohm"))
+                .with_validity(temperature.clone())
+                .unwrap(),
+        ),
+        Box::new(resistance()),
+        Box::new(
+            Polynomial::new(vec![q("1 ohm/K^2"), q("1 ohm/K"), q("1 ohm")])
+                .unwrap()
+                .with_validity(temperature.clone())
+                .unwrap(),
+        ),
+        Box::new(
+            Exponential::new(vec![ExpTerm {
+                amplitude: q("1 ohm"),
+                exponent: q("0.01 1/K"),
+            }])
+            .unwrap()
+            .with_validity(temperature.clone())
+            .unwrap(),
+        ),
+        Box::new(
+            Lookup::new(
+                vec![(q("0 K"), q("1 ohm")), (q("1000 K"), q("2 ohm"))],
+                Extrapolation::Hold,
+            )
+            .unwrap()
+            .with_validity(temperature.clone())
+            .unwrap(),
+        ),
+        Box::new(
+            Spline::new(
+                vec![
+                    (q("0 K"), q("1 ohm")),
+                    (q("500 K"), q("1.5 ohm")),
+                    (q("1000 K"), q("2 ohm")),
+                ],
+                SplineKind::Natural,
+                Extrapolation::Hold,
+            )
+            .unwrap()
+            .with_validity(temperature.clone())
+            .unwrap(),
+        ),
+    ];
+    for function in functions.iter() {
+        assert_eq!(function.validity_ranges(), vec![temperature.clone()]);
+    }
+
+    // Unit mismatch
+    assert_eq!(
+        Linear::new(q("0.5 ohm/K"), q("1 ohm"))
+            .with_validity(range("0 A", "1 A"))
+            .unwrap_err(),
+        UnitsNotEqual(
+            Unit::from(PredefUnit::Temperature),
+            Unit::from(PredefUnit::ElectricCurrent)
+        )
+    );
+
+    // Without a range
+    assert!(
+        Linear::new(q("0.5 ohm/K"), q("1 ohm"))
+            .validity_ranges()
+            .is_empty()
+    );
+
+    // The range is bound to the input name
+    let named = resistance().with_input_name("winding");
+    assert_eq!(named.validity_ranges()[0].input_name(), Some("winding"));
+}
+
+#[test]
+fn test_extrapolation_error() {
+    // Functions which reject extrapolation report their support points as range
+    let points = vec![(q("200 K"), q("1 ohm")), (q("400 K"), q("2 ohm"))];
+    let lookup = Lookup::new(points.clone(), Extrapolation::Error).unwrap();
+    let spline = Spline::new(points.clone(), SplineKind::Pchip, Extrapolation::Error).unwrap();
+    assert_eq!(lookup.validity_ranges(), vec![range("200 K", "400 K")]);
+    assert_eq!(spline.validity_ranges(), vec![range("200 K", "400 K")]);
+
+    // ... in addition to an explicit range
+    let lookup = lookup.with_validity(range("250 K", "300 K")).unwrap();
+    assert_eq!(
+        lookup.validity_ranges(),
+        vec![range("250 K", "300 K"), range("200 K", "400 K")]
+    );
+    assert!(
+        Lookup::new(points.clone(), Extrapolation::Hold)
+            .unwrap()
+            .validity_ranges()
+            .is_empty()
+    );
+
+    // Hence the policy of a wrapper applies instead of returning NaN
+    let wrapper = FunctionWrapper::<ElectricalResistance>::new(Box::new(spline.clone())).unwrap();
+    assert!(wrapper.call(&[q("500 K")]).get::<ohm>().is_nan());
+    let wrapper = wrapper.with_validity_policy(ValidityPolicy::Clamp);
+    assert_eq!(wrapper.call(&[q("500 K")]).get::<ohm>(), 2.0);
+    let wrapper = FunctionWrapper::<ElectricalResistance>::new(Box::new(spline))
+        .unwrap()
+        .with_validity_policy(ValidityPolicy::Error);
+    assert!(wrapper.try_call(&[q("500 K")]).is_err());
+}
+
+#[test]
+fn test_policies() {
+    let inside = [q("400 K")];
+    let outside = [q("600 K")];
+
+    // Ignore (default)
+    let ignore = FunctionWrapper::<ElectricalResistance>::new(Box::new(resistance())).unwrap();
+    assert_eq!(ignore.validity_policy(), ValidityPolicy::Ignore);
+    approx::assert_abs_diff_eq!(ignore.call(&outside).get::<ohm>(), 2.2, epsilon = 1e-12);
+    assert!(ignore.try_call(&outside).is_ok());
+
+    // Clamp
+    let clamp = wrapper(ValidityPolicy::Clamp);
+    approx::assert_abs_diff_eq!(clamp.call(&inside).get::<ohm>(), 1.4, epsilon = 1e-12);
+    approx::assert_abs_diff_eq!(clamp.call(&outside).get::<ohm>(), 1.8, epsilon = 1e-12);
+    approx::assert_abs_diff_eq!(
+        clamp.try_call(&outside).unwrap().get::<ohm>(),
+        1.8,
+        epsilon = 1e-12
+    );
+
+    // Error
+    let error = wrapper(ValidityPolicy::Error);
+    approx::assert_abs_diff_eq!(error.call(&inside).get::<ohm>(), 1.4, epsilon = 1e-12);
+    assert!(error.call(&outside).get::<ohm>().is_nan());
+    assert_eq!(
+        error.try_call(&outside),
+        Err(EvaluationError::InputOutOfRange {
+            input: q("600 K"),
+            lower: q("200 K"),
+            upper: q("500 K")
+        })
+    );
+
+    // Missing factors are not checked
+    assert!(error.try_call(&[]).is_ok());
+
+    // Warn
+    let warn = wrapper(ValidityPolicy::Warn);
+    approx::assert_abs_diff_eq!(warn.call(&outside).get::<ohm>(), 2.2, epsilon = 1e-12);
+    warn.call(&inside);
+    assert!(warn.try_call(&outside).is_ok());
+    let warnings = warn.take_warnings();
+    assert_eq!(warnings.len(), 1);
+    assert_eq!(warnings[0].count(), 2);
+    assert_eq!(warnings[0].range(), &range("200 K", "500 K"));
+    assert_eq!(
+        warnings[0].error(),
+        EvaluationError::InputOutOfRange {
+            input: q("600 K"),
+            lower: q("200 K"),
+            upper: q("500 K")
+        }
+    );
+    assert!(warn.take_warnings().is_empty());
+
+    // The warnings are aggregated per range and keep the farthest outlier
+    for value in 0..1000 {
+        warn.call(&[DynQuantity::new(
+            600.0 + value as f64,
+            PredefUnit::Temperature,
+        )]);
+    }
+    warn.call(&[q("100 K")]);
+    let warnings = warn.take_warnings();
+    assert_eq!(warnings.len(), 1);
+    assert_eq!(warnings[0].count(), 1001);
+    assert_eq!(warnings[0].worst_input(), &q("1599 K"));
+
+    // NaN inputs are the farthest outliers
+    warn.call(&[q("600 K")]);
+    warn.call(&[DynQuantity::new(f64::NAN, PredefUnit::Temperature)]);
+    let warnings = warn.take_warnings();
+    assert_eq!(warnings[0].count(), 2);
+    assert!(warnings[0].worst_input().value.is_nan());
+
+    // Derivatives are not affected by the policy
+    approx::assert_abs_diff_eq!(
+        clamp
+            .derivative::<ThermodynamicTemperature>(&outside)
+            .unwrap()
+            .value,
+        0.004,
+        epsilon = 1e-12
+    );
+}
+
+#[test]
+fn test_named() {
+    let fun = resistance().with_input_name("winding");
+    let clamp = FunctionWrapper::<ElectricalResistance>::new(Box::new(fun))
+        .unwrap()
+        .with_validity_policy(ValidityPolicy::Clamp);
+    let error = clamp.clone().with_validity_policy(ValidityPolicy::Error);
+
+    // Only the winding temperature is checked
+    let factors = [
+        InfluencingFactor::new("ambient", q("100 K")),
+        InfluencingFactor::new("winding", q("600 K")),
+    ];
+    approx::assert_abs_diff_eq!(
+        clamp.call_named(&factors).get::<ohm>(),
+        1.8,
+        epsilon = 1e-12
+    );
+    assert_eq!(
+        error.try_call_named(&factors),
+        Err(EvaluationError::InputOutOfRange {
+            input: q("600 K"),
+            lower: q("200 K"),
+            upper: q("500 K")
+        })
+    );
+
+    let factors = [
+        InfluencingFactor::new("ambient", q("100 K")),
+        InfluencingFactor::new("winding", q("400 K")),
+    ];
+    approx::assert_abs_diff_eq!(
+        error.try_call_named(&factors).unwrap().get::<ohm>(),
+        1.4,
+        epsilon = 1e-12
+    );
+}
+
+#[test]
+fn test_combinators() {
+    let current = Linear::new(q("0 1/A"), q("1"))
+        .with_validity(range("0 A", "10 A"))
+        .unwrap();
+    let product = Product::new(vec![Box::new(resistance()), Box::new(current)]);
+    assert_eq!(
+        product.validity_ranges(),
+        vec![range("200 K", "500 K"), range("0 A", "10 A")]
+    );
+
+    let sum = Sum::new(vec![Box::new(resistance()), Box::new(resistance())]).unwrap();
+    assert_eq!(sum.validity_ranges(), vec![range("200 K", "500 K")]);
+
+    let clamped = ClampedQuantity::new(10.0, 0.0, resistance()).unwrap();
+    assert_eq!(clamped.validity_ranges(), vec![range("200 K", "500 K")]);
+
+    // The range of the outer function on the intermediate quantity is omitted
+    let temperature = Linear::new(q("0.1 K/W"), q("300 K"))
+        .with_validity(range("0 W", "5000 W"))
+        .unwrap();
+    let compose = Compose::new(Box::new(resistance()), Box::new(temperature)).unwrap();
+    assert_eq!(compose.validity_ranges(), vec![range("0 W", "5000 W")]);
+
+    // The policy of a VarQuantity built via operators applies to all ranges
+    let a = VarQuantity::<ElectricalResistance>::try_from_quantity_function(resistance()).unwrap();
+    let VarQuantity::Function(sum) = a.clone() + a else {
+        panic!("sum of functions must be a function")
+    };
+    let sum = sum.with_validity_policy(ValidityPolicy::Clamp);
+    approx::assert_abs_diff_eq!(sum.call(&[q("600 K")]).get::<ohm>(), 3.6, epsilon = 1e-12);
+}
+
+#[test]
+fn test_serde() {
+    let yaml = indoc! {"
+        ---
+        FirstOrderTaylor:
+          base_value: 1 ohm
+          slope: 0.004 1/K
+          expansion_point: 300 K
+          validity:
+            lower: 200 K
+            upper: 500 K
+        "};
+    let fun: Box<dyn QuantityFunction> = serde_yaml::from_str(yaml).unwrap();
+    assert_eq!(fun.validity_ranges(), vec![range("200 K", "500 K")]);
+
+    // Round trip
+    let serialized = serde_yaml::to_string(&fun).unwrap();
+    assert!(serialized.contains("validity:"));
+    let deserialized: Box<dyn QuantityFunction> = serde_yaml::from_str(&serialized).unwrap();
+    assert_eq!(deserialized.validity_ranges(), fun.validity_ranges());
+
+    // Functions without a range do not serialize it
+    let fun: Box<dyn QuantityFunction> = Box::new(Linear::new(q("1 ohm/K"), q("1 ohm")));
+    assert!(!serde_yaml::to_string(&fun).unwrap().contains("validity"));
+
+    // Wrong unit
+    let yaml = indoc! {"
+        ---
+        Linear:
+          slope: 1 ohm/K
+          base_value: 1 ohm
+          validity:
+            lower: 0 A
+            upper: 10 A
+        "};
+    assert!(serde_yaml::from_str::<Box<dyn QuantityFunction>>(yaml).is_err());
+
+    // Lower limit above upper limit
+    let yaml = indoc! {"
+        ---
+        Linear:
+          slope: 1 ohm/K
+          base_value: 1 ohm
+          validity:
+            lower: 500 K
+            upper: 200 K
+        "};
+    assert!(serde_yaml::from_str::<Box<dyn QuantityFunction>>(yaml).is_err());
+
+    // Policy
+    let policy: ValidityPolicy = serde_yaml::from_str("Clamp").unwrap();
+    assert_eq!(policy, ValidityPolicy::Clamp);
+}
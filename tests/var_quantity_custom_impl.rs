@@ -4,7 +4,10 @@ use uom::si::{
     electric_current::ampere, electric_potential::volt, f64::*, frequency::hertz,
     magnetic_flux_density::tesla, power::watt, thermodynamic_temperature::degree_celsius,
 };
-use var_quantity::{FunctionWrapper, QuantityFunction, VarQuantity};
+use var_quantity::{
+    EvaluationError, FunctionWrapper, QuantityFunction, VarQuantity, combinators::Compose,
+    unary::Linear,
+};
 
 #[test]
 fn test_var_quantity() {
@@ -133,3 +136,147 @@ fn test_readme_example() {
     assert_eq!(losses(&model1, b, f).get::<watt>(), 1440.0);
     assert_eq!(losses(&model2, b, f).get::<watt>(), 1152.0);
 }
+
+#[test]
+fn test_try_get_unit_mismatch() {
+    // Buggy implementation which returns the first influencing factor as-is
+    #[derive(Serialize, Deserialize, Clone)]
+    struct ReturnFirstFactor(Power);
+
+    #[typetag::serde]
+    impl QuantityFunction for ReturnFirstFactor {
+        fn call(&self, influencing_factors: &[DynQuantity<f64>]) -> DynQuantity<f64> {
+            return influencing_factors
+                .first()
+                .cloned()
+                .unwrap_or(DynQuantity::from(self.0));
+        }
+    }
+
+    let var_quantity: VarQuantity<Power> =
+        VarQuantity::try_from_quantity_function(ReturnFirstFactor(Power::new::<watt>(2.0)))
+            .unwrap();
+
+    // Valid output
+    assert_eq!(var_quantity.try_get(&[]).unwrap().get::<watt>(), 2.0);
+    assert_eq!(
+        var_quantity
+            .try_get(&[DynQuantity::new(3.0, PredefUnit::Power)])
+            .unwrap()
+            .get::<watt>(),
+        3.0
+    );
+
+    // Invalid output
+    let influencing_factors = [ElectricCurrent::new::<ampere>(2.0).into()];
+    match var_quantity.try_get(&influencing_factors) {
+        Ok(_) => panic!("expected an error"),
+        Err(EvaluationError::UnitMismatch {
+            output,
+            expected,
+            influencing_factors: factors,
+        }) => {
+            assert_eq!(output, DynQuantity::new(2.0, PredefUnit::ElectricCurrent));
+            assert_eq!(expected, Unit::from(PredefUnit::Power));
+            assert_eq!(factors, influencing_factors.to_vec());
+        }
+        Err(err) => panic!("unexpected error {err}"),
+    }
+
+    // Constant quantities never fail
+    let var_quantity = VarQuantity::Constant(Power::new::<watt>(1.0));
+    assert_eq!(
+        var_quantity
+            .try_get(&influencing_factors)
+            .unwrap()
+            .get::<watt>(),
+        1.0
+    );
+}
+
+#[test]
+fn test_declared_output_unit() {
+    // The empty-input branch of this function returns a dimensionless value,
+    // but the declared output unit is watt.
+    #[derive(Serialize, Deserialize, Clone)]
+    struct PowerIfCurrent(ElectricPotential);
+
+    #[typetag::serde]
+    impl QuantityFunction for PowerIfCurrent {
+        fn call(&self, influencing_factors: &[DynQuantity<f64>]) -> DynQuantity<f64> {
+            if influencing_factors.is_empty() {
+                return DynQuantity::new(0.0, PredefUnit::None);
+            }
+            let current = influencing_factors
+                .iter()
+                .find(|iq| Unit::from(PredefUnit::ElectricCurrent) == iq.unit)
+                .cloned()
+                .unwrap_or(DynQuantity::new(0.0, PredefUnit::ElectricCurrent));
+            return DynQuantity::from(self.0) * current;
+        }
+
+        fn output_unit(&self) -> Unit {
+            return PredefUnit::Power.into();
+        }
+    }
+
+    let fun = PowerIfCurrent(ElectricPotential::new::<volt>(0.5));
+    assert!(FunctionWrapper::<Power>::new(Box::new(fun.clone())).is_ok());
+    assert!(FunctionWrapper::<f64>::new(Box::new(fun.clone())).is_err());
+
+    let var_quantity: VarQuantity<Power> = VarQuantity::try_from_quantity_function(fun).unwrap();
+    assert_eq!(
+        var_quantity
+            .get(&[ElectricCurrent::new::<ampere>(4.0).into()])
+            .get::<watt>(),
+        2.0
+    );
+}
+
+#[test]
+fn test_undeclared_influencing_units() {
+    // This function reads the current, but does not declare it.
+    #[derive(Serialize, Deserialize, Clone)]
+    struct PowerFromCurrent(ElectricPotential);
+
+    #[typetag::serde]
+    impl QuantityFunction for PowerFromCurrent {
+        fn call(&self, influencing_factors: &[DynQuantity<f64>]) -> DynQuantity<f64> {
+            let current = influencing_factors
+                .iter()
+                .find(|iq| Unit::from(PredefUnit::ElectricCurrent) == iq.unit)
+                .cloned()
+                .unwrap_or(DynQuantity::new(0.0, PredefUnit::ElectricCurrent));
+            return DynQuantity::from(self.0) * current;
+        }
+    }
+
+    let fun = PowerFromCurrent(ElectricPotential::new::<volt>(0.5));
+    assert_eq!(fun.influencing_units(), None);
+
+    // Unknown inputs are not reported as "nothing missing"
+    let var_quantity: VarQuantity<Power> =
+        VarQuantity::try_from_quantity_function(fun.clone()).unwrap();
+    assert_eq!(var_quantity.influencing_units(), None);
+    assert_eq!(var_quantity.missing_influencing_units(&[]), None);
+
+    // A constant does not have any influencing factors
+    let var_quantity = VarQuantity::Constant(Power::new::<watt>(1.0));
+    assert_eq!(var_quantity.influencing_units(), Some(vec![]));
+    assert_eq!(var_quantity.missing_influencing_units(&[]), Some(vec![]));
+
+    // The outer function of a composition may use the default implementation
+    let inner = Linear::new(
+        DynQuantity::new(
+            2.0,
+            Unit::from(PredefUnit::ElectricCurrent) / PredefUnit::Temperature.into(),
+        ),
+        DynQuantity::new(0.0, PredefUnit::ElectricCurrent),
+    );
+    let composed = Compose::new(Box::new(fun), Box::new(inner)).unwrap();
+    assert_eq!(composed.influencing_units(), None);
+    assert_eq!(
+        composed.call(&[DynQuantity::new(2.0, PredefUnit::Temperature)]),
+        DynQuantity::new(2.0, PredefUnit::Power)
+    );
+}
@@ -0,0 +1,205 @@
+use std::str::FromStr;
+
+use dyn_quantity::DynQuantity;
+use uom::si::{
+    area::square_meter, electric_current::ampere, electrical_resistance::ohm, f64::*,
+    length::meter, power::watt, thermodynamic_temperature::kelvin,
+};
+use var_quantity::{
+    VarQuantity,
+    unary::Linear,
+    validity::{ValidityPolicy, ValidityRange},
+};
+
+fn q(s: &str) -> DynQuantity<f64> {
+    return DynQuantity::from_str(s).unwrap();
+}
+
+/// Resistivity rho = 1.7e-8 ohm*m + 1e-10 ohm*m/K * T
+fn resistivity() -> VarQuantity<ElectricalResistivity> {
+    let lin = Linear::new(q("1e-10 ohm*m/K"), q("1.7e-8 ohm*m"));
+    return VarQuantity::try_from_quantity_function(lin).unwrap();
+}
+
+/// Current I = 1 A + 0.5 A/s * t
+fn current() -> VarQuantity<ElectricCurrent> {
+    let lin = Linear::new(q("0.5 A/s"), q("1 A"));
+    return VarQuantity::try_from_quantity_function(lin).unwrap();
+}
+
+fn temperature(value: f64) -> DynQuantity<f64> {
+    return ThermodynamicTemperature::new::<kelvin>(value).into();
+}
+
+#[test]
+fn test_constant_folding() {
+    let a = VarQuantity::from(Length::new::<meter>(3.0));
+    let b = VarQuantity::from(Length::new::<meter>(2.0));
+
+    let sum = a.clone() + b.clone();
+    let difference = a.clone() - b.clone();
+    let product = a.clone() * b.clone();
+    let quotient = a / b;
+
+    let VarQuantity::Constant(sum) = sum else {
+        panic!("sum of constants must be constant")
+    };
+    assert_eq!(sum.get::<meter>(), 5.0);
+    let VarQuantity::Constant(difference) = difference else {
+        panic!("difference of constants must be constant")
+    };
+    assert_eq!(difference.get::<meter>(), 1.0);
+    let VarQuantity::Constant(product) = product else {
+        panic!("product of constants must be constant")
+    };
+    assert_eq!(product.get::<square_meter>(), 6.0);
+    let VarQuantity::Constant(quotient) = quotient else {
+        panic!("quotient of constants must be constant")
+    };
+    assert_eq!(quotient.value, 1.5);
+
+    // Dimensionless f64
+    let VarQuantity::Constant(value) = VarQuantity::from(2.0) * VarQuantity::from(4.0) else {
+        panic!("product of constants must be constant")
+    };
+    assert_eq!(value, 8.0);
+
+    // Plain quantities and f64 values as right-hand operands
+    let a = VarQuantity::from(Length::new::<meter>(3.0));
+    let VarQuantity::Constant(sum) = a.clone() + Length::new::<meter>(2.0) else {
+        panic!("sum of constants must be constant")
+    };
+    assert_eq!(sum.get::<meter>(), 5.0);
+    let VarQuantity::Constant(product) = a.clone() * Length::new::<meter>(2.0) else {
+        panic!("product of constants must be constant")
+    };
+    assert_eq!(product.get::<square_meter>(), 6.0);
+    let VarQuantity::Constant(quotient) = a / 2.0 else {
+        panic!("quotient of constants must be constant")
+    };
+    assert_eq!(quotient.get::<meter>(), 1.5);
+}
+
+#[test]
+fn test_resistance_from_resistivity() {
+    let length = VarQuantity::from(Length::new::<meter>(2.0));
+    let area = VarQuantity::from(Area::new::<square_meter>(1e-6));
+
+    // Resistivity / length yields a derived type, which is then multiplied
+    // with another derived type
+    let per_area = resistivity() / area;
+    let resistance: VarQuantity<ElectricalResistance> = per_area * length;
+    assert!(matches!(resistance, VarQuantity::Function(_)));
+    approx::assert_abs_diff_eq!(
+        resistance.get(&[temperature(100.0)]).get::<ohm>(),
+        (1.7e-8 + 1e-8) * 2e6,
+        epsilon = 1e-12
+    );
+
+    // Derivative with respect to the temperature
+    let derivative = resistance
+        .derivative::<ThermodynamicTemperature>(&[temperature(100.0)])
+        .unwrap();
+    approx::assert_abs_diff_eq!(derivative.value, 1e-10 * 2e6, epsilon = 1e-12);
+}
+
+#[test]
+fn test_functions() {
+    let t = q("4 s");
+
+    // Function + constant and function - constant
+    let offset = VarQuantity::from(ElectricCurrent::new::<ampere>(1.0));
+    let sum = current() + offset.clone();
+    assert_eq!(sum.get(&[t]).get::<ampere>(), 4.0);
+    let difference = current() - offset.clone();
+    assert_eq!(difference.get(&[t]).get::<ampere>(), 2.0);
+
+    // Function with plain quantities and f64 values
+    let sum = current() + ElectricCurrent::new::<ampere>(1.0);
+    assert_eq!(sum.get(&[t]).get::<ampere>(), 4.0);
+    let difference = current() - ElectricCurrent::new::<ampere>(1.0);
+    assert_eq!(difference.get(&[t]).get::<ampere>(), 2.0);
+    let doubled = current() * 2.0;
+    assert_eq!(doubled.get(&[t]).get::<ampere>(), 6.0);
+    assert_eq!(doubled.gradient(&[t]), vec![q("1 A/s")]);
+    let halved = current() / 2.0;
+    assert_eq!(halved.get(&[t]).get::<ampere>(), 1.5);
+    let voltage: VarQuantity<ElectricPotential> = current() * ElectricalResistance::new::<ohm>(2.0);
+    assert_eq!(
+        voltage.get(&[t]).get::<uom::si::electric_potential::volt>(),
+        6.0
+    );
+
+    // Constant - function
+    let difference = offset - current();
+    assert_eq!(difference.get(&[t]).get::<ampere>(), -2.0);
+
+    // Function - function
+    let zero = current() - current();
+    assert_eq!(zero.get(&[t]).get::<ampere>(), 0.0);
+    assert_eq!(zero.gradient(&[t]), vec![q("0 A/s")]);
+
+    // Function * function: P = R * I² with a temperature-dependent resistance
+    let resistance = VarQuantity::<ElectricalResistance>::try_from_quantity_function(Linear::new(
+        q("0.01 ohm/K"),
+        q("1 ohm"),
+    ))
+    .unwrap();
+    let power: VarQuantity<Power> = resistance * current() * current();
+    let factors = [t, temperature(100.0)];
+    assert_eq!(power.get(&factors).get::<watt>(), 18.0);
+    assert_eq!(power.gradient(&factors), vec![q("6 W/s"), q("0.09 W/K")]);
+
+    // Constant / function and function / function
+    let voltage = VarQuantity::from(ElectricPotential::new::<uom::si::electric_potential::volt>(
+        6.0,
+    ));
+    let resistance: VarQuantity<ElectricalResistance> = voltage / current();
+    assert_eq!(resistance.get(&[t]).get::<ohm>(), 2.0);
+    let ratio: VarQuantity<Ratio> = current() / current();
+    assert_eq!(ratio.get(&[t]).value, 1.0);
+}
+
+#[test]
+fn test_validity_policy() {
+    let lin = Linear::new(q("0.5 A/s"), q("1 A"))
+        .with_validity(ValidityRange::new(q("0 s"), q("10 s")).unwrap())
+        .unwrap();
+    let VarQuantity::Function(wrapper) =
+        VarQuantity::<ElectricCurrent>::try_from_quantity_function(lin).unwrap()
+    else {
+        panic!("expected a function");
+    };
+    let clamped = VarQuantity::Function(wrapper.with_validity_policy(ValidityPolicy::Clamp));
+    assert_eq!(clamped.get(&[q("20 s")]).get::<ampere>(), 6.0);
+
+    // The result of an operation ignores the validity ranges by default ...
+    let VarQuantity::Function(wrapper) = clamped * 2.0 else {
+        panic!("expected a function");
+    };
+    assert_eq!(wrapper.validity_policy(), ValidityPolicy::Ignore);
+    assert_eq!(wrapper.call(&[q("20 s")]).get::<ampere>(), 22.0);
+
+    // ... but still reports them, so a policy can be set on the result
+    let wrapper = wrapper.with_validity_policy(ValidityPolicy::Clamp);
+    assert_eq!(wrapper.call(&[q("20 s")]).get::<ampere>(), 12.0);
+}
+
+#[test]
+fn test_serde() {
+    let area = VarQuantity::from(Area::new::<square_meter>(1e-6));
+    let per_area = resistivity() * VarQuantity::from(2.0) / area;
+
+    let yaml = serde_yaml::to_string(&per_area).unwrap();
+    let deserialized: VarQuantity<<ElectricalResistivity as std::ops::Div<Area>>::Output> =
+        serde_yaml::from_str(&yaml).unwrap();
+    assert_eq!(
+        deserialized.get(&[temperature(100.0)]),
+        per_area.get(&[temperature(100.0)])
+    );
+    approx::assert_abs_diff_eq!(
+        (deserialized.get(&[temperature(100.0)]) * Length::new::<meter>(1.0)).get::<ohm>(),
+        2.0 * 2.7e-8 * 1e6,
+        epsilon = 1e-12
+    );
+}
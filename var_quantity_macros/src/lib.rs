@@ -0,0 +1,424 @@
+/*!
+Procedural macros for the [var_quantity](https://crates.io/crates/var_quantity)
+crate. This crate should not be used directly - instead, activate the `macros`
+feature of var_quantity, which reexports the macros defined here.
+*/
+#![deny(missing_docs)]
+#![allow(clippy::needless_return)]
+
+use std::str::FromStr;
+
+use dyn_quantity::DynQuantity;
+use proc_macro::TokenStream;
+use proc_macro2::{Literal, TokenStream as TokenStream2};
+use quote::{format_ident, quote};
+use syn::{
+    Error, Expr, FnArg, Ident, ImplItem, ImplItemFn, ItemImpl, LitStr, Pat, parse_macro_input,
+    spanned::Spanned,
+};
+
+/**
+Implements `QuantityFunction` for a type based on a plain method which works
+with raw `f64` values.
+
+The attribute is placed on an inherent `impl` block which contains exactly one
+method. This method takes `&self` and one `f64` argument per influencing
+factor and returns the output value as `f64`. The units are declared in the
+attribute:
+
+- `output = "<unit>"`: The unit of the value returned by the method.
+- `inputs(<argument> = "<unit>", ...)`: The unit of each argument. An argument
+  can also be declared as `<argument>(unit = "<unit>", default = <value>)` to
+  define the value which is used if no influencing factor with the unit is
+  given. Without an explicit default, a missing influencing factor is zero.
+
+The units are parsed at compile time, hence a typo results in a compile error.
+They may contain a prefix (e.g. `"kHz"` or `"mW"`): The arguments are then
+passed to the method in the declared unit and the output is interpreted in the
+declared unit as well. Default values are given in the declared unit of their
+argument. Units which are converted with an offset (e.g. `"°C"`) are rejected,
+since the arguments are only scaled:
+
+```compile_fail
+use var_quantity::quantity_function;
+
+#[derive(Clone, serde::Serialize, serde::Deserialize)]
+struct Resistance;
+
+#[quantity_function(output = "Ohm", inputs(t = "°C"))]
+impl Resistance {
+    fn resistance(&self, t: f64) -> f64 {
+        return 1.0 + 0.004 * t;
+    }
+}
+```
+
+The macro keeps the `impl` block unchanged and generates the
+`QuantityFunction` implementation. When called with unnamed influencing
+factors, each argument takes the first factor with its unit (if several
+arguments have the same unit, they take the matching factors in order). When
+called with named influencing factors, an argument takes the factor which has
+its name and unit and otherwise falls back to its default, like the unary
+functions of var_quantity with an input name. If the
+`serde` feature of var_quantity is enabled, the implementation is annotated
+with `#[typetag::serde]`, so the type needs to implement `Serialize` and
+`Deserialize`.
+
+# Examples
+
+```
+use dyn_quantity::{DynQuantity, PredefUnit, Unit};
+use var_quantity::{QuantityFunction, quantity_function};
+
+// The serde annotations are just here because the doctests of this crate use
+// the serde feature - they are not needed if the serde feature is disabled.
+
+// Eddy current losses: p = k * f^2 * B^2
+#[derive(Clone, serde::Serialize, serde::Deserialize)]
+struct EddyCurrentLosses {
+    k: f64,
+}
+
+#[quantity_function(output = "W", inputs(b = "T", f(unit = "Hz", default = 50.0)))]
+impl EddyCurrentLosses {
+    fn losses(&self, b: f64, f: f64) -> f64 {
+        return self.k * f.powi(2) * b.powi(2);
+    }
+}
+
+let model = EddyCurrentLosses { k: 2.0 };
+assert_eq!(model.output_unit(), Unit::from(PredefUnit::Power));
+assert_eq!(
+    model.influencing_units(),
+    Some(vec![PredefUnit::MagneticFluxDensity.into(), PredefUnit::Frequency.into()])
+);
+
+let b = DynQuantity::new(1.2, PredefUnit::MagneticFluxDensity);
+let f = DynQuantity::new(20.0, PredefUnit::Frequency);
+assert_eq!(model.call(&[b, f]), DynQuantity::new(1152.0, PredefUnit::Power));
+
+// The frequency falls back to its default
+assert_eq!(model.call(&[b]), DynQuantity::new(7200.0, PredefUnit::Power));
+```
+*/
+#[proc_macro_attribute]
+pub fn quantity_function(attr: TokenStream, item: TokenStream) -> TokenStream {
+    let mut attributes = Attributes::default();
+    let parser = syn::meta::parser(|meta| attributes.parse(meta));
+    parse_macro_input!(attr with parser);
+    let item = parse_macro_input!(item as ItemImpl);
+
+    return match expand(attributes, item) {
+        Ok(tokens) => tokens.into(),
+        Err(error) => error.to_compile_error().into(),
+    };
+}
+
+/// A unit parsed at compile time.
+struct ParsedUnit {
+    exponents: [i32; 7],
+    /// Value of one declared unit in SI base units (e.g. 1000 for kHz).
+    scale: f64,
+}
+
+impl ParsedUnit {
+    fn parse(literal: &LitStr) -> syn::Result<Self> {
+        let text = literal.value();
+        let parse = |value: &str| {
+            DynQuantity::<f64>::from_str(&format!("{value} {}", text.trim())).map_err(|error| {
+                Error::new(literal.span(), format!("invalid unit \"{text}\": {error}"))
+            })
+        };
+
+        // Units like °C are converted with an offset, which can't be expressed
+        // by a scale factor
+        let zero = parse("0")?;
+        if zero.value != 0.0 {
+            return Err(Error::new(
+                literal.span(),
+                format!(
+                    "unit \"{text}\" has an offset to its SI base unit, which is not supported \
+                    (e.g. use \"K\" instead of \"°C\")"
+                ),
+            ));
+        }
+        let quantity = parse("1")?;
+        let unit = quantity.unit;
+        return Ok(Self {
+            exponents: [
+                unit.second,
+                unit.meter,
+                unit.kilogram,
+                unit.ampere,
+                unit.kelvin,
+                unit.mol,
+                unit.candela,
+            ],
+            scale: quantity.value,
+        });
+    }
+
+    fn to_tokens(&self) -> TokenStream2 {
+        let exponents = self.exponents;
+        return quote!(::var_quantity::__private::Unit::from([#(#exponents),*]));
+    }
+
+    fn scale(&self) -> Literal {
+        return Literal::f64_suffixed(self.scale);
+    }
+}
+
+/// An argument of the method declared in `inputs(...)`.
+struct Input {
+    name: Ident,
+    unit: ParsedUnit,
+    default: Option<Expr>,
+}
+
+#[derive(Default)]
+struct Attributes {
+    output: Option<ParsedUnit>,
+    inputs: Vec<Input>,
+}
+
+impl Attributes {
+    fn parse(&mut self, meta: syn::meta::ParseNestedMeta) -> syn::Result<()> {
+        if meta.path.is_ident("output") {
+            self.output = Some(ParsedUnit::parse(&meta.value()?.parse()?)?);
+            return Ok(());
+        }
+        if meta.path.is_ident("inputs") {
+            return meta.parse_nested_meta(|input| {
+                let name = input
+                    .path
+                    .get_ident()
+                    .cloned()
+                    .ok_or_else(|| input.error("expected an argument name"))?;
+                if self.inputs.iter().any(|other| other.name == name) {
+                    return Err(input.error(format!("argument `{name}` is declared twice")));
+                }
+
+                // Short form `name = "unit"`
+                if input.input.peek(syn::Token![=]) {
+                    let unit = ParsedUnit::parse(&input.value()?.parse()?)?;
+                    self.inputs.push(Input {
+                        name,
+                        unit,
+                        default: None,
+                    });
+                    return Ok(());
+                }
+
+                // Long form `name(unit = "unit", default = value)`
+                let mut unit = None;
+                let mut default = None;
+                input.parse_nested_meta(|option| {
+                    if option.path.is_ident("unit") {
+                        unit = Some(ParsedUnit::parse(&option.value()?.parse()?)?);
+                        return Ok(());
+                    }
+                    if option.path.is_ident("default") {
+                        default = Some(option.value()?.parse()?);
+                        return Ok(());
+                    }
+                    return Err(option.error("expected `unit` or `default`"));
+                })?;
+                let unit = unit.ok_or_else(|| input.error("missing `unit`"))?;
+                self.inputs.push(Input {
+                    name,
+                    unit,
+                    default,
+                });
+                return Ok(());
+            });
+        }
+        return Err(meta.error("expected `output` or `inputs`"));
+    }
+}
+
+fn expand(attributes: Attributes, item: ItemImpl) -> syn::Result<TokenStream2> {
+    if let Some((_, path, _)) = &item.trait_ {
+        return Err(Error::new(
+            path.span(),
+            "expected an inherent impl block, not a trait implementation",
+        ));
+    }
+    if !item.generics.params.is_empty() {
+        return Err(Error::new(
+            item.generics.span(),
+            "generic types are not supported, since QuantityFunction trait objects can only be deserialized for concrete types",
+        ));
+    }
+    let output = attributes.output.as_ref().ok_or_else(|| {
+        Error::new(
+            item.impl_token.span,
+            "missing output unit, e.g. `#[quantity_function(output = \"W\", ...)]`",
+        )
+    })?;
+
+    let methods: Vec<&ImplItemFn> = item
+        .items
+        .iter()
+        .filter_map(|item| match item {
+            ImplItem::Fn(method) => Some(method),
+            _ => None,
+        })
+        .collect();
+    let [method] = methods.as_slice() else {
+        return Err(Error::new(
+            item.impl_token.span,
+            "expected an impl block with exactly one method",
+        ));
+    };
+    let arguments = method_arguments(method)?;
+
+    // Every argument needs to be declared and vice versa
+    let mut selected = Vec::new();
+    for argument in arguments.iter() {
+        let input = attributes
+            .inputs
+            .iter()
+            .find(|input| input.name == *argument)
+            .ok_or_else(|| {
+                Error::new(
+                    argument.span(),
+                    format!("the unit of argument `{argument}` is not declared in `inputs(...)`"),
+                )
+            })?;
+        selected.push(input);
+    }
+    if let Some(input) = attributes
+        .inputs
+        .iter()
+        .find(|input| !arguments.contains(&input.name))
+    {
+        return Err(Error::new(
+            input.name.span(),
+            format!(
+                "`{}` is not an argument of `{}`",
+                input.name, method.sig.ident
+            ),
+        ));
+    }
+
+    let self_ty = &item.self_ty;
+    let method_name = &method.sig.ident;
+    let output_unit = output.to_tokens();
+    let output_scale = output.scale();
+
+    let mut influencing_units: Vec<&ParsedUnit> = Vec::new();
+    let mut unnamed = Vec::new();
+    let mut named = Vec::new();
+    for (index, input) in selected.iter().enumerate() {
+        // Arguments with the same unit take the matching factors in order
+        let occurrence = selected[..index]
+            .iter()
+            .filter(|other| other.unit.exponents == input.unit.exponents)
+            .count();
+        if occurrence == 0 {
+            influencing_units.push(&input.unit);
+        }
+        let variable = format_ident!("__{}", input.name);
+        let name = input.name.to_string();
+        let unit = input.unit.to_tokens();
+        let scale = input.unit.scale();
+        let default = match &input.default {
+            Some(default) => quote!(#default),
+            None => quote!(0.0),
+        };
+        unnamed.push(quote! {
+            let #variable: f64 = ::var_quantity::__private::select_factor(influencing_factors, #unit, #occurrence)
+                .map(|value| value / #scale)
+                .unwrap_or(#default);
+        });
+        named.push(quote! {
+            let #variable: f64 = ::var_quantity::__private::select_named_factor(influencing_factors, #name, #unit)
+                .map(|value| value / #scale)
+                .unwrap_or(#default);
+        });
+    }
+    let variables: Vec<Ident> = selected
+        .iter()
+        .map(|input| format_ident!("__{}", input.name))
+        .collect();
+    let influencing_units = influencing_units.iter().map(|unit| unit.to_tokens());
+
+    let typetag = if cfg!(feature = "serde") {
+        quote! {
+            use ::var_quantity::typetag;
+            #[typetag::serde]
+        }
+    } else {
+        TokenStream2::new()
+    };
+
+    return Ok(quote! {
+        #item
+
+        const _: () = {
+            #typetag
+            impl ::var_quantity::QuantityFunction for #self_ty {
+                fn call(
+                    &self,
+                    influencing_factors: &[::var_quantity::__private::DynQuantity<f64>],
+                ) -> ::var_quantity::__private::DynQuantity<f64> {
+                    #(#unnamed)*
+                    return ::var_quantity::__private::DynQuantity::new(
+                        self.#method_name(#(#variables),*) * #output_scale,
+                        #output_unit,
+                    );
+                }
+
+                fn output_unit(&self) -> ::var_quantity::__private::Unit {
+                    return #output_unit;
+                }
+
+                fn influencing_units(
+                    &self,
+                ) -> ::std::option::Option<::std::vec::Vec<::var_quantity::__private::Unit>> {
+                    return ::std::option::Option::Some(::std::vec![#(#influencing_units),*]);
+                }
+
+                fn call_named(
+                    &self,
+                    influencing_factors: &[::var_quantity::InfluencingFactor],
+                ) -> ::var_quantity::__private::DynQuantity<f64> {
+                    #(#named)*
+                    return ::var_quantity::__private::DynQuantity::new(
+                        self.#method_name(#(#variables),*) * #output_scale,
+                        #output_unit,
+                    );
+                }
+            }
+        };
+    });
+}
+
+/// Returns the argument names of `method`, which must take `&self`.
+fn method_arguments(method: &ImplItemFn) -> syn::Result<Vec<Ident>> {
+    let mut inputs = method.sig.inputs.iter();
+    match inputs.next() {
+        Some(FnArg::Receiver(receiver))
+            if receiver.reference.is_some() && receiver.mutability.is_none() => {}
+        _ => {
+            return Err(Error::new(
+                method.sig.span(),
+                "the method needs to take `&self` as its first argument",
+            ));
+        }
+    }
+    let mut arguments = Vec::new();
+    for input in inputs {
+        let FnArg::Typed(typed) = input else {
+            unreachable!("only the first argument can be a receiver")
+        };
+        let Pat::Ident(pattern) = &*typed.pat else {
+            return Err(Error::new(
+                typed.pat.span(),
+                "expected a plain argument name",
+            ));
+        };
+        arguments.push(pattern.ident.clone());
+    }
+    return Ok(arguments);
+}